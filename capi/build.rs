@@ -0,0 +1,18 @@
+// Generates `include/markdown.h` from `src/lib.rs`.
+
+use std::env;
+
+fn main() {
+    let directory = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config = cbindgen::Config::from_file("cbindgen.toml").expect("should read config");
+
+    cbindgen::Builder::new()
+        .with_crate(&directory)
+        .with_config(config)
+        .generate()
+        .expect("should generate bindings")
+        .write_to_file("include/markdown.h");
+
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
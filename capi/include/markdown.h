@@ -0,0 +1,210 @@
+/* C bindings for markdown-rs. */
+
+#ifndef MARKDOWN_H
+#define MARKDOWN_H
+
+/* Generated by cbindgen from `src/lib.rs`, do not edit. */
+
+#include <stdbool.h>
+
+// Kind of error.
+typedef enum MarkdownErrorCode {
+  // No error.
+  MARKDOWN_ERROR_CODE_NONE = 0,
+  // A pointer was null, or a string was not UTF-8.
+  MARKDOWN_ERROR_CODE_INVALID_ARGUMENT = 1,
+  // The document could not be parsed (which can only happen with MDX).
+  MARKDOWN_ERROR_CODE_PARSE = 2,
+  // Something went wrong inside the library.
+  MARKDOWN_ERROR_CODE_INTERNAL = 3,
+} MarkdownErrorCode;
+
+// Which constructs to enable and disable (opaque).
+typedef struct MarkdownConstructs MarkdownConstructs;
+
+// Configuration that describes how to parse from markdown and compile to
+// HTML (opaque).
+typedef struct MarkdownOptions MarkdownOptions;
+
+// Error.
+//
+// Owned by the caller.
+// When `code` is not `None`, `message` is a string that must be released
+// with [`markdown_error_clear()`][].
+typedef struct MarkdownError {
+  // Kind of error.
+  enum MarkdownErrorCode code;
+  // Description of the error, or null.
+  char *message;
+} MarkdownError;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Create constructs that follow `CommonMark`.
+//
+// Free with [`markdown_constructs_free()`][].
+struct MarkdownConstructs *markdown_constructs_new(void);
+
+// Create constructs that follow GFM.
+//
+// Free with [`markdown_constructs_free()`][].
+struct MarkdownConstructs *markdown_constructs_gfm(void);
+
+// Create constructs that follow MDX.
+//
+// Free with [`markdown_constructs_free()`][].
+struct MarkdownConstructs *markdown_constructs_mdx(void);
+
+// Turn a construct on or off.
+//
+// `name` is the name of a field of `Constructs` in `markdown-rs`, such as
+// `"gfm_table"` or `"code_indented"`.
+// Returns whether `name` is known.
+//
+// # Safety
+//
+// `constructs` must be null or come from a `markdown_constructs_*`
+// function, and `name` must be null or a null-terminated string.
+bool markdown_constructs_set(struct MarkdownConstructs *constructs, const char *name, bool value);
+
+// Free constructs.
+//
+// # Safety
+//
+// `constructs` must be null or come from a `markdown_constructs_*`
+// function, and must not be used afterwards.
+void markdown_constructs_free(struct MarkdownConstructs *constructs);
+
+// Create options that follow `CommonMark`.
+//
+// Free with [`markdown_options_free()`][].
+struct MarkdownOptions *markdown_options_new(void);
+
+// Create options that follow GFM.
+//
+// Free with [`markdown_options_free()`][].
+struct MarkdownOptions *markdown_options_gfm(void);
+
+// Set which constructs to use.
+//
+// `constructs` is copied, so it can be freed afterwards.
+// Returns whether both pointers were given.
+//
+// # Safety
+//
+// `options` must be null or come from a `markdown_options_*` function, and
+// `constructs` must be null or come from a `markdown_constructs_*`
+// function.
+bool markdown_options_set_constructs(struct MarkdownOptions *options,
+                                     const struct MarkdownConstructs *constructs);
+
+// Set a boolean option.
+//
+// `name` is one of `"allow_dangerous_html"`, `"allow_dangerous_protocol"`,
+// `"gfm_tagfilter"`, `"gfm_task_list_item_checkable"`, `"sourcepos"`
+// (compile options), `"gfm_strikethrough_single_tilde"`, or
+// `"math_text_single_dollar"` (parse options).
+// Returns whether `name` is known.
+//
+// # Safety
+//
+// `options` must be null or come from a `markdown_options_*` function, and
+// `name` must be null or a null-terminated string.
+bool markdown_options_set_bool(struct MarkdownOptions *options, const char *name, bool value);
+
+// Set a string option.
+//
+// `name` is one of `"default_line_ending"` (`"\n"`, `"\r\n"`, or `"\r"`),
+// `"gfm_footnote_back_label"`, `"gfm_footnote_clobber_prefix"`,
+// `"gfm_footnote_label"`, `"gfm_footnote_label_attributes"`, or
+// `"gfm_footnote_label_tag_name"`.
+// `value` is copied.
+// Returns whether `name` is known and `value` is valid.
+//
+// # Safety
+//
+// `options` must be null or come from a `markdown_options_*` function, and
+// `name` and `value` must be null or null-terminated strings.
+bool markdown_options_set_string(struct MarkdownOptions *options,
+                                 const char *name,
+                                 const char *value);
+
+// Free options.
+//
+// # Safety
+//
+// `options` must be null or come from a `markdown_options_*` function, and
+// must not be used afterwards.
+void markdown_options_free(struct MarkdownOptions *options);
+
+// Turn markdown into HTML, following `CommonMark`.
+//
+// Returns a string to free with [`markdown_string_free()`][], or null
+// when `value` is not valid, in which case `error` (if not null) is filled
+// in.
+//
+// # Safety
+//
+// `value` must be null or a null-terminated string, and `error` must be
+// null or point to a zeroed `MarkdownError` (or one filled in by this
+// library).
+char *markdown_to_html(const char *value, struct MarkdownError *error);
+
+// Turn markdown into HTML, with configuration.
+//
+// `options` can be null to use the defaults.
+// Returns a string to free with [`markdown_string_free()`][], or null
+// when `value` is not valid or cannot be parsed, in which case `error` (if
+// not null) is filled in.
+//
+// # Safety
+//
+// `value` must be null or a null-terminated string, `options` must be null
+// or come from a `markdown_options_*` function, and `error` must be null or
+// point to a zeroed `MarkdownError` (or one filled in by this library).
+char *markdown_to_html_with_options(const char *value,
+                                    const struct MarkdownOptions *options,
+                                    struct MarkdownError *error);
+
+// Turn markdown into a syntax tree, serialized as JSON.
+//
+// Only the parse options (such as constructs) in `options` are used, and
+// `options` can be null to use the defaults.
+// Returns a string to free with [`markdown_string_free()`][], or null
+// when `value` is not valid or cannot be parsed, in which case `error` (if
+// not null) is filled in.
+//
+// # Safety
+//
+// `value` must be null or a null-terminated string, `options` must be null
+// or come from a `markdown_options_*` function, and `error` must be null or
+// point to a zeroed `MarkdownError` (or one filled in by this library).
+char *markdown_to_mdast_json(const char *value,
+                             const struct MarkdownOptions *options,
+                             struct MarkdownError *error);
+
+// Free a string returned by this library.
+//
+// # Safety
+//
+// `value` must be null or come from this library, and must not be used
+// afterwards.
+void markdown_string_free(char *value);
+
+// Free the message of an error, and reset it.
+//
+// The error itself is owned by the caller.
+//
+// # Safety
+//
+// `error` must be null or point to a `MarkdownError` that was zeroed or
+// filled in by this library.
+void markdown_error_clear(struct MarkdownError *error);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* MARKDOWN_H */
@@ -0,0 +1,459 @@
+//! C bindings for `markdown-rs`.
+//!
+//! Exposes [`markdown::to_html()`][], [`markdown::to_html_with_options()`][],
+//! and [`markdown::to_mdast()`][] (as JSON) through a C ABI.
+//! See `include/markdown.h` for the header, which is generated by `build.rs`.
+//!
+//! Strings going in are null-terminated UTF-8.
+//! Strings coming out are owned by the caller, and must be freed with
+//! [`markdown_string_free()`][].
+//! Options and constructs are opaque: they are made with `*_new` functions,
+//! configured with `*_set*` functions, and freed with `*_free` functions.
+//! Functions that can fail take a [`MarkdownError`][] to fill in, and never
+//! unwind into C.
+
+use markdown::{Constructs, LineEnding, Options};
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+/// Kind of error.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MarkdownErrorCode {
+    /// No error.
+    None = 0,
+    /// A pointer was null, or a string was not UTF-8.
+    InvalidArgument = 1,
+    /// The document could not be parsed (which can only happen with MDX).
+    Parse = 2,
+    /// Something went wrong inside the library.
+    Internal = 3,
+}
+
+/// Error.
+///
+/// Owned by the caller.
+/// When `code` is not `None`, `message` is a string that must be released
+/// with [`markdown_error_clear()`][].
+#[repr(C)]
+#[derive(Debug)]
+pub struct MarkdownError {
+    /// Kind of error.
+    pub code: MarkdownErrorCode,
+    /// Description of the error, or null.
+    pub message: *mut c_char,
+}
+
+/// Which constructs to enable and disable (opaque).
+#[derive(Debug)]
+pub struct MarkdownConstructs(Constructs);
+
+/// Configuration that describes how to parse from markdown and compile to
+/// HTML (opaque).
+#[derive(Debug)]
+pub struct MarkdownOptions(Options);
+
+/// Create constructs that follow `CommonMark`.
+///
+/// Free with [`markdown_constructs_free()`][].
+#[no_mangle]
+pub extern "C" fn markdown_constructs_new() -> *mut MarkdownConstructs {
+    Box::into_raw(Box::new(MarkdownConstructs(Constructs::default())))
+}
+
+/// Create constructs that follow GFM.
+///
+/// Free with [`markdown_constructs_free()`][].
+#[no_mangle]
+pub extern "C" fn markdown_constructs_gfm() -> *mut MarkdownConstructs {
+    Box::into_raw(Box::new(MarkdownConstructs(Constructs::gfm())))
+}
+
+/// Create constructs that follow MDX.
+///
+/// Free with [`markdown_constructs_free()`][].
+#[no_mangle]
+pub extern "C" fn markdown_constructs_mdx() -> *mut MarkdownConstructs {
+    Box::into_raw(Box::new(MarkdownConstructs(Constructs::mdx())))
+}
+
+/// Turn a construct on or off.
+///
+/// `name` is the name of a field of `Constructs` in `markdown-rs`, such as
+/// `"gfm_table"` or `"code_indented"`.
+/// Returns whether `name` is known.
+///
+/// # Safety
+///
+/// `constructs` must be null or come from a `markdown_constructs_*`
+/// function, and `name` must be null or a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn markdown_constructs_set(
+    constructs: *mut MarkdownConstructs,
+    name: *const c_char,
+    value: bool,
+) -> bool {
+    let (constructs, name) = match (constructs.as_mut(), to_str(name)) {
+        (Some(constructs), Some(name)) => (&mut constructs.0, name),
+        _ => return false,
+    };
+
+    let field = match name {
+        "attention" => &mut constructs.attention,
+        "autolink" => &mut constructs.autolink,
+        "block_quote" => &mut constructs.block_quote,
+        "character_escape" => &mut constructs.character_escape,
+        "character_reference" => &mut constructs.character_reference,
+        "code_indented" => &mut constructs.code_indented,
+        "code_fenced" => &mut constructs.code_fenced,
+        "code_text" => &mut constructs.code_text,
+        "definition" => &mut constructs.definition,
+        "frontmatter" => &mut constructs.frontmatter,
+        "gfm_autolink_literal" => &mut constructs.gfm_autolink_literal,
+        "gfm_footnote_definition" => &mut constructs.gfm_footnote_definition,
+        "gfm_label_start_footnote" => &mut constructs.gfm_label_start_footnote,
+        "gfm_strikethrough" => &mut constructs.gfm_strikethrough,
+        "gfm_table" => &mut constructs.gfm_table,
+        "gfm_task_list_item" => &mut constructs.gfm_task_list_item,
+        "hard_break_escape" => &mut constructs.hard_break_escape,
+        "hard_break_trailing" => &mut constructs.hard_break_trailing,
+        "heading_atx" => &mut constructs.heading_atx,
+        "heading_setext" => &mut constructs.heading_setext,
+        "html_flow" => &mut constructs.html_flow,
+        "html_text" => &mut constructs.html_text,
+        "label_start_image" => &mut constructs.label_start_image,
+        "label_start_link" => &mut constructs.label_start_link,
+        "label_end" => &mut constructs.label_end,
+        "list_item" => &mut constructs.list_item,
+        "math_flow" => &mut constructs.math_flow,
+        "math_text" => &mut constructs.math_text,
+        "mdx_esm" => &mut constructs.mdx_esm,
+        "mdx_expression_flow" => &mut constructs.mdx_expression_flow,
+        "mdx_expression_text" => &mut constructs.mdx_expression_text,
+        "mdx_jsx_flow" => &mut constructs.mdx_jsx_flow,
+        "mdx_jsx_text" => &mut constructs.mdx_jsx_text,
+        "thematic_break" => &mut constructs.thematic_break,
+        _ => return false,
+    };
+
+    *field = value;
+    true
+}
+
+/// Free constructs.
+///
+/// # Safety
+///
+/// `constructs` must be null or come from a `markdown_constructs_*`
+/// function, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn markdown_constructs_free(constructs: *mut MarkdownConstructs) {
+    if !constructs.is_null() {
+        drop(Box::from_raw(constructs));
+    }
+}
+
+/// Create options that follow `CommonMark`.
+///
+/// Free with [`markdown_options_free()`][].
+#[no_mangle]
+pub extern "C" fn markdown_options_new() -> *mut MarkdownOptions {
+    Box::into_raw(Box::new(MarkdownOptions(Options::default())))
+}
+
+/// Create options that follow GFM.
+///
+/// Free with [`markdown_options_free()`][].
+#[no_mangle]
+pub extern "C" fn markdown_options_gfm() -> *mut MarkdownOptions {
+    Box::into_raw(Box::new(MarkdownOptions(Options::gfm())))
+}
+
+/// Set which constructs to use.
+///
+/// `constructs` is copied, so it can be freed afterwards.
+/// Returns whether both pointers were given.
+///
+/// # Safety
+///
+/// `options` must be null or come from a `markdown_options_*` function, and
+/// `constructs` must be null or come from a `markdown_constructs_*`
+/// function.
+#[no_mangle]
+pub unsafe extern "C" fn markdown_options_set_constructs(
+    options: *mut MarkdownOptions,
+    constructs: *const MarkdownConstructs,
+) -> bool {
+    match (options.as_mut(), constructs.as_ref()) {
+        (Some(options), Some(constructs)) => {
+            options.0.parse.constructs = constructs.0.clone();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Set a boolean option.
+///
+/// `name` is one of `"allow_dangerous_html"`, `"allow_dangerous_protocol"`,
+/// `"gfm_tagfilter"`, `"gfm_task_list_item_checkable"`, `"sourcepos"`
+/// (compile options), `"gfm_strikethrough_single_tilde"`, or
+/// `"math_text_single_dollar"` (parse options).
+/// Returns whether `name` is known.
+///
+/// # Safety
+///
+/// `options` must be null or come from a `markdown_options_*` function, and
+/// `name` must be null or a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn markdown_options_set_bool(
+    options: *mut MarkdownOptions,
+    name: *const c_char,
+    value: bool,
+) -> bool {
+    let (options, name) = match (options.as_mut(), to_str(name)) {
+        (Some(options), Some(name)) => (&mut options.0, name),
+        _ => return false,
+    };
+
+    let field = match name {
+        "allow_dangerous_html" => &mut options.compile.allow_dangerous_html,
+        "allow_dangerous_protocol" => &mut options.compile.allow_dangerous_protocol,
+        "gfm_tagfilter" => &mut options.compile.gfm_tagfilter,
+        "gfm_task_list_item_checkable" => &mut options.compile.gfm_task_list_item_checkable,
+        "sourcepos" => &mut options.compile.sourcepos,
+        "gfm_strikethrough_single_tilde" => &mut options.parse.gfm_strikethrough_single_tilde,
+        "math_text_single_dollar" => &mut options.parse.math_text_single_dollar,
+        _ => return false,
+    };
+
+    *field = value;
+    true
+}
+
+/// Set a string option.
+///
+/// `name` is one of `"default_line_ending"` (`"\n"`, `"\r\n"`, or `"\r"`),
+/// `"gfm_footnote_back_label"`, `"gfm_footnote_clobber_prefix"`,
+/// `"gfm_footnote_label"`, `"gfm_footnote_label_attributes"`, or
+/// `"gfm_footnote_label_tag_name"`.
+/// `value` is copied.
+/// Returns whether `name` is known and `value` is valid.
+///
+/// # Safety
+///
+/// `options` must be null or come from a `markdown_options_*` function, and
+/// `name` and `value` must be null or null-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn markdown_options_set_string(
+    options: *mut MarkdownOptions,
+    name: *const c_char,
+    value: *const c_char,
+) -> bool {
+    let (options, name, value) = match (options.as_mut(), to_str(name), to_str(value)) {
+        (Some(options), Some(name), Some(value)) => (&mut options.0.compile, name, value),
+        _ => return false,
+    };
+
+    let field = match name {
+        "default_line_ending" => {
+            return match value.parse::<LineEnding>() {
+                Ok(line_ending) => {
+                    options.default_line_ending = line_ending;
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+        "gfm_footnote_back_label" => &mut options.gfm_footnote_back_label,
+        "gfm_footnote_clobber_prefix" => &mut options.gfm_footnote_clobber_prefix,
+        "gfm_footnote_label" => &mut options.gfm_footnote_label,
+        "gfm_footnote_label_attributes" => &mut options.gfm_footnote_label_attributes,
+        "gfm_footnote_label_tag_name" => &mut options.gfm_footnote_label_tag_name,
+        _ => return false,
+    };
+
+    *field = Some(value.into());
+    true
+}
+
+/// Free options.
+///
+/// # Safety
+///
+/// `options` must be null or come from a `markdown_options_*` function, and
+/// must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn markdown_options_free(options: *mut MarkdownOptions) {
+    if !options.is_null() {
+        drop(Box::from_raw(options));
+    }
+}
+
+/// Turn markdown into HTML, following `CommonMark`.
+///
+/// Returns a string to free with [`markdown_string_free()`][], or null
+/// when `value` is not valid, in which case `error` (if not null) is filled
+/// in.
+///
+/// # Safety
+///
+/// `value` must be null or a null-terminated string, and `error` must be
+/// null or point to a zeroed `MarkdownError` (or one filled in by this
+/// library).
+#[no_mangle]
+pub unsafe extern "C" fn markdown_to_html(
+    value: *const c_char,
+    error: *mut MarkdownError,
+) -> *mut c_char {
+    run(error, || {
+        let value = to_str(value).ok_or_else(invalid_value)?;
+        Ok(markdown::to_html(value))
+    })
+}
+
+/// Turn markdown into HTML, with configuration.
+///
+/// `options` can be null to use the defaults.
+/// Returns a string to free with [`markdown_string_free()`][], or null
+/// when `value` is not valid or cannot be parsed, in which case `error` (if
+/// not null) is filled in.
+///
+/// # Safety
+///
+/// `value` must be null or a null-terminated string, `options` must be null
+/// or come from a `markdown_options_*` function, and `error` must be null or
+/// point to a zeroed `MarkdownError` (or one filled in by this library).
+#[no_mangle]
+pub unsafe extern "C" fn markdown_to_html_with_options(
+    value: *const c_char,
+    options: *const MarkdownOptions,
+    error: *mut MarkdownError,
+) -> *mut c_char {
+    run(error, || {
+        let value = to_str(value).ok_or_else(invalid_value)?;
+        let result = match options.as_ref() {
+            Some(options) => markdown::to_html_with_options(value, &options.0),
+            None => markdown::to_html_with_options(value, &Options::default()),
+        };
+        result.map_err(|message| (MarkdownErrorCode::Parse, message))
+    })
+}
+
+/// Turn markdown into a syntax tree, serialized as JSON.
+///
+/// Only the parse options (such as constructs) in `options` are used, and
+/// `options` can be null to use the defaults.
+/// Returns a string to free with [`markdown_string_free()`][], or null
+/// when `value` is not valid or cannot be parsed, in which case `error` (if
+/// not null) is filled in.
+///
+/// # Safety
+///
+/// `value` must be null or a null-terminated string, `options` must be null
+/// or come from a `markdown_options_*` function, and `error` must be null or
+/// point to a zeroed `MarkdownError` (or one filled in by this library).
+#[no_mangle]
+pub unsafe extern "C" fn markdown_to_mdast_json(
+    value: *const c_char,
+    options: *const MarkdownOptions,
+    error: *mut MarkdownError,
+) -> *mut c_char {
+    run(error, || {
+        let value = to_str(value).ok_or_else(invalid_value)?;
+        let tree = match options.as_ref() {
+            Some(options) => markdown::to_mdast(value, &options.0.parse),
+            None => markdown::to_mdast(value, &markdown::ParseOptions::default()),
+        }
+        .map_err(|message| (MarkdownErrorCode::Parse, message))?;
+        serde_json::to_string(&tree)
+            .map_err(|error| (MarkdownErrorCode::Internal, error.to_string()))
+    })
+}
+
+/// Free a string returned by this library.
+///
+/// # Safety
+///
+/// `value` must be null or come from this library, and must not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn markdown_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+/// Free the message of an error, and reset it.
+///
+/// The error itself is owned by the caller.
+///
+/// # Safety
+///
+/// `error` must be null or point to a `MarkdownError` that was zeroed or
+/// filled in by this library.
+#[no_mangle]
+pub unsafe extern "C" fn markdown_error_clear(error: *mut MarkdownError) {
+    if let Some(error) = error.as_mut() {
+        markdown_string_free(error.message);
+        error.code = MarkdownErrorCode::None;
+        error.message = ptr::null_mut();
+    }
+}
+
+/// Get a string from a C string, if it is not null and is UTF-8.
+unsafe fn to_str<'a>(value: *const c_char) -> Option<&'a str> {
+    if value.is_null() {
+        None
+    } else {
+        CStr::from_ptr(value).to_str().ok()
+    }
+}
+
+/// Error for when `value` is not valid.
+fn invalid_value() -> (MarkdownErrorCode, String) {
+    (
+        MarkdownErrorCode::InvalidArgument,
+        "Expected `value` to be a UTF-8 string".into(),
+    )
+}
+
+/// Run `callback`, turning its result into a C string and its errors (and
+/// panics) into `error`.
+unsafe fn run(
+    error: *mut MarkdownError,
+    callback: impl FnOnce() -> Result<String, (MarkdownErrorCode, String)>,
+) -> *mut c_char {
+    let result = match catch_unwind(AssertUnwindSafe(callback)) {
+        Ok(result) => result,
+        Err(_) => Err((
+            MarkdownErrorCode::Internal,
+            "Unexpected panic in markdown-rs".into(),
+        )),
+    };
+    let result = result.and_then(|value| {
+        CString::new(value).map_err(|_| {
+            (
+                MarkdownErrorCode::Internal,
+                "Unexpected null character in result".into(),
+            )
+        })
+    });
+
+    markdown_error_clear(error);
+
+    match result {
+        Ok(value) => value.into_raw(),
+        Err((code, message)) => {
+            if let Some(error) = error.as_mut() {
+                error.code = code;
+                // Messages do not contain null characters.
+                error.message = CString::new(message).unwrap_or_default().into_raw();
+            }
+
+            ptr::null_mut()
+        }
+    }
+}
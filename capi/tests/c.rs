@@ -0,0 +1,32 @@
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn c() {
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // The test binary is in `target/<profile>/deps/`, the library is in
+    // `target/<profile>/`.
+    let profile = env::current_exe()
+        .unwrap()
+        .parent()
+        .and_then(|d| d.parent())
+        .unwrap()
+        .to_path_buf();
+    let output = env::temp_dir().join(format!("markdown-capi-test-{}", std::process::id()));
+
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".into()))
+        .arg(manifest.join("tests/test.c"))
+        .arg("-I")
+        .arg(manifest.join("include"))
+        .arg(profile.join("libmarkdown_capi.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&output)
+        .status()
+        .expect("should run a C compiler");
+    assert!(status.success(), "should compile the C test program");
+
+    let status = Command::new(&output).status().expect("should run");
+    std::fs::remove_file(&output).unwrap();
+    assert!(status.success(), "should pass the C test program");
+}
@@ -0,0 +1,91 @@
+/* Tests for the C bindings, run by `tests/c.rs`. */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "markdown.h"
+
+static int failures = 0;
+
+/* Check that `actual` is `expected`, and free `actual`. */
+static void check(char *actual, const char *expected, const char *message) {
+  if (actual == NULL || strcmp(actual, expected) != 0) {
+    fprintf(stderr, "not ok: %s\n  expected: %s\n  actual: %s\n", message,
+            expected, actual == NULL ? "(null)" : actual);
+    failures++;
+  }
+
+  markdown_string_free(actual);
+}
+
+int main(void) {
+  MarkdownError error = {MARKDOWN_ERROR_CODE_NONE, NULL};
+
+  check(markdown_to_html("# Hello, *world*!", &error),
+        "<h1>Hello, <em>world</em>!</h1>", "should support `to_html`");
+
+  MarkdownOptions *options = markdown_options_gfm();
+  markdown_options_set_bool(options, "allow_dangerous_html", true);
+  markdown_options_set_string(options, "default_line_ending", "\r\n");
+  check(markdown_to_html_with_options("> ~a~ <b>", options, &error),
+        "<blockquote>\r\n<p><del>a</del> <b></p>\r\n</blockquote>",
+        "should support `to_html_with_options`");
+
+  if (markdown_options_set_bool(options, "missing", true) ||
+      markdown_options_set_string(options, "default_line_ending", "x")) {
+    fprintf(stderr, "not ok: should fail on unknown options\n");
+    failures++;
+  }
+
+  MarkdownConstructs *constructs = markdown_constructs_new();
+  markdown_constructs_set(constructs, "code_indented", false);
+  markdown_options_set_constructs(options, constructs);
+  markdown_constructs_free(constructs);
+  check(markdown_to_html_with_options("    a", options, &error), "<p>a</p>",
+        "should support constructs");
+  markdown_options_free(options);
+
+  char *json = markdown_to_mdast_json("*a*", NULL, &error);
+
+  if (json == NULL || strstr(json, "\"type\":\"emphasis\"") == NULL ||
+      strstr(json, "\"value\":\"a\"") == NULL) {
+    fprintf(stderr, "not ok: should support `to_mdast_json`\n");
+    failures++;
+  }
+
+  markdown_string_free(json);
+
+  constructs = markdown_constructs_mdx();
+  options = markdown_options_new();
+  markdown_options_set_constructs(options, constructs);
+  markdown_constructs_free(constructs);
+
+  if (markdown_to_mdast_json("a {b", options, &error) != NULL ||
+      error.code != MARKDOWN_ERROR_CODE_PARSE ||
+      strcmp(error.message, "1:5: Unexpected end of file in expression, "
+                            "expected a corresponding closing brace for "
+                            "`{`") != 0) {
+    fprintf(stderr, "not ok: should report parse errors\n");
+    failures++;
+  }
+
+  markdown_error_clear(&error);
+  markdown_options_free(options);
+
+  if (markdown_to_html("\xff", &error) != NULL ||
+      error.code != MARKDOWN_ERROR_CODE_INVALID_ARGUMENT ||
+      markdown_to_html(NULL, NULL) != NULL) {
+    fprintf(stderr, "not ok: should report invalid arguments\n");
+    failures++;
+  }
+
+  markdown_error_clear(&error);
+
+  if (error.code != MARKDOWN_ERROR_CODE_NONE || error.message != NULL) {
+    fprintf(stderr, "not ok: should clear errors\n");
+    failures++;
+  }
+
+  return failures == 0 ? EXIT_SUCCESS : EXIT_FAILURE;
+}
@@ -11,6 +11,7 @@ use std::fs;
 async fn main() {
     commonmark().await;
     punctuation().await;
+    kinds();
 }
 
 async fn commonmark() {
@@ -159,3 +160,115 @@ pub const PUNCTUATION: [char; {}] = [
 
     fs::write(code_url, doc).unwrap();
 }
+
+fn kinds() {
+    let data_url = "src/event.rs";
+    let code_url = "src/cst/kind.rs";
+
+    let value = fs::read_to_string(data_url).unwrap();
+    let start = value.find("pub enum Name {").unwrap();
+    let end = start + value[start..].find("\n}\n").unwrap();
+    let re_variant = Regex::new(r"^    ([A-Z][A-Za-z]*),$").unwrap();
+    let mut docs: Vec<&str> = vec![];
+    let mut variants = vec![];
+
+    for line in value[start..end].lines().skip(1) {
+        if let Some(doc) = line.strip_prefix("    ///") {
+            docs.push(doc.strip_prefix(' ').unwrap_or(doc));
+        } else if let Some(captures) = re_variant.captures(line) {
+            variants.push((captures[1].to_string(), kind_doc(&docs)));
+            docs.clear();
+        }
+    }
+
+    let doc = format!(
+        "//! Kinds of nodes in concrete syntax trees.
+//!
+//! > 👉 **Important**: this module is generated by `generate/src/main.rs`.
+//! > It is generated from the names of events in `src/event.rs`.
+
+use crate::event::Name;
+
+/// Kind of a node in a concrete syntax tree.
+///
+/// Nodes without children are tokens, such as
+/// [`SyntaxKind::ListItemMarker`][]; other nodes group them, such as
+/// [`SyntaxKind::ListItem`][].
+/// Bytes that no construct claims are [`SyntaxKind::Data`][].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SyntaxKind {{
+{}
+}}
+
+/// Get the kind of node for events named `name`.
+pub fn from_name(name: &Name) -> SyntaxKind {{
+    match name {{
+{}
+    }}
+}}
+",
+        variants
+            .iter()
+            .map(|(name, doc)| format!("{}    {},", doc, name))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        variants
+            .iter()
+            .map(|(name, _)| {
+                let arm = format!("        Name::{} => SyntaxKind::{},", name, name);
+
+                // Wrap like `rustfmt` does.
+                if arm.len() > 100 {
+                    format!(
+                        "        Name::{} => {{\n            SyntaxKind::{}\n        }}",
+                        name, name
+                    )
+                } else {
+                    arm
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+
+    fs::write(code_url, doc).unwrap();
+}
+
+/// Get the public docs of a kind from the docs of a name: the summary and
+/// the example, without the info that links to private constructs.
+fn kind_doc(docs: &[&str]) -> String {
+    let mut lines = vec![];
+    let mut index = 0;
+
+    while index < docs.len() && !docs[index].is_empty() {
+        lines.push(docs[index]);
+        index += 1;
+    }
+
+    while index < docs.len() && docs[index] != "## Example" {
+        index += 1;
+    }
+
+    // Skip the heading and the blank line after it.
+    index += 2;
+
+    if index < docs.len() {
+        lines.push("");
+
+        while index < docs.len() {
+            lines.push(docs[index]);
+            index += 1;
+        }
+    }
+
+    lines
+        .iter()
+        .map(|line| {
+            if line.is_empty() {
+                "    ///\n".to_string()
+            } else {
+                format!("    /// {}\n", line)
+            }
+        })
+        .collect()
+}
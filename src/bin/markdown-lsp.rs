@@ -0,0 +1,821 @@
+//! Language server for markdown.
+//!
+//! Speaks the [language server protocol][lsp] over stdio.
+//! Serves document symbols (headings), folding ranges (lists, code, block
+//! quotes), definitions and references of link, image, and footnote
+//! references, hover and completion for reference labels, and diagnostics
+//! for broken MDX and undefined references.
+//!
+//! Documents ending in `.mdx` are parsed as MDX.
+//! GFM and frontmatter are on by default, which can be configured with
+//! `initializationOptions` (`{"gfm": bool, "frontmatter": bool, "math": bool,
+//! "mdx": bool}`).
+//!
+//! [lsp]: https://microsoft.github.io/language-server-protocol/
+
+use markdown::{
+    lint::{self, Severity},
+    mdast::Node,
+    reparse, to_mdast,
+    unist::Position,
+    Constructs, Edit, Location, ParseOptions, PositionEncoding,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Read, Write};
+use std::process;
+
+/// Error code for bodies that are not JSON.
+const PARSE_ERROR: i64 = -32700;
+/// Error code for unknown methods.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// Error code for bad params.
+const INVALID_PARAMS: i64 = -32602;
+/// Error code for requests before `initialize`.
+const SERVER_NOT_INITIALIZED: i64 = -32002;
+
+/// Largest header line accepted, in bytes.
+const HEADER_SIZE_MAX: u64 = 8 * 1024;
+/// Largest message body accepted, in bytes.
+const MESSAGE_SIZE_MAX: usize = 64 * 1024 * 1024;
+
+/// Diagnostic severity for errors.
+const ERROR: u8 = 1;
+/// Diagnostic severity for warnings.
+const WARNING: u8 = 2;
+
+/// Symbol kind for headings (`String`).
+const SYMBOL_STRING: u8 = 15;
+/// Completion item kind for labels (`Reference`).
+const COMPLETION_REFERENCE: u8 = 18;
+
+/// Open document.
+struct Document {
+    /// Source.
+    value: String,
+    /// Syntax tree, if it parsed.
+    tree: Option<Node>,
+    /// Parse error, if it did not.
+    error: Option<String>,
+    /// Lines in `value`.
+    location: Location,
+    /// How to parse.
+    options: ParseOptions,
+}
+
+/// Configuration from `initializationOptions`.
+struct Config {
+    gfm: bool,
+    frontmatter: bool,
+    math: bool,
+    mdx: bool,
+}
+
+/// Server state.
+struct Server {
+    config: Config,
+    documents: HashMap<String, Document>,
+    initialized: bool,
+    shutdown: bool,
+}
+
+/// Reference or definition found in a tree.
+struct Label<'a> {
+    /// Normalized identifier.
+    identifier: &'a str,
+    /// Whether this is a footnote.
+    footnote: bool,
+    /// Whether this is a definition.
+    definition: bool,
+    /// Node.
+    node: &'a Node,
+}
+
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut input = stdin.lock();
+    let mut output = stdout.lock();
+    let mut server = Server {
+        config: Config {
+            gfm: true,
+            frontmatter: true,
+            math: false,
+            mdx: false,
+        },
+        documents: HashMap::new(),
+        initialized: false,
+        shutdown: false,
+    };
+
+    loop {
+        let body = match read_message(&mut input) {
+            Ok(Some(body)) => body,
+            Ok(None) => process::exit(1),
+            Err(message) => {
+                eprintln!("markdown-lsp: {}", message);
+                process::exit(1);
+            }
+        };
+
+        // The body was read whole, so the next message can still be read.
+        let replies = match serde_json::from_slice::<Value>(&body) {
+            Ok(message) => {
+                if message["method"] == "exit" {
+                    process::exit(if server.shutdown { 0 } else { 1 });
+                }
+
+                server.handle(&message)
+            }
+            Err(error) => vec![json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": {"code": PARSE_ERROR, "message": format!("Cannot parse body: {}", error)}
+            })],
+        };
+
+        for reply in replies {
+            if let Err(error) = write_message(&mut output, &reply) {
+                eprintln!("markdown-lsp: Cannot write message: {}", error);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Read the body of a message.
+///
+/// Returns `None` at the end of input.
+fn read_message(input: &mut impl BufRead) -> Result<Option<Vec<u8>>, String> {
+    let mut length = None;
+
+    loop {
+        let mut line = String::new();
+        let size = Read::take(&mut *input, HEADER_SIZE_MAX)
+            .read_line(&mut line)
+            .map_err(|error| format!("Cannot read header: {}", error))?;
+
+        if size == 0 {
+            return Ok(None);
+        }
+
+        if !line.ends_with('\n') && size as u64 == HEADER_SIZE_MAX {
+            return Err(format!(
+                "Cannot read header longer than {} bytes",
+                HEADER_SIZE_MAX
+            ));
+        }
+
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = Some(
+                    value
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|error| format!("Cannot parse `Content-Length`: {}", error))?,
+                );
+            }
+        }
+    }
+
+    let length = length.ok_or("Expected `Content-Length` header")?;
+
+    if length > MESSAGE_SIZE_MAX {
+        return Err(format!(
+            "Cannot read body of {} bytes, expected at most {} bytes",
+            length, MESSAGE_SIZE_MAX
+        ));
+    }
+
+    let mut body = vec![0; length];
+    input
+        .read_exact(&mut body)
+        .map_err(|error| format!("Cannot read body: {}", error))?;
+    Ok(Some(body))
+}
+
+/// Write a message.
+fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+impl Server {
+    /// Handle a message, returning messages to send back.
+    fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or("");
+        let params = &message["params"];
+
+        // Responses to our requests are not used.
+        if method.is_empty() {
+            return vec![];
+        }
+
+        let id = match message.get("id") {
+            Some(id) => id.clone(),
+            None => return self.notification(method, params),
+        };
+
+        let result = if method == "initialize" {
+            Ok(self.initialize(params))
+        } else if !self.initialized {
+            Err((SERVER_NOT_INITIALIZED, "Server not initialized".into()))
+        } else {
+            self.request(method, params)
+        };
+
+        vec![match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": {"code": code, "message": message}
+            }),
+        }]
+    }
+
+    /// Handle `initialize`.
+    fn initialize(&mut self, params: &Value) -> Value {
+        let options = &params["initializationOptions"];
+
+        for (key, value) in [
+            ("gfm", &mut self.config.gfm),
+            ("frontmatter", &mut self.config.frontmatter),
+            ("math", &mut self.config.math),
+            ("mdx", &mut self.config.mdx),
+        ] {
+            if let Some(option) = options[key].as_bool() {
+                *value = option;
+            }
+        }
+
+        self.initialized = true;
+
+        json!({
+            "capabilities": {
+                "textDocumentSync": {"openClose": true, "change": 2},
+                "documentSymbolProvider": true,
+                "foldingRangeProvider": true,
+                "definitionProvider": true,
+                "referencesProvider": true,
+                "hoverProvider": true,
+                "completionProvider": {"triggerCharacters": ["[", "^"]}
+            },
+            "serverInfo": {"name": "markdown-lsp", "version": env!("CARGO_PKG_VERSION")}
+        })
+    }
+
+    /// Handle a notification.
+    fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or("")
+            .to_string();
+
+        match method {
+            "textDocument/didOpen" => {
+                let value = params["textDocument"]["text"]
+                    .as_str()
+                    .unwrap_or("")
+                    .to_string();
+                let options = self.options(&uri);
+                self.documents
+                    .insert(uri.clone(), Document::new(value, options));
+            }
+            "textDocument/didChange" => {
+                let document = match self.documents.get_mut(&uri) {
+                    Some(document) => document,
+                    None => return vec![],
+                };
+                let empty = vec![];
+                let changes = params["contentChanges"].as_array().unwrap_or(&empty);
+
+                for change in changes {
+                    document.change(change);
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return vec![publish_diagnostics(&uri, &[])];
+            }
+            _ => return vec![],
+        }
+
+        match self.documents.get(&uri) {
+            Some(document) => vec![publish_diagnostics(&uri, &document.diagnostics())],
+            None => vec![],
+        }
+    }
+
+    /// Handle a request.
+    fn request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        if method == "shutdown" {
+            self.shutdown = true;
+            return Ok(Value::Null);
+        }
+
+        if !matches!(
+            method,
+            "textDocument/documentSymbol"
+                | "textDocument/foldingRange"
+                | "textDocument/definition"
+                | "textDocument/references"
+                | "textDocument/hover"
+                | "textDocument/completion"
+        ) {
+            return Err((METHOD_NOT_FOUND, format!("Unknown method `{}`", method)));
+        }
+
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or("");
+        let document = self
+            .documents
+            .get(uri)
+            .ok_or_else(|| (INVALID_PARAMS, format!("Unknown document `{}`", uri)))?;
+        let offset = document.offset(&params["position"]);
+
+        match method {
+            "textDocument/documentSymbol" => Ok(document.symbols()),
+            "textDocument/foldingRange" => Ok(document.folding_ranges()),
+            "textDocument/definition" => Ok(document.definition(uri, offset)),
+            "textDocument/references" => Ok(document.references(
+                uri,
+                offset,
+                params["context"]["includeDeclaration"]
+                    .as_bool()
+                    .unwrap_or(false),
+            )),
+            "textDocument/hover" => Ok(document.hover(offset)),
+            _ => Ok(document.completion(offset)),
+        }
+    }
+
+    /// Get parse options for a document.
+    fn options(&self, uri: &str) -> ParseOptions {
+        let mdx = self.config.mdx || uri.ends_with(".mdx");
+        let mut constructs = if mdx {
+            Constructs::mdx()
+        } else {
+            Constructs::default()
+        };
+
+        if self.config.gfm {
+            constructs.gfm_autolink_literal = true;
+            constructs.gfm_footnote_definition = true;
+            constructs.gfm_label_start_footnote = true;
+            constructs.gfm_strikethrough = true;
+            constructs.gfm_table = true;
+            constructs.gfm_task_list_item = true;
+        }
+
+        constructs.frontmatter = self.config.frontmatter;
+        constructs.math_flow = self.config.math;
+        constructs.math_text = self.config.math;
+
+        ParseOptions {
+            constructs,
+            ..ParseOptions::default()
+        }
+    }
+}
+
+impl Document {
+    /// Create a document.
+    fn new(value: String, options: ParseOptions) -> Self {
+        let mut document = Document {
+            location: Location::new(value.as_bytes()),
+            value,
+            tree: None,
+            error: None,
+            options,
+        };
+        document.parse();
+        document
+    }
+
+    /// Parse the whole document.
+    fn parse(&mut self) {
+        match to_mdast(&self.value, &self.options) {
+            Ok(tree) => {
+                self.tree = Some(tree);
+                self.error = None;
+            }
+            Err(error) => {
+                self.tree = None;
+                self.error = Some(error);
+            }
+        }
+    }
+
+    /// Apply a change.
+    fn change(&mut self, change: &Value) {
+        let text = change["text"].as_str().unwrap_or("").to_string();
+
+        if change.get("range").is_none() {
+            self.value = text;
+            self.location = Location::new(self.value.as_bytes());
+            self.parse();
+            return;
+        }
+
+        let start = self.offset(&change["range"]["start"]);
+        let end = self.offset(&change["range"]["end"]).max(start);
+        let edit = Edit {
+            start,
+            end,
+            value: text,
+        };
+
+        match self.tree.take() {
+            Some(tree) => match reparse(&self.value, tree, &edit, &self.options) {
+                Ok(result) => {
+                    self.value = result.value;
+                    self.tree = Some(result.tree);
+                    self.error = None;
+                    self.location = Location::new(self.value.as_bytes());
+                }
+                Err(error) => {
+                    self.value.replace_range(start..end, &edit.value);
+                    self.error = Some(error);
+                    self.location = Location::new(self.value.as_bytes());
+                }
+            },
+            None => {
+                self.value.replace_range(start..end, &edit.value);
+                self.location = Location::new(self.value.as_bytes());
+                self.parse();
+            }
+        }
+    }
+
+    /// Turn an LSP position (0-indexed line, UTF-16 character) into an
+    /// offset.
+    fn offset(&self, position: &Value) -> usize {
+        let line = position["line"].as_u64().unwrap_or(0) as usize;
+        let character = position["character"].as_u64().unwrap_or(0) as usize;
+        let start = match self.location.to_offset(line + 1, 1) {
+            Some(start) => start,
+            None => return self.value.len(),
+        };
+        let mut units = 0;
+
+        for (index, char) in self.value[start..].char_indices() {
+            if units >= character || char == '\n' || char == '\r' {
+                return start + index;
+            }
+
+            units += char.len_utf16();
+        }
+
+        self.value.len()
+    }
+
+    /// Turn an offset into an LSP position.
+    fn position(&self, offset: usize) -> Value {
+        let point = self
+            .location
+            .to_encoded_point(self.value.as_bytes(), offset, PositionEncoding::Utf16)
+            .expect("expected offset in document");
+        json!({"line": point.line - 1, "character": point.column - 1})
+    }
+
+    /// Turn a unist position into an LSP range.
+    fn range(&self, position: &Position) -> Value {
+        json!({
+            "start": self.position(position.start.offset),
+            "end": self.position(position.end.offset)
+        })
+    }
+
+    /// Get the source of a node.
+    fn source(&self, node: &Node) -> &str {
+        let position = node.position().unwrap();
+        &self.value[position.start.offset..position.end.offset]
+    }
+
+    /// Get all nodes, in tree order.
+    fn nodes(&self) -> Vec<&Node> {
+        let mut nodes = vec![];
+
+        if let Some(tree) = &self.tree {
+            collect(tree, &mut nodes);
+        }
+
+        nodes
+    }
+
+    /// Get references and definitions.
+    fn labels(&self) -> Vec<Label<'_>> {
+        self.nodes().into_iter().filter_map(label).collect()
+    }
+
+    /// Get the reference or definition at `offset`.
+    ///
+    /// A cursor right after a label is also on it.
+    fn label_at(&self, offset: usize) -> Option<Label<'_>> {
+        let tree = self.tree.as_ref()?;
+        let find = |offset| tree.at_offset(offset).into_iter().rev().find_map(label);
+        find(offset).or_else(|| offset.checked_sub(1).and_then(find))
+    }
+
+    /// Get the definition that `label` refers to.
+    fn definition_of(&self, label: &Label) -> Option<Label<'_>> {
+        // The first definition wins.
+        self.labels().into_iter().find(|definition| {
+            definition.definition
+                && definition.footnote == label.footnote
+                && definition.identifier == label.identifier
+        })
+    }
+
+    /// Get diagnostics.
+    fn diagnostics(&self) -> Vec<Value> {
+        let mut diagnostics = vec![];
+
+        if let Some(error) = &self.error {
+            diagnostics.push(self.error_diagnostic(error));
+        }
+
+        // Only references are checked, on the tree that is kept up to date
+        // by `reparse`: other rules would need parsing the whole document
+        // again on each change.
+        let options = lint::Options {
+            bare_url: Severity::Off,
+            duplicate_heading: Severity::Off,
+            hard_tab: Severity::Off,
+            heading_increment: Severity::Off,
+            image_alt: Severity::Off,
+            list_marker_style: Severity::Off,
+            parse: ParseOptions {
+                constructs: self.options.constructs.clone(),
+                ..ParseOptions::default()
+            },
+            trailing_space: Severity::Off,
+            undefined_reference: Severity::Warning,
+            unused_definition: Severity::Off,
+        };
+
+        if let Some(tree) = &self.tree {
+            for message in lint::lint_tree(&self.value, tree, &options) {
+                diagnostics.push(json!({
+                    "range": {
+                        "start": self.position(message.position.start.offset),
+                        "end": self.position(message.position.end.offset)
+                    },
+                    "severity": if message.severity == Severity::Error { ERROR } else { WARNING },
+                    "source": "markdown",
+                    "code": message.rule.as_str(),
+                    "message": message.reason
+                }));
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Turn a parse error (`line:column: reason`) into a diagnostic.
+    fn error_diagnostic(&self, error: &str) -> Value {
+        let mut parts = error.splitn(3, ':');
+        let line = parts.next().and_then(|d| d.parse::<usize>().ok());
+        let column = parts.next().and_then(|d| d.parse::<usize>().ok());
+        let (offset, message) = match (line, column, parts.next()) {
+            (Some(line), Some(column), Some(message)) => (
+                self.location
+                    .to_offset(line, column)
+                    .unwrap_or(self.value.len()),
+                message.trim(),
+            ),
+            _ => (0, error),
+        };
+        let position = self.position(offset);
+
+        json!({
+            "range": {"start": position, "end": position},
+            "severity": ERROR,
+            "source": "markdown",
+            "message": message
+        })
+    }
+
+    /// Whether footnotes are enabled.
+    fn footnotes(&self) -> bool {
+        self.options.constructs.gfm_label_start_footnote
+    }
+
+    /// Get headings as nested symbols.
+    fn symbols(&self) -> Value {
+        // Stack of open headings: depth and symbol.
+        let mut stack: Vec<(u8, Value)> = vec![];
+        let mut symbols = vec![];
+
+        for node in self.nodes() {
+            if let Node::Heading(heading) = node {
+                let range = self.range(node.position().unwrap());
+                let symbol = json!({
+                    "name": node.to_string(),
+                    "detail": "#".repeat(heading.depth as usize),
+                    "kind": SYMBOL_STRING,
+                    "range": range,
+                    "selectionRange": range,
+                    "children": []
+                });
+
+                while stack
+                    .last()
+                    .map_or(false, |(depth, _)| *depth >= heading.depth)
+                {
+                    close(&mut stack, &mut symbols);
+                }
+
+                stack.push((heading.depth, symbol));
+            }
+        }
+
+        while !stack.is_empty() {
+            close(&mut stack, &mut symbols);
+        }
+
+        Value::Array(symbols)
+    }
+
+    /// Get folding ranges.
+    fn folding_ranges(&self) -> Value {
+        let mut ranges = vec![];
+
+        for node in self.nodes() {
+            if let (Node::List(_) | Node::Code(_) | Node::BlockQuote(_), Some(position)) =
+                (node, node.position())
+            {
+                // Do not include a final line ending.
+                let end = if position.end.column == 1 {
+                    position.end.line - 1
+                } else {
+                    position.end.line
+                };
+
+                if end > position.start.line {
+                    ranges.push(json!({
+                        "startLine": position.start.line - 1,
+                        "endLine": end - 1
+                    }));
+                }
+            }
+        }
+
+        Value::Array(ranges)
+    }
+
+    /// Get the definition of the reference at `offset`.
+    fn definition(&self, uri: &str, offset: usize) -> Value {
+        match self
+            .label_at(offset)
+            .and_then(|label| self.definition_of(&label))
+        {
+            Some(definition) => json!({
+                "uri": uri,
+                "range": self.range(definition.node.position().unwrap())
+            }),
+            None => Value::Null,
+        }
+    }
+
+    /// Get references to the reference or definition at `offset`.
+    fn references(&self, uri: &str, offset: usize, declaration: bool) -> Value {
+        let target = match self.label_at(offset) {
+            Some(target) => target,
+            None => return Value::Null,
+        };
+        let locations = self
+            .labels()
+            .into_iter()
+            .filter(|label| {
+                label.identifier == target.identifier
+                    && label.footnote == target.footnote
+                    && (declaration || !label.definition)
+            })
+            .map(|label| json!({"uri": uri, "range": self.range(label.node.position().unwrap())}))
+            .collect();
+
+        Value::Array(locations)
+    }
+
+    /// Get info on the reference or definition at `offset`.
+    fn hover(&self, offset: usize) -> Value {
+        let label = match self.label_at(offset) {
+            Some(label) => label,
+            None => return Value::Null,
+        };
+        let count = self
+            .labels()
+            .iter()
+            .filter(|d| {
+                !d.definition && d.identifier == label.identifier && d.footnote == label.footnote
+            })
+            .count();
+        let value = if label.definition {
+            format!("{} reference{}", count, if count == 1 { "" } else { "s" })
+        } else {
+            match self.definition_of(&label) {
+                Some(definition) => format!("```markdown\n{}\n```", self.source(definition.node)),
+                None => return Value::Null,
+            }
+        };
+
+        json!({
+            "contents": {"kind": "markdown", "value": value},
+            "range": self.range(label.node.position().unwrap())
+        })
+    }
+
+    /// Get labels to complete at `offset`.
+    fn completion(&self, offset: usize) -> Value {
+        let before = &self.value[..offset];
+        let line = &before[before
+            .rfind(|d| d == '\n' || d == '\r')
+            .map_or(0, |d| d + 1)..];
+        let open = match line.rfind('[') {
+            Some(open) if !line[open..].contains(']') => open,
+            _ => return Value::Array(vec![]),
+        };
+        let footnote = self.footnotes() && line[open + 1..].starts_with('^');
+        let mut seen = vec![];
+        let mut items = vec![];
+
+        for label in self.labels() {
+            if label.definition && label.footnote == footnote && !seen.contains(&label.identifier) {
+                seen.push(label.identifier);
+                let (text, detail) = match label.node {
+                    Node::Definition(x) => (x.label.as_deref(), x.url.clone()),
+                    Node::FootnoteDefinition(x) => (x.label.as_deref(), label.node.to_string()),
+                    _ => unreachable!("expected definition"),
+                };
+                let text = text.unwrap_or(label.identifier);
+                items.push(json!({
+                    "label": text,
+                    "kind": COMPLETION_REFERENCE,
+                    "detail": detail,
+                    "insertText": text
+                }));
+            }
+        }
+
+        Value::Array(items)
+    }
+}
+
+/// Make a `textDocument/publishDiagnostics` notification.
+fn publish_diagnostics(uri: &str, diagnostics: &[Value]) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": {"uri": uri, "diagnostics": diagnostics}
+    })
+}
+
+/// Close the last symbol on `stack`, adding it to its parent or `symbols`.
+fn close(stack: &mut Vec<(u8, Value)>, symbols: &mut Vec<Value>) {
+    let (_, symbol) = stack.pop().unwrap();
+
+    match stack.last_mut() {
+        Some((_, parent)) => parent["children"].as_array_mut().unwrap().push(symbol),
+        None => symbols.push(symbol),
+    }
+}
+
+/// Collect `node` and its descendants, with positions.
+fn collect<'a>(node: &'a Node, nodes: &mut Vec<&'a Node>) {
+    if node.position().is_some() {
+        nodes.push(node);
+    }
+
+    if let Some(children) = node.children() {
+        for child in children {
+            collect(child, nodes);
+        }
+    }
+}
+
+/// Get a reference or definition from a node.
+fn label(node: &Node) -> Option<Label<'_>> {
+    let (identifier, footnote, definition) = match node {
+        Node::LinkReference(x) => (&x.identifier, false, false),
+        Node::ImageReference(x) => (&x.identifier, false, false),
+        Node::Definition(x) => (&x.identifier, false, true),
+        Node::FootnoteReference(x) => (&x.identifier, true, false),
+        Node::FootnoteDefinition(x) => (&x.identifier, true, true),
+        _ => return None,
+    };
+
+    Some(Label {
+        identifier,
+        footnote,
+        definition,
+        node,
+    })
+}
@@ -0,0 +1,237 @@
+//! Command-line interface to `markdown-rs`.
+//!
+//! Reads markdown from files (or stdin) and writes HTML, a syntax tree as
+//! JSON, or markdown to stdout.
+//! Run `markdown --help` for usage.
+
+use markdown::{
+    to_html_with_options, to_markdown_with_options, to_mdast, CompileOptions, Constructs,
+    FormatOptions, LineEnding, Options, ParseOptions,
+};
+use std::io::{self, Read, Write};
+use std::process;
+use std::{env, fs};
+
+/// Usage info.
+const HELP: &str = "Usage: markdown [options] [file...]
+
+Turn markdown into HTML, a syntax tree (mdast) as JSON, or markdown.
+Reads from the given files, or from stdin when no files (or `-`) are given.
+
+Options:
+  -f, --format <format>       output format: `html` (default), `mdast`, or
+                              `markdown`
+      --gfm                   turn on GFM (autolink literals, footnotes,
+                              strikethrough, tables, tasklists, tagfilter)
+      --mdx                   turn on MDX (ESM, expressions, JSX), turning off
+                              HTML, autolinks, and indented code
+      --frontmatter           turn on frontmatter (YAML, TOML)
+      --math                  turn on math (flow, text)
+      --allow-dangerous-html  keep raw HTML in the output
+      --allow-dangerous-protocol
+                              keep dangerous protocols in URLs
+      --line-ending <ending>  line ending to use when the input has none (for
+                              HTML) or always (for markdown): `lf`, `crlf`, or
+                              `cr`
+  -h, --help                  show this help
+  -v, --version               show the version
+";
+
+/// Output format.
+#[derive(Debug, PartialEq)]
+enum Format {
+    Html,
+    Mdast,
+    Markdown,
+}
+
+/// Configuration from arguments.
+#[derive(Debug)]
+struct Config {
+    files: Vec<String>,
+    format: Format,
+    line_ending: Option<LineEnding>,
+    options: Options,
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    if let Err(message) = run(&args) {
+        eprintln!("markdown: {}", message);
+        process::exit(1);
+    }
+}
+
+/// Run with arguments.
+fn run(args: &[String]) -> Result<(), String> {
+    let config = match parse_args(args)? {
+        Some(config) => config,
+        None => return Ok(()),
+    };
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    let files = if config.files.is_empty() {
+        vec!["-".into()]
+    } else {
+        config.files.clone()
+    };
+
+    for file in &files {
+        let value = read(file)?;
+        let result =
+            convert(&value, &config).map_err(|message| format!("{}: {}", file, message))?;
+        stdout
+            .write_all(result.as_bytes())
+            .map_err(|error| format!("Cannot write output: {}", error))?;
+    }
+
+    Ok(())
+}
+
+/// Turn arguments into configuration.
+///
+/// Returns `None` when help or the version were shown.
+fn parse_args(args: &[String]) -> Result<Option<Config>, String> {
+    let mut constructs = Constructs::default();
+    let mut compile = CompileOptions::default();
+    let mut format = Format::Html;
+    let mut line_ending = None;
+    let mut files = vec![];
+    let mut index = 0;
+
+    while index < args.len() {
+        let arg = args[index].as_str();
+        index += 1;
+
+        match arg {
+            "-h" | "--help" => {
+                print!("{}", HELP);
+                return Ok(None);
+            }
+            "-v" | "--version" => {
+                println!("markdown {}", env!("CARGO_PKG_VERSION"));
+                return Ok(None);
+            }
+            "--gfm" => {
+                constructs.gfm_autolink_literal = true;
+                constructs.gfm_footnote_definition = true;
+                constructs.gfm_label_start_footnote = true;
+                constructs.gfm_strikethrough = true;
+                constructs.gfm_table = true;
+                constructs.gfm_task_list_item = true;
+                compile.gfm_tagfilter = true;
+            }
+            "--mdx" => {
+                constructs.autolink = false;
+                constructs.code_indented = false;
+                constructs.html_flow = false;
+                constructs.html_text = false;
+                constructs.mdx_esm = true;
+                constructs.mdx_expression_flow = true;
+                constructs.mdx_expression_text = true;
+                constructs.mdx_jsx_flow = true;
+                constructs.mdx_jsx_text = true;
+            }
+            "--frontmatter" => constructs.frontmatter = true,
+            "--math" => {
+                constructs.math_flow = true;
+                constructs.math_text = true;
+            }
+            "--allow-dangerous-html" => compile.allow_dangerous_html = true,
+            "--allow-dangerous-protocol" => compile.allow_dangerous_protocol = true,
+            "-f" | "--format" | "--line-ending" => {
+                let value = args
+                    .get(index)
+                    .ok_or_else(|| format!("Missing value for `{}`", arg))?;
+                index += 1;
+
+                if arg == "--line-ending" {
+                    line_ending = Some(match value.as_str() {
+                        "lf" => LineEnding::LineFeed,
+                        "crlf" => LineEnding::CarriageReturnLineFeed,
+                        "cr" => LineEnding::CarriageReturn,
+                        _ => {
+                            return Err(format!(
+                                "Unknown line ending `{}`, expected `lf`, `crlf`, or `cr`",
+                                value
+                            ))
+                        }
+                    });
+                } else {
+                    format = match value.as_str() {
+                        "html" => Format::Html,
+                        "mdast" => Format::Mdast,
+                        "markdown" => Format::Markdown,
+                        _ => {
+                            return Err(format!(
+                                "Unknown format `{}`, expected `html`, `mdast`, or `markdown`",
+                                value
+                            ))
+                        }
+                    };
+                }
+            }
+            "-" => files.push(arg.into()),
+            _ if arg.starts_with('-') => {
+                return Err(format!("Unknown option `{}`, see `--help`", arg));
+            }
+            _ => files.push(arg.into()),
+        }
+    }
+
+    if let Some(line_ending) = &line_ending {
+        compile.default_line_ending = line_ending.clone();
+    }
+
+    Ok(Some(Config {
+        files,
+        format,
+        line_ending,
+        options: Options {
+            parse: ParseOptions {
+                constructs,
+                ..ParseOptions::default()
+            },
+            compile,
+        },
+    }))
+}
+
+/// Read a file, or stdin for `-`.
+fn read(file: &str) -> Result<String, String> {
+    if file == "-" {
+        let mut value = String::new();
+        io::stdin()
+            .read_to_string(&mut value)
+            .map_err(|error| format!("Cannot read stdin: {}", error))?;
+        Ok(value)
+    } else {
+        fs::read_to_string(file).map_err(|error| format!("Cannot read `{}`: {}", file, error))
+    }
+}
+
+/// Turn markdown into the configured format.
+fn convert(value: &str, config: &Config) -> Result<String, String> {
+    match config.format {
+        Format::Html => to_html_with_options(value, &config.options),
+        Format::Mdast => {
+            let tree = to_mdast(value, &config.options.parse)?;
+            let mut json = serde_json::to_string_pretty(&tree)
+                .map_err(|error| format!("Cannot serialize tree: {}", error))?;
+            json.push('\n');
+            Ok(json)
+        }
+        Format::Markdown => {
+            let tree = to_mdast(value, &config.options.parse)?;
+
+            Ok(to_markdown_with_options(
+                &tree,
+                &FormatOptions {
+                    line_ending: config.line_ending.clone(),
+                    ..FormatOptions::default()
+                },
+            ))
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use crate::util::{
+    html_handler::HtmlHandler,
     line_ending::LineEnding,
     mdx::{EsmParse as MdxEsmParse, ExpressionParse as MdxExpressionParse},
+    sanitize_html::SanitizeSchema,
 };
-use alloc::{boxed::Box, fmt, string::String};
+use alloc::{boxed::Box, fmt, string::String, sync::Arc, vec::Vec};
 
 /// Control which constructs are enabled.
 ///
@@ -434,6 +436,147 @@ impl Constructs {
     }
 }
 
+/// Limits on what is parsed, for input that is not trusted.
+///
+/// Each limit is `None` (the default) to not limit that, or `Some` with the
+/// largest value that is allowed.
+///
+/// Exceeding `size`, `definitions`, or `events` is an error.
+/// Exceeding `container_depth` or `inline_depth` is not: the syntax that
+/// would nest too deep is not parsed as a construct, but as text.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{to_html_with_options, Limits, Options, ParseOptions};
+/// # fn main() -> Result<(), String> {
+///
+/// let options = Options {
+///     parse: ParseOptions {
+///         limits: Limits {
+///             size: Some(1024),
+///             container_depth: Some(2),
+///             ..Limits::default()
+///         },
+///         ..ParseOptions::default()
+///     },
+///     ..Options::default()
+/// };
+///
+/// assert_eq!(
+///     to_html_with_options("> > > a", &options)?,
+///     "<blockquote>\n<blockquote>\n<p>&gt; a</p>\n</blockquote>\n</blockquote>"
+/// );
+///
+/// assert_eq!(
+///     to_html_with_options(&"a".repeat(1025), &options).unwrap_err(),
+///     "1:1: Unexpected document of 1025 bytes, expected at most 1024 bytes (limit:size)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Limits {
+    /// Size of the document, in bytes.
+    ///
+    /// Checked before parsing.
+    pub size: Option<usize>,
+    /// Depth of containers (block quotes, list items, and GFM footnote
+    /// definitions) in each other.
+    ///
+    /// ```markdown
+    /// > | > - > a
+    ///           ^ depth of 3
+    /// ```
+    pub container_depth: Option<usize>,
+    /// Depth of attention (emphasis, strong, GFM strikethrough) in each
+    /// other, and of labels (of links, images, and GFM footnote calls) in
+    /// each other.
+    ///
+    /// Attention and labels are counted separately.
+    /// Only attention and labels that form count.
+    ///
+    /// ```markdown
+    /// > | *a **b** c* [d ![e](f)](g)
+    ///          ^ depth of 2 ^ depth of 2
+    /// ```
+    pub inline_depth: Option<usize>,
+    /// Number of definitions.
+    ///
+    /// Checked when each definition is made.
+    pub definitions: Option<usize>,
+    /// Number of events made while parsing.
+    ///
+    /// Events are the smallest units of syntax the parser makes (such as the
+    /// start or end of a heading, or of its markers).
+    /// A document typically has several events per word.
+    /// Checked as events are made, which includes events that are dropped
+    /// later (such as when something turns out not to be a construct), so
+    /// that this limits both the time and memory parsing takes.
+    pub events: Option<usize>,
+}
+
+/// Kind of URL passed to [`UrlRewrite`][].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UrlKind {
+    /// Destination of a link (resource).
+    ///
+    /// ```markdown
+    /// > | [a](b)
+    ///         ^
+    /// ```
+    Link,
+    /// Destination of an image (resource).
+    ///
+    /// ```markdown
+    /// > | ![a](b)
+    ///          ^
+    /// ```
+    Image,
+    /// Destination of a definition, which is used by link and image
+    /// references.
+    ///
+    /// ```markdown
+    /// > | [a]: b
+    ///          ^
+    /// ```
+    Definition,
+    /// Autolink or GFM autolink literal.
+    ///
+    /// The URL includes the protocol that is added for emails (`mailto:`) and
+    /// for GFM `www.` literals (`http://`).
+    ///
+    /// ```markdown
+    /// > | <https://a> b@c.d
+    ///      ^^^^^^^^^  ^^^^^
+    /// ```
+    Autolink,
+}
+
+/// Signature of a function that rewrites URLs.
+///
+/// Gets the URL, not yet sanitized, and what kind of URL it is.
+/// Return `Some` with a new URL, or `None` to keep the URL as it is.
+pub type UrlRewrite = dyn Fn(&str, &UrlKind) -> Option<String> + Send + Sync;
+
+/// Signature of a function that is called while parsing to check whether to
+/// stop.
+///
+/// Return `true` to stop parsing.
+pub type ShouldAbort = dyn Fn() -> bool;
+
+/// Signature of a function that renders code (flow).
+///
+/// Gets the language (the first word of the info string, such as `rust` in
+/// ` ```rust title="x" `), the meta (the rest of the info string, such as
+/// `title="x"`), and the code.
+/// None of these are encoded.
+/// Code (indented) has no language and no meta.
+///
+/// Return `Some` with HTML to use instead of the default
+/// `<pre><code>` element, or `None` to use the default.
+pub type CodeBlockRender = dyn Fn(Option<&str>, Option<&str>, &str) -> Option<String> + Send + Sync;
+
 /// Configuration that describes how to compile to HTML.
 ///
 /// You likely either want to turn on the dangerous options
@@ -466,8 +609,9 @@ impl Constructs {
 /// # }
 /// ```
 #[allow(clippy::struct_excessive_bools)]
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Default)]
 pub struct CompileOptions {
+    // Note: when adding fields, don’t forget to add them to `fmt::Debug` below.
     /// Whether to allow (dangerous) HTML.
     ///
     /// The default is `false`, which still parses the HTML according to
@@ -550,6 +694,60 @@ pub struct CompileOptions {
     /// ```
     pub allow_dangerous_protocol: bool,
 
+    /// Function to render code (flow) with.
+    ///
+    /// This function can be used to plug in a syntax highlighter, or to turn
+    /// certain languages (such as diagrams) into something else.
+    /// It is called for code (fenced) and code (indented), but not for math
+    /// (flow).
+    /// See [`CodeBlockRender`][] for what it gets.
+    ///
+    /// The default is `None`, which outputs `<pre><code>` elements, with a
+    /// `language-*` class if there is a language.
+    /// When the function returns `None` for a certain code block, the default
+    /// is used for it.
+    ///
+    /// > 👉 **Note**: the result of the function is not sanitized.
+    /// > Make sure to encode the code yourself.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{to_html_with_options, CompileOptions, Options};
+    /// use std::sync::Arc;
+    /// # fn main() -> Result<(), String> {
+    ///
+    /// // `markdown-rs` outputs code with a class by default:
+    /// assert_eq!(
+    ///     to_html_with_options("```mermaid\ngraph TD;\n```", &Options::default())?,
+    ///     "<pre><code class=\"language-mermaid\">graph TD;\n</code></pre>"
+    /// );
+    ///
+    /// // Pass `code_block_render` to render some code yourself:
+    /// assert_eq!(
+    ///     to_html_with_options(
+    ///         "```mermaid\ngraph TD;\n```",
+    ///         &Options {
+    ///             compile: CompileOptions {
+    ///               code_block_render: Some(Arc::new(|lang, _meta, code| {
+    ///                   if lang == Some("mermaid") {
+    ///                       Some(format!("<div class=\"mermaid\">{}</div>", code))
+    ///                   } else {
+    ///                       None
+    ///                   }
+    ///               })),
+    ///               ..CompileOptions::default()
+    ///             },
+    ///             ..Options::default()
+    ///         }
+    ///     )?,
+    ///     "<div class=\"mermaid\">graph TD;</div>"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub code_block_render: Option<Arc<CodeBlockRender>>,
+
     /// Default line ending to use when compiling to HTML, for line endings not
     /// in `value`.
     ///
@@ -918,6 +1116,409 @@ pub struct CompileOptions {
     /// *   [*§ 6.1 Disallowed Raw HTML (extension)* in GFM](https://github.github.com/gfm/#disallowed-raw-html-extension-)
     /// *   [`cmark-gfm#extensions/tagfilter.c`](https://github.com/github/cmark-gfm/blob/master/extensions/tagfilter.c)
     pub gfm_tagfilter: bool,
+
+    /// Handler to customize how certain nodes are compiled to HTML.
+    ///
+    /// This can be used to, say, add `rel` and `target` attributes to
+    /// external links, `loading` attributes to images, anchors to headings,
+    /// or wrap tables in a `<div>`.
+    /// See [`HtmlHandler`][] and [`HtmlNode`][crate::HtmlNode] for which nodes
+    /// can be customized.
+    ///
+    /// The default is `None`, which uses the default HTML for all nodes.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{to_html_with_options, CompileOptions, HtmlHandler, HtmlNode, Options};
+    /// use std::sync::Arc;
+    /// # fn main() -> Result<(), String> {
+    ///
+    /// struct External;
+    ///
+    /// impl HtmlHandler for External {
+    ///     fn enter(&self, node: &HtmlNode, _children: &str) -> Option<String> {
+    ///         match node {
+    ///             HtmlNode::Link { url, .. } if url.starts_with("https://") => Some(format!(
+    ///                 "<a href=\"{}\" rel=\"noopener\" target=\"_blank\">",
+    ///                 url
+    ///             )),
+    ///             _ => None,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// // `markdown-rs` outputs plain links by default:
+    /// assert_eq!(
+    ///     to_html_with_options("[a](https://b)", &Options::default())?,
+    ///     "<p><a href=\"https://b\">a</a></p>"
+    /// );
+    ///
+    /// // Pass `html_handler` to customize them:
+    /// assert_eq!(
+    ///     to_html_with_options(
+    ///         "[a](https://b)",
+    ///         &Options {
+    ///             compile: CompileOptions {
+    ///               html_handler: Some(Arc::new(External)),
+    ///               ..CompileOptions::default()
+    ///             },
+    ///             ..Options::default()
+    ///         }
+    ///     )?,
+    ///     "<p><a href=\"https://b\" rel=\"noopener\" target=\"_blank\">a</a></p>"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub html_handler: Option<Arc<dyn HtmlHandler + Send + Sync>>,
+
+    /// Whether to sanitize HTML (flow) and HTML (text), with an allowlist of
+    /// elements, attributes, and protocols.
+    ///
+    /// The default is `None`, which passes HTML through untouched when
+    /// `allow_dangerous_html` is on.
+    /// Pass a [`SanitizeSchema`][] to only keep the allowed elements and
+    /// attributes: other tags, comments, event handlers, and dangerous URLs
+    /// (such as `javascript:`) are dropped, and open elements are closed when
+    /// a paragraph, heading, table cell, or container ends.
+    /// [`SanitizeSchema::default()`][SanitizeSchema::default] is the schema
+    /// GitHub uses.
+    ///
+    /// This option only has an effect when `allow_dangerous_html` is on.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{to_html_with_options, CompileOptions, Options, SanitizeSchema};
+    /// # fn main() -> Result<(), String> {
+    ///
+    /// // With `allow_dangerous_html`, `markdown-rs` passes HTML through untouched:
+    /// assert_eq!(
+    ///     to_html_with_options(
+    ///         "<b onclick=\"alert(1)\">a",
+    ///         &Options {
+    ///             compile: CompileOptions {
+    ///               allow_dangerous_html: true,
+    ///               ..CompileOptions::default()
+    ///             },
+    ///             ..Options::default()
+    ///         }
+    ///     )?,
+    ///     "<p><b onclick=\"alert(1)\">a</p>"
+    /// );
+    ///
+    /// // Pass `html_sanitize` to make it safe:
+    /// assert_eq!(
+    ///     to_html_with_options(
+    ///         "<b onclick=\"alert(1)\">a",
+    ///         &Options {
+    ///             compile: CompileOptions {
+    ///               allow_dangerous_html: true,
+    ///               html_sanitize: Some(SanitizeSchema::default()),
+    ///               ..CompileOptions::default()
+    ///             },
+    ///             ..Options::default()
+    ///         }
+    ///     )?,
+    ///     "<p><b>a</b></p>"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## References
+    ///
+    /// *   [`hast-util-sanitize`](https://github.com/syntax-tree/hast-util-sanitize)
+    pub html_sanitize: Option<SanitizeSchema>,
+
+    /// Whether to turn math into MathML.
+    ///
+    /// The default is `false`, which outputs math (flow) and math (text) as
+    /// `<code>` elements with `language-math` classes, and leaves rendering
+    /// to client-side JavaScript (such as KaTeX or MathJax).
+    /// Pass `true` to turn the LaTeX into MathML instead, which browsers,
+    /// email clients, and e-readers can show without JavaScript.
+    /// The original LaTeX is kept in an `<annotation>`.
+    ///
+    /// Only a subset of LaTeX is supported: identifiers, numbers, operators,
+    /// groups, sub- and superscripts, fractions, roots, common symbols,
+    /// fonts, accents, spacing, `\left` and `\right`, and matrix-like
+    /// environments.
+    /// Unsupported commands are shown as text.
+    ///
+    /// This option has no effect without the `mathml` feature.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{to_html_with_options, CompileOptions, Constructs, Options, ParseOptions};
+    /// # fn main() -> Result<(), String> {
+    ///
+    /// // `markdown-rs` outputs math as code by default:
+    /// assert_eq!(
+    ///     to_html_with_options(
+    ///         "$x^2$",
+    ///         &Options {
+    ///             parse: ParseOptions {
+    ///               constructs: Constructs {
+    ///                 math_text: true,
+    ///                 ..Constructs::default()
+    ///               },
+    ///               ..ParseOptions::default()
+    ///             },
+    ///             ..Options::default()
+    ///         }
+    ///     )?,
+    ///     "<p><code class=\"language-math math-inline\">x^2</code></p>"
+    /// );
+    ///
+    /// // Pass `math_mathml: true` to get MathML (with the `mathml` feature):
+    /// #[cfg(feature = "mathml")]
+    /// assert_eq!(
+    ///     to_html_with_options(
+    ///         "$x^2$",
+    ///         &Options {
+    ///             parse: ParseOptions {
+    ///               constructs: Constructs {
+    ///                 math_text: true,
+    ///                 ..Constructs::default()
+    ///               },
+    ///               ..ParseOptions::default()
+    ///             },
+    ///             compile: CompileOptions {
+    ///               math_mathml: true,
+    ///               ..CompileOptions::default()
+    ///             }
+    ///         }
+    ///     )?,
+    ///     "<p><math><semantics><mrow><msup><mi>x</mi><mn>2</mn></msup></mrow><annotation encoding=\"application/x-tex\">x^2</annotation></semantics></math></p>"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub math_mathml: bool,
+
+    /// Protocols to allow in URLs of links (`a[href]`).
+    ///
+    /// The default is `None`, which allows `http`, `https`, `irc`, `ircs`,
+    /// `mailto`, and `xmpp`, similar to GitHub.
+    /// Pass a list of protocols (without `:`, compared case-insensitively) to
+    /// allow those instead.
+    /// A value with a colon is used as a prefix instead, so
+    /// `data:image/png` allows `data:image/png;base64,…` but no other `data:`
+    /// URLs.
+    /// URLs without protocol (relative URLs) are always allowed.
+    ///
+    /// This option does nothing if `allow_dangerous_protocol` is turned on.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{to_html_with_options, CompileOptions, Options};
+    /// # fn main() -> Result<(), String> {
+    ///
+    /// // `markdown-rs` is safe by default:
+    /// assert_eq!(
+    ///     to_html_with_options("[a](tel:123)", &Options::default())?,
+    ///     "<p><a href=\"\">a</a></p>"
+    /// );
+    ///
+    /// // Pass `safe_protocol_href` to allow certain other protocols:
+    /// assert_eq!(
+    ///     to_html_with_options(
+    ///         "[a](tel:123)",
+    ///         &Options {
+    ///             compile: CompileOptions {
+    ///               safe_protocol_href: Some(vec!["https".into(), "tel".into()]),
+    ///               ..CompileOptions::default()
+    ///             },
+    ///             ..Options::default()
+    ///         }
+    ///     )?,
+    ///     "<p><a href=\"tel:123\">a</a></p>"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub safe_protocol_href: Option<Vec<String>>,
+
+    /// Protocols to allow in URLs of images (`img[src]`).
+    ///
+    /// The default is `None`, which allows `http` and `https`, similar to
+    /// GitHub.
+    /// Works like [`safe_protocol_href`][CompileOptions::safe_protocol_href]
+    /// otherwise.
+    ///
+    /// This option does nothing if `allow_dangerous_protocol` is turned on.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{to_html_with_options, CompileOptions, Options};
+    /// # fn main() -> Result<(), String> {
+    ///
+    /// // `markdown-rs` is safe by default:
+    /// assert_eq!(
+    ///     to_html_with_options("![a](data:image/png;base64,b)", &Options::default())?,
+    ///     "<p><img src=\"\" alt=\"a\" /></p>"
+    /// );
+    ///
+    /// // Pass `safe_protocol_src` to allow certain other protocols:
+    /// assert_eq!(
+    ///     to_html_with_options(
+    ///         "![a](data:image/png;base64,b)",
+    ///         &Options {
+    ///             compile: CompileOptions {
+    ///               safe_protocol_src: Some(vec!["https".into(), "data:image/png".into()]),
+    ///               ..CompileOptions::default()
+    ///             },
+    ///             ..Options::default()
+    ///         }
+    ///     )?,
+    ///     "<p><img src=\"data:image/png;base64,b\" alt=\"a\" /></p>"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub safe_protocol_src: Option<Vec<String>>,
+
+    /// Whether to add where elements come from in the markdown.
+    ///
+    /// The default is `false`.
+    /// Pass `true` to add `data-sourcepos` attributes to elements for
+    /// blocks (such as paragraphs, headings, list items, and table cells),
+    /// like `cmark` with `--sourcepos`.
+    /// The value is `start-end`, where each is `line:column` (1-indexed),
+    /// and the end is the last character of the block.
+    /// This is useful to sync the scroll position of an editor with a
+    /// preview.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{to_html_with_options, CompileOptions, Options};
+    /// # fn main() -> Result<(), String> {
+    ///
+    /// // `markdown-rs` does not add positions by default:
+    /// assert_eq!(
+    ///     to_html_with_options("# a\n\n- b", &Options::default())?,
+    ///     "<h1>a</h1>\n<ul>\n<li>b</li>\n</ul>"
+    /// );
+    ///
+    /// // Pass `sourcepos: true` to add them:
+    /// assert_eq!(
+    ///     to_html_with_options(
+    ///         "# a\n\n- b",
+    ///         &Options {
+    ///             compile: CompileOptions {
+    ///               sourcepos: true,
+    ///               ..CompileOptions::default()
+    ///             },
+    ///             ..Options::default()
+    ///         }
+    ///     )?,
+    ///     "<h1 data-sourcepos=\"1:1-1:3\">a</h1>\n<ul data-sourcepos=\"3:1-3:3\">\n<li data-sourcepos=\"3:1-3:3\">b</li>\n</ul>"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub sourcepos: bool,
+
+    /// Function to rewrite URLs with.
+    ///
+    /// This function can be used to, say, make relative URLs absolute, turn
+    /// links to `.md` files into links to `.html` files, or route images
+    /// through a CDN.
+    /// It gets the destinations of links, images, definitions, autolinks, and
+    /// GFM autolink literals, before they are sanitized.
+    /// See [`UrlRewrite`][] for what it gets.
+    ///
+    /// The default is `None`, which keeps URLs as they are.
+    ///
+    /// To rewrite URLs in mdast, see
+    /// [`Node::rewrite_urls`][crate::mdast::Node::rewrite_urls].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{to_html_with_options, CompileOptions, Options};
+    /// use std::sync::Arc;
+    /// # fn main() -> Result<(), String> {
+    ///
+    /// // `markdown-rs` keeps URLs as they are by default:
+    /// assert_eq!(
+    ///     to_html_with_options("[a](b.md)", &Options::default())?,
+    ///     "<p><a href=\"b.md\">a</a></p>"
+    /// );
+    ///
+    /// // Pass `url_rewrite` to change them:
+    /// assert_eq!(
+    ///     to_html_with_options(
+    ///         "[a](b.md)",
+    ///         &Options {
+    ///             compile: CompileOptions {
+    ///               url_rewrite: Some(Arc::new(|url, _kind| {
+    ///                   url.strip_suffix(".md").map(|stem| format!("{}.html", stem))
+    ///               })),
+    ///               ..CompileOptions::default()
+    ///             },
+    ///             ..Options::default()
+    ///         }
+    ///     )?,
+    ///     "<p><a href=\"b.html\">a</a></p>"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub url_rewrite: Option<Arc<UrlRewrite>>,
+    // Note: when adding fields, don’t forget to add them to `fmt::Debug` below.
+}
+
+impl fmt::Debug for CompileOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompileOptions")
+            .field("allow_dangerous_html", &self.allow_dangerous_html)
+            .field("allow_dangerous_protocol", &self.allow_dangerous_protocol)
+            .field(
+                "code_block_render",
+                &self.code_block_render.as_ref().map(|_d| "[Function]"),
+            )
+            .field("default_line_ending", &self.default_line_ending)
+            .field("gfm_footnote_label", &self.gfm_footnote_label)
+            .field(
+                "gfm_footnote_label_tag_name",
+                &self.gfm_footnote_label_tag_name,
+            )
+            .field(
+                "gfm_footnote_label_attributes",
+                &self.gfm_footnote_label_attributes,
+            )
+            .field("gfm_footnote_back_label", &self.gfm_footnote_back_label)
+            .field(
+                "gfm_footnote_clobber_prefix",
+                &self.gfm_footnote_clobber_prefix,
+            )
+            .field(
+                "gfm_task_list_item_checkable",
+                &self.gfm_task_list_item_checkable,
+            )
+            .field("gfm_tagfilter", &self.gfm_tagfilter)
+            .field(
+                "html_handler",
+                &self.html_handler.as_ref().map(|_d| "[Handler]"),
+            )
+            .field("html_sanitize", &self.html_sanitize)
+            .field("math_mathml", &self.math_mathml)
+            .field("safe_protocol_href", &self.safe_protocol_href)
+            .field("safe_protocol_src", &self.safe_protocol_src)
+            .field("sourcepos", &self.sourcepos)
+            .field(
+                "url_rewrite",
+                &self.url_rewrite.as_ref().map(|_d| "[Function]"),
+            )
+            .finish()
+    }
 }
 
 impl CompileOptions {
@@ -938,6 +1539,35 @@ impl CompileOptions {
     }
 }
 
+/// How columns and offsets in positions are counted.
+///
+/// Used in [`ParseOptions`][].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PositionEncoding {
+    /// Bytes (UTF-8 code units).
+    ///
+    /// This is the default.
+    /// Columns are virtual: tabs count to the next tab stop (of size 4),
+    /// like how markdown sees them.
+    Byte,
+    /// UTF-16 code units, as used by the language server protocol, VS Code,
+    /// Monaco, `CodeMirror`, and JavaScript.
+    ///
+    /// Tabs count as one.
+    Utf16,
+    /// Unicode code points (UTF-32 code units), as used by Python.
+    ///
+    /// Tabs count as one.
+    CodePoint,
+}
+
+impl Default for PositionEncoding {
+    /// Bytes.
+    fn default() -> Self {
+        PositionEncoding::Byte
+    }
+}
+
 /// Configuration that describes how to parse from markdown.
 ///
 /// You can use this:
@@ -1140,6 +1770,104 @@ pub struct ParseOptions {
     /// For an example that adds support for JavaScript with SWC, see
     /// `tests/test_utils/mod.rs`.
     pub mdx_esm_parse: Option<Box<MdxEsmParse>>,
+
+    /// How to count columns and offsets in positions.
+    ///
+    /// This affects the positions of nodes made by
+    /// [`to_mdast()`][crate::to_mdast()] and
+    /// [`to_mdast_inline()`][crate::to_mdast_inline()], and the places
+    /// (`line:column`) in error messages.
+    /// Other APIs, such as [`reparse()`][crate::reparse()] and
+    /// [`Parser`][crate::Parser], which need byte offsets to work, keep
+    /// using bytes.
+    ///
+    /// The default is [`PositionEncoding::Byte`][].
+    /// Pass [`PositionEncoding::Utf16`][] to get positions that can be used
+    /// in editors (such as with the language server protocol), or
+    /// [`PositionEncoding::CodePoint`][] to get positions that can be used
+    /// in Python.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{to_mdast, ParseOptions, PositionEncoding};
+    /// # fn main() -> Result<(), String> {
+    ///
+    /// // `markdown-rs` counts bytes by default:
+    /// let tree = to_mdast("😀 *a*", &ParseOptions::default())?;
+    /// let emphasis = &tree.children().unwrap()[0].children().unwrap()[1];
+    /// assert_eq!(emphasis.position().unwrap().start.column, 6);
+    ///
+    /// // Pass `position_encoding` to count something else:
+    /// let tree = to_mdast(
+    ///     "😀 *a*",
+    ///     &ParseOptions {
+    ///         position_encoding: PositionEncoding::Utf16,
+    ///         ..ParseOptions::default()
+    ///     },
+    /// )?;
+    /// let emphasis = &tree.children().unwrap()[0].children().unwrap()[1];
+    /// assert_eq!(emphasis.position().unwrap().start.column, 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub position_encoding: PositionEncoding,
+
+    /// Limits on what is parsed.
+    ///
+    /// The default is to not limit anything.
+    /// Pass [`Limits`][] when parsing input you do not trust, so that
+    /// documents that are very large or nest very deep do not take too much
+    /// time or memory.
+    pub limits: Limits,
+
+    /// Function to check whether to stop parsing.
+    ///
+    /// The parser calls this function every so often (once every 1024
+    /// steps of its state machine).
+    /// When it returns `true`, parsing stops with an error that ends in
+    /// `(aborted)`.
+    /// This can be used to cancel parsing, or to give it a time budget, such
+    /// as when handling requests with deadlines.
+    ///
+    /// The default is `None`, to never stop.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{to_mdast, ParseOptions};
+    /// # fn main() {
+    ///
+    /// let options = ParseOptions {
+    ///     should_abort: Some(Box::new(|| true)),
+    ///     ..ParseOptions::default()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     to_mdast(&"a ".repeat(1000), &options).unwrap_err(),
+    ///     "1:1: Unexpected abort, expected `should_abort` to return `false` (aborted)"
+    /// );
+    /// # }
+    /// ```
+    ///
+    /// With `std`, to stop after a time budget:
+    ///
+    /// ```
+    /// use markdown::{to_mdast, ParseOptions};
+    /// use std::time::{Duration, Instant};
+    /// # fn main() -> Result<(), String> {
+    ///
+    /// let deadline = Instant::now() + Duration::from_millis(100);
+    /// let options = ParseOptions {
+    ///     should_abort: Some(Box::new(move || Instant::now() > deadline)),
+    ///     ..ParseOptions::default()
+    /// };
+    ///
+    /// to_mdast("# Hi!", &options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub should_abort: Option<Box<ShouldAbort>>,
     // Note: when adding fields, don’t forget to add them to `fmt::Debug` below.
 }
 
@@ -1160,6 +1888,12 @@ impl fmt::Debug for ParseOptions {
                 "mdx_esm_parse",
                 &self.mdx_esm_parse.as_ref().map(|_d| "[Function]"),
             )
+            .field("position_encoding", &self.position_encoding)
+            .field("limits", &self.limits)
+            .field(
+                "should_abort",
+                &self.should_abort.as_ref().map(|_d| "[Function]"),
+            )
             .finish()
     }
 }
@@ -1173,6 +1907,9 @@ impl Default for ParseOptions {
             math_text_single_dollar: true,
             mdx_expression_parse: None,
             mdx_esm_parse: None,
+            position_encoding: PositionEncoding::default(),
+            limits: Limits::default(),
+            should_abort: None,
         }
     }
 }
@@ -1266,12 +2003,343 @@ impl Options {
     }
 }
 
+/// Configuration that describes how to format markdown.
+///
+/// In most cases, you will want to use the default trait, optionally with
+/// the `parse` field set to the constructs you use.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{FormatOptions, ParseOptions};
+/// # fn main() {
+///
+/// // Use the default trait to format `CommonMark`:
+/// let commonmark = FormatOptions::default();
+///
+/// // Set `parse` to format GFM:
+/// let gfm = FormatOptions {
+///     parse: ParseOptions::gfm(),
+///     ..FormatOptions::default()
+/// };
+/// # }
+/// ```
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug)]
+pub struct FormatOptions {
+    /// Whether to pad table cells so that columns line up.
+    ///
+    /// The default is `true`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{format, FormatOptions, ParseOptions};
+    ///
+    /// let gfm = FormatOptions {
+    ///     parse: ParseOptions::gfm(),
+    ///     ..FormatOptions::default()
+    /// };
+    ///
+    /// // `markdown-rs` aligns tables by default:
+    /// assert_eq!(
+    ///     format("| a | b |\n| - | :- |\n| cde | f |", &gfm),
+    ///     "| a   | b   |\n| --- | :-- |\n| cde | f   |\n"
+    /// );
+    ///
+    /// // Pass `align_tables: false` to turn that off:
+    /// assert_eq!(
+    ///     format(
+    ///         "| a | b |\n| - | :- |\n| cde | f |",
+    ///         &FormatOptions {
+    ///             align_tables: false,
+    ///             ..gfm
+    ///         }
+    ///     ),
+    ///     "| a | b |\n| --- | :-- |\n| cde | f |\n"
+    /// );
+    /// ```
+    pub align_tables: bool,
+
+    /// Marker to use for unordered list items.
+    ///
+    /// Can be `-`, `*`, or `+`.
+    /// Adjacent lists alternate between this marker and another one, as they
+    /// would otherwise join together.
+    ///
+    /// The default is `-`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{format, FormatOptions};
+    ///
+    /// // `markdown-rs` uses `-` by default:
+    /// assert_eq!(format("* a", &FormatOptions::default()), "- a\n");
+    ///
+    /// // Pass `bullet` to use something else:
+    /// assert_eq!(
+    ///     format(
+    ///         "* a",
+    ///         &FormatOptions {
+    ///             bullet: '+',
+    ///             ..FormatOptions::default()
+    ///         }
+    ///     ),
+    ///     "+ a\n"
+    /// );
+    /// ```
+    pub bullet: char,
+
+    /// Marker to use after the number of ordered list items.
+    ///
+    /// Can be `.` or `)`.
+    /// Adjacent lists alternate between the two, as they would otherwise
+    /// join together.
+    ///
+    /// The default is `.`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{format, FormatOptions};
+    ///
+    /// // `markdown-rs` uses `.` by default:
+    /// assert_eq!(format("1) a", &FormatOptions::default()), "1. a\n");
+    ///
+    /// // Pass `bullet_ordered` to use `)`:
+    /// assert_eq!(
+    ///     format(
+    ///         "1. a",
+    ///         &FormatOptions {
+    ///             bullet_ordered: ')',
+    ///             ..FormatOptions::default()
+    ///         }
+    ///     ),
+    ///     "1) a\n"
+    /// );
+    /// ```
+    pub bullet_ordered: char,
+
+    /// Marker to use for emphasis.
+    ///
+    /// Can be `*` or `_`.
+    /// Emphasis that directly contains emphasis alternates between the two,
+    /// as it would otherwise turn into strong.
+    /// Intraword emphasis always uses `*`, as `_` does not work there.
+    ///
+    /// The default is `*`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{format, FormatOptions};
+    ///
+    /// // `markdown-rs` uses `*` by default:
+    /// assert_eq!(format("_a_", &FormatOptions::default()), "*a*\n");
+    ///
+    /// // Pass `emphasis: '_'` to use underscores:
+    /// assert_eq!(
+    ///     format(
+    ///         "*a* b*c*",
+    ///         &FormatOptions {
+    ///             emphasis: '_',
+    ///             ..FormatOptions::default()
+    ///         }
+    ///     ),
+    ///     "_a_ b*c*\n"
+    /// );
+    /// ```
+    pub emphasis: char,
+
+    /// Line ending to use.
+    ///
+    /// All line endings are turned into this one, including those in text,
+    /// code, and HTML.
+    ///
+    /// The default is `None`, which keeps line endings in the tree as they
+    /// are, and uses `\n` for new ones.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{format, FormatOptions, LineEnding};
+    ///
+    /// // `markdown-rs` keeps line endings in text by default:
+    /// assert_eq!(
+    ///     format("* a\r\n* b\r\n\r\nc\r\nd", &FormatOptions::default()),
+    ///     "- a\n- b\n\nc\r\nd\n"
+    /// );
+    ///
+    /// // Pass `line_ending` to use one line ending:
+    /// assert_eq!(
+    ///     format(
+    ///         "* a\r\n* b\r\n\r\nc\r\nd",
+    ///         &FormatOptions {
+    ///             line_ending: Some(LineEnding::CarriageReturnLineFeed),
+    ///             ..FormatOptions::default()
+    ///         }
+    ///     ),
+    ///     "- a\r\n- b\r\n\r\nc\r\nd\r\n"
+    /// );
+    /// ```
+    pub line_ending: Option<LineEnding>,
+
+    /// Width to wrap paragraphs at.
+    ///
+    /// Lines are broken at spaces, so words (and things such as links and
+    /// code) longer than this are not broken.
+    /// The width includes the indent of containers such as block quotes and
+    /// lists.
+    ///
+    /// The default is `None`, which keeps line endings as they are.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{format, FormatOptions};
+    ///
+    /// // `markdown-rs` does not wrap by default:
+    /// assert_eq!(
+    ///     format("a b\nc d", &FormatOptions::default()),
+    ///     "a b\nc d\n"
+    /// );
+    ///
+    /// // Pass `line_width` to wrap:
+    /// assert_eq!(
+    ///     format(
+    ///         "> a b\nc d",
+    ///         &FormatOptions {
+    ///             line_width: Some(5),
+    ///             ..FormatOptions::default()
+    ///         }
+    ///     ),
+    ///     "> a b\n> c d\n"
+    /// );
+    /// ```
+    pub line_width: Option<usize>,
+
+    /// Configuration that describes how to parse from markdown.
+    pub parse: ParseOptions,
+
+    /// Whether to use setext headings for rank 1 and 2 headings.
+    ///
+    /// The default is `false`, which uses ATX headings, except for headings
+    /// that contain line endings, which can only be setext.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{format, FormatOptions};
+    ///
+    /// // `markdown-rs` uses ATX headings by default:
+    /// assert_eq!(format("a\n=", &FormatOptions::default()), "# a\n");
+    ///
+    /// // Pass `setext: true` to use setext headings:
+    /// assert_eq!(
+    ///     format(
+    ///         "## abcd",
+    ///         &FormatOptions {
+    ///             setext: true,
+    ///             ..FormatOptions::default()
+    ///         }
+    ///     ),
+    ///     "abcd\n----\n"
+    /// );
+    /// ```
+    pub setext: bool,
+
+    /// Whether to move definitions to the end of the document, ordered by
+    /// where they are first referenced.
+    ///
+    /// Only definitions in the root are moved.
+    /// Unused definitions come last, in the order they were in.
+    ///
+    /// The default is `false`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{format, FormatOptions};
+    ///
+    /// // `markdown-rs` keeps definitions where they are by default:
+    /// assert_eq!(
+    ///     format("[a]: b\n\n[a]", &FormatOptions::default()),
+    ///     "[a]: b\n\n[a]\n"
+    /// );
+    ///
+    /// // Pass `sort_definitions: true` to move them:
+    /// assert_eq!(
+    ///     format(
+    ///         "[a]: b\n[c]: d\n\n[c] [a]",
+    ///         &FormatOptions {
+    ///             sort_definitions: true,
+    ///             ..FormatOptions::default()
+    ///         }
+    ///     ),
+    ///     "[c] [a]\n\n[c]: d\n\n[a]: b\n"
+    /// );
+    /// ```
+    pub sort_definitions: bool,
+
+    /// Marker to use for strong.
+    ///
+    /// Can be `*` or `_`.
+    /// Intraword strong always uses `*`, as `_` does not work there.
+    ///
+    /// The default is `*`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{format, FormatOptions};
+    ///
+    /// // `markdown-rs` uses `*` by default:
+    /// assert_eq!(format("__a__", &FormatOptions::default()), "**a**\n");
+    ///
+    /// // Pass `strong: '_'` to use underscores:
+    /// assert_eq!(
+    ///     format(
+    ///         "**a**",
+    ///         &FormatOptions {
+    ///             strong: '_',
+    ///             ..FormatOptions::default()
+    ///         }
+    ///     ),
+    ///     "__a__\n"
+    /// );
+    /// ```
+    pub strong: char,
+}
+
+impl Default for FormatOptions {
+    /// `CommonMark` defaults, with `-`, `.`, and `*` as markers, aligned
+    /// tables, and no wrapping.
+    fn default() -> Self {
+        Self {
+            align_tables: true,
+            bullet: '-',
+            bullet_ordered: '.',
+            emphasis: '*',
+            line_ending: None,
+            line_width: None,
+            parse: ParseOptions::default(),
+            setext: false,
+            sort_definitions: false,
+            strong: '*',
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util::mdx::Signal;
     use alloc::format;
 
+    /// Check that `T` can be shared across threads.
+    fn assert_send_sync<T: Send + Sync>() {}
+
     #[test]
     fn test_constructs() {
         Constructs::default();
@@ -1365,7 +2433,7 @@ mod tests {
 
         assert_eq!(
             format!("{:?}", ParseOptions::default()),
-            "ParseOptions { constructs: Constructs { attention: true, autolink: true, block_quote: true, character_escape: true, character_reference: true, code_indented: true, code_fenced: true, code_text: true, definition: true, frontmatter: false, gfm_autolink_literal: false, gfm_footnote_definition: false, gfm_label_start_footnote: false, gfm_strikethrough: false, gfm_table: false, gfm_task_list_item: false, hard_break_escape: true, hard_break_trailing: true, heading_atx: true, heading_setext: true, html_flow: true, html_text: true, label_start_image: true, label_start_link: true, label_end: true, list_item: true, math_flow: false, math_text: false, mdx_esm: false, mdx_expression_flow: false, mdx_expression_text: false, mdx_jsx_flow: false, mdx_jsx_text: false, thematic_break: true }, gfm_strikethrough_single_tilde: true, math_text_single_dollar: true, mdx_expression_parse: None, mdx_esm_parse: None }",
+            "ParseOptions { constructs: Constructs { attention: true, autolink: true, block_quote: true, character_escape: true, character_reference: true, code_indented: true, code_fenced: true, code_text: true, definition: true, frontmatter: false, gfm_autolink_literal: false, gfm_footnote_definition: false, gfm_label_start_footnote: false, gfm_strikethrough: false, gfm_table: false, gfm_task_list_item: false, hard_break_escape: true, hard_break_trailing: true, heading_atx: true, heading_setext: true, html_flow: true, html_text: true, label_start_image: true, label_start_link: true, label_end: true, list_item: true, math_flow: false, math_text: false, mdx_esm: false, mdx_expression_flow: false, mdx_expression_text: false, mdx_jsx_flow: false, mdx_jsx_text: false, thematic_break: true }, gfm_strikethrough_single_tilde: true, math_text_single_dollar: true, mdx_expression_parse: None, mdx_esm_parse: None, position_encoding: Byte, limits: Limits { size: None, container_depth: None, inline_depth: None, definitions: None, events: None }, should_abort: None }",
             "should support `Debug` trait"
         );
         assert_eq!(
@@ -1378,7 +2446,7 @@ mod tests {
                 })),
                 ..Default::default()
             }),
-            "ParseOptions { constructs: Constructs { attention: true, autolink: true, block_quote: true, character_escape: true, character_reference: true, code_indented: true, code_fenced: true, code_text: true, definition: true, frontmatter: false, gfm_autolink_literal: false, gfm_footnote_definition: false, gfm_label_start_footnote: false, gfm_strikethrough: false, gfm_table: false, gfm_task_list_item: false, hard_break_escape: true, hard_break_trailing: true, heading_atx: true, heading_setext: true, html_flow: true, html_text: true, label_start_image: true, label_start_link: true, label_end: true, list_item: true, math_flow: false, math_text: false, mdx_esm: false, mdx_expression_flow: false, mdx_expression_text: false, mdx_jsx_flow: false, mdx_jsx_text: false, thematic_break: true }, gfm_strikethrough_single_tilde: true, math_text_single_dollar: true, mdx_expression_parse: Some(\"[Function]\"), mdx_esm_parse: Some(\"[Function]\") }",
+            "ParseOptions { constructs: Constructs { attention: true, autolink: true, block_quote: true, character_escape: true, character_reference: true, code_indented: true, code_fenced: true, code_text: true, definition: true, frontmatter: false, gfm_autolink_literal: false, gfm_footnote_definition: false, gfm_label_start_footnote: false, gfm_strikethrough: false, gfm_table: false, gfm_task_list_item: false, hard_break_escape: true, hard_break_trailing: true, heading_atx: true, heading_setext: true, html_flow: true, html_text: true, label_start_image: true, label_start_link: true, label_end: true, list_item: true, math_flow: false, math_text: false, mdx_esm: false, mdx_expression_flow: false, mdx_expression_text: false, mdx_jsx_flow: false, mdx_jsx_text: false, thematic_break: true }, gfm_strikethrough_single_tilde: true, math_text_single_dollar: true, mdx_expression_parse: Some(\"[Function]\"), mdx_esm_parse: Some(\"[Function]\"), position_encoding: Byte, limits: Limits { size: None, container_depth: None, inline_depth: None, definitions: None, events: None }, should_abort: None }",
             "should support `Debug` trait on mdx functions"
         );
     }
@@ -1407,6 +2475,33 @@ mod tests {
             options.gfm_tagfilter,
             "should support safe `gfm` shortcut (1)"
         );
+
+        assert!(
+            format!("{:?}", CompileOptions::default()).contains("code_block_render: None"),
+            "should support `Debug` trait"
+        );
+        assert!(
+            format!(
+                "{:?}",
+                CompileOptions {
+                    code_block_render: Some(Arc::new(|_lang, _meta, _value| None)),
+                    ..Default::default()
+                }
+            )
+            .contains("code_block_render: Some(\"[Function]\")"),
+            "should support `Debug` trait on code block render functions"
+        );
+
+        let options = CompileOptions {
+            code_block_render: Some(Arc::new(|_lang, _meta, _value| None)),
+            ..Default::default()
+        };
+        assert!(
+            options.clone().code_block_render.is_some(),
+            "should support `Clone` trait"
+        );
+
+        assert_send_sync::<CompileOptions>();
     }
 
     #[test]
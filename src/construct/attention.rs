@@ -108,6 +108,9 @@ struct Sequence {
     open: bool,
     /// Whether this sequence can close attention.
     close: bool,
+    /// Depth of the deepest attention made with this sequence, or of
+    /// attention that was in attention made with this sequence.
+    depth: usize,
 }
 
 /// At start of attention.
@@ -200,8 +203,21 @@ pub fn resolve(tokenizer: &mut Tokenizer) -> Option<Subresult> {
                         continue;
                     }
 
+                    // Attention can’t nest deeper than the limit.
+                    let depth = 1 + sequences[open..=close]
+                        .iter()
+                        .map(|sequence| sequence.depth)
+                        .max()
+                        .unwrap_or(0);
+
+                    if let Some(max) = tokenizer.parse_state.options.limits.inline_depth {
+                        if depth > max {
+                            break;
+                        }
+                    }
+
                     // We found a match!
-                    next_index = match_sequences(tokenizer, &mut sequences, open, close);
+                    next_index = match_sequences(tokenizer, &mut sequences, open, close, depth);
 
                     break;
                 }
@@ -269,6 +285,7 @@ fn get_sequences(tokenizer: &mut Tokenizer) -> Vec<Sequence> {
                         close
                     },
                     marker,
+                    depth: 0,
                 });
             }
         } else if enter.kind == Kind::Enter {
@@ -290,6 +307,7 @@ fn match_sequences(
     sequences: &mut Vec<Sequence>,
     open: usize,
     close: usize,
+    depth: usize,
 ) -> usize {
     // Where to move to next.
     // Stay on this closing sequence for the next iteration: it
@@ -346,6 +364,14 @@ fn match_sequences(
     sequences[open].end_point.index -= take;
     sequences[close].start_point.column += take;
     sequences[close].start_point.index += take;
+    sequences[open].depth = depth;
+    sequences[close].depth = depth;
+
+    // If both sequences are fully used, attention around this attention
+    // includes the sequence before it.
+    if open > 0 && sequences[open].size == 0 && sequences[close].size == 0 {
+        sequences[open - 1].depth = sequences[open - 1].depth.max(depth);
+    }
 
     // Opening.
     tokenizer.map.add_before(
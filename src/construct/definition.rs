@@ -104,6 +104,7 @@
 use crate::construct::partial_space_or_tab::space_or_tab;
 use crate::construct::partial_space_or_tab_eol::space_or_tab_eol;
 use crate::event::Name;
+use crate::parser::limit_error;
 use crate::state::{Name as StateName, State};
 use crate::tokenizer::Tokenizer;
 use crate::util::{
@@ -307,6 +308,22 @@ pub fn after_whitespace(tokenizer: &mut Tokenizer) -> State {
         None | Some(b'\n') => {
             tokenizer.exit(Name::Definition);
 
+            let made = &tokenizer.parse_state.definitions_made;
+            made.set(made.get() + 1);
+
+            if let Some(max) = tokenizer.parse_state.options.limits.definitions {
+                if made.get() > max {
+                    let position =
+                        Position::from_exit_event(&tokenizer.events, tokenizer.events.len() - 1);
+                    return State::Error(limit_error(
+                        tokenizer.parse_state,
+                        position.start,
+                        "definition",
+                        max,
+                    ));
+                }
+            }
+
             // Note: we don’t care about uniqueness.
             // It’s likely that that doesn’t happen very frequently.
             // It is more likely that it wastes precious time.
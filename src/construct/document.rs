@@ -154,6 +154,14 @@ pub fn container_new_before(tokenizer: &mut Tokenizer) -> State {
         }
     }
 
+    // Containers can’t nest deeper than the limit: what would start another
+    // one is then part of the flow.
+    if let Some(max) = tokenizer.parse_state.options.limits.container_depth {
+        if tokenizer.tokenize_state.document_continued >= max {
+            return State::Retry(StateName::DocumentContainersAfter);
+        }
+    }
+
     // Check for a new container.
     // Block quote?
     // Add a new container at the end of the stack.
@@ -487,6 +495,7 @@ fn exit_containers(tokenizer: &mut Tokenizer, phase: &Phase) -> Result<(), Strin
             .unwrap_or(State::Next(StateName::FlowStart));
 
         child.flush(state, false)?;
+        child.interrupt = false;
     }
 
     if !stack_close.is_empty() {
@@ -531,8 +540,6 @@ fn exit_containers(tokenizer: &mut Tokenizer, phase: &Phase) -> Result<(), Strin
         tokenizer.tokenize_state.document_exits[index] = Some(exits);
     }
 
-    child.interrupt = false;
-
     Ok(())
 }
 
@@ -83,6 +83,7 @@ pub fn open(tokenizer: &mut Tokenizer) -> State {
                 kind: LabelKind::GfmFootnote,
                 start: (tokenizer.events.len() - 6, tokenizer.events.len() - 1),
                 inactive: false,
+                depth: 0,
             });
             tokenizer.register_resolver_before(ResolveName::Label);
             State::Ok
@@ -442,6 +442,7 @@ pub fn head_delimiter_start(tokenizer: &mut Tokenizer) -> State {
     tokenizer.interrupt = false;
 
     if tokenizer.lazy || tokenizer.pierce {
+        tokenizer.tokenize_state.seen = false;
         tokenizer.tokenize_state.size = 0;
         State::Nok
     } else {
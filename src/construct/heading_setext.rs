@@ -242,6 +242,9 @@ pub fn resolve(tokenizer: &mut Tokenizer) -> Option<Subresult> {
                 // Remove *including* HeadingSetextUnderline:Exit, until the line ending.
                 tokenizer.map.add(enter + 1, exit - enter, vec![]);
                 // Remove old Paragraph:Enter.
+                // Also change its type, so that a later underline does not
+                // see it as the start of this paragraph.
+                tokenizer.events[exit + 3].name = Name::Data;
                 tokenizer.map.add(exit + 3, 1, vec![]);
             } else {
                 // Swap type.
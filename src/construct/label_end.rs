@@ -362,10 +362,17 @@ pub fn ok(tokenizer: &mut Tokenizer) -> State {
         }
     }
 
+    // Let the label this label is in know how deep labels are in it.
+    let depth = label_start.depth + 1;
+    if let Some(label_start) = tokenizer.tokenize_state.label_starts.last_mut() {
+        label_start.depth = label_start.depth.max(depth);
+    }
+
     tokenizer.tokenize_state.labels.push(Label {
         kind: label_start.kind,
         start: label_start.start,
         end: (tokenizer.tokenize_state.end, tokenizer.events.len() - 1),
+        depth,
     });
     tokenizer.tokenize_state.end = 0;
     tokenizer.register_resolver_before(ResolveName::Label);
@@ -386,6 +393,12 @@ pub fn ok(tokenizer: &mut Tokenizer) -> State {
 /// ```
 pub fn nok(tokenizer: &mut Tokenizer) -> State {
     let start = tokenizer.tokenize_state.label_starts.pop().unwrap();
+
+    // Labels that closed in this start are in the label before it.
+    if let Some(label_start) = tokenizer.tokenize_state.label_starts.last_mut() {
+        label_start.depth = label_start.depth.max(start.depth);
+    }
+
     tokenizer.tokenize_state.label_starts_loose.push(start);
     tokenizer.tokenize_state.end = 0;
     State::Nok
@@ -691,6 +704,19 @@ fn inject_labels(tokenizer: &mut Tokenizer, labels: &[Label]) {
     let mut index = 0;
     while index < labels.len() {
         let label = &labels[index];
+        index += 1;
+
+        // Labels can’t nest deeper than the limit: those are data.
+        // They are still parsed, so that a limit never changes which other
+        // labels form.
+        if let Some(max) = tokenizer.parse_state.options.limits.inline_depth {
+            if label.depth > max {
+                mark_indices_as_data(tokenizer, label.start);
+                mark_indices_as_data(tokenizer, label.end);
+                continue;
+            }
+        }
+
         let group_name = if label.kind == LabelKind::GfmFootnote {
             Name::GfmFootnoteCall
         } else if label.kind == LabelKind::Image {
@@ -801,8 +827,6 @@ fn inject_labels(tokenizer: &mut Tokenizer, labels: &[Label]) {
                 link: None,
             }],
         );
-
-        index += 1;
     }
 }
 
@@ -811,28 +835,31 @@ fn mark_as_data(tokenizer: &mut Tokenizer, events: &[LabelStart]) {
     let mut index = 0;
 
     while index < events.len() {
-        let data_enter_index = events[index].start.0;
-        let data_exit_index = events[index].start.1;
-
-        tokenizer.map.add(
-            data_enter_index,
-            data_exit_index - data_enter_index + 1,
-            vec![
-                Event {
-                    kind: Kind::Enter,
-                    name: Name::Data,
-                    point: tokenizer.events[data_enter_index].point.clone(),
-                    link: None,
-                },
-                Event {
-                    kind: Kind::Exit,
-                    name: Name::Data,
-                    point: tokenizer.events[data_exit_index].point.clone(),
-                    link: None,
-                },
-            ],
-        );
-
+        mark_indices_as_data(tokenizer, events[index].start);
         index += 1;
     }
 }
+
+/// Replace the events from an enter to an exit with data.
+fn mark_indices_as_data(tokenizer: &mut Tokenizer, indices: (usize, usize)) {
+    let (data_enter_index, data_exit_index) = indices;
+
+    tokenizer.map.add(
+        data_enter_index,
+        data_exit_index - data_enter_index + 1,
+        vec![
+            Event {
+                kind: Kind::Enter,
+                name: Name::Data,
+                point: tokenizer.events[data_enter_index].point.clone(),
+                link: None,
+            },
+            Event {
+                kind: Kind::Exit,
+                name: Name::Data,
+                point: tokenizer.events[data_exit_index].point.clone(),
+                link: None,
+            },
+        ],
+    );
+}
@@ -112,6 +112,7 @@ pub fn after(tokenizer: &mut Tokenizer) -> State {
             kind: LabelKind::Image,
             start: (tokenizer.events.len() - 6, tokenizer.events.len() - 1),
             inactive: false,
+            depth: 0,
         });
         tokenizer.register_resolver_before(ResolveName::Label);
         State::Ok
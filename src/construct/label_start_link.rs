@@ -55,6 +55,7 @@ pub fn start(tokenizer: &mut Tokenizer) -> State {
             kind: LabelKind::Link,
             start: (start, tokenizer.events.len() - 1),
             inactive: false,
+            depth: 0,
         });
         tokenizer.register_resolver_before(ResolveName::Label);
         State::Ok
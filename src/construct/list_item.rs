@@ -383,7 +383,27 @@ pub fn resolve(tokenizer: &mut Tokenizer) -> Option<Subresult> {
 
         if event.name == Name::ListItem {
             if event.kind == Kind::Enter {
-                let end = skip::opt(&tokenizer.events, index, &[Name::ListItem]) - 1;
+                // Find the matching exit.
+                // Not with `skip::opt`, as that also skips a list item
+                // directly after this one (such as when a line ending was
+                // taken by unclosed fenced code).
+                let mut end = index + 1;
+                let mut depth = 1;
+                loop {
+                    if tokenizer.events[end].name == Name::ListItem {
+                        if tokenizer.events[end].kind == Kind::Enter {
+                            depth += 1;
+                        } else {
+                            depth -= 1;
+
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                    }
+
+                    end += 1;
+                }
                 let marker = skip::to(&tokenizer.events, index, &[Name::ListItemMarker]);
                 // Guaranteed to be a valid ASCII byte.
                 let marker = tokenizer.parse_state.bytes[tokenizer.events[marker].point.index];
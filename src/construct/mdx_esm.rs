@@ -32,7 +32,7 @@
 use crate::event::Name;
 use crate::state::{Name as StateName, State};
 use crate::tokenizer::Tokenizer;
-use crate::util::{mdx_collect::collect, slice::Slice};
+use crate::util::{location::format_place, mdx_collect::collect, slice::Slice};
 use crate::MdxSignal;
 use alloc::format;
 
@@ -213,14 +213,18 @@ fn parse_esm(tokenizer: &mut Tokenizer) -> State {
                 .expect("expected location index if aware mdx is on")
                 .relative_to_point(&result.stops, relative)
                 .expect("expected non-empty string");
-            State::Error(format!("{}:{}: {}", point.line, point.column, message))
+            let place = format_place(
+                tokenizer.parse_state.bytes,
+                point.line,
+                point.column,
+                point.offset,
+                tokenizer.parse_state.options.position_encoding,
+            );
+            State::Error(format!("{}: {}", place, message))
         }
         MdxSignal::Eof(message) => {
             if tokenizer.current.is_none() {
-                State::Error(format!(
-                    "{}:{}: {}",
-                    tokenizer.point.line, tokenizer.point.column, message
-                ))
+                State::Error(format!("{}: {}", tokenizer.place(), message))
             } else {
                 tokenizer.tokenize_state.mdx_last_parse_error = Some(message);
                 State::Retry(StateName::MdxEsmContinuationStart)
@@ -60,7 +60,7 @@ use crate::construct::partial_space_or_tab::space_or_tab_min_max;
 use crate::event::Name;
 use crate::state::{Name as StateName, State};
 use crate::tokenizer::Tokenizer;
-use crate::util::{constant::TAB_SIZE, mdx_collect::collect};
+use crate::util::{constant::TAB_SIZE, location::format_place, mdx_collect::collect};
 use crate::{MdxExpressionKind, MdxExpressionParse, MdxSignal};
 use alloc::format;
 
@@ -90,8 +90,8 @@ pub fn before(tokenizer: &mut Tokenizer) -> State {
     match tokenizer.current {
         None => {
             State::Error(format!(
-                "{}:{}: {}",
-                tokenizer.point.line, tokenizer.point.column,
+                "{}: {}",
+                tokenizer.place(),
                 tokenizer.tokenize_state.mdx_last_parse_error.take()
                     .unwrap_or_else(|| "Unexpected end of file in expression, expected a corresponding closing brace for `{`".into())
             ))
@@ -168,8 +168,8 @@ pub fn eol_after(tokenizer: &mut Tokenizer) -> State {
         && tokenizer.lazy
     {
         State::Error(format!(
-            "{}:{}: Unexpected lazy line in expression in container, expected line to be prefixed with `>` when in a block quote, whitespace when in a list, etc",
-            tokenizer.point.line, tokenizer.point.column
+            "{}: Unexpected lazy line in expression in container, expected line to be prefixed with `>` when in a block quote, whitespace when in a list, etc",
+            tokenizer.place()
         ))
     } else if matches!(tokenizer.current, Some(b'\t' | b' ')) {
         tokenizer.attempt(State::Next(StateName::MdxExpressionBefore), State::Nok);
@@ -221,17 +221,26 @@ fn parse_expression(tokenizer: &mut Tokenizer, parse: &MdxExpressionParse) -> St
     match parse(&result.value, &kind) {
         MdxSignal::Ok => State::Ok,
         MdxSignal::Error(message, relative) => {
-            let point = tokenizer
+            let place = tokenizer
                 .parse_state
                 .location
                 .as_ref()
                 .expect("expected location index if aware mdx is on")
                 .relative_to_point(&result.stops, relative)
-                .map_or((tokenizer.point.line, tokenizer.point.column), |d| {
-                    (d.line, d.column)
-                });
+                .map_or_else(
+                    || tokenizer.place(),
+                    |d| {
+                        format_place(
+                            tokenizer.parse_state.bytes,
+                            d.line,
+                            d.column,
+                            d.offset,
+                            tokenizer.parse_state.options.position_encoding,
+                        )
+                    },
+                );
 
-            State::Error(format!("{}:{}: {}", point.0, point.1, message))
+            State::Error(format!("{}: {}", place, message))
         }
         MdxSignal::Eof(message) => {
             tokenizer.tokenize_state.mdx_last_parse_error = Some(message);
@@ -1093,8 +1093,8 @@ pub fn es_whitespace_eol_after(tokenizer: &mut Tokenizer) -> State {
     // Lazy continuation in a flow tag is a syntax error.
     if tokenizer.tokenize_state.token_1 == Name::MdxJsxFlowTag && tokenizer.lazy {
         State::Error(format!(
-            "{}:{}: Unexpected lazy line in jsx in container, expected line to be prefixed with `>` when in a block quote, whitespace when in a list, etc",
-            tokenizer.point.line, tokenizer.point.column
+            "{}: Unexpected lazy line in jsx in container, expected line to be prefixed with `>` when in a block quote, whitespace when in a list, etc",
+            tokenizer.place()
         ))
     } else {
         State::Retry(StateName::MdxJsxEsWhitespaceStart)
@@ -1115,9 +1115,8 @@ fn id_cont_opt(code: Option<char>) -> bool {
 /// instead.
 fn crash(tokenizer: &Tokenizer, at: &str, expect: &str) -> State {
     State::Error(format!(
-        "{}:{}: Unexpected {} {}, expected {}",
-        tokenizer.point.line,
-        tokenizer.point.column,
+        "{}: Unexpected {} {}, expected {}",
+        tokenizer.place(),
         format_char_opt(if tokenizer.current.is_none() {
             None
         } else {
@@ -0,0 +1,1529 @@
+//! Kinds of nodes in concrete syntax trees.
+//!
+//! > 👉 **Important**: this module is generated by `generate/src/main.rs`.
+//! > It is generated from the names of events in `src/event.rs`.
+
+use crate::event::Name;
+
+/// Kind of a node in a concrete syntax tree.
+///
+/// Nodes without children are tokens, such as
+/// [`SyntaxKind::ListItemMarker`][]; other nodes group them, such as
+/// [`SyntaxKind::ListItem`][].
+/// Bytes that no construct claims are [`SyntaxKind::Data`][].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SyntaxKind {
+    /// Attention sequence.
+    AttentionSequence,
+    /// Whole autolink.
+    ///
+    /// ```markdown
+    /// > | <https://example.com> and <admin@example.com>
+    ///     ^^^^^^^^^^^^^^^^^^^^^     ^^^^^^^^^^^^^^^^^^^
+    /// ```
+    Autolink,
+    /// Email autolink w/o markers.
+    ///
+    /// ```markdown
+    /// > | <admin@example.com>
+    ///      ^^^^^^^^^^^^^^^^^
+    /// ```
+    AutolinkEmail,
+    /// Marker of an autolink.
+    ///
+    /// ```markdown
+    /// > | <https://example.com>
+    ///     ^                   ^
+    /// ```
+    AutolinkMarker,
+    /// Protocol autolink w/o markers.
+    ///
+    /// ```markdown
+    /// > | <https://example.com>
+    ///      ^^^^^^^^^^^^^^^^^^^
+    /// ```
+    AutolinkProtocol,
+    /// Line ending preceded only by whitespace or nothing at all.
+    ///
+    /// ```markdown
+    /// > | ␠␠␊
+    ///       ^
+    /// ```
+    BlankLineEnding,
+    /// Whole block quote.
+    ///
+    /// ```markdown
+    /// > | > a
+    ///     ^^^
+    /// > | b
+    ///     ^
+    /// ```
+    BlockQuote,
+    /// Block quote marker.
+    ///
+    /// ```markdown
+    /// > | > a
+    ///     ^
+    ///   | b
+    /// ```
+    BlockQuoteMarker,
+    /// Block quote prefix.
+    ///
+    /// ```markdown
+    /// > | > a
+    ///     ^^
+    ///   | b
+    /// ```
+    BlockQuotePrefix,
+    /// Byte order mark.
+    ByteOrderMark,
+    /// Whole character escape.
+    ///
+    /// ```markdown
+    /// > | a \- b
+    ///       ^^
+    /// ```
+    CharacterEscape,
+    /// Character escape marker.
+    ///
+    /// ```markdown
+    /// > | a \- b
+    ///       ^
+    /// ```
+    CharacterEscapeMarker,
+    /// Character escape value.
+    ///
+    /// ```markdown
+    /// > | a \- b
+    ///        ^
+    /// ```
+    CharacterEscapeValue,
+    /// Whole character reference.
+    ///
+    /// ```markdown
+    /// > | a &amp; b &#8800; c &#x1D306; d
+    ///       ^^^^^   ^^^^^^^   ^^^^^^^^^
+    /// ```
+    CharacterReference,
+    /// Character reference opening marker.
+    ///
+    /// ```markdown
+    /// > | a &amp; b &#8800; c &#x1D306; d
+    ///       ^       ^         ^
+    /// ```
+    CharacterReferenceMarker,
+    /// Character reference hexadecimal numeric marker.
+    ///
+    /// ```markdown
+    /// > | a &amp; b &#8800; c &#x1D306; d
+    ///                           ^
+    /// ```
+    CharacterReferenceMarkerHexadecimal,
+    /// Character reference numeric marker.
+    ///
+    /// ```markdown
+    /// > | a &amp; b &#8800; c &#x1D306; d
+    ///                ^         ^
+    /// ```
+    CharacterReferenceMarkerNumeric,
+    /// Character reference closing marker.
+    ///
+    /// ```markdown
+    /// > | a &amp; b &#8800; c &#x1D306; d
+    ///           ^         ^           ^
+    /// ```
+    CharacterReferenceMarkerSemi,
+    /// Character reference value.
+    ///
+    /// ```markdown
+    /// > | a &amp; b &#8800; c &#x1D306; d
+    ///        ^^^      ^^^^       ^^^^^
+    /// ```
+    CharacterReferenceValue,
+    /// Whole code (fenced).
+    ///
+    /// ````markdown
+    /// > | ```js
+    ///     ^^^^^
+    /// > | console.log(1)
+    ///     ^^^^^^^^^^^^^^
+    /// > | ```
+    ///     ^^^
+    /// ````
+    CodeFenced,
+    /// A code (fenced) fence.
+    ///
+    /// ````markdown
+    /// > | ```js
+    ///     ^^^^^
+    ///   | console.log(1)
+    /// > | ```
+    ///     ^^^
+    /// ````
+    CodeFencedFence,
+    /// A code (fenced) fence info word.
+    ///
+    /// ````markdown
+    /// > | ```js
+    ///        ^^
+    ///   | console.log(1)
+    ///   | ```
+    /// ````
+    CodeFencedFenceInfo,
+    /// A code (fenced) fence meta string.
+    ///
+    /// ````markdown
+    /// > | ```js highlight="1"
+    ///           ^^^^^^^^^^^^^
+    ///   | console.log(1)
+    ///   | ```
+    /// ````
+    CodeFencedFenceMeta,
+    /// A code (fenced) fence sequence.
+    ///
+    /// ````markdown
+    /// > | ```js
+    ///     ^^^
+    ///   | console.log(1)
+    /// > | ```
+    ///     ^^^
+    /// ````
+    CodeFencedFenceSequence,
+    /// A code (fenced, indented) chunk.
+    ///
+    /// ````markdown
+    ///   | ```js
+    /// > | console.log(1)
+    ///     ^^^^^^^^^^^^^^
+    ///   | ```
+    /// ````
+    ///
+    /// ```markdown
+    /// > | ␠␠␠␠console.log(1)
+    ///         ^^^^^^^^^^^^^^
+    /// ```
+    CodeFlowChunk,
+    /// Whole code (indented).
+    ///
+    /// ```markdown
+    /// ␠␠␠␠console.log(1)
+    /// ^^^^^^^^^^^^^^^^^^
+    /// ```
+    CodeIndented,
+    /// Whole code (text).
+    ///
+    /// ```markdown
+    /// > | a `b` c
+    ///       ^^^
+    /// ```
+    CodeText,
+    /// Code (text) data.
+    ///
+    /// ```markdown
+    /// > | a `b` c
+    ///        ^
+    /// ```
+    CodeTextData,
+    /// Code (text) sequence.
+    ///
+    /// ```markdown
+    /// > | a `b` c
+    ///       ^ ^
+    /// ```
+    CodeTextSequence,
+    /// Content.
+    ///
+    /// ```markdown
+    /// > | [a]: b
+    ///     ^^^^^^
+    /// > | c.
+    ///     ^^
+    /// ```
+    Content,
+    /// Data.
+    ///
+    /// ```markdown
+    /// > | aa *bb* cc
+    ///     ^^^ ^^ ^^^
+    /// ```
+    Data,
+    /// Whole definition.
+    ///
+    /// ```markdown
+    /// > | [a]: b "c"
+    ///     ^^^^^^^^^^
+    /// ```
+    Definition,
+    /// Whole definition destination.
+    ///
+    /// ```markdown
+    /// > | [a]: b "c"
+    ///          ^
+    /// > | [a]: <b> "c"
+    ///          ^^^
+    /// ```
+    DefinitionDestination,
+    /// Definition destination literal.
+    ///
+    /// ```markdown
+    /// > | [a]: <b> "c"
+    ///          ^^^
+    /// ```
+    DefinitionDestinationLiteral,
+    /// Definition destination literal marker.
+    ///
+    /// ```markdown
+    /// > | [a]: <b> "c"
+    ///          ^ ^
+    /// ```
+    DefinitionDestinationLiteralMarker,
+    /// Definition destination raw.
+    ///
+    /// ```markdown
+    /// > | [a]: b "c"
+    ///          ^
+    /// ```
+    DefinitionDestinationRaw,
+    /// Definition destination data.
+    ///
+    /// ```markdown
+    /// > | [a]: b "c"
+    ///          ^
+    /// > | [a]: <b> "c"
+    ///           ^
+    /// ```
+    DefinitionDestinationString,
+    /// Whole definition label.
+    ///
+    /// ```markdown
+    /// > | [a]: b "c"
+    ///     ^^^
+    /// ```
+    DefinitionLabel,
+    /// Definition label marker.
+    ///
+    /// ```markdown
+    /// > | [a]: b "c"
+    ///     ^ ^
+    /// ```
+    DefinitionLabelMarker,
+    /// Definition label data.
+    ///
+    /// ```markdown
+    /// > | [a]: b "c"
+    ///      ^
+    /// ```
+    DefinitionLabelString,
+    /// Definition marker.
+    ///
+    /// ```markdown
+    /// > | [a]: b "c"
+    ///        ^
+    /// ```
+    DefinitionMarker,
+    /// Whole definition title.
+    ///
+    /// ```markdown
+    /// > | [a]: b "c"
+    ///            ^^^
+    /// ```
+    DefinitionTitle,
+    /// Definition title marker.
+    ///
+    /// ```markdown
+    /// > | [a]: b "c"
+    ///            ^ ^
+    /// ```
+    DefinitionTitleMarker,
+    /// Definition title data.
+    ///
+    /// ```markdown
+    /// > | [a]: b "c"
+    ///             ^
+    /// ```
+    DefinitionTitleString,
+    /// Emphasis.
+    ///
+    /// ```markdown
+    /// > | *a*
+    ///     ^^^
+    /// ```
+    Emphasis,
+    /// Emphasis sequence.
+    ///
+    /// ```markdown
+    /// > | *a*
+    ///     ^ ^
+    /// ```
+    EmphasisSequence,
+    /// Emphasis text.
+    ///
+    /// ```markdown
+    /// > | *a*
+    ///      ^
+    /// ```
+    EmphasisText,
+    /// Whole frontmatter.
+    ///
+    /// ```markdown
+    /// > | ---
+    ///     ^^^
+    /// > | title: Neptune
+    ///     ^^^^^^^^^^^^^^
+    /// > | ---
+    ///     ^^^
+    /// ```
+    Frontmatter,
+    /// Frontmatter chunk.
+    ///
+    /// ```markdown
+    ///   | ---
+    /// > | title: Neptune
+    ///     ^^^^^^^^^^^^^^
+    ///   | ---
+    /// ```
+    FrontmatterChunk,
+    /// Frontmatter fence.
+    ///
+    /// ```markdown
+    /// > | ---
+    ///     ^^^
+    ///   | title: Neptune
+    /// > | ---
+    ///     ^^^
+    /// ```
+    FrontmatterFence,
+    /// Frontmatter sequence.
+    ///
+    /// ```markdown
+    /// > | ---
+    ///     ^^^
+    ///   | title: Neptune
+    /// > | ---
+    ///     ^^^
+    /// ```
+    FrontmatterSequence,
+    /// GFM extension: email autolink.
+    ///
+    /// ```markdown
+    /// > | context@example.com
+    ///     ^^^^^^^^^^^^^^^^^^^
+    /// ```
+    GfmAutolinkLiteralEmail,
+    /// GFM extension: email autolink w/ explicit `mailto`.
+    ///
+    /// ```markdown
+    /// > | mailto:context@example.com
+    ///     ^^^^^^^^^^^^^^^^^^^^^^^^^^
+    /// ```
+    GfmAutolinkLiteralMailto,
+    /// GFM extension: autolink w/ protocol.
+    ///
+    /// ```markdown
+    /// > | https://example.com
+    ///     ^^^^^^^^^^^^^^^^^^^
+    /// ```
+    GfmAutolinkLiteralProtocol,
+    /// GFM extension: autolink w/ www.
+    ///
+    /// ```markdown
+    /// > | www.example.com
+    ///     ^^^^^^^^^^^^^^^
+    /// ```
+    GfmAutolinkLiteralWww,
+    /// GFM extension: email autolink w/ explicit `xmpp`.
+    ///
+    /// ```markdown
+    /// > | mailto:a@b.c/d
+    ///     ^^^^^^^^^^^^^^
+    /// ```
+    GfmAutolinkLiteralXmpp,
+    /// GFM extension: whole footnote call.
+    ///
+    /// ```markdown
+    /// > | a [^b] c
+    ///       ^^^^
+    /// ```
+    GfmFootnoteCall,
+    /// GFM extension: label start (footnote).
+    ///
+    /// ```markdown
+    /// > | a [^b] c
+    ///       ^^
+    /// ```
+    GfmFootnoteCallLabel,
+    /// GFM extension: label start (footnote) marker.
+    ///
+    /// ```markdown
+    /// > | a [^b] c
+    ///        ^
+    /// ```
+    GfmFootnoteCallMarker,
+    /// GFM extension: whole footnote definition.
+    ///
+    /// ```markdown
+    /// > | [^a]: b
+    ///     ^^^^^^^
+    /// ```
+    GfmFootnoteDefinition,
+    /// GFM extension: footnote definition prefix.
+    ///
+    /// ```markdown
+    /// > | [^a]: b
+    ///     ^^^^^^
+    /// ```
+    GfmFootnoteDefinitionPrefix,
+    /// GFM extension: footnote definition label.
+    ///
+    /// ```markdown
+    /// > | [^a]: b
+    ///     ^^^^
+    /// ```
+    GfmFootnoteDefinitionLabel,
+    /// GFM extension: footnote definition label marker.
+    ///
+    /// ```markdown
+    /// > | [^a]: b
+    ///     ^  ^
+    /// ```
+    GfmFootnoteDefinitionLabelMarker,
+    /// GFM extension: footnote definition label string.
+    ///
+    /// ```markdown
+    /// > | [^a]: b
+    ///       ^
+    /// ```
+    GfmFootnoteDefinitionLabelString,
+    /// GFM extension: footnote definition marker.
+    ///
+    /// ```markdown
+    /// > | [^a]: b
+    ///      ^
+    /// ```
+    GfmFootnoteDefinitionMarker,
+    /// GFM extension: Strikethrough.
+    ///
+    /// ```markdown
+    /// > | ~a~
+    ///     ^^^
+    /// ```
+    GfmStrikethrough,
+    /// GFM extension: Strikethrough sequence.
+    ///
+    /// ```markdown
+    /// > | ~a~
+    ///     ^ ^
+    /// ```
+    GfmStrikethroughSequence,
+    /// GFM extension: Strikethrough text.
+    ///
+    /// ```markdown
+    /// > | ~a~
+    ///      ^
+    /// ```
+    GfmStrikethroughText,
+    /// GFM extension: Table.
+    ///
+    /// ```markdown
+    /// > | | a |
+    ///     ^^^^^
+    /// > | | - |
+    ///     ^^^^^
+    /// > | | b |
+    ///     ^^^^^
+    /// ```
+    GfmTable,
+    /// GFM extension: Table body.
+    ///
+    /// ```markdown
+    ///   | | a |
+    ///   | | - |
+    /// > | | b |
+    ///     ^^^^^
+    /// ```
+    GfmTableBody,
+    /// GFM extension: Table cell.
+    ///
+    /// ```markdown
+    /// > | | a |
+    ///     ^^^^^
+    ///   | | - |
+    /// > | | b |
+    ///     ^^^^^
+    /// ```
+    GfmTableCell,
+    /// GFM extension: Table cell text.
+    ///
+    /// ```markdown
+    /// > | | a |
+    ///       ^
+    ///   | | - |
+    /// > | | b |
+    ///       ^
+    /// ```
+    GfmTableCellText,
+    /// GFM extension: Table cell divider.
+    ///
+    /// ```markdown
+    /// > | | a |
+    ///     ^   ^
+    /// > | | - |
+    ///     ^   ^
+    /// > | | b |
+    ///     ^   ^
+    /// ```
+    GfmTableCellDivider,
+    /// GFM extension: Table delimiter row.
+    ///
+    /// ```markdown
+    ///   | | a |
+    /// > | | - |
+    ///     ^^^^^
+    ///   | | b |
+    /// ```
+    GfmTableDelimiterRow,
+    /// GFM extension: Table delimiter alignment marker.
+    ///
+    /// ```markdown
+    ///   | | a  |
+    /// > | | :- |
+    ///       ^
+    ///   | | b  |
+    /// ```
+    GfmTableDelimiterMarker,
+    /// GFM extension: Table delimiter cell.
+    ///
+    /// ```markdown
+    ///   | | a |
+    /// > | | - |
+    ///     ^^^^^
+    ///   | | b |
+    /// ```
+    GfmTableDelimiterCell,
+    /// GFM extension: Table delimiter cell alignment.
+    ///
+    /// ```markdown
+    ///   | | a |
+    /// > | | - |
+    ///       ^
+    ///   | | b |
+    /// ```
+    GfmTableDelimiterCellValue,
+    /// GFM extension: Table delimiter filler.
+    ///
+    /// ```markdown
+    ///   | | a |
+    /// > | | - |
+    ///       ^
+    ///   | | b |
+    /// ```
+    GfmTableDelimiterFiller,
+    /// GFM extension: Table head.
+    ///
+    /// ```markdown
+    /// > | | a |
+    ///     ^^^^^
+    /// > | | - |
+    ///     ^^^^^
+    ///   | | b |
+    /// ```
+    GfmTableHead,
+    /// GFM extension: Table row.
+    ///
+    /// ```markdown
+    /// > | | a |
+    ///     ^^^^^
+    ///   | | - |
+    /// > | | b |
+    ///     ^^^^^
+    /// ```
+    GfmTableRow,
+    /// GFM extension: task list item check.
+    ///
+    /// ```markdown
+    /// > | * [x] y.
+    ///       ^^^
+    /// ```
+    GfmTaskListItemCheck,
+    /// GFM extension: task list item check marker.
+    ///
+    /// ```markdown
+    /// > | * [x] y.
+    ///       ^ ^
+    /// ```
+    GfmTaskListItemMarker,
+    /// GFM extension: task list item value: checked.
+    ///
+    /// ```markdown
+    /// > | * [x] y.
+    ///        ^
+    /// ```
+    GfmTaskListItemValueChecked,
+    /// GFM extension: task list item value: unchecked.
+    ///
+    /// ```markdown
+    /// > | * [ ] z.
+    ///        ^
+    /// ```
+    GfmTaskListItemValueUnchecked,
+    /// Whole hard break (escape).
+    ///
+    /// ```markdown
+    /// > | a\␊
+    ///      ^
+    /// > | b
+    /// ```
+    HardBreakEscape,
+    /// Whole hard break (trailing).
+    ///
+    /// ```markdown
+    /// > | a␠␠␊
+    ///      ^^
+    /// > | b
+    /// ```
+    HardBreakTrailing,
+    /// Whole heading (atx).
+    ///
+    /// ```markdown
+    /// > | # alpha
+    ///     ^^^^^^^
+    /// ```
+    HeadingAtx,
+    /// Heading (atx) sequence.
+    ///
+    /// ```markdown
+    /// > | # alpha
+    ///     ^
+    /// ```
+    HeadingAtxSequence,
+    /// Heading (atx) data.
+    ///
+    /// ```markdown
+    /// > | # alpha
+    ///       ^^^^^
+    /// ```
+    HeadingAtxText,
+    /// Whole heading (setext).
+    ///
+    /// ```markdown
+    /// > | alpha
+    ///     ^^^^^
+    /// > | =====
+    ///     ^^^^^
+    /// ```
+    HeadingSetext,
+    /// Heading (setext) data.
+    ///
+    /// ```markdown
+    /// > | alpha
+    ///     ^^^^^
+    ///   | =====
+    /// ```
+    HeadingSetextText,
+    /// Heading (setext) underline.
+    ///
+    /// ```markdown
+    ///   | alpha
+    /// > | =====
+    ///     ^^^^^
+    /// ```
+    HeadingSetextUnderline,
+    /// Heading (setext) underline sequence.
+    ///
+    /// ```markdown
+    ///   | alpha
+    /// > | =====
+    ///     ^^^^^
+    /// ```
+    HeadingSetextUnderlineSequence,
+    /// Whole html (flow).
+    ///
+    /// ```markdown
+    /// > | <div>
+    ///     ^^^^^
+    /// ```
+    HtmlFlow,
+    /// HTML (flow) data.
+    ///
+    /// ```markdown
+    /// > | <div>
+    ///     ^^^^^
+    /// ```
+    HtmlFlowData,
+    /// Whole html (text).
+    ///
+    /// ```markdown
+    /// > | a <b> c
+    ///       ^^^
+    /// ```
+    HtmlText,
+    /// HTML (text) data.
+    ///
+    /// ```markdown
+    /// > | a <b> c
+    ///       ^^^
+    /// ```
+    HtmlTextData,
+    /// Image.
+    ///
+    /// ```markdown
+    /// > | a ![b] c
+    ///       ^^^^
+    /// > | a ![b][c] d
+    ///       ^^^^^^^
+    /// > | a ![b](c) d
+    ///       ^^^^^^^
+    /// ```
+    Image,
+    /// Label.
+    ///
+    /// ```markdown
+    /// > | a [b] c
+    ///       ^^^
+    /// > | a ![b][c] d
+    ///       ^^^^
+    /// > | a [b](c) d
+    ///       ^^^
+    /// ```
+    Label,
+    /// Label end.
+    ///
+    /// ```markdown
+    /// > | a ![b](c) d
+    ///          ^
+    /// > | a [b](c) d
+    ///         ^
+    /// ```
+    LabelEnd,
+    /// Label start (image).
+    ///
+    /// ```markdown
+    /// > | a ![b](c) d
+    ///       ^^
+    /// ```
+    LabelImage,
+    /// Label start (image) marker.
+    ///
+    /// ```markdown
+    /// > | a ![b](c) d
+    ///       ^
+    /// ```
+    LabelImageMarker,
+    /// Label start (link).
+    ///
+    /// ```markdown
+    /// > | a [b](c) d
+    ///       ^
+    /// ```
+    LabelLink,
+    /// Label marker.
+    ///
+    /// ```markdown
+    /// > | a ![b](c) d
+    ///        ^ ^
+    /// > | a [b](c) d
+    ///       ^ ^
+    /// ```
+    LabelMarker,
+    /// Label text.
+    ///
+    /// ```markdown
+    /// > | a [b] c
+    ///        ^
+    /// > | a ![b][c] d
+    ///         ^
+    /// > | a [b](c) d
+    ///        ^
+    /// ```
+    LabelText,
+    /// Line ending.
+    ///
+    /// ```markdown
+    /// > | a␊
+    ///      ^
+    ///   | b
+    /// ```
+    LineEnding,
+    /// Link.
+    ///
+    /// ```markdown
+    /// > | a [b] c
+    ///       ^^^
+    /// > | a [b][c] d
+    ///       ^^^^^^
+    /// > | a [b](c) d
+    ///       ^^^^^^
+    /// ```
+    Link,
+    /// List item.
+    ///
+    /// ```markdown
+    /// > | * a
+    ///     ^^^
+    /// > | 1. b
+    ///     ^^^^
+    /// ```
+    ListItem,
+    /// List item (marker).
+    ///
+    /// ```markdown
+    /// > | * a
+    ///     ^
+    /// > | 1. b
+    ///      ^
+    /// ```
+    ListItemMarker,
+    /// List item (prefix).
+    ///
+    /// ```markdown
+    /// > | * a
+    ///     ^^
+    /// > |   b
+    ///     ^^
+    /// ```
+    ListItemPrefix,
+    /// List item (value).
+    ///
+    /// ```markdown
+    /// > | 1. b
+    ///     ^
+    /// ```
+    ListItemValue,
+    /// List (ordered).
+    ///
+    /// ```markdown
+    /// > | 1. a
+    ///     ^^^^
+    /// > | 2. b
+    ///     ^^^^
+    /// ```
+    ListOrdered,
+    /// List (unordered).
+    ///
+    /// ```markdown
+    /// > | * a
+    ///     ^^^
+    /// > | * b
+    ///     ^^^
+    /// ```
+    ListUnordered,
+    /// Whole math (flow).
+    ///
+    /// ```markdown
+    /// > | $$
+    ///     ^^
+    /// > | \frac{1}{2}
+    ///     ^^^^^^^^^^^
+    /// > | $$
+    ///     ^^
+    /// ```
+    MathFlow,
+    /// A math (flow) fence.
+    ///
+    /// ```markdown
+    /// > | $$
+    ///     ^^
+    ///   | \frac{1}{2}
+    /// > | $$
+    ///     ^^
+    /// ```
+    MathFlowFence,
+    /// A math (flow) fence meta string.
+    ///
+    /// ```markdown
+    /// > | $$alpha bravo
+    ///       ^^^^^^^^^^^
+    ///   | \frac{1}{2}
+    ///   | $$
+    /// ```
+    MathFlowFenceMeta,
+    /// A math (flow) fence sequence.
+    ///
+    /// ```markdown
+    /// > | $$
+    ///     ^^
+    ///   | \frac{1}{2}
+    /// > | $$
+    ///     ^^
+    /// ```
+    MathFlowFenceSequence,
+    /// A math (flow) chunk.
+    ///
+    /// ```markdown
+    ///   | $$
+    /// > | \frac{1}{2}
+    ///     ^^^^^^^^^^^
+    ///   | $$
+    /// ```
+    MathFlowChunk,
+    /// Whole math (text).
+    ///
+    /// ```markdown
+    /// > | a $b$ c
+    ///       ^^^
+    /// ```
+    MathText,
+    /// Math (text) data.
+    ///
+    /// ```markdown
+    /// > | a `b` c
+    ///        ^
+    /// ```
+    MathTextData,
+    /// Math (text) sequence.
+    ///
+    /// ```markdown
+    /// > | a $b$ c
+    ///       ^ ^
+    /// ```
+    MathTextSequence,
+    /// MDX extension: ESM.
+    ///
+    /// ```markdown
+    /// > | import a from 'b'
+    ///     ^^^^^^^^^^^^^^^^^
+    /// ```
+    MdxEsm,
+    /// MDX extension: ESM data.
+    ///
+    /// ```markdown
+    /// > | import a from 'b'
+    ///     ^^^^^^^^^^^^^^^^^
+    /// ```
+    MdxEsmData,
+    /// MDX extension: expression marker.
+    ///
+    /// ```markdown
+    /// > | {Math.PI}
+    ///     ^       ^
+    /// ```
+    MdxExpressionMarker,
+    /// MDX extension: expression data.
+    ///
+    /// ```markdown
+    /// > | {Math.PI}
+    ///      ^^^^^^^
+    /// ```
+    MdxExpressionData,
+    /// MDX extension: expression (flow).
+    ///
+    /// ```markdown
+    /// > | {Math.PI}
+    ///     ^^^^^^^^^
+    /// ```
+    MdxFlowExpression,
+    /// MDX extension: expression (text).
+    ///
+    /// ```markdown
+    /// > | a {Math.PI} b
+    ///       ^^^^^^^^^
+    /// ```
+    MdxTextExpression,
+    /// MDX extension: JSX (flow).
+    ///
+    /// ```markdown
+    /// > | <B />
+    ///     ^^^^^
+    /// ```
+    MdxJsxFlowTag,
+    /// MDX extension: JSX (text).
+    ///
+    /// ```markdown
+    /// > | a <B /> c
+    ///       ^^^^^
+    /// ```
+    MdxJsxTextTag,
+    /// MDX extension: JSX: ECMAScript whitespace.
+    ///
+    /// ```markdown
+    /// > | a <B /> c
+    ///         ^
+    /// ```
+    MdxJsxEsWhitespace,
+    /// MDX extension: JSX: tag marker.
+    ///
+    /// ```markdown
+    /// > | a <B /> c
+    ///       ^   ^
+    /// ```
+    MdxJsxTagMarker,
+    /// MDX extension: JSX: closing tag marker.
+    ///
+    /// ```markdown
+    /// > | a </B> c
+    ///        ^
+    /// ```
+    MdxJsxTagClosingMarker,
+    /// MDX extension: JSX: tag name.
+    ///
+    /// ```markdown
+    /// > | a <b> c
+    ///        ^
+    /// > | a <b:c> d
+    ///        ^^^
+    /// > | a <b.c> d
+    ///        ^^^
+    /// ```
+    MdxJsxTagName,
+    /// MDX extension: JSX: primary tag name.
+    ///
+    /// ```markdown
+    /// > | a <b> c
+    ///        ^
+    /// > | a <b:c> d
+    ///        ^
+    /// > | a <b.c> d
+    ///        ^
+    /// ```
+    MdxJsxTagNamePrimary,
+    /// MDX extension: JSX: tag name member marker.
+    ///
+    /// ```markdown
+    /// > | a <b.c> d
+    ///         ^
+    /// ```
+    MdxJsxTagNameMemberMarker,
+    /// MDX extension: JSX: tag name prefix marker.
+    ///
+    /// ```markdown
+    /// > | a <b:c> d
+    ///         ^
+    /// ```
+    MdxJsxTagNamePrefixMarker,
+    /// MDX extension: JSX: tag name member.
+    ///
+    /// ```markdown
+    /// > | a <b.c> d
+    ///          ^
+    /// ```
+    MdxJsxTagNameMember,
+    /// MDX extension: JSX: tag name local.
+    ///
+    /// ```markdown
+    /// > | a <b:c> d
+    ///          ^
+    /// ```
+    MdxJsxTagNameLocal,
+    /// MDX extension: JSX: attribute.
+    ///
+    /// ```markdown
+    /// > | a <b c> d
+    ///          ^
+    /// > | a <b c="d"> e
+    ///          ^^^^^
+    /// > | a <b c={d}> e
+    ///          ^^^^^
+    /// ```
+    MdxJsxTagAttribute,
+    /// MDX extension: JSX tag attribute expression.
+    ///
+    /// ```markdown
+    /// > | a <b {Math.PI} /> c
+    ///          ^^^^^^^^^
+    /// ```
+    MdxJsxTagAttributeExpression,
+    /// MDX extension: JSX: attribute name.
+    ///
+    /// ```markdown
+    /// > | a <b c> d
+    ///          ^
+    /// > | a <b c:d="e"> f
+    ///          ^^^
+    /// ```
+    MdxJsxTagAttributeName,
+    /// MDX extension: JSX: primary attribute name.
+    ///
+    /// ```markdown
+    /// > | a <b c> d
+    ///          ^
+    /// > | a <b c:d="e"> f
+    ///          ^
+    /// ```
+    MdxJsxTagAttributePrimaryName,
+    /// MDX extension: JSX: attribute name prefix marker.
+    ///
+    /// ```markdown
+    /// > | a <b c:d="e"> f
+    ///           ^
+    /// ```
+    MdxJsxTagAttributeNamePrefixMarker,
+    /// MDX extension: JSX: local attribute name.
+    ///
+    /// ```markdown
+    /// > | a <b c:d="e"> f
+    ///            ^
+    /// ```
+    MdxJsxTagAttributeNameLocal,
+    /// MDX extension: JSX: attribute initializer marker.
+    ///
+    /// ```markdown
+    /// > | a <b c="d"> e
+    ///           ^
+    /// ```
+    MdxJsxTagAttributeInitializerMarker,
+    /// MDX extension: JSX tag attribute value expression.
+    ///
+    /// ```markdown
+    /// > | a <b c={Math.PI} /> d
+    ///            ^^^^^^^^^
+    /// ```
+    MdxJsxTagAttributeValueExpression,
+    /// MDX extension: JSX: attribute value literal.
+    ///
+    /// ```markdown
+    /// > | a <b c="d"> e
+    ///            ^^^
+    /// ```
+    MdxJsxTagAttributeValueLiteral,
+    /// MDX extension: JSX: attribute value literal marker.
+    ///
+    /// ```markdown
+    /// > | a <b c="d"> e
+    ///            ^ ^
+    /// ```
+    MdxJsxTagAttributeValueLiteralMarker,
+    /// MDX extension: JSX: attribute value literal value.
+    ///
+    /// ```markdown
+    /// > | a <b c="d"> e
+    ///             ^
+    /// ```
+    MdxJsxTagAttributeValueLiteralValue,
+    /// MDX extension: JSX: self-closing tag marker.
+    ///
+    /// ```markdown
+    /// > | a <b /> c
+    ///          ^
+    /// ```
+    MdxJsxTagSelfClosingMarker,
+    /// Paragraph.
+    ///
+    /// ```markdown
+    /// > | a b
+    ///     ^^^
+    /// > | c.
+    ///     ^^
+    /// ```
+    Paragraph,
+    /// Reference.
+    ///
+    /// ```markdown
+    /// > | a ![b][c] d
+    ///           ^^^
+    /// ```
+    Reference,
+    /// Reference marker.
+    ///
+    /// ```markdown
+    /// > | a ![b][c] d
+    ///           ^ ^
+    /// ```
+    ReferenceMarker,
+    /// Reference string.
+    ///
+    /// ```markdown
+    /// > | a ![b][c] d
+    ///            ^
+    /// ```
+    ReferenceString,
+    /// Resource.
+    ///
+    /// ```markdown
+    /// > | a ![b](c "d") e
+    ///           ^^^^^^^
+    /// > | a [b](c) d
+    ///          ^^^
+    /// ```
+    Resource,
+    /// Resource destination.
+    ///
+    /// ```markdown
+    /// > | a ![b](c "d") e
+    ///            ^
+    /// ```
+    ResourceDestination,
+    /// Resource destination literal.
+    ///
+    /// ```markdown
+    /// > | a ![b](<c> "d") e
+    ///            ^^^
+    /// ```
+    ResourceDestinationLiteral,
+    /// Resource destination literal marker.
+    ///
+    /// ```markdown
+    /// > | a ![b](<c> "d") e
+    ///            ^ ^
+    /// ```
+    ResourceDestinationLiteralMarker,
+    /// Resource destination raw.
+    ///
+    /// ```markdown
+    /// > | a ![b](c "d") e
+    ///            ^
+    /// ```
+    ResourceDestinationRaw,
+    /// Resource destination raw.
+    ///
+    /// ```markdown
+    /// > | a ![b](<c> "d") e
+    ///             ^
+    /// > | a ![b](c "d") e
+    ///            ^
+    /// ```
+    ResourceDestinationString,
+    /// Resource marker.
+    ///
+    /// ```markdown
+    /// > | a ![b](c "d") e
+    ///           ^     ^
+    /// ```
+    ResourceMarker,
+    /// Resource title.
+    ///
+    /// ```markdown
+    /// > | a ![b](<c> "d") e
+    ///                ^^^
+    /// ```
+    ResourceTitle,
+    /// Resource title marker.
+    ///
+    /// ```markdown
+    /// > | a ![b](<c> "d") e
+    ///                ^ ^
+    /// ```
+    ResourceTitleMarker,
+    /// Resource title string.
+    ///
+    /// ```markdown
+    /// > | a ![b](<c> "d") e
+    ///                 ^
+    /// ```
+    ResourceTitleString,
+    /// Spaces and tabs.
+    ///
+    /// ```markdown
+    /// > | ␠* * *␠
+    ///     ^ ^ ^ ^
+    /// ```
+    SpaceOrTab,
+    /// Strong.
+    ///
+    /// ```markdown
+    /// > | **a**
+    ///     ^^^^^
+    /// ```
+    Strong,
+    /// Strong sequence.
+    ///
+    /// ```markdown
+    /// > | **a**
+    ///     ^^ ^^
+    /// ```
+    StrongSequence,
+    /// Strong text.
+    ///
+    /// ```markdown
+    /// > | **a**
+    ///       ^
+    /// ```
+    StrongText,
+    /// Whole thematic break.
+    ///
+    /// ```markdown
+    /// > | * * *
+    ///     ^^^^^
+    /// ```
+    ThematicBreak,
+    /// Thematic break sequence.
+    ///
+    /// ```markdown
+    /// > | * * *
+    ///     ^ ^ ^
+    /// ```
+    ThematicBreakSequence,
+}
+
+/// Get the kind of node for events named `name`.
+pub fn from_name(name: &Name) -> SyntaxKind {
+    match name {
+        Name::AttentionSequence => SyntaxKind::AttentionSequence,
+        Name::Autolink => SyntaxKind::Autolink,
+        Name::AutolinkEmail => SyntaxKind::AutolinkEmail,
+        Name::AutolinkMarker => SyntaxKind::AutolinkMarker,
+        Name::AutolinkProtocol => SyntaxKind::AutolinkProtocol,
+        Name::BlankLineEnding => SyntaxKind::BlankLineEnding,
+        Name::BlockQuote => SyntaxKind::BlockQuote,
+        Name::BlockQuoteMarker => SyntaxKind::BlockQuoteMarker,
+        Name::BlockQuotePrefix => SyntaxKind::BlockQuotePrefix,
+        Name::ByteOrderMark => SyntaxKind::ByteOrderMark,
+        Name::CharacterEscape => SyntaxKind::CharacterEscape,
+        Name::CharacterEscapeMarker => SyntaxKind::CharacterEscapeMarker,
+        Name::CharacterEscapeValue => SyntaxKind::CharacterEscapeValue,
+        Name::CharacterReference => SyntaxKind::CharacterReference,
+        Name::CharacterReferenceMarker => SyntaxKind::CharacterReferenceMarker,
+        Name::CharacterReferenceMarkerHexadecimal => {
+            SyntaxKind::CharacterReferenceMarkerHexadecimal
+        }
+        Name::CharacterReferenceMarkerNumeric => SyntaxKind::CharacterReferenceMarkerNumeric,
+        Name::CharacterReferenceMarkerSemi => SyntaxKind::CharacterReferenceMarkerSemi,
+        Name::CharacterReferenceValue => SyntaxKind::CharacterReferenceValue,
+        Name::CodeFenced => SyntaxKind::CodeFenced,
+        Name::CodeFencedFence => SyntaxKind::CodeFencedFence,
+        Name::CodeFencedFenceInfo => SyntaxKind::CodeFencedFenceInfo,
+        Name::CodeFencedFenceMeta => SyntaxKind::CodeFencedFenceMeta,
+        Name::CodeFencedFenceSequence => SyntaxKind::CodeFencedFenceSequence,
+        Name::CodeFlowChunk => SyntaxKind::CodeFlowChunk,
+        Name::CodeIndented => SyntaxKind::CodeIndented,
+        Name::CodeText => SyntaxKind::CodeText,
+        Name::CodeTextData => SyntaxKind::CodeTextData,
+        Name::CodeTextSequence => SyntaxKind::CodeTextSequence,
+        Name::Content => SyntaxKind::Content,
+        Name::Data => SyntaxKind::Data,
+        Name::Definition => SyntaxKind::Definition,
+        Name::DefinitionDestination => SyntaxKind::DefinitionDestination,
+        Name::DefinitionDestinationLiteral => SyntaxKind::DefinitionDestinationLiteral,
+        Name::DefinitionDestinationLiteralMarker => SyntaxKind::DefinitionDestinationLiteralMarker,
+        Name::DefinitionDestinationRaw => SyntaxKind::DefinitionDestinationRaw,
+        Name::DefinitionDestinationString => SyntaxKind::DefinitionDestinationString,
+        Name::DefinitionLabel => SyntaxKind::DefinitionLabel,
+        Name::DefinitionLabelMarker => SyntaxKind::DefinitionLabelMarker,
+        Name::DefinitionLabelString => SyntaxKind::DefinitionLabelString,
+        Name::DefinitionMarker => SyntaxKind::DefinitionMarker,
+        Name::DefinitionTitle => SyntaxKind::DefinitionTitle,
+        Name::DefinitionTitleMarker => SyntaxKind::DefinitionTitleMarker,
+        Name::DefinitionTitleString => SyntaxKind::DefinitionTitleString,
+        Name::Emphasis => SyntaxKind::Emphasis,
+        Name::EmphasisSequence => SyntaxKind::EmphasisSequence,
+        Name::EmphasisText => SyntaxKind::EmphasisText,
+        Name::Frontmatter => SyntaxKind::Frontmatter,
+        Name::FrontmatterChunk => SyntaxKind::FrontmatterChunk,
+        Name::FrontmatterFence => SyntaxKind::FrontmatterFence,
+        Name::FrontmatterSequence => SyntaxKind::FrontmatterSequence,
+        Name::GfmAutolinkLiteralEmail => SyntaxKind::GfmAutolinkLiteralEmail,
+        Name::GfmAutolinkLiteralMailto => SyntaxKind::GfmAutolinkLiteralMailto,
+        Name::GfmAutolinkLiteralProtocol => SyntaxKind::GfmAutolinkLiteralProtocol,
+        Name::GfmAutolinkLiteralWww => SyntaxKind::GfmAutolinkLiteralWww,
+        Name::GfmAutolinkLiteralXmpp => SyntaxKind::GfmAutolinkLiteralXmpp,
+        Name::GfmFootnoteCall => SyntaxKind::GfmFootnoteCall,
+        Name::GfmFootnoteCallLabel => SyntaxKind::GfmFootnoteCallLabel,
+        Name::GfmFootnoteCallMarker => SyntaxKind::GfmFootnoteCallMarker,
+        Name::GfmFootnoteDefinition => SyntaxKind::GfmFootnoteDefinition,
+        Name::GfmFootnoteDefinitionPrefix => SyntaxKind::GfmFootnoteDefinitionPrefix,
+        Name::GfmFootnoteDefinitionLabel => SyntaxKind::GfmFootnoteDefinitionLabel,
+        Name::GfmFootnoteDefinitionLabelMarker => SyntaxKind::GfmFootnoteDefinitionLabelMarker,
+        Name::GfmFootnoteDefinitionLabelString => SyntaxKind::GfmFootnoteDefinitionLabelString,
+        Name::GfmFootnoteDefinitionMarker => SyntaxKind::GfmFootnoteDefinitionMarker,
+        Name::GfmStrikethrough => SyntaxKind::GfmStrikethrough,
+        Name::GfmStrikethroughSequence => SyntaxKind::GfmStrikethroughSequence,
+        Name::GfmStrikethroughText => SyntaxKind::GfmStrikethroughText,
+        Name::GfmTable => SyntaxKind::GfmTable,
+        Name::GfmTableBody => SyntaxKind::GfmTableBody,
+        Name::GfmTableCell => SyntaxKind::GfmTableCell,
+        Name::GfmTableCellText => SyntaxKind::GfmTableCellText,
+        Name::GfmTableCellDivider => SyntaxKind::GfmTableCellDivider,
+        Name::GfmTableDelimiterRow => SyntaxKind::GfmTableDelimiterRow,
+        Name::GfmTableDelimiterMarker => SyntaxKind::GfmTableDelimiterMarker,
+        Name::GfmTableDelimiterCell => SyntaxKind::GfmTableDelimiterCell,
+        Name::GfmTableDelimiterCellValue => SyntaxKind::GfmTableDelimiterCellValue,
+        Name::GfmTableDelimiterFiller => SyntaxKind::GfmTableDelimiterFiller,
+        Name::GfmTableHead => SyntaxKind::GfmTableHead,
+        Name::GfmTableRow => SyntaxKind::GfmTableRow,
+        Name::GfmTaskListItemCheck => SyntaxKind::GfmTaskListItemCheck,
+        Name::GfmTaskListItemMarker => SyntaxKind::GfmTaskListItemMarker,
+        Name::GfmTaskListItemValueChecked => SyntaxKind::GfmTaskListItemValueChecked,
+        Name::GfmTaskListItemValueUnchecked => SyntaxKind::GfmTaskListItemValueUnchecked,
+        Name::HardBreakEscape => SyntaxKind::HardBreakEscape,
+        Name::HardBreakTrailing => SyntaxKind::HardBreakTrailing,
+        Name::HeadingAtx => SyntaxKind::HeadingAtx,
+        Name::HeadingAtxSequence => SyntaxKind::HeadingAtxSequence,
+        Name::HeadingAtxText => SyntaxKind::HeadingAtxText,
+        Name::HeadingSetext => SyntaxKind::HeadingSetext,
+        Name::HeadingSetextText => SyntaxKind::HeadingSetextText,
+        Name::HeadingSetextUnderline => SyntaxKind::HeadingSetextUnderline,
+        Name::HeadingSetextUnderlineSequence => SyntaxKind::HeadingSetextUnderlineSequence,
+        Name::HtmlFlow => SyntaxKind::HtmlFlow,
+        Name::HtmlFlowData => SyntaxKind::HtmlFlowData,
+        Name::HtmlText => SyntaxKind::HtmlText,
+        Name::HtmlTextData => SyntaxKind::HtmlTextData,
+        Name::Image => SyntaxKind::Image,
+        Name::Label => SyntaxKind::Label,
+        Name::LabelEnd => SyntaxKind::LabelEnd,
+        Name::LabelImage => SyntaxKind::LabelImage,
+        Name::LabelImageMarker => SyntaxKind::LabelImageMarker,
+        Name::LabelLink => SyntaxKind::LabelLink,
+        Name::LabelMarker => SyntaxKind::LabelMarker,
+        Name::LabelText => SyntaxKind::LabelText,
+        Name::LineEnding => SyntaxKind::LineEnding,
+        Name::Link => SyntaxKind::Link,
+        Name::ListItem => SyntaxKind::ListItem,
+        Name::ListItemMarker => SyntaxKind::ListItemMarker,
+        Name::ListItemPrefix => SyntaxKind::ListItemPrefix,
+        Name::ListItemValue => SyntaxKind::ListItemValue,
+        Name::ListOrdered => SyntaxKind::ListOrdered,
+        Name::ListUnordered => SyntaxKind::ListUnordered,
+        Name::MathFlow => SyntaxKind::MathFlow,
+        Name::MathFlowFence => SyntaxKind::MathFlowFence,
+        Name::MathFlowFenceMeta => SyntaxKind::MathFlowFenceMeta,
+        Name::MathFlowFenceSequence => SyntaxKind::MathFlowFenceSequence,
+        Name::MathFlowChunk => SyntaxKind::MathFlowChunk,
+        Name::MathText => SyntaxKind::MathText,
+        Name::MathTextData => SyntaxKind::MathTextData,
+        Name::MathTextSequence => SyntaxKind::MathTextSequence,
+        Name::MdxEsm => SyntaxKind::MdxEsm,
+        Name::MdxEsmData => SyntaxKind::MdxEsmData,
+        Name::MdxExpressionMarker => SyntaxKind::MdxExpressionMarker,
+        Name::MdxExpressionData => SyntaxKind::MdxExpressionData,
+        Name::MdxFlowExpression => SyntaxKind::MdxFlowExpression,
+        Name::MdxTextExpression => SyntaxKind::MdxTextExpression,
+        Name::MdxJsxFlowTag => SyntaxKind::MdxJsxFlowTag,
+        Name::MdxJsxTextTag => SyntaxKind::MdxJsxTextTag,
+        Name::MdxJsxEsWhitespace => SyntaxKind::MdxJsxEsWhitespace,
+        Name::MdxJsxTagMarker => SyntaxKind::MdxJsxTagMarker,
+        Name::MdxJsxTagClosingMarker => SyntaxKind::MdxJsxTagClosingMarker,
+        Name::MdxJsxTagName => SyntaxKind::MdxJsxTagName,
+        Name::MdxJsxTagNamePrimary => SyntaxKind::MdxJsxTagNamePrimary,
+        Name::MdxJsxTagNameMemberMarker => SyntaxKind::MdxJsxTagNameMemberMarker,
+        Name::MdxJsxTagNamePrefixMarker => SyntaxKind::MdxJsxTagNamePrefixMarker,
+        Name::MdxJsxTagNameMember => SyntaxKind::MdxJsxTagNameMember,
+        Name::MdxJsxTagNameLocal => SyntaxKind::MdxJsxTagNameLocal,
+        Name::MdxJsxTagAttribute => SyntaxKind::MdxJsxTagAttribute,
+        Name::MdxJsxTagAttributeExpression => SyntaxKind::MdxJsxTagAttributeExpression,
+        Name::MdxJsxTagAttributeName => SyntaxKind::MdxJsxTagAttributeName,
+        Name::MdxJsxTagAttributePrimaryName => SyntaxKind::MdxJsxTagAttributePrimaryName,
+        Name::MdxJsxTagAttributeNamePrefixMarker => SyntaxKind::MdxJsxTagAttributeNamePrefixMarker,
+        Name::MdxJsxTagAttributeNameLocal => SyntaxKind::MdxJsxTagAttributeNameLocal,
+        Name::MdxJsxTagAttributeInitializerMarker => {
+            SyntaxKind::MdxJsxTagAttributeInitializerMarker
+        }
+        Name::MdxJsxTagAttributeValueExpression => SyntaxKind::MdxJsxTagAttributeValueExpression,
+        Name::MdxJsxTagAttributeValueLiteral => SyntaxKind::MdxJsxTagAttributeValueLiteral,
+        Name::MdxJsxTagAttributeValueLiteralMarker => {
+            SyntaxKind::MdxJsxTagAttributeValueLiteralMarker
+        }
+        Name::MdxJsxTagAttributeValueLiteralValue => {
+            SyntaxKind::MdxJsxTagAttributeValueLiteralValue
+        }
+        Name::MdxJsxTagSelfClosingMarker => SyntaxKind::MdxJsxTagSelfClosingMarker,
+        Name::Paragraph => SyntaxKind::Paragraph,
+        Name::Reference => SyntaxKind::Reference,
+        Name::ReferenceMarker => SyntaxKind::ReferenceMarker,
+        Name::ReferenceString => SyntaxKind::ReferenceString,
+        Name::Resource => SyntaxKind::Resource,
+        Name::ResourceDestination => SyntaxKind::ResourceDestination,
+        Name::ResourceDestinationLiteral => SyntaxKind::ResourceDestinationLiteral,
+        Name::ResourceDestinationLiteralMarker => SyntaxKind::ResourceDestinationLiteralMarker,
+        Name::ResourceDestinationRaw => SyntaxKind::ResourceDestinationRaw,
+        Name::ResourceDestinationString => SyntaxKind::ResourceDestinationString,
+        Name::ResourceMarker => SyntaxKind::ResourceMarker,
+        Name::ResourceTitle => SyntaxKind::ResourceTitle,
+        Name::ResourceTitleMarker => SyntaxKind::ResourceTitleMarker,
+        Name::ResourceTitleString => SyntaxKind::ResourceTitleString,
+        Name::SpaceOrTab => SyntaxKind::SpaceOrTab,
+        Name::Strong => SyntaxKind::Strong,
+        Name::StrongSequence => SyntaxKind::StrongSequence,
+        Name::StrongText => SyntaxKind::StrongText,
+        Name::ThematicBreak => SyntaxKind::ThematicBreak,
+        Name::ThematicBreakSequence => SyntaxKind::ThematicBreakSequence,
+    }
+}
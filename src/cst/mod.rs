@@ -0,0 +1,563 @@
+//! Lossless concrete syntax tree.
+//!
+//! mdast drops what is not needed to know what a document means: markers,
+//! whitespace, escapes, and choices such as `*` or `_`.
+//! [`parse()`][] instead turns markdown into a [`SyntaxTree`][] in which
+//! every byte of the input belongs to exactly one token, so tools can change
+//! markdown while keeping the rest byte-for-byte as the author wrote it.
+//!
+//! Nodes have a [`SyntaxKind`][], such as [`SyntaxKind::ListItemMarker`][],
+//! [`SyntaxKind::HeadingAtxSequence`][], [`SyntaxKind::CodeFencedFenceInfo`][],
+//! and [`SyntaxKind::CharacterEscape`][].
+//! Nodes without children are tokens.
+//! Nodes only have byte offsets, use [`SyntaxNode::text`][] to get their
+//! source.
+//! Typed views, such as [`ListItem`][] and [`CodeFenced`][], give access to
+//! the parts of common constructs.
+//!
+//! ## Examples
+//!
+//! ```
+//! use markdown::cst::{parse, SyntaxKind};
+//! # fn main() -> Result<(), String> {
+//! let value = "* a\n* \\*b";
+//! let tree = parse(value, &Default::default())?;
+//!
+//! let markers: Vec<_> = tree
+//!     .find_all(SyntaxKind::ListItemMarker)
+//!     .iter()
+//!     .map(|d| d.text(value))
+//!     .collect();
+//! assert_eq!(markers, vec!["*", "*"]);
+//!
+//! let tokens: String = tree.tokens().iter().map(|d| d.text(value)).collect();
+//! assert_eq!(tokens, value);
+//! # Ok(())
+//! # }
+//! ```
+
+mod kind;
+
+pub use kind::SyntaxKind;
+
+use crate::event::{Event, Kind};
+use crate::parser::parse as parse_events;
+use crate::ParseOptions;
+use alloc::{string::String, vec, vec::Vec};
+use kind::from_name;
+
+/// Node in a concrete syntax tree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyntaxNode {
+    /// What this is.
+    pub kind: SyntaxKind,
+    /// Byte offset where this starts in the document.
+    pub start: usize,
+    /// Byte offset where this ends in the document.
+    pub end: usize,
+    /// Nodes in this node; empty for tokens.
+    pub children: Vec<SyntaxNode>,
+}
+
+/// Concrete syntax tree of a document.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyntaxTree {
+    /// Top-level nodes.
+    pub children: Vec<SyntaxNode>,
+}
+
+impl SyntaxNode {
+    /// Whether this is a token (it has no children).
+    #[must_use]
+    pub fn is_token(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Get the source of this node in `value`, the document it was parsed
+    /// from.
+    #[must_use]
+    pub fn text<'a>(&self, value: &'a str) -> &'a str {
+        &value[self.start..self.end]
+    }
+
+    /// Get the first child of `kind`.
+    #[must_use]
+    pub fn child(&self, kind: SyntaxKind) -> Option<&SyntaxNode> {
+        self.children.iter().find(|child| child.kind == kind)
+    }
+
+    /// Get the first descendant of `kind`, in tree order.
+    #[must_use]
+    pub fn find(&self, kind: SyntaxKind) -> Option<&SyntaxNode> {
+        find(&self.children, kind)
+    }
+
+    /// Get all descendants of `kind`, in tree order.
+    #[must_use]
+    pub fn find_all(&self, kind: SyntaxKind) -> Vec<&SyntaxNode> {
+        let mut found = vec![];
+        find_all(&self.children, kind, &mut found);
+        found
+    }
+
+    /// Get the tokens in this node, in order (or the node itself, if it is
+    /// a token).
+    #[must_use]
+    pub fn tokens(&self) -> Vec<&SyntaxNode> {
+        let mut tokens = vec![];
+        if self.is_token() {
+            tokens.push(self);
+        } else {
+            collect_tokens(&self.children, &mut tokens);
+        }
+        tokens
+    }
+}
+
+impl SyntaxTree {
+    /// Get the first node of `kind`, in tree order.
+    #[must_use]
+    pub fn find(&self, kind: SyntaxKind) -> Option<&SyntaxNode> {
+        find(&self.children, kind)
+    }
+
+    /// Get all nodes of `kind`, in tree order.
+    #[must_use]
+    pub fn find_all(&self, kind: SyntaxKind) -> Vec<&SyntaxNode> {
+        let mut found = vec![];
+        find_all(&self.children, kind, &mut found);
+        found
+    }
+
+    /// Get all tokens, in order.
+    ///
+    /// Together, they are the whole document.
+    #[must_use]
+    pub fn tokens(&self) -> Vec<&SyntaxNode> {
+        let mut tokens = vec![];
+        collect_tokens(&self.children, &mut tokens);
+        tokens
+    }
+}
+
+/// Turn markdown into a concrete syntax tree.
+///
+/// ## Errors
+///
+/// `parse()` never errors with normal markdown because markdown does not
+/// have syntax errors, so feel free to `unwrap()`.
+/// However, MDX does have syntax errors.
+/// When MDX is turned on, there are several errors that can occur with how
+/// expressions, ESM, and JSX are written.
+pub fn parse(value: &str, options: &ParseOptions) -> Result<SyntaxTree, String> {
+    let (events, _) = parse_events(value, options)?;
+    let mut at = 0;
+    let mut children = normalize(nest(&events), &mut at);
+
+    if at < value.len() {
+        children.push(gap(at, value.len()));
+    }
+
+    Ok(SyntaxTree { children })
+}
+
+/// Turn events into nodes.
+///
+/// The ranges of nodes are not yet checked.
+fn nest(events: &[Event]) -> Vec<SyntaxNode> {
+    let mut stack: Vec<SyntaxNode> = vec![];
+    let mut children = vec![];
+    let mut index = 0;
+
+    while index < events.len() {
+        let event = &events[index];
+
+        if event.kind == Kind::Enter {
+            stack.push(SyntaxNode {
+                kind: from_name(&event.name),
+                start: event.point.index,
+                end: event.point.index,
+                children: vec![],
+            });
+        } else {
+            let mut node = stack.pop().expect("expected open node");
+            debug_assert_eq!(node.kind, from_name(&event.name), "expected matching exit");
+            node.end = event.point.index;
+
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(node),
+                None => children.push(node),
+            }
+        }
+
+        index += 1;
+    }
+
+    children
+}
+
+/// Make sure every byte after `at` belongs to exactly one token.
+///
+/// Events can overlap a bit, such as where tabs are split into virtual
+/// spaces, so empty tokens are dropped, tokens are cut off where an earlier
+/// token ends, and nodes get the range of their tokens.
+fn normalize(children: Vec<SyntaxNode>, at: &mut usize) -> Vec<SyntaxNode> {
+    let mut result = vec![];
+
+    for mut node in children {
+        if node.is_token() {
+            if node.start >= node.end || node.end <= *at {
+                continue;
+            }
+
+            if node.start > *at {
+                result.push(gap(*at, node.start));
+            }
+
+            node.start = node.start.max(*at);
+            *at = node.end;
+            result.push(node);
+        } else {
+            node.children = normalize(core::mem::take(&mut node.children), at);
+
+            if let (Some(first), Some(last)) = (node.children.first(), node.children.last()) {
+                node.start = first.start;
+                node.end = last.end;
+                result.push(node);
+            }
+        }
+    }
+
+    result
+}
+
+/// Make a token for bytes that no construct claims.
+fn gap(start: usize, end: usize) -> SyntaxNode {
+    SyntaxNode {
+        kind: SyntaxKind::Data,
+        start,
+        end,
+        children: vec![],
+    }
+}
+
+/// Get the first node of `kind` in `nodes`.
+fn find(nodes: &[SyntaxNode], kind: SyntaxKind) -> Option<&SyntaxNode> {
+    let mut index = 0;
+
+    while index < nodes.len() {
+        let node = &nodes[index];
+
+        if node.kind == kind {
+            return Some(node);
+        }
+
+        if let Some(found) = find(&node.children, kind) {
+            return Some(found);
+        }
+
+        index += 1;
+    }
+
+    None
+}
+
+/// Add all nodes of `kind` in `nodes` to `found`.
+fn find_all<'a>(nodes: &'a [SyntaxNode], kind: SyntaxKind, found: &mut Vec<&'a SyntaxNode>) {
+    let mut index = 0;
+
+    while index < nodes.len() {
+        if nodes[index].kind == kind {
+            found.push(&nodes[index]);
+        }
+
+        find_all(&nodes[index].children, kind, found);
+        index += 1;
+    }
+}
+
+/// Add all tokens in `nodes` to `tokens`.
+fn collect_tokens<'a>(nodes: &'a [SyntaxNode], tokens: &mut Vec<&'a SyntaxNode>) {
+    let mut index = 0;
+
+    while index < nodes.len() {
+        if nodes[index].is_token() {
+            tokens.push(&nodes[index]);
+        } else {
+            collect_tokens(&nodes[index].children, tokens);
+        }
+
+        index += 1;
+    }
+}
+
+/// Define a typed view of a node.
+macro_rules! typed {
+    ($(#[$meta:meta])* $view:ident, $kind:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        pub struct $view<'a> {
+            syntax: &'a SyntaxNode,
+        }
+
+        impl<'a> $view<'a> {
+            #[doc = concat!("View `node` as this, if it is a [`SyntaxKind::", stringify!($kind), "`][].")]
+            #[must_use]
+            pub fn cast(node: &'a SyntaxNode) -> Option<Self> {
+                if node.kind == SyntaxKind::$kind {
+                    Some(Self { syntax: node })
+                } else {
+                    None
+                }
+            }
+
+            /// Get the node.
+            #[must_use]
+            pub fn syntax(&self) -> &'a SyntaxNode {
+                self.syntax
+            }
+        }
+    };
+}
+
+typed!(
+    /// List item.
+    ///
+    /// ```markdown
+    /// > | 1. [x] a
+    ///     ^^^^^^^^
+    /// ```
+    ListItem,
+    ListItem
+);
+
+impl<'a> ListItem<'a> {
+    /// Get the marker (`*`, `+`, `-`, `.`, or `)`).
+    #[must_use]
+    pub fn marker(&self) -> Option<&'a SyntaxNode> {
+        self.prefix()?.child(SyntaxKind::ListItemMarker)
+    }
+
+    /// Get the value of an ordered list item (`1`).
+    #[must_use]
+    pub fn value(&self) -> Option<&'a SyntaxNode> {
+        self.prefix()?.child(SyntaxKind::ListItemValue)
+    }
+
+    /// Get the prefix (value, marker, and whitespace after it).
+    #[must_use]
+    pub fn prefix(&self) -> Option<&'a SyntaxNode> {
+        self.syntax.child(SyntaxKind::ListItemPrefix)
+    }
+
+    /// Get the GFM task list item check (`[x]`).
+    #[must_use]
+    pub fn check(&self) -> Option<&'a SyntaxNode> {
+        self.syntax
+            .children
+            .get(1)
+            .and_then(|first| first.children.first())
+            .filter(|check| check.kind == SyntaxKind::GfmTaskListItemCheck)
+    }
+}
+
+typed!(
+    /// Heading (atx).
+    ///
+    /// ```markdown
+    /// > | ## a ##
+    ///     ^^^^^^^
+    /// ```
+    HeadingAtx,
+    HeadingAtx
+);
+
+impl<'a> HeadingAtx<'a> {
+    /// Get the opening sequence (`##`).
+    #[must_use]
+    pub fn opening_sequence(&self) -> Option<&'a SyntaxNode> {
+        self.syntax
+            .children
+            .first()
+            .filter(|node| node.kind == SyntaxKind::HeadingAtxSequence)
+    }
+
+    /// Get the closing sequence (`##`), if there is one.
+    #[must_use]
+    pub fn closing_sequence(&self) -> Option<&'a SyntaxNode> {
+        self.syntax
+            .children
+            .iter()
+            .skip(1)
+            .find(|node| node.kind == SyntaxKind::HeadingAtxSequence)
+    }
+
+    /// Get the text.
+    #[must_use]
+    pub fn text(&self) -> Option<&'a SyntaxNode> {
+        self.syntax.child(SyntaxKind::HeadingAtxText)
+    }
+
+    /// Get the rank (`1` to `6`).
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.opening_sequence()
+            .map_or(0, |sequence| sequence.end - sequence.start)
+    }
+}
+
+typed!(
+    /// Code (fenced).
+    ///
+    /// ```markdown
+    /// > | ~~~js eval
+    ///     ^^^^^^^^^^
+    /// > | a
+    ///     ^
+    /// > | ~~~
+    ///     ^^^
+    /// ```
+    CodeFenced,
+    CodeFenced
+);
+
+impl<'a> CodeFenced<'a> {
+    /// Get the opening fence.
+    #[must_use]
+    pub fn opening_fence(&self) -> Option<&'a SyntaxNode> {
+        self.syntax.child(SyntaxKind::CodeFencedFence)
+    }
+
+    /// Get the closing fence, if there is one.
+    #[must_use]
+    pub fn closing_fence(&self) -> Option<&'a SyntaxNode> {
+        self.syntax
+            .children
+            .iter()
+            .filter(|node| node.kind == SyntaxKind::CodeFencedFence)
+            .nth(1)
+    }
+
+    /// Get the sequence of the opening fence (`~~~`).
+    #[must_use]
+    pub fn sequence(&self) -> Option<&'a SyntaxNode> {
+        self.opening_fence()?
+            .child(SyntaxKind::CodeFencedFenceSequence)
+    }
+
+    /// Get the info string (`js`).
+    #[must_use]
+    pub fn info(&self) -> Option<&'a SyntaxNode> {
+        self.opening_fence()?.child(SyntaxKind::CodeFencedFenceInfo)
+    }
+
+    /// Get the meta string (`eval`).
+    #[must_use]
+    pub fn meta(&self) -> Option<&'a SyntaxNode> {
+        self.opening_fence()?.child(SyntaxKind::CodeFencedFenceMeta)
+    }
+
+    /// Get the lines of code.
+    #[must_use]
+    pub fn chunks(&self) -> Vec<&'a SyntaxNode> {
+        self.syntax
+            .children
+            .iter()
+            .filter(|node| node.kind == SyntaxKind::CodeFlowChunk)
+            .collect()
+    }
+}
+
+typed!(
+    /// Character escape.
+    ///
+    /// ```markdown
+    /// > | a \* b
+    ///       ^^
+    /// ```
+    CharacterEscape,
+    CharacterEscape
+);
+
+impl<'a> CharacterEscape<'a> {
+    /// Get the marker (`\`).
+    #[must_use]
+    pub fn marker(&self) -> Option<&'a SyntaxNode> {
+        self.syntax.child(SyntaxKind::CharacterEscapeMarker)
+    }
+
+    /// Get the escaped character (`*`).
+    #[must_use]
+    pub fn value(&self) -> Option<&'a SyntaxNode> {
+        self.syntax.child(SyntaxKind::CharacterEscapeValue)
+    }
+}
+
+typed!(
+    /// Character reference.
+    ///
+    /// ```markdown
+    /// > | a &amp; b
+    ///       ^^^^^
+    /// ```
+    CharacterReference,
+    CharacterReference
+);
+
+impl<'a> CharacterReference<'a> {
+    /// Get the value (`amp`, `#123`, or `#x7B`, without `#` and `x`).
+    #[must_use]
+    pub fn value(&self) -> Option<&'a SyntaxNode> {
+        self.syntax.child(SyntaxKind::CharacterReferenceValue)
+    }
+}
+
+typed!(
+    /// Emphasis.
+    ///
+    /// ```markdown
+    /// > | a _b_ c
+    ///       ^^^
+    /// ```
+    Emphasis,
+    Emphasis
+);
+
+impl<'a> Emphasis<'a> {
+    /// Get the opening sequence (`*` or `_`).
+    #[must_use]
+    pub fn sequence(&self) -> Option<&'a SyntaxNode> {
+        self.syntax.child(SyntaxKind::EmphasisSequence)
+    }
+
+    /// Get the text.
+    #[must_use]
+    pub fn text(&self) -> Option<&'a SyntaxNode> {
+        self.syntax.child(SyntaxKind::EmphasisText)
+    }
+}
+
+typed!(
+    /// Strong.
+    ///
+    /// ```markdown
+    /// > | a __b__ c
+    ///       ^^^^^
+    /// ```
+    Strong,
+    Strong
+);
+
+impl<'a> Strong<'a> {
+    /// Get the opening sequence (`**` or `__`).
+    #[must_use]
+    pub fn sequence(&self) -> Option<&'a SyntaxNode> {
+        self.syntax.child(SyntaxKind::StrongSequence)
+    }
+
+    /// Get the text.
+    #[must_use]
+    pub fn text(&self) -> Option<&'a SyntaxNode> {
+        self.syntax.child(SyntaxKind::StrongText)
+    }
+}
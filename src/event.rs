@@ -1208,6 +1208,7 @@ pub enum Name {
     /// ```markdown
     /// > | [^a]: b
     ///     ^  ^
+    /// ```
     GfmFootnoteDefinitionLabelMarker,
     /// GFM extension: footnote definition label string.
     ///
@@ -1225,6 +1226,7 @@ pub enum Name {
     /// ```markdown
     /// > | [^a]: b
     ///       ^
+    /// ```
     GfmFootnoteDefinitionLabelString,
     /// GFM extension: footnote definition marker.
     ///
@@ -1242,6 +1244,7 @@ pub enum Name {
     /// ```markdown
     /// > | [^a]: b
     ///      ^
+    /// ```
     GfmFootnoteDefinitionMarker,
     /// GFM extension: Strikethrough.
     ///
@@ -3262,7 +3265,7 @@ pub enum Name {
     ///                 ^
     /// ```
     ResourceTitleString,
-    /// SpaceOrTab.
+    /// Spaces and tabs.
     ///
     /// ## Info
     ///
@@ -0,0 +1,214 @@
+//! Format markdown.
+
+use crate::mdast::{AttributeContent, AttributeValue, Node};
+use crate::to_markdown::{line_endings, serialize};
+use crate::{to_mdast, FormatOptions};
+use alloc::{format, string::String, vec::Vec};
+
+/// Format markdown.
+///
+/// Parses `value` and serializes it again in a consistent style: which
+/// markers are used for lists and attention, how headings look, whether
+/// tables are aligned, where definitions are, and where lines are wrapped,
+/// can be configured with [`FormatOptions`][].
+/// Frontmatter and MDX (ESM, expressions, JSX) are kept as they were.
+///
+/// When `value` cannot be formatted, it is returned as-is.
+/// Use [`try_format()`][] to know when, and why, that happens.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{format, FormatOptions};
+///
+/// assert_eq!(
+///     format("Hey, _you_!\n\n+ a\n+ b\n\nc\n=", &FormatOptions::default()),
+///     "Hey, *you*!\n\n- a\n- b\n\n# c\n"
+/// );
+/// ```
+pub fn format(value: &str, options: &FormatOptions) -> String {
+    try_format(value, options).unwrap_or_else(|_| value.into())
+}
+
+/// Format markdown, or get why that is not possible.
+///
+/// Like [`format()`][], but errors instead of returning `value` as-is.
+///
+/// The result is parsed again and compared to the original syntax tree
+/// (ignoring positions, and, when wrapping, whether words are separated by
+/// spaces or line endings).
+///
+/// ## Errors
+///
+/// `try_format()` errors when `value` cannot be parsed (which can happen
+/// with MDX), and when the result would have a different syntax tree, with
+/// the place of the first top-level node that would change.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{try_format, FormatOptions, ParseOptions};
+/// # fn main() -> Result<(), String> {
+///
+/// assert_eq!(
+///     try_format("+ _a_", &FormatOptions::default())?,
+///     "- *a*\n"
+/// );
+///
+/// let gfm = FormatOptions {
+///     parse: ParseOptions::gfm(),
+///     ..FormatOptions::default()
+/// };
+///
+/// assert_eq!(
+///     try_format("a\n\n[www.c~](d)", &gfm),
+///     Err("3:1: Unexpected different syntax tree after formatting, expected the same tree".into())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn try_format(value: &str, options: &FormatOptions) -> Result<String, String> {
+    // Line endings are changed in the input, so that the trees match.
+    let normalized;
+    let value = if let Some(line_ending) = &options.line_ending {
+        normalized = line_endings(value, line_ending.as_str());
+        &normalized
+    } else {
+        value
+    };
+    let mut tree = to_mdast(value, &options.parse)?;
+
+    if options.sort_definitions {
+        sort_definitions(&mut tree);
+    }
+
+    let result = serialize(&tree, options, Some(value));
+    let wrap = options.line_width.is_some();
+    // Where each top-level node starts, as positions are stripped below.
+    let starts = tree.children().map_or(Vec::new(), |children| {
+        children
+            .iter()
+            .map(|child| child.position().map(|position| position.start.clone()))
+            .collect()
+    });
+    strip(&mut tree, wrap);
+
+    let mut again = to_mdast(&result, &options.parse).ok();
+
+    if let Some(again) = &mut again {
+        strip(again, wrap);
+
+        if *again == tree {
+            return Ok(result);
+        }
+    }
+
+    // Find the first top-level node that changed.
+    let children = tree.children().map_or(&[][..], Vec::as_slice);
+    let children_again = again
+        .as_ref()
+        .and_then(Node::children)
+        .map_or(&[][..], Vec::as_slice);
+    let mut index = 0;
+
+    while index < children.len()
+        && index < children_again.len()
+        && children[index] == children_again[index]
+    {
+        index += 1;
+    }
+
+    let place = match starts.get(index) {
+        Some(Some(point)) => format!("{}:{}", point.line, point.column),
+        _ => "1:1".into(),
+    };
+
+    Err(format!(
+        "{}: Unexpected different syntax tree after formatting, expected the same tree",
+        place
+    ))
+}
+
+/// Move definitions in the root to the end, ordered by where they are first
+/// referenced.
+fn sort_definitions(tree: &mut Node) {
+    let mut identifiers = Vec::new();
+    references(tree, &mut identifiers);
+
+    if let Some(children) = tree.children_mut() {
+        let (mut definitions, rest): (Vec<_>, Vec<_>) = children
+            .drain(..)
+            .partition(|child| matches!(child, Node::Definition(_)));
+        definitions.sort_by_key(|child| match child {
+            Node::Definition(x) => identifiers
+                .iter()
+                .position(|d| *d == x.identifier)
+                .unwrap_or(identifiers.len()),
+            _ => identifiers.len(),
+        });
+        children.extend(rest);
+        children.extend(definitions);
+    }
+}
+
+/// Collect identifiers of references, in order.
+fn references(node: &Node, identifiers: &mut Vec<String>) {
+    match node {
+        Node::LinkReference(x) => identifiers.push(x.identifier.clone()),
+        Node::ImageReference(x) => identifiers.push(x.identifier.clone()),
+        _ => {}
+    }
+
+    if let Some(children) = node.children() {
+        let mut index = 0;
+        while index < children.len() {
+            references(&children[index], identifiers);
+            index += 1;
+        }
+    }
+}
+
+/// Remove positions from a tree, including those of MDX stops.
+///
+/// When `wrap`, line endings in text are turned into spaces.
+fn strip(node: &mut Node, wrap: bool) {
+    node.position_set(None);
+
+    match node {
+        Node::Text(x) if wrap => {
+            x.value = x.value.replace("\r\n", " ").replace(['\r', '\n'], " ");
+        }
+        Node::MdxjsEsm(x) => x.stops.clear(),
+        Node::MdxFlowExpression(x) => x.stops.clear(),
+        Node::MdxTextExpression(x) => x.stops.clear(),
+        Node::MdxJsxFlowElement(x) => strip_attributes(&mut x.attributes),
+        Node::MdxJsxTextElement(x) => strip_attributes(&mut x.attributes),
+        _ => {}
+    }
+
+    if let Some(children) = node.children_mut() {
+        let mut index = 0;
+        while index < children.len() {
+            strip(&mut children[index], wrap);
+            index += 1;
+        }
+    }
+}
+
+/// Remove stops from JSX attributes.
+fn strip_attributes(attributes: &mut [AttributeContent]) {
+    let mut index = 0;
+
+    while index < attributes.len() {
+        match &mut attributes[index] {
+            AttributeContent::Expression { stops, .. } => stops.clear(),
+            AttributeContent::Property(property) => {
+                if let Some(AttributeValue::Expression(value)) = &mut property.value {
+                    value.stops.clear();
+                }
+            }
+        }
+
+        index += 1;
+    }
+}
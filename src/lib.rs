@@ -9,8 +9,36 @@
 //!     — like `to_html` but lets you configure how markdown is turned into
 //!     HTML, such as allowing dangerous HTML or turning on/off different
 //!     constructs (GFM, MDX, and the like)
+//! *   [`to_html_into()`][]
+//!     — like `to_html_with_options` but writes the HTML to a
+//!     [`fmt::Write`][] as it goes
 //! *   [`to_mdast()`][]
 //!     — turn markdown into a syntax tree
+//! *   [`Parser`][]
+//!     — turn chunks of markdown into a syntax tree, getting top-level nodes
+//!     as soon as they are done
+//! *   [`reparse()`][]
+//!     — turn markdown into a syntax tree again after an edit, reusing
+//!     the parts of the previous tree that did not change
+//! *   [`to_markdown()`][]
+//!     — turn a syntax tree back into markdown
+//!     ([`to_markdown_with_options()`][] configures how)
+//! *   [`format()`][]
+//!     — format markdown in a consistent style
+//!     ([`try_format()`][] errors when that is not possible)
+//! *   [`cst::parse()`][]
+//!     — turn markdown into a lossless concrete syntax tree
+//! *   [`lint::lint()`][]
+//!     — check markdown for common problems
+//!     ([`lint::lint_tree()`][] checks a syntax tree instead)
+//! *   [`visit::visit()`][] and [`visit::visit_mut()`][]
+//!     — walk a syntax tree, optionally changing it
+//! *   [`references::resolve_references()`][]
+//!     — pair references with definitions in a syntax tree
+//! *   [`select::select()`][] and [`select::select_all()`][]
+//!     — find nodes in a syntax tree with CSS-like selectors
+//! *   [`mdast!`][]
+//!     — create a syntax tree
 //!
 //! ## Features
 //!
@@ -21,6 +49,18 @@
 //! *   **`log`**
 //!     — enable logging (includes `dep:log`);
 //!     you can show logs with `RUST_LOG=debug`
+//! *   **`mathml`**
+//!     — enable turning math into MathML
+//!     (see `CompileOptions::math_mathml`)
+//! *   **`std`**
+//!     — enable writing HTML to an [`io::Write`][std::io::Write]
+//!     (see `to_html_into_io`)
+//! *   **`cli`**
+//!     — build the `markdown` command-line binary
+//!     (includes `std`, `serde`, and `dep:serde_json`)
+//! *   **`lsp`**
+//!     — build the `markdown-lsp` language server binary
+//!     (includes `std` and `dep:serde_json`)
 
 #![no_std]
 #![deny(clippy::pedantic)]
@@ -33,20 +73,31 @@
 )]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 mod configuration;
 mod construct;
 mod event;
+mod format;
 mod parser;
+mod reparse;
 mod resolve;
 mod state;
+mod stream;
 mod subtokenize;
 mod to_html;
+mod to_markdown;
 mod to_mdast;
 mod tokenizer;
 mod util;
 
+pub mod cst;
+pub mod lint;
 pub mod mdast; // To do: externalize?
+pub mod references;
+pub mod select;
 pub mod unist; // To do: externalize.
+pub mod visit;
 
 #[doc(hidden)]
 pub use util::identifier::{id_cont, id_start};
@@ -57,16 +108,29 @@ pub use util::sanitize_uri::sanitize;
 #[doc(hidden)]
 pub use util::location::Location;
 
+pub use util::html_handler::{HtmlHandler, HtmlNode};
+
+pub use util::sanitize_html::SanitizeSchema;
+
 pub use util::line_ending::LineEnding;
 
+pub use format::{format, try_format};
+pub use reparse::{reparse, Edit, Reparse};
+pub use stream::Parser;
+
 pub use util::mdx::{
     EsmParse as MdxEsmParse, ExpressionKind as MdxExpressionKind,
     ExpressionParse as MdxExpressionParse, Signal as MdxSignal,
 };
 
-pub use configuration::{CompileOptions, Constructs, Options, ParseOptions};
+pub use configuration::{
+    CodeBlockRender, CompileOptions, Constructs, FormatOptions, Limits, Options, ParseOptions,
+    PositionEncoding, ShouldAbort, UrlKind, UrlRewrite,
+};
 
-use alloc::string::String;
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use util::normalize_identifier::normalize_identifier;
 
 /// Turn markdown into HTML.
 ///
@@ -129,6 +193,170 @@ pub fn to_html_with_options(value: &str, options: &Options) -> Result<String, St
     ))
 }
 
+/// Turn markdown into HTML, with configuration, writing it to `out`.
+///
+/// Completed top-level blocks (such as paragraphs and lists) are written as
+/// soon as they are compiled, so that the whole HTML does not have to be
+/// kept in memory, and the first bytes are available early.
+/// Only what is still needed later (such as footnote definitions) is kept.
+///
+/// ## Errors
+///
+/// `to_html_into()` errors if `out` errors.
+/// See [`to_html_with_options()`][] for the other errors.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{to_html_into, Options};
+/// # fn main() -> Result<(), String> {
+///
+/// let mut result = String::new();
+/// to_html_into("# Hello, world!", &Options::default(), &mut result)?;
+///
+/// assert_eq!(result, "<h1>Hello, world!</h1>");
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_html_into<W: fmt::Write>(
+    value: &str,
+    options: &Options,
+    out: &mut W,
+) -> Result<(), String> {
+    let (events, parse_state) = parser::parse(value, &options.parse)?;
+    to_html::compile_into(&events, parse_state.bytes, &[], &options.compile, out)
+        .map_err(|_| "Cannot write HTML".into())
+}
+
+/// Turn markdown into HTML, with configuration, writing it to `out`.
+///
+/// Like [`to_html_into()`][], but for [`io::Write`][std::io::Write], such
+/// as files, sockets, or standard output.
+///
+/// This function is only available with the `std` feature.
+///
+/// ## Errors
+///
+/// `to_html_into_io()` errors if `out` errors.
+/// See [`to_html_with_options()`][] for the other errors.
+///
+/// ## Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # fn main() -> Result<(), String> {
+/// use markdown::{to_html_into_io, Options};
+///
+/// let mut result = vec![];
+/// to_html_into_io("# Hello, world!", &Options::default(), &mut result)?;
+///
+/// assert_eq!(result, b"<h1>Hello, world!</h1>");
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "std"))]
+/// # fn main() {}
+/// ```
+#[cfg(feature = "std")]
+pub fn to_html_into_io<W: std::io::Write>(
+    value: &str,
+    options: &Options,
+    out: &mut W,
+) -> Result<(), String> {
+    use alloc::format;
+
+    /// Adapter from `io::Write` to `fmt::Write`, which keeps the error.
+    struct Adapter<'a, W: std::io::Write> {
+        inner: &'a mut W,
+        error: Option<std::io::Error>,
+    }
+
+    impl<W: std::io::Write> fmt::Write for Adapter<'_, W> {
+        fn write_str(&mut self, value: &str) -> fmt::Result {
+            self.inner.write_all(value.as_bytes()).map_err(|error| {
+                self.error = Some(error);
+                fmt::Error
+            })
+        }
+    }
+
+    let mut adapter = Adapter {
+        inner: out,
+        error: None,
+    };
+
+    match to_html_into(value, options, &mut adapter) {
+        Err(message) => Err(if let Some(error) = adapter.error {
+            format!("Cannot write HTML: {}", error)
+        } else {
+            message
+        }),
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Turn a single line of markdown into HTML, with configuration.
+///
+/// Only text (phrasing) content is parsed: there are no flow constructs
+/// (such as headings, lists, or code (fenced)), and there is no paragraph
+/// around the result.
+/// This is useful for titles, labels, and table cells.
+/// Blank lines are kept as line endings, but constructs (such as emphasis)
+/// do not cross them.
+///
+/// As there are no definitions in text, `definitions` can be passed for
+/// references to resolve to (such as from [`to_mdast()`][]).
+/// Their `url` and `title` are used as they are, and `label` is ignored.
+///
+/// ## Errors
+///
+/// `to_html_inline()` never errors with normal markdown because markdown
+/// does not have syntax errors, so feel free to `unwrap()`.
+/// However, MDX does have syntax errors.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{mdast::Definition, to_html_inline, Options};
+/// # fn main() -> Result<(), String> {
+///
+/// assert_eq!(
+///     to_html_inline("# *Hello*, [world]!", &[], &Options::default())?,
+///     "# <em>Hello</em>, [world]!"
+/// );
+///
+/// let definitions = vec![Definition {
+///     url: "https://example.com".into(),
+///     title: None,
+///     identifier: "world".into(),
+///     label: None,
+///     position: None,
+/// }];
+///
+/// assert_eq!(
+///     to_html_inline("- [world]", &definitions, &Options::default())?,
+///     "- <a href=\"https://example.com\">world</a>"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_html_inline(
+    value: &str,
+    definitions: &[mdast::Definition],
+    options: &Options,
+) -> Result<String, String> {
+    let (events, parse_state) = parser::parse_inline(
+        value,
+        &options.parse,
+        definitions_to_identifiers(definitions),
+    )?;
+    Ok(to_html::compile_with_definitions(
+        &events,
+        parse_state.bytes,
+        definitions,
+        &options.compile,
+    ))
+}
+
 /// Turn markdown into a syntax tree.
 ///
 /// ## Errors
@@ -154,6 +382,116 @@ pub fn to_html_with_options(value: &str, options: &Options) -> Result<String, St
 /// ```
 pub fn to_mdast(value: &str, options: &ParseOptions) -> Result<mdast::Node, String> {
     let (events, parse_state) = parser::parse(value, options)?;
-    let node = to_mdast::compile(&events, parse_state.bytes)?;
+    let mut node = to_mdast::compile(&events, parse_state.bytes, options.position_encoding)?;
+    to_mdast::encode_positions(&mut node, parse_state.bytes, options.position_encoding);
+    Ok(node)
+}
+
+/// Turn a single line of markdown into a syntax tree.
+///
+/// Only text (phrasing) content is parsed, so the root contains phrasing
+/// nodes (such as text and emphasis) instead of flow nodes.
+/// `definitions` can be passed for references to resolve to, only their
+/// `identifier` is used.
+/// See [`to_html_inline()`][] for more info.
+///
+/// ## Errors
+///
+/// `to_mdast_inline()` never errors with normal markdown because markdown
+/// does not have syntax errors, so feel free to `unwrap()`.
+/// However, MDX does have syntax errors.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{mdast::Node, to_mdast_inline, ParseOptions};
+/// # fn main() -> Result<(), String> {
+///
+/// let tree = to_mdast_inline("# *a*", &[], &ParseOptions::default())?;
+///
+/// if let Node::Root(root) = tree {
+///     assert_eq!(root.children.len(), 2);
+///     assert!(matches!(root.children[1], Node::Emphasis(_)));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_mdast_inline(
+    value: &str,
+    definitions: &[mdast::Definition],
+    options: &ParseOptions,
+) -> Result<mdast::Node, String> {
+    let (events, parse_state) =
+        parser::parse_inline(value, options, definitions_to_identifiers(definitions))?;
+    let mut node = to_mdast::compile(&events, parse_state.bytes, options.position_encoding)?;
+    to_mdast::encode_positions(&mut node, parse_state.bytes, options.position_encoding);
     Ok(node)
 }
+
+/// Turn a syntax tree into markdown.
+///
+/// The result is meant to parse back into the same tree (ignoring
+/// positions), which is tested with the `CommonMark` examples, but that is
+/// not guaranteed for all trees.
+/// [`try_format()`][] checks it, for when that matters.
+/// Characters that would otherwise turn into syntax are escaped, and
+/// whitespace that would otherwise be stripped is encoded as character
+/// references.
+/// Things that are not kept in the tree, such as which markers, fences, or
+/// indents were used, are normalized.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{to_markdown, to_mdast, ParseOptions};
+/// # fn main() -> Result<(), String> {
+///
+/// let tree = to_mdast("Hey, _you_!\n\n+ a\n+ b", &ParseOptions::default())?;
+///
+/// assert_eq!(to_markdown(&tree), "Hey, *you*!\n\n- a\n- b\n");
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_markdown(tree: &mdast::Node) -> String {
+    to_markdown_with_options(tree, &FormatOptions::default())
+}
+
+/// Turn a syntax tree into markdown, with configuration.
+///
+/// Like [`to_markdown()`][], but with how to serialize configured by
+/// [`FormatOptions`][] (its `parse` field and `sort_definitions` are not
+/// used).
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{to_markdown_with_options, to_mdast, FormatOptions, LineEnding, ParseOptions};
+/// # fn main() -> Result<(), String> {
+///
+/// let tree = to_mdast("* a\r\n* b", &ParseOptions::default())?;
+///
+/// assert_eq!(
+///     to_markdown_with_options(
+///         &tree,
+///         &FormatOptions {
+///             bullet: '*',
+///             line_ending: Some(LineEnding::CarriageReturnLineFeed),
+///             ..FormatOptions::default()
+///         }
+///     ),
+///     "* a\r\n* b\r\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_markdown_with_options(tree: &mdast::Node, options: &FormatOptions) -> String {
+    to_markdown::serialize(tree, options, None)
+}
+
+/// Get normalized identifiers of definitions.
+fn definitions_to_identifiers(definitions: &[mdast::Definition]) -> Vec<String> {
+    definitions
+        .iter()
+        .map(|definition| normalize_identifier(&definition.identifier))
+        .collect()
+}
@@ -0,0 +1,699 @@
+//! Check markdown for common problems.
+//!
+//! [`lint()`][] parses markdown and reports [`Message`][]s for problems such
+//! as skipped heading ranks, references to things that are not defined, or
+//! whitespace at the end of lines.
+//! Which problems are reported, and how severe they are, is configured with
+//! [`Options`][].
+//! The rules are modelled after those of
+//! [`remark-lint`](https://github.com/remarkjs/remark-lint) and
+//! [`markdownlint`](https://github.com/DavidAnson/markdownlint).
+
+use crate::event::{Event, Kind, Name};
+use crate::mdast::Node;
+use crate::parser::parse;
+use crate::to_mdast::compile;
+use crate::unist::{Point, Position};
+use crate::util::{location::Location, normalize_identifier::normalize_identifier};
+use crate::ParseOptions;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// How severe a problem is.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Severity {
+    /// The rule is turned off.
+    Off,
+    /// The problem is reported as a warning.
+    Warning,
+    /// The problem is reported as an error.
+    Error,
+}
+
+/// Rule that found a problem.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Rule {
+    /// URL that is not in a link, or a GFM autolink literal.
+    ///
+    /// ```markdown
+    /// > | See https://example.com.
+    ///         ^^^^^^^^^^^^^^^^^^^
+    /// ```
+    BareUrl,
+    /// Heading with the same text as an earlier heading.
+    ///
+    /// ```markdown
+    ///   | # a
+    /// > | # a
+    ///     ^^^
+    /// ```
+    DuplicateHeading,
+    /// Tab.
+    ///
+    /// ```markdown
+    /// > | a␉b
+    ///      ^
+    /// ```
+    HardTab,
+    /// Heading that is more than one rank below the previous heading.
+    ///
+    /// ```markdown
+    ///   | # a
+    /// > | ### b
+    ///     ^^^^^
+    /// ```
+    HeadingIncrement,
+    /// Image without alternative text.
+    ///
+    /// ```markdown
+    /// > | ![](a.png)
+    ///     ^^^^^^^^^^
+    /// ```
+    ImageAlt,
+    /// List item marker that is different from earlier markers of the same
+    /// kind (unordered or ordered).
+    ///
+    /// ```markdown
+    ///   | - a
+    ///   |
+    /// > | * b
+    ///     ^
+    /// ```
+    ListMarkerStyle,
+    /// Whitespace at the end of a line, that is not a hard break.
+    ///
+    /// ```markdown
+    /// > | a␠
+    ///      ^
+    /// ```
+    TrailingSpace,
+    /// Full reference or footnote call that does not match a definition.
+    ///
+    /// ```markdown
+    /// > | [a][b] [^c]
+    ///        ^^^ ^^^^
+    /// ```
+    UndefinedReference,
+    /// Definition or footnote definition that is not referenced.
+    ///
+    /// ```markdown
+    /// > | [a]: b
+    ///     ^^^^^^
+    /// ```
+    UnusedDefinition,
+}
+
+impl Rule {
+    /// Turn the rule into a [str].
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Rule::BareUrl => "bare-url",
+            Rule::DuplicateHeading => "duplicate-heading",
+            Rule::HardTab => "hard-tab",
+            Rule::HeadingIncrement => "heading-increment",
+            Rule::ImageAlt => "image-alt",
+            Rule::ListMarkerStyle => "list-marker-style",
+            Rule::TrailingSpace => "trailing-space",
+            Rule::UndefinedReference => "undefined-reference",
+            Rule::UnusedDefinition => "unused-definition",
+        }
+    }
+}
+
+/// Problem found in a document.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Message {
+    /// Rule that found the problem.
+    pub rule: Rule,
+    /// How severe the problem is.
+    pub severity: Severity,
+    /// Description of the problem.
+    pub reason: String,
+    /// Place of the problem in the document.
+    ///
+    /// Columns and offsets are counted in the `position_encoding` of
+    /// [`Options::parse`][].
+    pub position: Position,
+}
+
+/// Configuration that describes which rules to use, and how to parse.
+///
+/// Each rule can be turned off with [`Severity::Off`][], or reported as a
+/// warning or an error.
+/// All rules default to warnings.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{lint::{Options, Severity}, ParseOptions};
+/// # fn main() {
+///
+/// // Use the default trait to check `CommonMark` with all rules:
+/// let commonmark = Options::default();
+///
+/// // Configure rules and parsing:
+/// let gfm = Options {
+///     hard_tab: Severity::Off,
+///     undefined_reference: Severity::Error,
+///     parse: ParseOptions::gfm(),
+///     ..Options::default()
+/// };
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Options {
+    /// Severity of [`Rule::BareUrl`][].
+    pub bare_url: Severity,
+    /// Severity of [`Rule::DuplicateHeading`][].
+    pub duplicate_heading: Severity,
+    /// Severity of [`Rule::HardTab`][].
+    pub hard_tab: Severity,
+    /// Severity of [`Rule::HeadingIncrement`][].
+    pub heading_increment: Severity,
+    /// Severity of [`Rule::ImageAlt`][].
+    pub image_alt: Severity,
+    /// Severity of [`Rule::ListMarkerStyle`][].
+    pub list_marker_style: Severity,
+    /// Configuration that describes how to parse from markdown.
+    pub parse: ParseOptions,
+    /// Severity of [`Rule::TrailingSpace`][].
+    pub trailing_space: Severity,
+    /// Severity of [`Rule::UndefinedReference`][].
+    pub undefined_reference: Severity,
+    /// Severity of [`Rule::UnusedDefinition`][].
+    pub unused_definition: Severity,
+}
+
+impl Default for Options {
+    /// All rules as warnings, `CommonMark` parsing.
+    fn default() -> Self {
+        Self {
+            bare_url: Severity::Warning,
+            duplicate_heading: Severity::Warning,
+            hard_tab: Severity::Warning,
+            heading_increment: Severity::Warning,
+            image_alt: Severity::Warning,
+            list_marker_style: Severity::Warning,
+            parse: ParseOptions::default(),
+            trailing_space: Severity::Warning,
+            undefined_reference: Severity::Warning,
+            unused_definition: Severity::Warning,
+        }
+    }
+}
+
+impl Options {
+    /// Get the severity of `rule`.
+    #[must_use]
+    pub fn severity(&self, rule: Rule) -> Severity {
+        match rule {
+            Rule::BareUrl => self.bare_url,
+            Rule::DuplicateHeading => self.duplicate_heading,
+            Rule::HardTab => self.hard_tab,
+            Rule::HeadingIncrement => self.heading_increment,
+            Rule::ImageAlt => self.image_alt,
+            Rule::ListMarkerStyle => self.list_marker_style,
+            Rule::TrailingSpace => self.trailing_space,
+            Rule::UndefinedReference => self.undefined_reference,
+            Rule::UnusedDefinition => self.unused_definition,
+        }
+    }
+}
+
+/// Info used while linting.
+struct Context<'a> {
+    /// Document.
+    value: &'a str,
+    /// Configuration.
+    options: &'a Options,
+    /// Lines in `value`.
+    location: Location,
+    /// Problems found so far.
+    messages: Vec<Message>,
+}
+
+impl Context<'_> {
+    /// Get the point of byte index `offset`, counted in the configured
+    /// encoding.
+    fn point(&self, offset: usize) -> Option<Point> {
+        self.location.to_encoded_point(
+            self.value.as_bytes(),
+            offset,
+            self.options.parse.position_encoding,
+        )
+    }
+
+    /// Report a problem from byte index `start` to `end`.
+    fn report(&mut self, rule: Rule, start: usize, end: usize, reason: String) {
+        let severity = self.options.severity(rule);
+
+        if severity == Severity::Off {
+            return;
+        }
+
+        if let (Some(start), Some(end)) = (self.point(start), self.point(end)) {
+            self.messages.push(Message {
+                rule,
+                severity,
+                reason,
+                position: Position { start, end },
+            });
+        }
+    }
+}
+
+/// Check markdown for problems.
+///
+/// Returns the problems found, ordered by where they start.
+///
+/// ## Errors
+///
+/// `lint()` errors when `value` cannot be parsed, which can only happen for
+/// MDX.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::lint::{lint, Options, Rule};
+/// # fn main() -> Result<(), String> {
+///
+/// let messages = lint("# a\n\n### b", &Options::default())?;
+///
+/// assert_eq!(messages.len(), 1);
+/// assert_eq!(messages[0].rule, Rule::HeadingIncrement);
+/// assert_eq!(
+///     messages[0].reason,
+///     "Unexpected heading rank `3`, expected rank `2`"
+/// );
+/// assert_eq!(messages[0].position.start.line, 3);
+/// # Ok(())
+/// # }
+/// ```
+pub fn lint(value: &str, options: &Options) -> Result<Vec<Message>, String> {
+    let (events, parse_state) = parse(value, &options.parse)?;
+    let tree = compile(&events, parse_state.bytes, options.parse.position_encoding)?;
+    let mut context = Context {
+        value,
+        options,
+        location: Location::new(value.as_bytes()),
+        messages: vec![],
+    };
+
+    list_markers(&mut context, &events);
+    lines(&mut context, &events);
+    Ok(tree_rules(context, &tree))
+}
+
+/// Check a syntax tree of markdown for problems.
+///
+/// Like [`lint()`][], but takes a tree that was already made from `value`,
+/// such as by [`to_mdast()`][crate::to_mdast()] or
+/// [`reparse()`][crate::reparse()], instead of parsing `value` again.
+/// Positions in `tree` must be counted in bytes.
+///
+/// Only rules that look at the tree are checked: [`Rule::HardTab`][],
+/// [`Rule::ListMarkerStyle`][], and [`Rule::TrailingSpace`][] need the
+/// parser, so they are not.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{lint::{lint_tree, Options, Rule}, to_mdast, ParseOptions};
+/// # fn main() -> Result<(), String> {
+///
+/// let value = "[a][b]\t\n";
+/// let tree = to_mdast(value, &ParseOptions::default())?;
+/// let messages = lint_tree(value, &tree, &Options::default());
+///
+/// assert_eq!(messages.len(), 1);
+/// assert_eq!(messages[0].rule, Rule::UndefinedReference);
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub fn lint_tree(value: &str, tree: &Node, options: &Options) -> Vec<Message> {
+    let context = Context {
+        value,
+        options,
+        location: Location::new(value.as_bytes()),
+        messages: vec![],
+    };
+
+    tree_rules(context, tree)
+}
+
+/// Check the rules that look at the tree, and get all problems in order.
+fn tree_rules(mut context: Context, tree: &Node) -> Vec<Message> {
+    let mut nodes = vec![];
+    collect(tree, &mut nodes);
+
+    headings(&mut context, &nodes);
+    references(&mut context, &nodes);
+    urls(&mut context, tree, false);
+    images(&mut context, &nodes);
+
+    // Sorting is stable, so problems at the same place stay in rule order.
+    context
+        .messages
+        .sort_by_key(|message| message.position.start.offset);
+    context.messages
+}
+
+/// Check that list item markers are consistent.
+fn list_markers(context: &mut Context, events: &[Event]) {
+    let bytes = context.value.as_bytes();
+    let mut unordered = None;
+    let mut ordered = None;
+    let mut index = 0;
+
+    while index < events.len() {
+        let event = &events[index];
+
+        if event.kind == Kind::Enter && event.name == Name::ListItemMarker {
+            let offset = event.point.index;
+            let marker = bytes[offset];
+            let expected = if matches!(marker, b'.' | b')') {
+                ordered.get_or_insert(marker)
+            } else {
+                unordered.get_or_insert(marker)
+            };
+
+            if *expected != marker {
+                let reason = format!(
+                    "Unexpected list item marker `{}`, expected `{}`",
+                    char::from(marker),
+                    char::from(*expected)
+                );
+                context.report(Rule::ListMarkerStyle, offset, offset + 1, reason);
+            }
+        }
+
+        index += 1;
+    }
+}
+
+/// Check tabs and whitespace at the end of lines.
+fn lines(context: &mut Context, events: &[Event]) {
+    // Trailing whitespace that forms hard breaks is fine.
+    let mut breaks = vec![];
+    let mut index = 0;
+
+    while index < events.len() {
+        let event = &events[index];
+
+        if event.kind == Kind::Enter && event.name == Name::HardBreakTrailing {
+            breaks.push(event.point.index);
+        }
+
+        index += 1;
+    }
+
+    let bytes = context.value.as_bytes();
+    let mut start = 0;
+    index = 0;
+
+    while index <= bytes.len() {
+        if index == bytes.len() || matches!(bytes[index], b'\n' | b'\r') {
+            let mut end = index;
+
+            while end > start && matches!(bytes[end - 1], b' ' | b'\t') {
+                end -= 1;
+            }
+
+            if end < index && !breaks.contains(&end) {
+                let reason = "Unexpected whitespace at end of line".into();
+                context.report(Rule::TrailingSpace, end, index, reason);
+            }
+
+            let mut tab = start;
+
+            while tab < index {
+                if bytes[tab] == b'\t' {
+                    context.report(Rule::HardTab, tab, tab + 1, "Unexpected tab".into());
+                }
+
+                tab += 1;
+            }
+
+            if index + 1 < bytes.len() && bytes[index] == b'\r' && bytes[index + 1] == b'\n' {
+                index += 1;
+            }
+
+            start = index + 1;
+        }
+
+        index += 1;
+    }
+}
+
+/// Check heading ranks and text.
+fn headings(context: &mut Context, nodes: &[&Node]) {
+    let mut previous: Option<u8> = None;
+    let mut texts: Vec<(String, &Node)> = vec![];
+
+    for &node in nodes {
+        if let (Node::Heading(heading), Some(position)) = (node, node.position()) {
+            let (start, end) = (position.start.offset, position.end.offset);
+
+            if let Some(rank) = previous {
+                if heading.depth > rank + 1 {
+                    let reason = format!(
+                        "Unexpected heading rank `{}`, expected rank `{}`",
+                        heading.depth,
+                        rank + 1
+                    );
+                    context.report(Rule::HeadingIncrement, start, end, reason);
+                }
+            }
+
+            previous = Some(heading.depth);
+            let text = node.to_string().trim().to_lowercase();
+
+            if !text.is_empty() {
+                if let Some((_, first)) = texts.iter().find(|(d, _)| *d == text) {
+                    let first = context
+                        .point(first.position().unwrap().start.offset)
+                        .unwrap();
+                    let reason = format!(
+                        "Unexpected heading with the same text as the heading at {}:{}",
+                        first.line, first.column
+                    );
+                    context.report(Rule::DuplicateHeading, start, end, reason);
+                } else {
+                    texts.push((text, node));
+                }
+            }
+        }
+    }
+}
+
+/// Check that references are defined, and definitions are referenced.
+fn references(context: &mut Context, nodes: &[&Node]) {
+    let footnotes = context.options.parse.constructs.gfm_label_start_footnote;
+    let mut defined = vec![];
+    let mut referenced = vec![];
+
+    for node in nodes {
+        match node {
+            Node::Definition(x) => defined.push((x.identifier.as_str(), false)),
+            Node::FootnoteDefinition(x) => defined.push((x.identifier.as_str(), true)),
+            Node::LinkReference(x) => referenced.push((x.identifier.as_str(), false)),
+            Node::ImageReference(x) => referenced.push((x.identifier.as_str(), false)),
+            Node::FootnoteReference(x) => referenced.push((x.identifier.as_str(), true)),
+            _ => {}
+        }
+    }
+
+    for node in nodes {
+        let position = match node.position() {
+            Some(position) => position,
+            None => continue,
+        };
+        let start = position.start.offset;
+        let end = position.end.offset;
+
+        match node {
+            Node::Text(_) => {
+                let source = &context.value[start..end];
+
+                for (from, to, footnote) in undefined(source, footnotes) {
+                    let identifier = normalize_identifier(&source[from + 1..to - 1]).to_lowercase();
+                    let identifier = identifier.trim_start_matches('^');
+
+                    if !defined.contains(&(identifier, footnote)) {
+                        let reason = format!(
+                            "Undefined {} `{}`",
+                            if footnote { "footnote" } else { "reference" },
+                            &source[from..to]
+                        );
+                        context.report(Rule::UndefinedReference, start + from, start + to, reason);
+                    }
+                }
+            }
+            Node::Definition(x) if !referenced.contains(&(x.identifier.as_str(), false)) => {
+                let reason = format!(
+                    "Unused definition `[{}]`",
+                    x.label.as_deref().unwrap_or(&x.identifier)
+                );
+                context.report(Rule::UnusedDefinition, start, end, reason);
+            }
+            Node::FootnoteDefinition(x) if !referenced.contains(&(x.identifier.as_str(), true)) => {
+                let reason = format!(
+                    "Unused footnote definition `[^{}]`",
+                    x.label.as_deref().unwrap_or(&x.identifier)
+                );
+                context.report(Rule::UnusedDefinition, start, end, reason);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Find what look like references in the source of text, which did not
+/// match a definition.
+///
+/// Looks for the label of full references (`[a][b]`) and footnote calls
+/// (`[^b]`), as other things in brackets (`[b]`) are often not meant as
+/// references.
+/// Returns the start and end (including brackets) and whether it is a
+/// footnote.
+fn undefined(source: &str, footnotes: bool) -> Vec<(usize, usize, bool)> {
+    let bytes = source.as_bytes();
+    let mut results = vec![];
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let escaped = index > 0 && bytes[index - 1] == b'\\';
+
+        if bytes[index] == b'[' && !escaped {
+            let full = index > 0 && bytes[index - 1] == b']';
+            let footnote = footnotes && bytes.get(index + 1) == Some(&b'^');
+
+            if full || footnote {
+                let mut end = index + 1;
+
+                while end < bytes.len() && bytes[end] != b'[' && bytes[end] != b']' {
+                    end += if bytes[end] == b'\\' { 2 } else { 1 };
+                }
+
+                let label = &source[index + 1..end.min(bytes.len())];
+
+                if end < bytes.len()
+                    && bytes[end] == b']'
+                    && !label.trim_start_matches('^').trim().is_empty()
+                {
+                    results.push((index, end + 1, footnote));
+                    index = end;
+                }
+            }
+        }
+
+        index += 1;
+    }
+
+    results
+}
+
+/// Check for bare URLs.
+///
+/// `link` is whether `node` is in a link, where URLs are fine.
+fn urls(context: &mut Context, node: &Node, link: bool) {
+    if let Some(position) = node.position() {
+        let start = position.start.offset;
+        let end = position.end.offset;
+        let source = &context.value[start..end];
+
+        match node {
+            // GFM autolink literals start with the URL itself.
+            Node::Link(x) if !link && !source.starts_with('<') && !source.starts_with('[') => {
+                let reason = format!("Unexpected bare URL `{}`, expected `<{}>`", x.url, x.url);
+                context.report(Rule::BareUrl, start, end, reason);
+            }
+            Node::Text(_) if !link => {
+                let bytes = source.as_bytes();
+                let mut index = 0;
+
+                while index < bytes.len() {
+                    let rest = source.get(index..).unwrap_or("");
+                    let after_word = index == 0 || !bytes[index - 1].is_ascii_alphanumeric();
+
+                    if after_word && (rest.starts_with("http://") || rest.starts_with("https://")) {
+                        let size = rest
+                            .find(|d: char| d.is_whitespace() || d == '<' || d == '>')
+                            .unwrap_or(rest.len());
+                        // Trailing punctuation is likely not part of the URL.
+                        let url = rest[..size].trim_end_matches(['.', ',', ':', ';', '!', '?']);
+                        let reason = format!("Unexpected bare URL `{}`, expected `<{}>`", url, url);
+                        context.report(
+                            Rule::BareUrl,
+                            start + index,
+                            start + index + url.len(),
+                            reason,
+                        );
+                        index += size;
+                        continue;
+                    }
+
+                    index += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let link = link || matches!(node, Node::Link(_) | Node::LinkReference(_));
+
+    if let Some(children) = node.children() {
+        for child in children {
+            urls(context, child, link);
+        }
+    }
+}
+
+/// Check that images have alternative text.
+fn images(context: &mut Context, nodes: &[&Node]) {
+    for node in nodes {
+        let alt = match node {
+            Node::Image(x) => &x.alt,
+            Node::ImageReference(x) => &x.alt,
+            _ => continue,
+        };
+
+        if let (true, Some(position)) = (alt.trim().is_empty(), node.position()) {
+            let reason = "Unexpected image without alternative text".to_string();
+            context.report(
+                Rule::ImageAlt,
+                position.start.offset,
+                position.end.offset,
+                reason,
+            );
+        }
+    }
+}
+
+/// Collect `node` and its descendants, in order.
+fn collect<'a>(node: &'a Node, nodes: &mut Vec<&'a Node>) {
+    nodes.push(node);
+
+    if let Some(children) = node.children() {
+        for child in children {
+            collect(child, nodes);
+        }
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! [mdast]: https://github.com/syntax-tree/mdast
 
-use crate::unist::Position;
+use crate::unist::{Point, Position};
+use crate::util::normalize_identifier::normalize_identifier;
+use crate::{UrlKind, UrlRewrite};
 use alloc::{
     fmt,
     string::{String, ToString},
@@ -439,6 +441,541 @@ impl Node {
             Node::Paragraph(x) => x.position = position,
         }
     }
+
+    /// Get the deepest node that covers `offset`, and its ancestors.
+    ///
+    /// The nodes are returned from this node to the deepest one.
+    /// A node covers an offset if it starts at or before it, and ends after
+    /// it: when thinking of an offset as a cursor between two characters,
+    /// this finds the node of the character after the cursor.
+    /// The end of this node itself is also covered, so that the end of a
+    /// document gets the root.
+    /// Nodes without positions are never found.
+    /// When nothing covers `offset`, the list is empty.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{mdast::Node, to_mdast};
+    /// # fn main() -> Result<(), String> {
+    /// let tree = to_mdast("# a *b*", &Default::default())?;
+    /// let path = tree.at_offset(5);
+    ///
+    /// assert_eq!(path.len(), 4);
+    /// assert!(matches!(path[1], Node::Heading(_)));
+    /// assert_eq!(path[3].to_string(), "b");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn at_offset(&self, offset: usize) -> Vec<&Node> {
+        self.path(&|position, inclusive| {
+            position.start.offset <= offset
+                && (offset < position.end.offset || (inclusive && offset == position.end.offset))
+        })
+    }
+
+    /// Like [`at_offset`][Node::at_offset], but for the line and column of
+    /// `point` (its offset is ignored).
+    #[must_use]
+    pub fn path_at(&self, point: &Point) -> Vec<&Node> {
+        let at = (point.line, point.column);
+        self.path(&|position, inclusive| {
+            let end = (position.end.line, position.end.column);
+            (position.start.line, position.start.column) <= at
+                && (at < end || (inclusive && at == end))
+        })
+    }
+
+    /// Get this node and the descendants covered by `covers`, each in the
+    /// one before it.
+    ///
+    /// `covers` gets whether the end of the position is included, which is
+    /// only the case for this node.
+    fn path(&self, covers: &dyn Fn(&Position, bool) -> bool) -> Vec<&Node> {
+        let mut path = Vec::new();
+        let mut node = self;
+
+        while node
+            .position()
+            .map_or(false, |position| covers(position, path.is_empty()))
+        {
+            path.push(node);
+
+            match node.children() {
+                Some(children) => {
+                    let mut index = 0;
+
+                    while index < children.len() {
+                        if children[index]
+                            .position()
+                            .map_or(false, |position| covers(position, false))
+                        {
+                            break;
+                        }
+
+                        index += 1;
+                    }
+
+                    match children.get(index) {
+                        Some(child) => node = child,
+                        None => break,
+                    }
+                }
+                None => break,
+            }
+        }
+
+        path
+    }
+
+    /// Rewrite the URLs of links, images, and definitions in this node and
+    /// its descendants.
+    ///
+    /// This is the mdast counterpart of
+    /// [`url_rewrite`][crate::CompileOptions::url_rewrite].
+    /// Autolinks and GFM autolink literals are links in mdast, so they get
+    /// [`UrlKind::Link`][].
+    pub fn rewrite_urls(&mut self, rewrite: &UrlRewrite) {
+        let target = match self {
+            Node::Link(x) => Some((&mut x.url, UrlKind::Link)),
+            Node::Image(x) => Some((&mut x.url, UrlKind::Image)),
+            Node::Definition(x) => Some((&mut x.url, UrlKind::Definition)),
+            _ => None,
+        };
+
+        if let Some((url, kind)) = target {
+            if let Some(value) = rewrite(url, &kind) {
+                *url = value;
+            }
+        }
+
+        if let Some(children) = self.children_mut() {
+            let mut index = 0;
+            while index < children.len() {
+                children[index].rewrite_urls(rewrite);
+                index += 1;
+            }
+        }
+    }
+}
+
+/// Constructors, to create nodes without positional info.
+///
+/// Strings can be passed as anything that turns into a `String` (such as
+/// `&str`).
+/// Identifiers of references and definitions are made from their label, by
+/// normalizing it.
+/// See [`mdast!`][crate::mdast!] to create trees of them.
+impl Node {
+    /// Create a [`Root`][].
+    #[must_use]
+    pub fn root(children: Vec<Node>) -> Node {
+        Node::Root(Root {
+            children,
+            position: None,
+        })
+    }
+
+    /// Create a [`BlockQuote`][].
+    #[must_use]
+    pub fn block_quote(children: Vec<Node>) -> Node {
+        Node::BlockQuote(BlockQuote {
+            children,
+            position: None,
+        })
+    }
+
+    /// Create a [`FootnoteDefinition`][] with `label`.
+    #[must_use]
+    pub fn footnote_definition(label: impl Into<String>, children: Vec<Node>) -> Node {
+        let label = label.into();
+        Node::FootnoteDefinition(FootnoteDefinition {
+            children,
+            position: None,
+            identifier: normalize(&label),
+            label: Some(label),
+        })
+    }
+
+    /// Create an [`MdxJsxFlowElement`][] named `name` (`None` for a fragment),
+    /// without attributes.
+    #[must_use]
+    pub fn mdx_jsx_flow_element(name: Option<&str>, children: Vec<Node>) -> Node {
+        Node::MdxJsxFlowElement(MdxJsxFlowElement {
+            children,
+            position: None,
+            name: name.map(Into::into),
+            attributes: Vec::new(),
+        })
+    }
+
+    /// Create a [`List`][], which is tight, and starts at `1` if `ordered`.
+    #[must_use]
+    pub fn list(ordered: bool, children: Vec<Node>) -> Node {
+        Node::List(List {
+            children,
+            position: None,
+            ordered,
+            start: if ordered { Some(1) } else { None },
+            spread: false,
+        })
+    }
+
+    /// Create an [`MdxjsEsm`][].
+    #[must_use]
+    pub fn mdxjs_esm(value: impl Into<String>) -> Node {
+        Node::MdxjsEsm(MdxjsEsm {
+            value: value.into(),
+            position: None,
+            stops: Vec::new(),
+        })
+    }
+
+    /// Create a [`Toml`][].
+    #[must_use]
+    pub fn toml(value: impl Into<String>) -> Node {
+        Node::Toml(Toml {
+            value: value.into(),
+            position: None,
+        })
+    }
+
+    /// Create a [`Yaml`][].
+    #[must_use]
+    pub fn yaml(value: impl Into<String>) -> Node {
+        Node::Yaml(Yaml {
+            value: value.into(),
+            position: None,
+        })
+    }
+
+    /// Create a [`Break`][].
+    ///
+    /// Named `hard_break` as `break` is a keyword.
+    #[must_use]
+    pub fn hard_break() -> Node {
+        Node::Break(Break { position: None })
+    }
+
+    /// Create an [`InlineCode`][].
+    #[must_use]
+    pub fn inline_code(value: impl Into<String>) -> Node {
+        Node::InlineCode(InlineCode {
+            value: value.into(),
+            position: None,
+        })
+    }
+
+    /// Create an [`InlineMath`][].
+    #[must_use]
+    pub fn inline_math(value: impl Into<String>) -> Node {
+        Node::InlineMath(InlineMath {
+            value: value.into(),
+            position: None,
+        })
+    }
+
+    /// Create a [`Delete`][].
+    #[must_use]
+    pub fn delete(children: Vec<Node>) -> Node {
+        Node::Delete(Delete {
+            children,
+            position: None,
+        })
+    }
+
+    /// Create an [`Emphasis`][].
+    #[must_use]
+    pub fn emphasis(children: Vec<Node>) -> Node {
+        Node::Emphasis(Emphasis {
+            children,
+            position: None,
+        })
+    }
+
+    /// Create an [`MdxTextExpression`][].
+    #[must_use]
+    pub fn mdx_text_expression(value: impl Into<String>) -> Node {
+        Node::MdxTextExpression(MdxTextExpression {
+            value: value.into(),
+            position: None,
+            stops: Vec::new(),
+        })
+    }
+
+    /// Create a [`FootnoteReference`][] to `label`.
+    #[must_use]
+    pub fn footnote_reference(label: impl Into<String>) -> Node {
+        let label = label.into();
+        Node::FootnoteReference(FootnoteReference {
+            position: None,
+            identifier: normalize(&label),
+            label: Some(label),
+        })
+    }
+
+    /// Create an [`Html`][].
+    #[must_use]
+    pub fn html(value: impl Into<String>) -> Node {
+        Node::Html(Html {
+            value: value.into(),
+            position: None,
+        })
+    }
+
+    /// Create an [`Image`][] of `url`, without title.
+    #[must_use]
+    pub fn image(url: impl Into<String>, alt: impl Into<String>) -> Node {
+        Node::Image(Image {
+            position: None,
+            alt: alt.into(),
+            url: url.into(),
+            title: None,
+        })
+    }
+
+    /// Create a full [`ImageReference`][] to `label`.
+    #[must_use]
+    pub fn image_reference(label: impl Into<String>, alt: impl Into<String>) -> Node {
+        let label = label.into();
+        Node::ImageReference(ImageReference {
+            position: None,
+            alt: alt.into(),
+            reference_kind: ReferenceKind::Full,
+            identifier: normalize(&label),
+            label: Some(label),
+        })
+    }
+
+    /// Create an [`MdxJsxTextElement`][] named `name` (`None` for a fragment),
+    /// without attributes.
+    #[must_use]
+    pub fn mdx_jsx_text_element(name: Option<&str>, children: Vec<Node>) -> Node {
+        Node::MdxJsxTextElement(MdxJsxTextElement {
+            children,
+            position: None,
+            name: name.map(Into::into),
+            attributes: Vec::new(),
+        })
+    }
+
+    /// Create a [`Link`][] to `url`, without title.
+    #[must_use]
+    pub fn link(url: impl Into<String>, children: Vec<Node>) -> Node {
+        Node::Link(Link {
+            children,
+            position: None,
+            url: url.into(),
+            title: None,
+        })
+    }
+
+    /// Create a full [`LinkReference`][] to `label`.
+    #[must_use]
+    pub fn link_reference(label: impl Into<String>, children: Vec<Node>) -> Node {
+        let label = label.into();
+        Node::LinkReference(LinkReference {
+            children,
+            position: None,
+            reference_kind: ReferenceKind::Full,
+            identifier: normalize(&label),
+            label: Some(label),
+        })
+    }
+
+    /// Create a [`Strong`][].
+    #[must_use]
+    pub fn strong(children: Vec<Node>) -> Node {
+        Node::Strong(Strong {
+            children,
+            position: None,
+        })
+    }
+
+    /// Create a [`Text`][].
+    #[must_use]
+    pub fn text(value: impl Into<String>) -> Node {
+        Node::Text(Text {
+            value: value.into(),
+            position: None,
+        })
+    }
+
+    /// Create a [`Code`][], with `lang`, and without meta.
+    #[must_use]
+    pub fn code(lang: Option<&str>, value: impl Into<String>) -> Node {
+        Node::Code(Code {
+            value: value.into(),
+            position: None,
+            lang: lang.map(Into::into),
+            meta: None,
+        })
+    }
+
+    /// Create a [`Math`][], without meta.
+    #[must_use]
+    pub fn math(value: impl Into<String>) -> Node {
+        Node::Math(Math {
+            value: value.into(),
+            position: None,
+            meta: None,
+        })
+    }
+
+    /// Create an [`MdxFlowExpression`][].
+    #[must_use]
+    pub fn mdx_flow_expression(value: impl Into<String>) -> Node {
+        Node::MdxFlowExpression(MdxFlowExpression {
+            value: value.into(),
+            position: None,
+            stops: Vec::new(),
+        })
+    }
+
+    /// Create a [`Heading`][] of rank `depth` (between `1` and `6`).
+    #[must_use]
+    pub fn heading(depth: u8, children: Vec<Node>) -> Node {
+        Node::Heading(Heading {
+            children,
+            position: None,
+            depth,
+        })
+    }
+
+    /// Create a [`Table`][], with `align` for each column.
+    #[must_use]
+    pub fn table(align: Vec<AlignKind>, children: Vec<Node>) -> Node {
+        Node::Table(Table {
+            children,
+            position: None,
+            align,
+        })
+    }
+
+    /// Create a [`ThematicBreak`][].
+    #[must_use]
+    pub fn thematic_break() -> Node {
+        Node::ThematicBreak(ThematicBreak { position: None })
+    }
+
+    /// Create a [`TableRow`][].
+    #[must_use]
+    pub fn table_row(children: Vec<Node>) -> Node {
+        Node::TableRow(TableRow {
+            children,
+            position: None,
+        })
+    }
+
+    /// Create a [`TableCell`][].
+    #[must_use]
+    pub fn table_cell(children: Vec<Node>) -> Node {
+        Node::TableCell(TableCell {
+            children,
+            position: None,
+        })
+    }
+
+    /// Create a [`ListItem`][], which is tight, and not a task.
+    #[must_use]
+    pub fn list_item(children: Vec<Node>) -> Node {
+        Node::ListItem(ListItem {
+            children,
+            position: None,
+            spread: false,
+            checked: None,
+        })
+    }
+
+    /// Create a [`Definition`][] of `label`, to `url`, without title.
+    #[must_use]
+    pub fn definition(label: impl Into<String>, url: impl Into<String>) -> Node {
+        let label = label.into();
+        Node::Definition(Definition {
+            position: None,
+            url: url.into(),
+            title: None,
+            identifier: normalize(&label),
+            label: Some(label),
+        })
+    }
+
+    /// Create a [`Paragraph`][].
+    #[must_use]
+    pub fn paragraph(children: Vec<Node>) -> Node {
+        Node::Paragraph(Paragraph {
+            children,
+            position: None,
+        })
+    }
+}
+
+/// Create an mdast tree.
+///
+/// Each node is the name of a [`Node`][] constructor (such as `heading`),
+/// followed by its arguments in parens (if it takes more than children, such
+/// as `heading(2)`), followed by its children in brackets (if it takes
+/// children, such as `paragraph ["a"]`).
+/// Children are separated by commas.
+/// A child can also be a string, which makes a text, or any expression
+/// that results in a node in braces (`{node}`).
+/// Nodes have no positional info.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{mdast, mdast::Node, to_markdown};
+///
+/// let title = "Hi";
+/// let tree = mdast!(root [
+///     heading(1) [{Node::text(title)}],
+///     paragraph ["a ", emphasis ["b"], " ", link("https://example.com") ["c"]],
+///     thematic_break,
+///     code(Some("js"), "console.log(1)"),
+/// ]);
+///
+/// assert_eq!(
+///     to_markdown(&tree),
+///     "# Hi\n\na *b* [c](https://example.com)\n\n***\n\n```js\nconsole.log(1)\n```\n"
+/// );
+/// ```
+#[macro_export]
+macro_rules! mdast {
+    ($name:ident ( $($argument:expr),* $(,)? ) [ $($children:tt)* ]) => {
+        $crate::mdast::Node::$name($($argument,)* $crate::mdast!(@children [] $($children)*))
+    };
+    ($name:ident [ $($children:tt)* ]) => {
+        $crate::mdast::Node::$name($crate::mdast!(@children [] $($children)*))
+    };
+    ($name:ident ( $($argument:expr),* $(,)? )) => {
+        $crate::mdast::Node::$name($($argument),*)
+    };
+    ($name:ident) => {
+        $crate::mdast::Node::$name()
+    };
+    // Children, collected into `$done`.
+    (@children [$($done:expr,)*]) => {
+        $crate::mdast::__vec![$($done),*]
+    };
+    (@children [$($done:expr,)*] $value:literal $(, $($rest:tt)*)?) => {
+        $crate::mdast!(@children [$($done,)* $crate::mdast::Node::text($value),] $($($rest)*)?)
+    };
+    (@children [$($done:expr,)*] { $node:expr } $(, $($rest:tt)*)?) => {
+        $crate::mdast!(@children [$($done,)* $node,] $($($rest)*)?)
+    };
+    (@children [$($done:expr,)*] $name:ident $(( $($argument:tt)* ))? $([ $($children:tt)* ])? $(, $($rest:tt)*)?) => {
+        $crate::mdast!(@children [$($done,)* $crate::mdast!($name $(( $($argument)* ))? $([ $($children)* ])?),] $($($rest)*)?)
+    };
+}
+
+/// Used by [`mdast!`][crate::mdast!] to make lists of children.
+#[doc(hidden)]
+pub use alloc::vec as __vec;
+
+/// Normalize a label into an identifier.
+fn normalize(label: &str) -> String {
+    normalize_identifier(label).to_lowercase()
 }
 
 /// MDX: attribute content.
@@ -2318,4 +2855,49 @@ mod tests {
             "should support `position_set`"
         );
     }
+
+    #[test]
+    fn rewrite_urls() {
+        let mut node = Node::Root(Root {
+            children: vec![
+                Node::Paragraph(Paragraph {
+                    children: vec![
+                        Node::Link(Link {
+                            children: vec![Node::Image(Image {
+                                alt: "a".into(),
+                                url: "b.png".into(),
+                                title: None,
+                                position: None,
+                            })],
+                            url: "c.md".into(),
+                            title: None,
+                            position: None,
+                        }),
+                        Node::Text(Text {
+                            value: "d.md".into(),
+                            position: None,
+                        }),
+                    ],
+                    position: None,
+                }),
+                Node::Definition(Definition {
+                    url: "e.md".into(),
+                    title: None,
+                    identifier: "f".into(),
+                    label: None,
+                    position: None,
+                }),
+            ],
+            position: None,
+        });
+
+        node.rewrite_urls(&|url, kind| Some(format!("{:?}:{}", kind, url)));
+
+        assert_eq!(node.to_string(), "d.md", "should not rewrite text");
+        assert_eq!(
+            format!("{:?}", node),
+            "Root { children: [Paragraph { children: [Link { children: [Image { position: None, alt: \"a\", url: \"Image:b.png\", title: None }], position: None, url: \"Link:c.md\", title: None }, Text { value: \"d.md\", position: None }], position: None }, Definition { position: None, url: \"Definition:e.md\", title: None, identifier: \"f\", label: None }], position: None }",
+            "should support `rewrite_urls`"
+        );
+    }
 }
@@ -1,12 +1,16 @@
 //! Turn bytes of markdown into events.
 
-use crate::event::{Event, Point};
+use crate::event::{Event, Kind, Name, Point};
 use crate::state::{Name as StateName, State};
-use crate::subtokenize::subtokenize;
+use crate::subtokenize::{subtokenize, Subresult};
 use crate::tokenizer::Tokenizer;
-use crate::util::location::Location;
+use crate::util::{
+    constant::TAB_SIZE,
+    location::{format_place, Location},
+};
 use crate::ParseOptions;
-use alloc::{string::String, vec, vec::Vec};
+use alloc::{format, string::String, vec, vec::Vec};
+use core::cell::Cell;
 
 /// Info needed, in all content types, when parsing markdown.
 ///
@@ -24,6 +28,13 @@ pub struct ParseState<'a> {
     pub definitions: Vec<String>,
     /// Set of defined GFM footnote definition identifiers.
     pub gfm_footnote_definitions: Vec<String>,
+    /// Number of steps taken by all tokenizers, to know when to check
+    /// whether to abort.
+    pub steps: Cell<usize>,
+    /// Number of events made by all tokenizers, to check the `events` limit.
+    pub events_made: Cell<usize>,
+    /// Number of definitions made, to check the `definitions` limit.
+    pub definitions_made: Cell<usize>,
 }
 
 /// Turn a string of markdown into events.
@@ -32,9 +43,64 @@ pub struct ParseState<'a> {
 pub fn parse<'a>(
     value: &'a str,
     options: &'a ParseOptions,
+) -> Result<(Vec<Event>, ParseState<'a>), String> {
+    parse_with_definitions(value, options, vec![], vec![])
+}
+
+/// Turn a string of markdown into events, with definitions that are defined
+/// elsewhere.
+///
+/// `definitions` and `gfm_footnote_definitions` are lists of normalized
+/// identifiers, so that references and footnote calls to them can be formed.
+pub fn parse_with_definitions<'a>(
+    value: &'a str,
+    options: &'a ParseOptions,
+    definitions: Vec<String>,
+    gfm_footnote_definitions: Vec<String>,
+) -> Result<(Vec<Event>, ParseState<'a>), String> {
+    parse_impl(
+        value,
+        options,
+        StateName::DocumentStart,
+        definitions,
+        gfm_footnote_definitions,
+    )
+}
+
+/// Turn a string of markdown into events, as text (phrasing) content only.
+///
+/// There are no flow constructs (such as headings or lists), and there is no
+/// paragraph.
+/// `definitions` is a list of normalized identifiers of definitions that are
+/// defined elsewhere, so that references to them can be formed.
+pub fn parse_inline<'a>(
+    value: &'a str,
+    options: &'a ParseOptions,
+    definitions: Vec<String>,
+) -> Result<(Vec<Event>, ParseState<'a>), String> {
+    parse_impl(value, options, StateName::TextStart, definitions, vec![])
+}
+
+/// Turn a string of markdown into events, starting in `state_name`.
+fn parse_impl<'a>(
+    value: &'a str,
+    options: &'a ParseOptions,
+    state_name: StateName,
+    definitions: Vec<String>,
+    gfm_footnote_definitions: Vec<String>,
 ) -> Result<(Vec<Event>, ParseState<'a>), String> {
     let bytes = value.as_bytes();
 
+    if let Some(max) = options.limits.size {
+        if bytes.len() > max {
+            return Err(format!(
+                "1:1: Unexpected document of {} bytes, expected at most {} bytes (limit:size)",
+                bytes.len(),
+                max
+            ));
+        }
+    }
+
     let mut parse_state = ParseState {
         options,
         bytes,
@@ -43,8 +109,11 @@ pub fn parse<'a>(
         } else {
             None
         },
-        definitions: vec![],
-        gfm_footnote_definitions: vec![],
+        definitions,
+        gfm_footnote_definitions,
+        steps: Cell::new(0),
+        events_made: Cell::new(0),
+        definitions_made: Cell::new(0),
     };
 
     let start = Point {
@@ -53,15 +122,19 @@ pub fn parse<'a>(
         index: 0,
         vs: 0,
     };
-    let mut tokenizer = Tokenizer::new(start, &parse_state);
 
-    let state = tokenizer.push(
-        (0, 0),
-        (parse_state.bytes.len(), 0),
-        State::Next(StateName::DocumentStart),
-    );
-    let mut result = tokenizer.flush(state, true)?;
-    let mut events = tokenizer.events;
+    let (mut events, mut result) = if state_name == StateName::TextStart {
+        tokenize_text(&parse_state)?
+    } else {
+        let mut tokenizer = Tokenizer::new(start, &parse_state);
+        let state = tokenizer.push(
+            (0, 0),
+            (parse_state.bytes.len(), 0),
+            State::Next(state_name),
+        );
+        let result = tokenizer.flush(state, true)?;
+        (tokenizer.events, result)
+    };
 
     loop {
         let fn_defs = &mut parse_state.gfm_footnote_definitions;
@@ -76,3 +149,157 @@ pub fn parse<'a>(
         result = subtokenize(&mut events, &parse_state, &None)?;
     }
 }
+
+/// Turn the whole document into events, as text (phrasing) content only.
+///
+/// Text never contains blank lines in a document, as they end paragraphs,
+/// and constructs depend on that.
+/// So, each run of lines that are not blank is tokenized on its own, like a
+/// paragraph.
+/// Blank lines at the start and end are ignored, line endings of blank lines
+/// between runs are added as line endings.
+fn tokenize_text(parse_state: &ParseState) -> Result<(Vec<Event>, Subresult), String> {
+    let bytes = parse_state.bytes;
+    let mut events = vec![];
+    let mut result = Subresult {
+        done: true,
+        gfm_footnote_definitions: vec![],
+        definitions: vec![],
+    };
+    let mut point = Point {
+        line: 1,
+        column: 1,
+        index: 0,
+        vs: 0,
+    };
+    // Start of the current run of lines.
+    let mut start: Option<Point> = None;
+    // End of the last line that is not blank.
+    let mut end = point.clone();
+    // Line endings after the last line that is not blank.
+    let mut line_endings: Vec<(Point, Point)> = vec![];
+
+    loop {
+        let line_start = point.clone();
+        let mut blank = true;
+
+        while point.index < bytes.len() && !matches!(bytes[point.index], b'\n' | b'\r') {
+            if bytes[point.index] == b'\t' {
+                let remainder = point.column % TAB_SIZE;
+                point.column += if remainder == 0 {
+                    1
+                } else {
+                    1 + TAB_SIZE - remainder
+                };
+            } else {
+                if bytes[point.index] != b' ' {
+                    blank = false;
+                }
+
+                point.column += 1;
+            }
+
+            point.index += 1;
+        }
+
+        if !blank {
+            if let Some(from) = &start {
+                // Blank lines between, so end the current run.
+                if line_endings.len() > 1 {
+                    tokenize_text_run(parse_state, from, &end, &mut events, &mut result)?;
+
+                    let mut index = 0;
+                    while index < line_endings.len() {
+                        let (enter, exit) = &line_endings[index];
+                        events.push(Event {
+                            kind: Kind::Enter,
+                            name: Name::LineEnding,
+                            point: enter.clone(),
+                            link: None,
+                        });
+                        events.push(Event {
+                            kind: Kind::Exit,
+                            name: Name::LineEnding,
+                            point: exit.clone(),
+                            link: None,
+                        });
+                        index += 1;
+                    }
+
+                    start = Some(line_start);
+                }
+            } else {
+                start = Some(line_start);
+            }
+
+            line_endings.clear();
+            end = point.clone();
+        }
+
+        if point.index == bytes.len() {
+            break;
+        }
+
+        let enter = point.clone();
+        point.index += if bytes[point.index] == b'\r'
+            && point.index + 1 < bytes.len()
+            && bytes[point.index + 1] == b'\n'
+        {
+            2
+        } else {
+            1
+        };
+        point.line += 1;
+        point.column = 1;
+        line_endings.push((enter, point.clone()));
+    }
+
+    if let Some(from) = &start {
+        tokenize_text_run(parse_state, from, &end, &mut events, &mut result)?;
+    }
+
+    Ok((events, result))
+}
+
+/// Turn a run of lines, from `from` to `to`, into events, as text (phrasing)
+/// content.
+fn tokenize_text_run(
+    parse_state: &ParseState,
+    from: &Point,
+    to: &Point,
+    events: &mut Vec<Event>,
+    result: &mut Subresult,
+) -> Result<(), String> {
+    let mut tokenizer = Tokenizer::new(from.clone(), parse_state);
+    let state = tokenizer.push(
+        (from.index, 0),
+        (to.index, 0),
+        State::Next(StateName::TextStart),
+    );
+    let mut subresult = tokenizer.flush(state, true)?;
+    events.append(&mut tokenizer.events);
+    result.done = result.done && subresult.done;
+    result
+        .gfm_footnote_definitions
+        .append(&mut subresult.gfm_footnote_definitions);
+    result.definitions.append(&mut subresult.definitions);
+    Ok(())
+}
+
+/// Make an error for a limit of `max` things of `kind`, exceeded at `point`.
+pub fn limit_error(parse_state: &ParseState, point: &Point, kind: &str, max: usize) -> String {
+    format!(
+        "{}: Unexpected {}, expected the number of {}s to be at most {} (limit:{}s)",
+        format_place(
+            parse_state.bytes,
+            point.line,
+            point.column,
+            point.index,
+            parse_state.options.position_encoding,
+        ),
+        kind,
+        kind,
+        max,
+        kind
+    )
+}
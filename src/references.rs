@@ -0,0 +1,289 @@
+//! Resolve references in syntax trees.
+//!
+//! [`resolve_references()`][] pairs link and image references with
+//! definitions, and footnote references with footnote definitions, like
+//! `to_html` does.
+//! It reports references to things that are not defined, and definitions
+//! that are not referenced, and gets footnote definitions in the order they
+//! are called in.
+//! The [`References`][] it returns can then
+//! [`rewrite`][References::rewrite] a tree, turning references into links and
+//! images.
+//!
+//! Identifiers are compared after
+//! [normalizing](https://spec.commonmark.org/0.31.2/#matches) them.
+//! When an identifier is defined several times, the first definition wins.
+//!
+//! > 👉 **Note**: `to_mdast` only makes references when they are defined
+//! > (otherwise they are text), so undefined references only occur in trees
+//! > that were changed afterwards, such as when merging documents.
+
+use crate::mdast::{Definition, FootnoteDefinition, Image, Link, Node};
+use crate::unist::Position;
+use crate::util::normalize_identifier::normalize_identifier;
+use crate::visit::{visit, visit_mut, Action, ActionMut};
+use alloc::{string::String, vec, vec::Vec};
+
+/// Reference or definition, in a report.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Label {
+    /// Identifier, as in the node.
+    pub identifier: String,
+    /// Whether this is a footnote reference or footnote definition.
+    pub footnote: bool,
+    /// Positional info of the node.
+    pub position: Option<Position>,
+}
+
+/// Result of [`resolve_references()`][].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct References {
+    /// Definitions, the first one of each identifier, in tree order.
+    pub definitions: Vec<Definition>,
+    /// Footnote definitions that are referenced, the first one of each
+    /// identifier, in the order they are first referenced.
+    ///
+    /// This is the order footnotes are numbered and listed in with GFM:
+    /// references in the document come first, then references in footnote
+    /// definitions, in the order those are listed.
+    pub footnote_definitions: Vec<FootnoteDefinition>,
+    /// Link, image, and footnote references to identifiers that are not
+    /// defined, in tree order.
+    pub undefined: Vec<Label>,
+    /// Definitions and footnote definitions of identifiers that are not
+    /// referenced, in tree order.
+    pub unused: Vec<Label>,
+}
+
+impl References {
+    /// Get the definition of `identifier`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{references::resolve_references, to_mdast};
+    /// # fn main() -> Result<(), String> {
+    /// let tree = to_mdast("[a]: b", &Default::default())?;
+    /// let references = resolve_references(&tree);
+    ///
+    /// assert_eq!(references.definition("A").map(|d| d.url.as_str()), Some("b"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn definition(&self, identifier: &str) -> Option<&Definition> {
+        let identifier = normalize(identifier);
+        self.definitions
+            .iter()
+            .find(|definition| normalize(&definition.identifier) == identifier)
+    }
+
+    /// Get the footnote definition of `identifier`, if it is referenced.
+    #[must_use]
+    pub fn footnote_definition(&self, identifier: &str) -> Option<&FootnoteDefinition> {
+        let identifier = normalize(identifier);
+        self.footnote_definitions
+            .iter()
+            .find(|definition| normalize(&definition.identifier) == identifier)
+    }
+
+    /// Turn link references and image references in `tree` that are defined
+    /// into links and images.
+    ///
+    /// Definitions, footnotes, and undefined references are left as they
+    /// are.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use markdown::{references::resolve_references, to_markdown, to_mdast};
+    /// # fn main() -> Result<(), String> {
+    /// let mut tree = to_mdast("[a][] ![b][a]\n\n[a]: c \"d\"", &Default::default())?;
+    /// resolve_references(&tree).rewrite(&mut tree);
+    ///
+    /// assert_eq!(to_markdown(&tree), "[a](c \"d\") ![b](c \"d\")\n\n[a]: c \"d\"\n");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn rewrite(&self, tree: &mut Node) {
+        visit_mut(tree, &mut |node: &mut Node, _: &[usize]| {
+            let replacement = match node {
+                Node::LinkReference(x) => self.definition(&x.identifier).map(|definition| {
+                    Node::Link(Link {
+                        children: core::mem::take(&mut x.children),
+                        position: x.position.take(),
+                        url: definition.url.clone(),
+                        title: definition.title.clone(),
+                    })
+                }),
+                Node::ImageReference(x) => self.definition(&x.identifier).map(|definition| {
+                    Node::Image(Image {
+                        position: x.position.take(),
+                        alt: core::mem::take(&mut x.alt),
+                        url: definition.url.clone(),
+                        title: definition.title.clone(),
+                    })
+                }),
+                _ => None,
+            };
+
+            match replacement {
+                Some(replacement) => ActionMut::Replace(replacement),
+                None => ActionMut::Continue,
+            }
+        });
+    }
+}
+
+/// Resolve the references in `tree`.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{references::resolve_references, to_mdast, ParseOptions};
+/// # fn main() -> Result<(), String> {
+/// let tree = to_mdast("a[^b] c[^d]\n\n[^d]: e\n[^b]: f\n[^g]: h", &ParseOptions::gfm())?;
+/// let references = resolve_references(&tree);
+///
+/// assert_eq!(
+///     references.footnote_definitions.iter().map(|d| d.identifier.as_str()).collect::<Vec<_>>(),
+///     vec!["b", "d"]
+/// );
+/// assert_eq!(
+///     references.unused.iter().map(|d| d.identifier.as_str()).collect::<Vec<_>>(),
+///     vec!["g"]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub fn resolve_references(tree: &Node) -> References {
+    let mut result = References::default();
+    let mut footnote_definitions: Vec<FootnoteDefinition> = vec![];
+    // Normalized identifiers, and whether they are footnotes, of all
+    // definitions and references.
+    let mut defined: Vec<(String, bool, Label)> = vec![];
+    let mut referenced: Vec<(String, bool, Label)> = vec![];
+
+    visit(tree, &mut |node: &Node, _: &[&Node], _: Option<usize>| {
+        let (identifier, footnote, definition) = match node {
+            Node::Definition(x) => (&x.identifier, false, true),
+            Node::FootnoteDefinition(x) => (&x.identifier, true, true),
+            Node::LinkReference(x) => (&x.identifier, false, false),
+            Node::ImageReference(x) => (&x.identifier, false, false),
+            Node::FootnoteReference(x) => (&x.identifier, true, false),
+            _ => return Action::Continue,
+        };
+        let normalized = normalize(identifier);
+        let label = Label {
+            identifier: identifier.clone(),
+            footnote,
+            position: node.position().cloned(),
+        };
+
+        if definition {
+            if !contains(&defined, &normalized, footnote) {
+                match node {
+                    Node::Definition(x) => result.definitions.push(x.clone()),
+                    Node::FootnoteDefinition(x) => footnote_definitions.push(x.clone()),
+                    _ => {}
+                }
+            }
+
+            defined.push((normalized, footnote, label));
+        } else {
+            referenced.push((normalized, footnote, label));
+        }
+
+        Action::Continue
+    });
+
+    let mut index = 0;
+
+    while index < referenced.len() {
+        let (identifier, footnote, label) = &referenced[index];
+
+        if !contains(&defined, identifier, *footnote) {
+            result.undefined.push(label.clone());
+        }
+
+        index += 1;
+    }
+
+    index = 0;
+
+    while index < defined.len() {
+        let (identifier, footnote, label) = &defined[index];
+
+        if !contains(&referenced, identifier, *footnote) {
+            result.unused.push(label.clone());
+        }
+
+        index += 1;
+    }
+
+    // Footnotes are called in the document first, and then in footnote
+    // definitions, in the order they are called in.
+    let mut called = vec![];
+    calls(tree, &footnote_definitions, &mut called);
+    index = 0;
+
+    while index < called.len() {
+        let definition = &footnote_definitions[called[index]];
+        let mut child = 0;
+
+        while child < definition.children.len() {
+            calls(
+                &definition.children[child],
+                &footnote_definitions,
+                &mut called,
+            );
+            child += 1;
+        }
+
+        index += 1;
+    }
+
+    result.footnote_definitions = called
+        .iter()
+        .map(|index| footnote_definitions[*index].clone())
+        .collect();
+    result
+}
+
+/// Add the indices of footnote definitions that are called in `tree` to
+/// `called`, skipping footnote definitions in it.
+fn calls(tree: &Node, footnote_definitions: &[FootnoteDefinition], called: &mut Vec<usize>) {
+    visit(tree, &mut |node: &Node, _: &[&Node], _: Option<usize>| {
+        match node {
+            Node::FootnoteDefinition(_) => return Action::Skip,
+            Node::FootnoteReference(x) => {
+                let identifier = normalize(&x.identifier);
+
+                if let Some(index) = footnote_definitions
+                    .iter()
+                    .position(|definition| normalize(&definition.identifier) == identifier)
+                {
+                    if !called.contains(&index) {
+                        called.push(index);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Action::Continue
+    });
+}
+
+/// Check whether `list` has a normalized `identifier` of the footnote kind
+/// `footnote`.
+fn contains(list: &[(String, bool, Label)], identifier: &str, footnote: bool) -> bool {
+    list.iter()
+        .any(|(other, other_footnote, _)| other == identifier && *other_footnote == footnote)
+}
+
+/// Normalize an identifier.
+fn normalize(identifier: &str) -> String {
+    normalize_identifier(identifier).to_lowercase()
+}
@@ -0,0 +1,358 @@
+//! Parse markdown again after an edit.
+
+use crate::mdast::Node;
+use crate::parser::parse_with_definitions;
+use crate::stream::{can_split, collect_definitions, shift, starts_with_fence};
+use crate::to_mdast::compile;
+use crate::{to_mdast, ParseOptions, PositionEncoding};
+use alloc::{format, string::String, vec};
+use core::ops::Range;
+
+/// Change to a document.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Edit {
+    /// Byte index in the previous document where the replaced text starts.
+    pub start: usize,
+    /// Byte index in the previous document where the replaced text ends.
+    pub end: usize,
+    /// Text to insert instead.
+    pub value: String,
+}
+
+/// Result of [`reparse()`][].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reparse {
+    /// Document after the edit.
+    pub value: String,
+    /// Syntax tree of the document after the edit.
+    pub tree: Node,
+    /// Indices of top-level nodes in `tree` that were parsed again.
+    ///
+    /// Other top-level nodes are reused from the previous tree (with their
+    /// positions moved).
+    /// This can include nodes next to the edit that turned out the same.
+    pub changed: Range<usize>,
+}
+
+/// Turn markdown into a syntax tree again, after an edit.
+///
+/// Takes the previous document and its syntax tree (from
+/// [`to_mdast()`][crate::to_mdast()] or an earlier `reparse()`), and an
+/// edit.
+/// Only the top-level nodes (such as paragraphs and lists) around the edit
+/// are parsed again, starting one node before it (or earlier, if that node
+/// continues the ones before it), until the first node that comes out the
+/// same as before.
+/// Everything else is reused.
+/// The whole document is parsed again when the edit adds or removes
+/// definitions, when frontmatter is enabled and the document starts with a
+/// fence, when positions are not counted in bytes, or when the tree is not
+/// a root with positions.
+///
+/// ## Errors
+///
+/// `reparse()` errors when the edit is not in the document, or not at
+/// character boundaries.
+/// It never errors otherwise with normal markdown, because markdown does not
+/// have syntax errors.
+/// However, MDX does have syntax errors.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{reparse, to_mdast, Edit, ParseOptions};
+/// # fn main() -> Result<(), String> {
+///
+/// let options = ParseOptions::default();
+/// let value = "# a\n\nb\n\n- c";
+/// let tree = to_mdast(value, &options)?;
+///
+/// let edit = Edit { start: 5, end: 6, value: "*d*".into() };
+/// let result = reparse(value, tree, &edit, &options)?;
+///
+/// assert_eq!(result.value, "# a\n\n*d*\n\n- c");
+/// assert_eq!(result.tree, to_mdast(&result.value, &options)?);
+/// assert_eq!(result.changed, 0..2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn reparse(
+    value: &str,
+    tree: Node,
+    edit: &Edit,
+    options: &ParseOptions,
+) -> Result<Reparse, String> {
+    if edit.start > edit.end
+        || edit.end > value.len()
+        || !value.is_char_boundary(edit.start)
+        || !value.is_char_boundary(edit.end)
+    {
+        return Err(format!(
+            "Cannot apply edit at {}-{}: expected character boundaries in a document of {} bytes",
+            edit.start,
+            edit.end,
+            value.len()
+        ));
+    }
+
+    let mut next = String::with_capacity(value.len() - (edit.end - edit.start) + edit.value.len());
+    next.push_str(&value[..edit.start]);
+    next.push_str(&edit.value);
+    next.push_str(&value[edit.end..]);
+
+    let mut root = match tree {
+        Node::Root(root)
+            if options.position_encoding == PositionEncoding::Byte
+                && root.position.is_some()
+                && root.children.iter().all(|child| child.position().is_some()) =>
+        {
+            root
+        }
+        _ => return full(next, options),
+    };
+    let children = &mut root.children;
+    let len = children.len();
+
+    // Start one node before the first node touching the edit, as the edit
+    // can turn the start of that node into a continuation of the one before.
+    // Then, go back to where the content that node is part of starts, as
+    // lines there can continue each other.
+    let mut first = 0;
+    while first < len && children[first].position().unwrap().end.offset < edit.start {
+        first += 1;
+    }
+    first = first.saturating_sub(1);
+    // Containers (such as lists) and the flow after them are parsed
+    // together, so go back to before them too.
+    while first > 0
+        && (!can_split(&children[first - 1], &children[first])
+            || matches!(
+                children[first - 1],
+                Node::BlockQuote(_) | Node::FootnoteDefinition(_) | Node::List(_)
+            ))
+    {
+        first -= 1;
+    }
+
+    let region_start = if first == 0 {
+        0
+    } else {
+        line_start(value, children[first].position().unwrap().start.offset)
+    };
+    let region_line = if first == 0 {
+        1
+    } else {
+        children[first].position().unwrap().start.line
+    };
+    // When not at the start of the document, include the line ending before
+    // it, so that the first line is never frontmatter.
+    let tail_start = if region_start == 0 {
+        0
+    } else {
+        region_start - 1
+    };
+
+    // Whether there is frontmatter, and where it ends, depends on the rest of
+    // the document.
+    if options.constructs.frontmatter && (starts_with_fence(value) || starts_with_fence(&next)) {
+        return full(next, options);
+    }
+
+    let mut definitions = vec![];
+    let mut gfm_footnote_definitions = vec![];
+    let mut index = 0;
+    while index < len {
+        collect_definitions(
+            &children[index],
+            &mut definitions,
+            &mut gfm_footnote_definitions,
+        );
+        index += 1;
+    }
+
+    // First node that is entirely after the edit.
+    let mut reused = first;
+    while reused < len
+        && line_start(value, children[reused].position().unwrap().start.offset) <= edit.end
+    {
+        reused += 1;
+    }
+    let mut step = 1;
+
+    loop {
+        // Parse up to and including `reused`, and check that it did not change.
+        let old_cut = if reused + 1 < len {
+            line_start(value, children[reused + 1].position().unwrap().start.offset)
+        } else {
+            value.len()
+        };
+        let new_cut = old_cut - edit.end + edit.start + edit.value.len();
+
+        let (events, parse_state) = parse_with_definitions(
+            &next[tail_start..new_cut],
+            options,
+            definitions.clone(),
+            gfm_footnote_definitions.clone(),
+        )?;
+        let mut tree = compile(
+            &events,
+            parse_state.bytes,
+            parse_state.options.position_encoding,
+        )?;
+        let end = tree.position().unwrap().end.clone();
+        let mut parsed = tree.children_mut().unwrap().split_off(0);
+
+        if tail_start != 0 {
+            let mut index = 0;
+            while index < parsed.len() {
+                shift(&mut parsed[index], (2, 0), (region_line, tail_start));
+                index += 1;
+            }
+        }
+
+        // Where `reused` starts, before and after the edit.
+        let mapping = if reused < len {
+            let old_start = line_start(value, children[reused].position().unwrap().start.offset);
+            let new_start = old_start - edit.end + edit.start + edit.value.len();
+            let old_line = children[reused].position().unwrap().start.line;
+            let new_line = old_line + lines(&next[region_start..new_start])
+                - lines(&value[region_start..old_start]);
+            let mapping = ((old_line, old_start), (new_line, new_start));
+
+            // The node after it must not continue it either.
+            let same = match parsed.last_mut() {
+                Some(last)
+                    if last.position().unwrap().start.offset >= new_start
+                        && (reused + 1 == len
+                            || can_split(&children[reused], &children[reused + 1])) =>
+                {
+                    shift(last, mapping.1, mapping.0);
+                    *last == children[reused]
+                }
+                _ => false,
+            };
+
+            if !same {
+                reused = if reused + step < len {
+                    reused + step
+                } else {
+                    len
+                };
+                step *= 2;
+                continue;
+            }
+
+            parsed.pop();
+            Some(mapping)
+        } else {
+            None
+        };
+
+        let replaced_end = if mapping.is_some() { reused } else { len };
+
+        // Definitions changed: references elsewhere could change.
+        let mut old_definitions = vec![];
+        let mut old_gfm_footnote_definitions = vec![];
+        let mut new_definitions = vec![];
+        let mut new_gfm_footnote_definitions = vec![];
+        let mut index = first;
+        while index < replaced_end {
+            collect_definitions(
+                &children[index],
+                &mut old_definitions,
+                &mut old_gfm_footnote_definitions,
+            );
+            index += 1;
+        }
+        let mut index = 0;
+        while index < parsed.len() {
+            collect_definitions(
+                &parsed[index],
+                &mut new_definitions,
+                &mut new_gfm_footnote_definitions,
+            );
+            index += 1;
+        }
+        old_definitions.sort();
+        old_gfm_footnote_definitions.sort();
+        new_definitions.sort();
+        new_gfm_footnote_definitions.sort();
+
+        if old_definitions != new_definitions
+            || old_gfm_footnote_definitions != new_gfm_footnote_definitions
+        {
+            return full(next, options);
+        }
+
+        let changed = first..(first + parsed.len());
+        let mut after = children.split_off(replaced_end);
+        children.truncate(first);
+        children.append(&mut parsed);
+
+        let position = root.position.as_mut().unwrap();
+
+        if tail_start == 0 {
+            position.start = tree.position().unwrap().start.clone();
+        }
+
+        if let Some((from, to)) = mapping {
+            let mut index = 0;
+            while index < after.len() {
+                shift(&mut after[index], from, to);
+                index += 1;
+            }
+            children.append(&mut after);
+            position.end.line = position.end.line - from.0 + to.0;
+            position.end.offset = position.end.offset - from.1 + to.1;
+        } else if tail_start == 0 {
+            position.end = end;
+        } else {
+            position.end.line = end.line - 2 + region_line;
+            position.end.column = end.column;
+            position.end.offset = end.offset + tail_start;
+        }
+
+        return Ok(Reparse {
+            value: next,
+            tree: Node::Root(root),
+            changed,
+        });
+    }
+}
+
+/// Parse the whole document.
+fn full(value: String, options: &ParseOptions) -> Result<Reparse, String> {
+    let tree = to_mdast(&value, options)?;
+    let changed = 0..tree.children().unwrap().len();
+    Ok(Reparse {
+        value,
+        tree,
+        changed,
+    })
+}
+
+/// Get the index where the line that `index` is on starts.
+fn line_start(value: &str, index: usize) -> usize {
+    value[..index]
+        .rfind(|c| matches!(c, '\n' | '\r'))
+        .map_or(0, |index| index + 1)
+}
+
+/// Count line endings in `value`.
+fn lines(value: &str) -> usize {
+    let bytes = value.as_bytes();
+    let mut count = 0;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'\n'
+            || (bytes[index] == b'\r' && (index + 1 == bytes.len() || bytes[index + 1] != b'\n'))
+        {
+            count += 1;
+        }
+
+        index += 1;
+    }
+
+    count
+}
@@ -0,0 +1,968 @@
+//! Query syntax trees with CSS-like selectors.
+//!
+//! [`select()`][] gets the first node that matches a selector, and
+//! [`select_all()`][] gets all of them, in tree order.
+//!
+//! This is modelled after
+//! [`unist-util-select`](https://github.com/syntax-tree/unist-util-select).
+//! The following is supported:
+//!
+//! *   types (`heading`, `listItem`) and `*`
+//! *   attributes on fields, compared as strings:
+//!     `[checked]` (exists), `[depth=2]`, `[url^="https:"]` (starts with),
+//!     `[url$=".md"]` (ends with), `[value*="x"]` (contains), and
+//!     `[meta~=x]` (contains the whitespace-separated word);
+//!     fields are `alt`, `checked`, `depth`, `identifier`, `label`, `lang`,
+//!     `meta`, `name`, `ordered`, `referenceKind`, `spread`, `start`,
+//!     `title`, `url`, and `value`
+//! *   combinators: `a b` (descendant), `a > b` (child), `a + b` (next
+//!     sibling), and `a ~ b` (later sibling)
+//! *   lists: `a, b`
+//! *   pseudo-classes: `:first-child`, `:last-child`, `:only-child`,
+//!     `:nth-child(2n+1)`, `:nth-last-child(1)`, `:empty`, `:root`,
+//!     `:scope`, `:has(> a)`, `:is(a, b)`, and `:not(a, b)`
+//!
+//! ## Examples
+//!
+//! ```
+//! use markdown::{select::select, to_mdast};
+//! # fn main() -> Result<(), String> {
+//! let tree = to_mdast("# a\n\n## Install\n\nRun [b](c).", &Default::default())?;
+//! let node = select(&tree, "heading[depth=2] + paragraph link")?;
+//!
+//! assert_eq!(node.map(ToString::to_string), Some("b".into()));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::mdast::{Node, ReferenceKind};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::convert::TryFrom;
+
+/// How a compound selector relates to the one before it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Combinator {
+    /// `a b`.
+    Descendant,
+    /// `a > b`.
+    Child,
+    /// `a + b`.
+    NextSibling,
+    /// `a ~ b`.
+    LaterSibling,
+}
+
+/// How an attribute value is compared.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Operator {
+    /// `[a=b]`.
+    Equal,
+    /// `[a^=b]`.
+    Prefix,
+    /// `[a$=b]`.
+    Suffix,
+    /// `[a*=b]`.
+    Contains,
+    /// `[a~=b]`.
+    Word,
+}
+
+/// Attribute selector, such as `[depth=2]`.
+#[derive(Debug)]
+struct Attribute {
+    /// Field name.
+    name: String,
+    /// Comparison, if any (`[checked]` only checks that the field exists).
+    test: Option<(Operator, String)>,
+}
+
+/// Pseudo-class, such as `:first-child`.
+#[derive(Debug)]
+enum Pseudo {
+    FirstChild,
+    LastChild,
+    OnlyChild,
+    /// `:nth-child(an+b)`, with `a` and `b`.
+    NthChild(i64, i64),
+    /// `:nth-last-child(an+b)`, with `a` and `b`.
+    NthLastChild(i64, i64),
+    Empty,
+    Root,
+    Scope,
+    Has(Vec<Complex>),
+    Is(Vec<Complex>),
+    Not(Vec<Complex>),
+}
+
+/// Compound selector, such as `heading[depth=2]:first-child`.
+#[derive(Debug, Default)]
+struct Compound {
+    /// Type, `None` for `*` (or when there is no type).
+    name: Option<String>,
+    attributes: Vec<Attribute>,
+    pseudos: Vec<Pseudo>,
+}
+
+/// Compound selector, and how it relates to the compound before it.
+#[derive(Debug)]
+struct Part {
+    combinator: Combinator,
+    compound: Compound,
+}
+
+/// Complex selector, such as `heading + paragraph link`.
+#[derive(Debug)]
+struct Complex {
+    parts: Vec<Part>,
+}
+
+/// Where a node is in a tree.
+#[derive(Clone, Copy)]
+struct Location<'a> {
+    /// Ancestors of the node, the root first.
+    ancestors: &'a [&'a Node],
+    /// Index of each ancestor (except for the root) in its parent.
+    indices: &'a [usize],
+    /// Index of the node in its parent, `None` for the root.
+    index: Option<usize>,
+    node: &'a Node,
+}
+
+impl<'a> Location<'a> {
+    /// Get the parent of the node.
+    fn parent(&self) -> Option<&'a Node> {
+        self.ancestors.last().copied()
+    }
+
+    /// Get the siblings of the node (including the node).
+    fn siblings(&self) -> &'a [Node] {
+        self.parent()
+            .and_then(Node::children)
+            .map_or(&[], |children| children.as_slice())
+    }
+
+    /// Get the location of the ancestor at `depth` (`0` for the root).
+    fn ancestor(&self, depth: usize) -> Location<'a> {
+        Location {
+            ancestors: &self.ancestors[..depth],
+            indices: &self.indices[..depth.saturating_sub(1)],
+            index: if depth == 0 {
+                None
+            } else {
+                Some(self.indices[depth - 1])
+            },
+            node: self.ancestors[depth],
+        }
+    }
+
+    /// Get the location of the sibling at `index`.
+    fn sibling(&self, index: usize) -> Location<'a> {
+        Location {
+            ancestors: self.ancestors,
+            indices: self.indices,
+            index: Some(index),
+            node: &self.siblings()[index],
+        }
+    }
+}
+
+/// Get the first node in `tree` that matches `selector`.
+///
+/// The tree itself can match too.
+///
+/// ## Errors
+///
+/// There are errors for invalid selectors.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{mdast::Node, select::select, to_mdast};
+/// # fn main() -> Result<(), String> {
+/// let tree = to_mdast("```js\na\n```\n\n```rs\nb\n```", &Default::default())?;
+///
+/// if let Some(Node::Code(code)) = select(&tree, "code:not([lang=js])")? {
+///     assert_eq!(code.value, "b");
+/// }
+///
+/// assert!(select(&tree, "code[lang=py]")?.is_none());
+/// assert_eq!(
+///     select(&tree, "code[").unwrap_err(),
+///     "Unexpected end of selector, expected an attribute name"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn select<'a>(tree: &'a Node, selector: &str) -> Result<Option<&'a Node>, String> {
+    let list = parse(selector)?;
+    let mut found = None;
+    walk(
+        tree,
+        &mut vec![],
+        &mut vec![],
+        None,
+        &mut |location, node| {
+            if any(&list, &location, tree) {
+                found = Some(node);
+                true
+            } else {
+                false
+            }
+        },
+    );
+    Ok(found)
+}
+
+/// Get all nodes in `tree` that match `selector`, in tree order.
+///
+/// The tree itself can match too.
+///
+/// ## Errors
+///
+/// There are errors for invalid selectors.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{select::select_all, to_mdast, ParseOptions};
+/// # fn main() -> Result<(), String> {
+/// let tree = to_mdast("* [x] a\n* [ ] b\n* c", &ParseOptions::gfm())?;
+/// let items = select_all(&tree, "listItem[checked=true], listItem:not([checked])")?;
+///
+/// assert_eq!(
+///     items.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+///     vec!["a", "c"]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn select_all<'a>(tree: &'a Node, selector: &str) -> Result<Vec<&'a Node>, String> {
+    let list = parse(selector)?;
+    let mut found = vec![];
+    walk(
+        tree,
+        &mut vec![],
+        &mut vec![],
+        None,
+        &mut |location, node| {
+            if any(&list, &location, tree) {
+                found.push(node);
+            }
+            false
+        },
+    );
+    Ok(found)
+}
+
+/// Walk `node` and its descendants in tree order, calling `each` with
+/// where they are.
+///
+/// Returns whether `each` asked to stop.
+fn walk<'a>(
+    node: &'a Node,
+    ancestors: &mut Vec<&'a Node>,
+    indices: &mut Vec<usize>,
+    index: Option<usize>,
+    each: &mut dyn FnMut(Location, &'a Node) -> bool,
+) -> bool {
+    let location = Location {
+        ancestors,
+        indices,
+        index,
+        node,
+    };
+
+    if each(location, node) {
+        return true;
+    }
+
+    if let Some(children) = node.children() {
+        ancestors.push(node);
+        if let Some(index) = index {
+            indices.push(index);
+        }
+
+        let mut child = 0;
+        let mut stop = false;
+
+        while child < children.len() {
+            if walk(&children[child], ancestors, indices, Some(child), each) {
+                stop = true;
+                break;
+            }
+
+            child += 1;
+        }
+
+        ancestors.pop();
+        if index.is_some() {
+            indices.pop();
+        }
+
+        stop
+    } else {
+        false
+    }
+}
+
+/// Check whether any selector in `list` matches at `location`.
+fn any(list: &[Complex], location: &Location, scope: &Node) -> bool {
+    let mut index = 0;
+
+    while index < list.len() {
+        if matches(&list[index].parts, location, scope) {
+            return true;
+        }
+
+        index += 1;
+    }
+
+    false
+}
+
+/// Check whether `parts` match at `location`, from right to left.
+fn matches(parts: &[Part], location: &Location, scope: &Node) -> bool {
+    let (part, rest) = match parts.split_last() {
+        Some(split) => split,
+        None => return true,
+    };
+
+    if !compound(&part.compound, location, scope) {
+        return false;
+    }
+
+    if rest.is_empty() {
+        return true;
+    }
+
+    match part.combinator {
+        Combinator::Descendant => {
+            let mut depth = location.ancestors.len();
+
+            while depth > 0 {
+                depth -= 1;
+
+                if matches(rest, &location.ancestor(depth), scope) {
+                    return true;
+                }
+            }
+
+            false
+        }
+        Combinator::Child => {
+            let depth = location.ancestors.len();
+            depth > 0 && matches(rest, &location.ancestor(depth - 1), scope)
+        }
+        Combinator::NextSibling => match location.index {
+            Some(index) if index > 0 => matches(rest, &location.sibling(index - 1), scope),
+            _ => false,
+        },
+        Combinator::LaterSibling => {
+            let mut index = location.index.unwrap_or(0);
+
+            while index > 0 {
+                index -= 1;
+
+                if matches(rest, &location.sibling(index), scope) {
+                    return true;
+                }
+            }
+
+            false
+        }
+    }
+}
+
+/// Check whether a compound selector matches at `location`.
+fn compound(compound: &Compound, location: &Location, scope: &Node) -> bool {
+    if let Some(name) = &compound.name {
+        if name != kind(location.node) {
+            return false;
+        }
+    }
+
+    let mut index = 0;
+
+    while index < compound.attributes.len() {
+        let attribute = &compound.attributes[index];
+        let value = match field(location.node, &attribute.name) {
+            Some(value) => value,
+            None => return false,
+        };
+
+        let ok = match &attribute.test {
+            None => true,
+            Some((Operator::Equal, expected)) => value == *expected,
+            Some((Operator::Prefix, expected)) => value.starts_with(expected.as_str()),
+            Some((Operator::Suffix, expected)) => value.ends_with(expected.as_str()),
+            Some((Operator::Contains, expected)) => value.contains(expected.as_str()),
+            Some((Operator::Word, expected)) => {
+                value.split_ascii_whitespace().any(|word| word == expected)
+            }
+        };
+
+        if !ok {
+            return false;
+        }
+
+        index += 1;
+    }
+
+    index = 0;
+
+    while index < compound.pseudos.len() {
+        if !pseudo(&compound.pseudos[index], location, scope) {
+            return false;
+        }
+
+        index += 1;
+    }
+
+    true
+}
+
+/// Check whether a pseudo-class matches at `location`.
+fn pseudo(pseudo: &Pseudo, location: &Location, scope: &Node) -> bool {
+    let count = location.siblings().len();
+
+    match pseudo {
+        Pseudo::FirstChild => location.index == Some(0),
+        Pseudo::LastChild => location.index.is_some() && location.index == count.checked_sub(1),
+        Pseudo::OnlyChild => location.index.is_some() && count == 1,
+        Pseudo::NthChild(a, b) => location.index.map_or(false, |index| nth(*a, *b, index + 1)),
+        Pseudo::NthLastChild(a, b) => location
+            .index
+            .map_or(false, |index| nth(*a, *b, count - index)),
+        Pseudo::Empty => {
+            location.node.children().map_or(true, Vec::is_empty)
+                && field(location.node, "value").map_or(true, |value| value.is_empty())
+        }
+        Pseudo::Root => location.ancestors.is_empty(),
+        Pseudo::Scope => core::ptr::eq(location.node, scope),
+        Pseudo::Has(list) => {
+            let mut ancestors = location.ancestors.to_vec();
+            let mut indices = location.indices.to_vec();
+            let mut found = false;
+
+            if let Some(children) = location.node.children() {
+                ancestors.push(location.node);
+                if let Some(index) = location.index {
+                    indices.push(index);
+                }
+
+                let mut index = 0;
+
+                while index < children.len() {
+                    if walk(
+                        &children[index],
+                        &mut ancestors,
+                        &mut indices,
+                        Some(index),
+                        &mut |descendant, _| any(list, &descendant, location.node),
+                    ) {
+                        found = true;
+                        break;
+                    }
+
+                    index += 1;
+                }
+            }
+
+            found
+        }
+        Pseudo::Is(list) => any(list, location, scope),
+        Pseudo::Not(list) => !any(list, location, scope),
+    }
+}
+
+/// Check whether the 1-indexed `position` is matched by `an+b`.
+fn nth(a: i64, b: i64, position: usize) -> bool {
+    let position = i64::try_from(position).unwrap_or(i64::MAX);
+
+    if a == 0 {
+        position == b
+    } else {
+        let difference = position - b;
+        difference % a == 0 && difference / a >= 0
+    }
+}
+
+/// Get the type of a node, as used in mdast.
+fn kind(node: &Node) -> &'static str {
+    match node {
+        Node::Root(_) => "root",
+        Node::BlockQuote(_) => "blockquote",
+        Node::FootnoteDefinition(_) => "footnoteDefinition",
+        Node::MdxJsxFlowElement(_) => "mdxJsxFlowElement",
+        Node::List(_) => "list",
+        Node::MdxjsEsm(_) => "mdxjsEsm",
+        Node::Toml(_) => "toml",
+        Node::Yaml(_) => "yaml",
+        Node::Break(_) => "break",
+        Node::InlineCode(_) => "inlineCode",
+        Node::InlineMath(_) => "inlineMath",
+        Node::Delete(_) => "delete",
+        Node::Emphasis(_) => "emphasis",
+        Node::MdxTextExpression(_) => "mdxTextExpression",
+        Node::FootnoteReference(_) => "footnoteReference",
+        Node::Html(_) => "html",
+        Node::Image(_) => "image",
+        Node::ImageReference(_) => "imageReference",
+        Node::MdxJsxTextElement(_) => "mdxJsxTextElement",
+        Node::Link(_) => "link",
+        Node::LinkReference(_) => "linkReference",
+        Node::Strong(_) => "strong",
+        Node::Text(_) => "text",
+        Node::Code(_) => "code",
+        Node::Math(_) => "math",
+        Node::MdxFlowExpression(_) => "mdxFlowExpression",
+        Node::Heading(_) => "heading",
+        Node::Table(_) => "table",
+        Node::ThematicBreak(_) => "thematicBreak",
+        Node::TableRow(_) => "tableRow",
+        Node::TableCell(_) => "tableCell",
+        Node::ListItem(_) => "listItem",
+        Node::Definition(_) => "definition",
+        Node::Paragraph(_) => "paragraph",
+    }
+}
+
+/// Get a field of a node as a string, if it has it.
+fn field(node: &Node, name: &str) -> Option<String> {
+    match (node, name) {
+        (Node::Heading(x), "depth") => Some(x.depth.to_string()),
+        (Node::List(x), "ordered") => Some(x.ordered.to_string()),
+        (Node::List(x), "start") => x.start.map(|d| d.to_string()),
+        (Node::List(x), "spread") => Some(x.spread.to_string()),
+        (Node::ListItem(x), "spread") => Some(x.spread.to_string()),
+        (Node::ListItem(x), "checked") => x.checked.map(|d| d.to_string()),
+        (Node::Code(x), "lang") => x.lang.clone(),
+        (Node::Code(x), "meta") => x.meta.clone(),
+        (Node::Math(x), "meta") => x.meta.clone(),
+        (Node::Link(x), "url") => Some(x.url.clone()),
+        (Node::Image(x), "url") => Some(x.url.clone()),
+        (Node::Definition(x), "url") => Some(x.url.clone()),
+        (Node::Link(x), "title") => x.title.clone(),
+        (Node::Image(x), "title") => x.title.clone(),
+        (Node::Definition(x), "title") => x.title.clone(),
+        (Node::Image(x), "alt") => Some(x.alt.clone()),
+        (Node::ImageReference(x), "alt") => Some(x.alt.clone()),
+        (Node::Definition(x), "identifier") => Some(x.identifier.clone()),
+        (Node::LinkReference(x), "identifier") => Some(x.identifier.clone()),
+        (Node::ImageReference(x), "identifier") => Some(x.identifier.clone()),
+        (Node::FootnoteDefinition(x), "identifier") => Some(x.identifier.clone()),
+        (Node::FootnoteReference(x), "identifier") => Some(x.identifier.clone()),
+        (Node::Definition(x), "label") => x.label.clone(),
+        (Node::LinkReference(x), "label") => x.label.clone(),
+        (Node::ImageReference(x), "label") => x.label.clone(),
+        (Node::FootnoteDefinition(x), "label") => x.label.clone(),
+        (Node::FootnoteReference(x), "label") => x.label.clone(),
+        (Node::LinkReference(x), "referenceKind") => Some(reference_kind(x.reference_kind)),
+        (Node::ImageReference(x), "referenceKind") => Some(reference_kind(x.reference_kind)),
+        (Node::MdxJsxFlowElement(x), "name") => x.name.clone(),
+        (Node::MdxJsxTextElement(x), "name") => x.name.clone(),
+        (Node::Text(x), "value") => Some(x.value.clone()),
+        (Node::InlineCode(x), "value") => Some(x.value.clone()),
+        (Node::InlineMath(x), "value") => Some(x.value.clone()),
+        (Node::Code(x), "value") => Some(x.value.clone()),
+        (Node::Math(x), "value") => Some(x.value.clone()),
+        (Node::Html(x), "value") => Some(x.value.clone()),
+        (Node::Yaml(x), "value") => Some(x.value.clone()),
+        (Node::Toml(x), "value") => Some(x.value.clone()),
+        (Node::MdxjsEsm(x), "value") => Some(x.value.clone()),
+        (Node::MdxFlowExpression(x), "value") => Some(x.value.clone()),
+        (Node::MdxTextExpression(x), "value") => Some(x.value.clone()),
+        _ => None,
+    }
+}
+
+/// Get a reference kind as a string, as used in mdast.
+fn reference_kind(kind: ReferenceKind) -> String {
+    match kind {
+        ReferenceKind::Shortcut => "shortcut",
+        ReferenceKind::Collapsed => "collapsed",
+        ReferenceKind::Full => "full",
+    }
+    .into()
+}
+
+/// Parse a selector.
+fn parse(selector: &str) -> Result<Vec<Complex>, String> {
+    let mut parser = Parser {
+        value: selector,
+        bytes: selector.as_bytes(),
+        index: 0,
+    };
+    let list = parser.list(false)?;
+
+    if parser.index < parser.bytes.len() {
+        Err(parser.unexpected("a combinator or `,`"))
+    } else {
+        Ok(list)
+    }
+}
+
+/// Selector parser.
+struct Parser<'a> {
+    value: &'a str,
+    bytes: &'a [u8],
+    index: usize,
+}
+
+impl Parser<'_> {
+    /// Parse a list of complex selectors, such as `a, b c`.
+    ///
+    /// Relative selectors (in `:has()`) can start with a combinator.
+    fn list(&mut self, relative: bool) -> Result<Vec<Complex>, String> {
+        let mut list = vec![];
+
+        loop {
+            self.whitespace();
+            list.push(self.complex(relative)?);
+            self.whitespace();
+
+            if !self.eat(b',') {
+                break;
+            }
+        }
+
+        Ok(list)
+    }
+
+    /// Parse a complex selector, such as `a > b c`.
+    fn complex(&mut self, relative: bool) -> Result<Complex, String> {
+        let mut parts = vec![];
+        let mut combinator = Combinator::Descendant;
+
+        if relative {
+            parts.push(Part {
+                combinator,
+                compound: Compound {
+                    pseudos: vec![Pseudo::Scope],
+                    ..Compound::default()
+                },
+            });
+
+            if let Some(explicit) = self.combinator() {
+                combinator = explicit;
+                self.whitespace();
+            }
+        }
+
+        loop {
+            let compound = self.compound()?;
+            parts.push(Part {
+                combinator,
+                compound,
+            });
+
+            let whitespace = self.whitespace();
+
+            if let Some(explicit) = self.combinator() {
+                combinator = explicit;
+                self.whitespace();
+            } else if whitespace && !matches!(self.bytes.get(self.index), None | Some(b',' | b')'))
+            {
+                combinator = Combinator::Descendant;
+            } else {
+                break;
+            }
+        }
+
+        Ok(Complex { parts })
+    }
+
+    /// Parse an explicit combinator (`>`, `+`, `~`), if there is one.
+    fn combinator(&mut self) -> Option<Combinator> {
+        let combinator = match self.bytes.get(self.index) {
+            Some(b'>') => Combinator::Child,
+            Some(b'+') => Combinator::NextSibling,
+            Some(b'~') => Combinator::LaterSibling,
+            _ => return None,
+        };
+        self.index += 1;
+        Some(combinator)
+    }
+
+    /// Parse a compound selector, such as `a[b]:c`.
+    fn compound(&mut self) -> Result<Compound, String> {
+        let start = self.index;
+        let mut compound = Compound::default();
+
+        if !self.eat(b'*') {
+            compound.name = self.identifier();
+        }
+
+        loop {
+            if self.eat(b'[') {
+                compound.attributes.push(self.attribute()?);
+            } else if self.eat(b':') {
+                compound.pseudos.push(self.pseudo()?);
+            } else {
+                break;
+            }
+        }
+
+        if self.index == start {
+            Err(self.unexpected("a type, `*`, `[`, or `:`"))
+        } else {
+            Ok(compound)
+        }
+    }
+
+    /// Parse the rest of an attribute selector, after `[`.
+    fn attribute(&mut self) -> Result<Attribute, String> {
+        self.whitespace();
+        let name = self
+            .identifier()
+            .ok_or_else(|| self.unexpected("an attribute name"))?;
+        self.whitespace();
+
+        let operator = match self.bytes.get(self.index) {
+            Some(b'=') => Some(Operator::Equal),
+            Some(b'^') => Some(Operator::Prefix),
+            Some(b'$') => Some(Operator::Suffix),
+            Some(b'*') => Some(Operator::Contains),
+            Some(b'~') => Some(Operator::Word),
+            _ => None,
+        };
+
+        let test = if let Some(operator) = operator {
+            self.index += 1;
+
+            if operator != Operator::Equal && !self.eat(b'=') {
+                return Err(self.unexpected("`=`"));
+            }
+
+            self.whitespace();
+            let value = self.attribute_value()?;
+            self.whitespace();
+            Some((operator, value))
+        } else {
+            None
+        };
+
+        if self.eat(b']') {
+            Ok(Attribute { name, test })
+        } else {
+            Err(self.unexpected("`]`"))
+        }
+    }
+
+    /// Parse an attribute value, quoted or not.
+    fn attribute_value(&mut self) -> Result<String, String> {
+        let quote = match self.bytes.get(self.index) {
+            Some(b'"') => b'"',
+            Some(b'\'') => b'\'',
+            _ => {
+                let start = self.index;
+
+                while self.index < self.bytes.len()
+                    && self.bytes[self.index] != b']'
+                    && !self.bytes[self.index].is_ascii_whitespace()
+                {
+                    self.index += 1;
+                }
+
+                return if self.index == start {
+                    Err(self.unexpected("an attribute value"))
+                } else {
+                    Ok(self.value[start..self.index].into())
+                };
+            }
+        };
+
+        self.index += 1;
+        let mut value = String::new();
+        let mut start = self.index;
+
+        while self.index < self.bytes.len() {
+            let byte = self.bytes[self.index];
+
+            if byte == quote {
+                value.push_str(&self.value[start..self.index]);
+                self.index += 1;
+                return Ok(value);
+            }
+
+            if byte == b'\\' && self.index + 1 < self.bytes.len() {
+                value.push_str(&self.value[start..self.index]);
+                start = self.index + 1;
+                self.index += 1;
+            }
+
+            self.index += 1;
+        }
+
+        Err(self.unexpected(if quote == b'"' { "`\"`" } else { "`'`" }))
+    }
+
+    /// Parse the rest of a pseudo-class, after `:`.
+    fn pseudo(&mut self) -> Result<Pseudo, String> {
+        let start = self.index;
+        let name = self
+            .identifier()
+            .ok_or_else(|| self.unexpected("a pseudo-class name"))?;
+
+        let pseudo = match name.as_str() {
+            "first-child" => Pseudo::FirstChild,
+            "last-child" => Pseudo::LastChild,
+            "only-child" => Pseudo::OnlyChild,
+            "empty" => Pseudo::Empty,
+            "root" => Pseudo::Root,
+            "scope" => Pseudo::Scope,
+            "nth-child" | "nth-last-child" => {
+                self.open()?;
+                let (a, b) = self.nth()?;
+                self.close()?;
+
+                if name == "nth-child" {
+                    Pseudo::NthChild(a, b)
+                } else {
+                    Pseudo::NthLastChild(a, b)
+                }
+            }
+            "has" | "is" | "not" => {
+                self.open()?;
+                let list = self.list(name == "has")?;
+                self.close()?;
+
+                match name.as_str() {
+                    "has" => Pseudo::Has(list),
+                    "is" => Pseudo::Is(list),
+                    _ => Pseudo::Not(list),
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "Unexpected unknown pseudo-class `:{}` at {} in selector",
+                    name,
+                    start - 1
+                ))
+            }
+        };
+
+        Ok(pseudo)
+    }
+
+    /// Parse `an+b`, `odd`, or `even`.
+    fn nth(&mut self) -> Result<(i64, i64), String> {
+        self.whitespace();
+        let start = self.index;
+
+        while self.index < self.bytes.len() && self.bytes[self.index] != b')' {
+            self.index += 1;
+        }
+
+        let value: String = self.value[start..self.index]
+            .chars()
+            .filter(|d| !d.is_ascii_whitespace())
+            .collect();
+        let invalid = || {
+            format!(
+                "Unexpected `{}` at {} in selector, expected `an+b`, `odd`, or `even`",
+                value, start
+            )
+        };
+
+        let result = match value.as_str() {
+            "odd" => (2, 1),
+            "even" => (2, 0),
+            _ => {
+                if let Some(n) = value.find('n') {
+                    let a = match &value[..n] {
+                        "" | "+" => 1,
+                        "-" => -1,
+                        a => a.parse().map_err(|_| invalid())?,
+                    };
+                    let b = match &value[n + 1..] {
+                        "" => 0,
+                        b if b.starts_with('+') => b[1..].parse().map_err(|_| invalid())?,
+                        b if b.starts_with('-') => b.parse().map_err(|_| invalid())?,
+                        _ => return Err(invalid()),
+                    };
+                    (a, b)
+                } else {
+                    (0, value.parse().map_err(|_| invalid())?)
+                }
+            }
+        };
+
+        Ok(result)
+    }
+
+    /// Parse `(`.
+    fn open(&mut self) -> Result<(), String> {
+        if self.eat(b'(') {
+            Ok(())
+        } else {
+            Err(self.unexpected("`(`"))
+        }
+    }
+
+    /// Parse `)`, after optional whitespace.
+    fn close(&mut self) -> Result<(), String> {
+        self.whitespace();
+
+        if self.eat(b')') {
+            Ok(())
+        } else {
+            Err(self.unexpected("`)`"))
+        }
+    }
+
+    /// Parse a name, such as `listItem` or `first-child`.
+    fn identifier(&mut self) -> Option<String> {
+        let start = self.index;
+
+        while self.index < self.bytes.len()
+            && (self.bytes[self.index].is_ascii_alphanumeric()
+                || matches!(self.bytes[self.index], b'-' | b'_'))
+        {
+            self.index += 1;
+        }
+
+        if self.index == start {
+            None
+        } else {
+            Some(self.value[start..self.index].into())
+        }
+    }
+
+    /// Skip whitespace, returning whether there was some.
+    fn whitespace(&mut self) -> bool {
+        let start = self.index;
+
+        while self.index < self.bytes.len() && self.bytes[self.index].is_ascii_whitespace() {
+            self.index += 1;
+        }
+
+        self.index > start
+    }
+
+    /// Skip `byte` if it is next, returning whether it was.
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.bytes.get(self.index) == Some(&byte) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Make an error for something unexpected at the current index.
+    fn unexpected(&self, expected: &str) -> String {
+        match self.value[self.index..].chars().next() {
+            Some(char) => format!(
+                "Unexpected `{}` at {} in selector, expected {}",
+                char, self.index, expected
+            ),
+            None => format!("Unexpected end of selector, expected {}", expected),
+        }
+    }
+}
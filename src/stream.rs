@@ -0,0 +1,433 @@
+//! Parse markdown from chunks.
+
+use crate::mdast::Node;
+use crate::parser::parse_with_definitions;
+use crate::to_mdast::compile;
+use crate::unist::Point;
+use crate::util::{
+    constant::STREAM_REPARSE_SIZE_MAX,
+    location::{count, Location},
+    normalize_identifier::normalize_identifier,
+};
+use crate::visit::{visit_mut, ActionMut};
+use crate::{to_mdast, ParseOptions, PositionEncoding};
+use alloc::{string::String, vec, vec::Vec};
+
+/// Parser that turns chunks of markdown into a syntax tree.
+///
+/// Markdown can be pushed in pieces (such as from a network stream, or
+/// tokens generated by a language model), and top-level nodes (such as
+/// paragraphs and lists) are returned as soon as they can no longer change.
+/// A node can no longer change when the first line of a following node is
+/// complete.
+///
+/// Unfinished markdown is parsed again when a line is completed.
+/// When it is large (such as a long code block that is not yet closed), it is
+/// only parsed again once it has doubled in size, so finished nodes can be
+/// returned a bit later than when they are finished.
+///
+/// Returned nodes have positions relative to the whole document, and are the
+/// same as in the tree from [`finish()`][Parser::finish].
+/// Text that looks like a reference (such as `[a]` or `[^a]`) can turn into
+/// one when its definition (such as `[a]: b`) comes in a later chunk, so a
+/// node with such text, and the nodes after it, are only returned by
+/// `finish()`.
+///
+/// When frontmatter is enabled and the document starts with a fence, no
+/// nodes are returned until the frontmatter is closed.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{mdast::Node, ParseOptions, Parser};
+/// # fn main() -> Result<(), String> {
+///
+/// let options = ParseOptions::default();
+/// let mut parser = Parser::new(&options);
+///
+/// // Not done yet: the paragraph could still turn into a heading.
+/// assert_eq!(parser.push("Hello, ")?, vec![]);
+/// assert_eq!(parser.push("world!\n\n- a")?, vec![]);
+///
+/// // Done: the list started.
+/// let nodes = parser.push("\n")?;
+/// assert_eq!(nodes.len(), 1);
+/// assert!(matches!(nodes[0], Node::Paragraph(_)));
+///
+/// let tree = parser.finish()?;
+/// assert_eq!(tree.children().map(Vec::len), Some(2));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Parser<'a> {
+    /// Configuration.
+    options: &'a ParseOptions,
+    /// Everything pushed so far.
+    value: String,
+    /// Index of the start of the line where unfinished nodes start.
+    start: usize,
+    /// Line of `start`.
+    line: usize,
+    /// Size of the unfinished markdown when it was last parsed.
+    parsed: usize,
+    /// Normalized identifiers of definitions in finished nodes.
+    definitions: Vec<String>,
+    /// Normalized identifiers of GFM footnote definitions in finished nodes.
+    gfm_footnote_definitions: Vec<String>,
+    /// Index, and the size of the document before it in
+    /// `position_encoding`, to count positions of finished nodes from.
+    encoded: (usize, usize),
+}
+
+impl<'a> Parser<'a> {
+    /// Create a new parser.
+    pub fn new(options: &'a ParseOptions) -> Self {
+        Self {
+            options,
+            value: String::new(),
+            start: 0,
+            line: 1,
+            parsed: 0,
+            definitions: vec![],
+            gfm_footnote_definitions: vec![],
+            encoded: (0, 0),
+        }
+    }
+
+    /// Add a chunk of markdown, returning top-level nodes that are finished.
+    ///
+    /// ## Errors
+    ///
+    /// `push()` never errors with normal markdown because markdown does not
+    /// have syntax errors.
+    /// However, MDX does have syntax errors.
+    /// As MDX is parsed while it is pushed, an error can be the result of
+    /// a construct that is not complete yet.
+    pub fn push(&mut self, chunk: &str) -> Result<Vec<Node>, String> {
+        let before = self.value.len();
+        self.value.push_str(chunk);
+
+        // Only look further if a line was completed.
+        if !chunk.contains(|c| matches!(c, '\n' | '\r')) {
+            return Ok(vec![]);
+        }
+
+        let complete = self.value[before..]
+            .rfind(|c| matches!(c, '\n' | '\r'))
+            .map(|index| before + index + 1)
+            .unwrap();
+
+        // Parsing everything that is unfinished again on every line is
+        // quadratic when it stays unfinished, so when it is large, wait until
+        // it doubled.
+        if complete - self.start > STREAM_REPARSE_SIZE_MAX
+            && complete - self.start < self.parsed * 2
+        {
+            return Ok(vec![]);
+        }
+
+        // Parse from the start of the line where unfinished nodes start.
+        // When not at the start of the document, include the line ending
+        // before it, so that the first line is never frontmatter.
+        let tail_start = if self.start == 0 { 0 } else { self.start - 1 };
+        let (events, parse_state) = parse_with_definitions(
+            &self.value[tail_start..],
+            self.options,
+            self.definitions.clone(),
+            self.gfm_footnote_definitions.clone(),
+        )?;
+        let mut tree = compile(
+            &events,
+            parse_state.bytes,
+            parse_state.options.position_encoding,
+        )?;
+        let children = tree.children_mut().unwrap();
+
+        // Frontmatter is only known when its closing fence is seen, so
+        // nothing is finished while an opening fence is not closed.
+        if tail_start == 0
+            && self.options.constructs.frontmatter
+            && !matches!(children.first(), Some(Node::Yaml(_) | Node::Toml(_)))
+            && starts_with_fence(&self.value[..complete])
+        {
+            self.parsed = complete;
+            return Ok(vec![]);
+        }
+
+        if tail_start != 0 {
+            let mut index = 0;
+            while index < children.len() {
+                shift(&mut children[index], (2, 0), (self.line, tail_start));
+                index += 1;
+            }
+        }
+
+        // Nodes before a node whose first line is complete are finished, if
+        // that node would be the same when parsed on its own.
+        let mut done = 0;
+        let mut index = 1;
+        while index < children.len() {
+            if children[index].position().unwrap().start.offset < complete
+                && can_split(&children[index - 1], &children[index])
+            {
+                done = index;
+            }
+            index += 1;
+        }
+
+        // References in finished nodes must not use definitions in
+        // unfinished nodes, as those can still change.
+        let mut unfinished_definitions = vec![];
+        let mut unfinished_gfm_footnote_definitions = vec![];
+        let mut index = done;
+        while index < children.len() {
+            collect_definitions(
+                &children[index],
+                &mut unfinished_definitions,
+                &mut unfinished_gfm_footnote_definitions,
+            );
+            index += 1;
+        }
+
+        // Text that looks like a reference can turn into one when its
+        // definition comes in a later chunk, so it is not finished either.
+        let references = self.options.constructs.label_end
+            && (self.options.constructs.definition
+                || self.options.constructs.gfm_footnote_definition);
+        let mut index = 0;
+        while index < done {
+            if has_reference_to(
+                &children[index],
+                &unfinished_definitions,
+                &unfinished_gfm_footnote_definitions,
+            ) || (references && has_bracketed_text(&children[index], &mut false))
+            {
+                done = index;
+                break;
+            }
+            index += 1;
+        }
+
+        let rest = children.split_off(done);
+        let mut finished = children.split_off(0);
+        let encoding = self.options.position_encoding;
+
+        // Positions are in bytes until now, as that is what the above needs.
+        if encoding != PositionEncoding::Byte && !finished.is_empty() {
+            let bytes = self.value.as_bytes();
+            self.encoded.1 += count(&bytes[self.encoded.0..tail_start], encoding);
+            self.encoded.0 = tail_start;
+            encode_positions(&mut finished, &bytes[tail_start..], self.encoded, encoding);
+        }
+
+        if let Some(node) = rest.first() {
+            let point = &node.position().unwrap().start;
+            self.start = self.value[..point.offset]
+                .rfind(|c| matches!(c, '\n' | '\r'))
+                .map_or(0, |index| index + 1);
+            self.line = point.line;
+        }
+
+        self.parsed = complete - self.start;
+
+        let mut index = 0;
+        while index < finished.len() {
+            collect_definitions(
+                &finished[index],
+                &mut self.definitions,
+                &mut self.gfm_footnote_definitions,
+            );
+            index += 1;
+        }
+
+        Ok(finished)
+    }
+
+    /// Get the whole syntax tree.
+    ///
+    /// This parses everything that was pushed again, so that all references
+    /// to definitions are known.
+    ///
+    /// ## Errors
+    ///
+    /// `finish()` never errors with normal markdown because markdown does
+    /// not have syntax errors, so feel free to `unwrap()`.
+    /// However, MDX does have syntax errors.
+    pub fn finish(self) -> Result<Node, String> {
+        to_mdast(&self.value, self.options)
+    }
+}
+
+/// Whether `next` can be parsed without `previous`, as the lines of `next`
+/// are not part of `previous`.
+///
+/// After a blank line, a node is parsed the same way on its own.
+/// Without one, lines after content (paragraphs, definitions, setext
+/// headings, footnote definitions) can still belong to that content, such as
+/// an indented line after a definition, which is otherwise code (indented).
+/// Likewise, code (indented) can continue with indented lines.
+pub fn can_split(previous: &Node, next: &Node) -> bool {
+    let position = previous.position().unwrap();
+
+    if next.position().unwrap().start.line > position.end.line + 1 {
+        return true;
+    }
+
+    match previous {
+        Node::Code(_) | Node::Definition(_) | Node::FootnoteDefinition(_) | Node::Paragraph(_) => {
+            false
+        }
+        // Heading (atx) is one line, heading (setext) is more.
+        Node::Heading(_) => position.start.line == position.end.line,
+        _ => true,
+    }
+}
+
+/// Whether the first line in `value` looks like a frontmatter fence.
+pub fn starts_with_fence(value: &str) -> bool {
+    let fence = value
+        .split(|c| matches!(c, '\n' | '\r'))
+        .next()
+        .unwrap()
+        .trim_end_matches(|c| matches!(c, ' ' | '\t'));
+    fence == "---" || fence == "+++"
+}
+
+/// Move the positions in `node` and its descendants, so that `from` (a line
+/// and an offset) ends up at `to`.
+///
+/// Positions must not be before `from`.
+pub fn shift(node: &mut Node, from: (usize, usize), to: (usize, usize)) {
+    if let Some(position) = node.position_mut() {
+        position.start.line = position.start.line - from.0 + to.0;
+        position.start.offset = position.start.offset - from.1 + to.1;
+        position.end.line = position.end.line - from.0 + to.0;
+        position.end.offset = position.end.offset - from.1 + to.1;
+    }
+
+    if let Some(children) = node.children_mut() {
+        let mut index = 0;
+        while index < children.len() {
+            shift(&mut children[index], from, to);
+            index += 1;
+        }
+    }
+}
+
+/// Count the columns and offsets of positions in `nodes`, which are in bytes
+/// of the whole document, in `encoding`.
+///
+/// `bytes` is the rest of the document from `start.0`, which is `start.1`
+/// in `encoding`.
+/// It starts at the start of a line, or at the line ending before one.
+fn encode_positions(
+    nodes: &mut [Node],
+    bytes: &[u8],
+    start: (usize, usize),
+    encoding: PositionEncoding,
+) {
+    let location = Location::new(bytes);
+    let encode = |point: &mut Point| {
+        if let Some(encoded) = location.to_encoded_point(bytes, point.offset - start.0, encoding) {
+            point.column = encoded.column;
+            point.offset = start.1 + encoded.offset;
+        }
+    };
+    let mut index = 0;
+
+    while index < nodes.len() {
+        visit_mut(&mut nodes[index], &mut |node: &mut Node, _: &[usize]| {
+            if let Some(position) = node.position_mut() {
+                encode(&mut position.start);
+                encode(&mut position.end);
+            }
+
+            ActionMut::Continue
+        });
+        index += 1;
+    }
+}
+
+/// Whether `node` or its descendants reference one of `definitions` or
+/// `gfm_footnote_definitions` (normalized identifiers).
+fn has_reference_to(
+    node: &Node,
+    definitions: &[String],
+    gfm_footnote_definitions: &[String],
+) -> bool {
+    let found = match node {
+        Node::LinkReference(x) => definitions.contains(&normalize_identifier(&x.identifier)),
+        Node::ImageReference(x) => definitions.contains(&normalize_identifier(&x.identifier)),
+        Node::FootnoteReference(x) => {
+            gfm_footnote_definitions.contains(&normalize_identifier(&x.identifier))
+        }
+        _ => false,
+    };
+
+    found
+        || node.children().map_or(false, |children| {
+            let mut index = 0;
+            while index < children.len() {
+                if has_reference_to(&children[index], definitions, gfm_footnote_definitions) {
+                    return true;
+                }
+                index += 1;
+            }
+            false
+        })
+}
+
+/// Whether text in `node` or its descendants has a `[` followed by a `]`,
+/// which could be a reference to a definition that is not seen yet.
+///
+/// `open` is whether a `[` was seen in earlier text.
+fn has_bracketed_text(node: &Node, open: &mut bool) -> bool {
+    if let Node::Text(x) = node {
+        let bytes = x.value.as_bytes();
+        let mut index = 0;
+        while index < bytes.len() {
+            if bytes[index] == b'[' {
+                *open = true;
+            } else if bytes[index] == b']' && *open {
+                return true;
+            }
+            index += 1;
+        }
+    }
+
+    node.children().map_or(false, |children| {
+        let mut index = 0;
+        while index < children.len() {
+            if has_bracketed_text(&children[index], open) {
+                return true;
+            }
+            index += 1;
+        }
+        false
+    })
+}
+
+/// Add the normalized identifiers of definitions in `node` and its
+/// descendants.
+pub fn collect_definitions(
+    node: &Node,
+    definitions: &mut Vec<String>,
+    gfm_footnote_definitions: &mut Vec<String>,
+) {
+    match node {
+        Node::Definition(x) => definitions.push(normalize_identifier(&x.identifier)),
+        Node::FootnoteDefinition(x) => {
+            gfm_footnote_definitions.push(normalize_identifier(&x.identifier));
+        }
+        _ => {}
+    }
+
+    if let Some(children) = node.children() {
+        let mut index = 0;
+        while index < children.len() {
+            collect_definitions(&children[index], definitions, gfm_footnote_definitions);
+            index += 1;
+        }
+    }
+}
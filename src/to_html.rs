@@ -1,25 +1,29 @@
 //! Turn events into a string of HTML.
 use crate::event::{Event, Kind, Name};
-use crate::mdast::AlignKind;
+use crate::mdast::{self, AlignKind};
+#[cfg(feature = "mathml")]
+use crate::util::mathml::to_mathml;
 use crate::util::{
     character_reference::decode as decode_character_reference,
     constant::{SAFE_PROTOCOL_HREF, SAFE_PROTOCOL_SRC},
     encode::encode,
     gfm_tagfilter::gfm_tagfilter,
+    html_handler::HtmlNode,
     infer::{gfm_table_align, list_loose},
     normalize_identifier::normalize_identifier,
+    sanitize_html::Sanitizer,
     sanitize_uri::{sanitize, sanitize_with_protocols},
     skip,
     slice::{Position, Slice},
 };
-use crate::{CompileOptions, LineEnding};
+use crate::{CompileOptions, LineEnding, UrlKind};
 use alloc::{
     format,
     string::{String, ToString},
     vec,
     vec::Vec,
 };
-use core::str;
+use core::{fmt, str};
 
 /// Link, image, or footnote call.
 /// Resource or reference.
@@ -71,6 +75,24 @@ struct Definition {
     title: Option<String>,
 }
 
+/// Raw code (flow), collected for
+/// [`code_block_render`][CompileOptions::code_block_render].
+#[derive(Debug, Default)]
+struct CodeBlock {
+    /// The language (first word of the info string).
+    ///
+    /// Interpreted string content, not encoded.
+    lang: Option<String>,
+    /// The rest of the info string.
+    ///
+    /// Interpreted string content, not encoded.
+    meta: Option<String>,
+    /// The code.
+    ///
+    /// Not encoded.
+    value: String,
+}
+
 /// Context used to compile markdown.
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Debug)]
@@ -92,6 +114,8 @@ struct CompileContext<'a> {
     raw_flow_seen_data: Option<bool>,
     /// Number of raw (flow) fences.
     raw_flow_fences_count: Option<usize>,
+    /// Raw code (flow), when there is a `code_block_render` hook.
+    code_block: Option<CodeBlock>,
     /// Whether we are in code (text).
     raw_text_inside: bool,
     /// Whether we are in image text.
@@ -100,6 +124,11 @@ struct CompileContext<'a> {
     character_reference_marker: Option<u8>,
     /// Whether we are expecting the first list item marker.
     list_expect_first_marker: Option<bool>,
+    /// Stack of list starts, when there is a `html_handler`.
+    list_start_stack: Vec<Option<u32>>,
+    /// Stack of where the content of nodes starts in their buffer, after
+    /// their opening tag, when there is a `html_handler`.
+    content_start_stack: Vec<usize>,
     /// Stack of media (link, image).
     media_stack: Vec<Media>,
     /// Stack of containers.
@@ -116,6 +145,9 @@ struct CompileContext<'a> {
     gfm_table_align: Option<Vec<AlignKind>>,
     /// Current GFM table column.
     gfm_table_column: usize,
+    /// Sanitizer of HTML (flow) and HTML (text), when there is a
+    /// `html_sanitize` schema.
+    html_sanitizer: Option<Sanitizer>,
     // Fields used to influance the current compilation.
     /// Ignore the next line ending.
     slurp_one_line_ending: bool,
@@ -127,8 +159,13 @@ struct CompileContext<'a> {
     // Intermediate results.
     /// Stack of buffers.
     buffers: Vec<String>,
+    /// Last byte that was written to the output.
+    written_last: Option<u8>,
     /// Current event index.
     index: usize,
+    /// Index of the matching exit of each enter, and the other way around,
+    /// when `sourcepos` is on.
+    pairs: Vec<usize>,
 }
 
 impl<'a> CompileContext<'a> {
@@ -146,9 +183,12 @@ impl<'a> CompileContext<'a> {
             heading_setext_buffer: None,
             raw_flow_seen_data: None,
             raw_flow_fences_count: None,
+            code_block: None,
             raw_text_inside: false,
             character_reference_marker: None,
             list_expect_first_marker: None,
+            list_start_stack: vec![],
+            content_start_stack: vec![],
             media_stack: vec![],
             definitions: vec![],
             gfm_footnote_definitions: vec![],
@@ -157,13 +197,24 @@ impl<'a> CompileContext<'a> {
             gfm_table_in_head: false,
             gfm_table_align: None,
             gfm_table_column: 0,
+            html_sanitizer: if options.allow_dangerous_html && options.html_sanitize.is_some() {
+                Some(Sanitizer::default())
+            } else {
+                None
+            },
             tight_stack: vec![],
             slurp_one_line_ending: false,
             image_alt_inside: false,
             encode_html: true,
             line_ending_default: line_ending,
             buffers: vec![String::new()],
+            written_last: None,
             index: 0,
+            pairs: if options.sourcepos {
+                pairs(events)
+            } else {
+                vec![]
+            },
             options,
         }
     }
@@ -178,6 +229,13 @@ impl<'a> CompileContext<'a> {
         self.buffers.pop().expect("Cannot resume w/o buffer")
     }
 
+    /// Mark that the content of a buffered node starts here, after its
+    /// opening tag.
+    fn content_start(&mut self) {
+        let start = self.buffers.last().map_or(0, String::len);
+        self.content_start_stack.push(start);
+    }
+
     /// Push a str to the last buffer.
     fn push(&mut self, value: &str) {
         let last_buf_opt = self.buffers.last_mut();
@@ -191,11 +249,31 @@ impl<'a> CompileContext<'a> {
         self.push(&eol);
     }
 
+    /// Enter a scope in which elements opened in HTML are closed.
+    fn html_scope_enter(&mut self) {
+        if let Some(sanitizer) = self.html_sanitizer.as_mut() {
+            sanitizer.enter();
+        }
+    }
+
+    /// Exit a scope, closing elements opened in HTML in it.
+    fn html_scope_exit(&mut self) {
+        if let Some(sanitizer) = self.html_sanitizer.as_mut() {
+            let value = sanitizer.exit();
+            self.push(&value);
+        }
+    }
+
     /// Add a line ending if needed (as in, there’s no eol/eof already).
     fn line_ending_if_needed(&mut self) {
         let last_buf_opt = self.buffers.last();
         let last_buf = last_buf_opt.expect("at least one buffer should exist");
-        let last_byte = last_buf.as_bytes().last();
+        let mut last_byte = last_buf.as_bytes().last();
+
+        // Look at what was already written, when nothing is buffered.
+        if last_byte.is_none() && self.buffers.len() == 1 {
+            last_byte = self.written_last.as_ref();
+        }
 
         if !matches!(last_byte, None | Some(b'\n' | b'\r')) {
             self.line_ending();
@@ -205,6 +283,35 @@ impl<'a> CompileContext<'a> {
 
 /// Turn events and bytes into a string of HTML.
 pub fn compile(events: &[Event], bytes: &[u8], options: &CompileOptions) -> String {
+    compile_with_definitions(events, bytes, &[], options)
+}
+
+/// Turn events and bytes into a string of HTML, with definitions that are
+/// defined elsewhere.
+pub fn compile_with_definitions(
+    events: &[Event],
+    bytes: &[u8],
+    definitions: &[mdast::Definition],
+    options: &CompileOptions,
+) -> String {
+    let mut result = String::new();
+    compile_into(events, bytes, definitions, options, &mut result)
+        .expect("writing to a string cannot fail");
+    result
+}
+
+/// Turn events and bytes into HTML, writing it to `out`.
+///
+/// Completed top-level blocks are written as soon as they are compiled, so
+/// only what is still needed (such as footnote definitions) is kept in
+/// memory.
+pub fn compile_into<W: fmt::Write>(
+    events: &[Event],
+    bytes: &[u8],
+    definitions: &[mdast::Definition],
+    options: &CompileOptions,
+    out: &mut W,
+) -> fmt::Result {
     let mut index = 0;
     let mut line_ending_inferred = None;
 
@@ -229,6 +336,17 @@ pub fn compile(events: &[Event], bytes: &[u8], options: &CompileOptions) -> Stri
         line_ending_inferred.unwrap_or_else(|| options.default_line_ending.clone());
 
     let mut context = CompileContext::new(events, bytes, options, line_ending_default);
+
+    // Add definitions defined elsewhere.
+    for definition in definitions {
+        let destination = rewrite_url(&context, definition.url.clone(), UrlKind::Definition);
+        context.definitions.push(Definition {
+            id: normalize_identifier(&definition.identifier),
+            destination: Some(destination),
+            title: definition.title.as_ref().map(|title| encode(title, true)),
+        });
+    }
+
     let mut definition_indices = vec![];
     let mut index = 0;
     let mut definition_inside = false;
@@ -271,6 +389,7 @@ pub fn compile(events: &[Event], bytes: &[u8], options: &CompileOptions) -> Stri
     let mut jump = definition_indices
         .get(definition_index)
         .unwrap_or(&jump_default);
+    let mut depth = 0;
 
     while index < events.len() {
         if index == jump.0 {
@@ -281,21 +400,50 @@ pub fn compile(events: &[Event], bytes: &[u8], options: &CompileOptions) -> Stri
                 .unwrap_or(&jump_default);
         } else {
             handle(&mut context, index);
+
+            if events[index].kind == Kind::Enter {
+                depth += 1;
+            } else {
+                depth -= 1;
+
+                // Top-level block is done.
+                if depth == 0 {
+                    flush(&mut context, out)?;
+                }
+            }
+
             index += 1;
         }
     }
 
+    // Close elements still open in HTML.
+    if let Some(sanitizer) = context.html_sanitizer.as_mut() {
+        let value = sanitizer.close();
+        context.push(&value);
+    }
+
     // No section to generate.
     if !context.gfm_footnote_definition_calls.is_empty() {
         generate_footnote_section(&mut context);
     }
 
     debug_assert_eq!(context.buffers.len(), 1, "expected 1 final buffer");
-    context
-        .buffers
-        .get(0)
-        .expect("expected 1 final buffer")
-        .into()
+    flush(&mut context, out)
+}
+
+/// Write what is compiled so far to `out`, if nothing is being buffered.
+fn flush<W: fmt::Write>(context: &mut CompileContext, out: &mut W) -> fmt::Result {
+    if context.buffers.len() == 1 {
+        let buffer = &mut context.buffers[0];
+
+        if let Some(last) = buffer.as_bytes().last() {
+            context.written_last = Some(*last);
+            out.write_str(buffer)?;
+            buffer.clear();
+        }
+    }
+
+    Ok(())
 }
 
 /// Handle the event at `index`.
@@ -312,15 +460,10 @@ fn handle(context: &mut CompileContext, index: usize) {
 /// Handle [`Enter`][Kind::Enter].
 fn enter(context: &mut CompileContext) {
     match context.events[context.index].name {
-        Name::CodeFencedFenceInfo
-        | Name::CodeFencedFenceMeta
-        | Name::MathFlowFenceMeta
+        Name::MathFlowFenceMeta
         | Name::DefinitionLabelString
         | Name::DefinitionTitleString
         | Name::GfmFootnoteDefinitionPrefix
-        | Name::HeadingAtxText
-        | Name::HeadingSetextText
-        | Name::Label
         | Name::MdxEsm
         | Name::MdxFlowExpression
         | Name::MdxTextExpression
@@ -332,6 +475,9 @@ fn enter(context: &mut CompileContext) {
         Name::BlockQuote => on_enter_block_quote(context),
         Name::CodeIndented => on_enter_code_indented(context),
         Name::CodeFenced | Name::MathFlow => on_enter_raw_flow(context),
+        Name::CodeFencedFenceInfo | Name::CodeFencedFenceMeta => {
+            on_enter_raw_flow_fence_info_or_meta(context);
+        }
         Name::CodeText | Name::MathText => on_enter_raw_text(context),
         Name::Definition => on_enter_definition(context),
         Name::DefinitionDestinationString => on_enter_definition_destination_string(context),
@@ -346,9 +492,11 @@ fn enter(context: &mut CompileContext) {
         Name::GfmTableHead => on_enter_gfm_table_head(context),
         Name::GfmTableRow => on_enter_gfm_table_row(context),
         Name::GfmTaskListItemCheck => on_enter_gfm_task_list_item_check(context),
+        Name::HeadingAtxText | Name::HeadingSetextText => on_enter_heading_text(context),
         Name::HtmlFlow => on_enter_html_flow(context),
         Name::HtmlText => on_enter_html_text(context),
         Name::Image => on_enter_image(context),
+        Name::Label => on_enter_label(context),
         Name::Link => on_enter_link(context),
         Name::ListItemMarker => on_enter_list_item_marker(context),
         Name::ListOrdered | Name::ListUnordered => on_enter_list(context),
@@ -363,8 +511,7 @@ fn enter(context: &mut CompileContext) {
 /// Handle [`Exit`][Kind::Exit].
 fn exit(context: &mut CompileContext) {
     match context.events[context.index].name {
-        Name::CodeFencedFenceMeta
-        | Name::MathFlowFenceMeta
+        Name::MathFlowFenceMeta
         | Name::MdxJsxTextTag
         | Name::MdxTextExpression
         | Name::Resource => {
@@ -389,6 +536,7 @@ fn exit(context: &mut CompileContext) {
         Name::CodeFenced | Name::CodeIndented | Name::MathFlow => on_exit_raw_flow(context),
         Name::CodeFencedFence | Name::MathFlowFence => on_exit_raw_flow_fence(context),
         Name::CodeFencedFenceInfo => on_exit_raw_flow_fence_info(context),
+        Name::CodeFencedFenceMeta => on_exit_raw_flow_fence_meta(context),
         Name::CodeFlowChunk | Name::MathFlowChunk => on_exit_raw_flow_chunk(context),
         Name::CodeText | Name::MathText => on_exit_raw_text(context),
         Name::Definition => on_exit_definition(context),
@@ -452,33 +600,76 @@ fn on_enter_buffer(context: &mut CompileContext) {
 fn on_enter_block_quote(context: &mut CompileContext) {
     context.tight_stack.push(false);
     context.line_ending_if_needed();
-    context.push("<blockquote>");
+
+    let open = format!("<blockquote{}>", sourcepos(context, context.index));
+    push_open(context, &open);
+    context.html_scope_enter();
 }
 
 /// Handle [`Enter`][Kind::Enter]:[`CodeIndented`][Name::CodeIndented].
 fn on_enter_code_indented(context: &mut CompileContext) {
     context.raw_flow_seen_data = Some(false);
     context.line_ending_if_needed();
-    context.push("<pre><code>");
+
+    if context.options.code_block_render.is_some() {
+        context.code_block = Some(CodeBlock::default());
+        // Collect the default HTML, in case the hook does not handle it.
+        context.buffer();
+    }
+
+    let open = format!("<pre{}><code>", sourcepos(context, context.index));
+    context.push(&open);
 }
 
 /// Handle [`Enter`][Kind::Enter]:{[`CodeFenced`][Name::CodeFenced],[`MathFlow`][Name::MathFlow]}.
 fn on_enter_raw_flow(context: &mut CompileContext) {
     context.raw_flow_seen_data = Some(false);
     context.line_ending_if_needed();
-    // Note that no `>` is used, which is added later (due to info)
-    context.push("<pre><code");
     context.raw_flow_fences_count = Some(0);
 
+    if context.events[context.index].name == Name::MathFlow && mathml(context).is_some() {
+        // Collect the raw LaTeX, which is turned into MathML at the exit.
+        context.buffer();
+        context.encode_html = false;
+        return;
+    }
+
+    if context.events[context.index].name == Name::CodeFenced
+        && context.options.code_block_render.is_some()
+    {
+        context.code_block = Some(CodeBlock::default());
+        // Collect the default HTML, in case the hook does not handle it.
+        context.buffer();
+    }
+
+    // Note that no `>` is used, which is added later (due to info)
+    let open = format!("<pre{}><code", sourcepos(context, context.index));
+    context.push(&open);
+
     if context.events[context.index].name == Name::MathFlow {
         context.push(" class=\"language-math math-display\"");
     }
 }
 
+/// Handle [`Enter`][Kind::Enter]:{[`CodeFencedFenceInfo`][Name::CodeFencedFenceInfo],[`CodeFencedFenceMeta`][Name::CodeFencedFenceMeta]}.
+fn on_enter_raw_flow_fence_info_or_meta(context: &mut CompileContext) {
+    context.buffer();
+
+    // Collect the raw value for the hook, which is encoded later if needed.
+    if context.code_block.is_some() {
+        context.encode_html = false;
+    }
+}
+
 /// Handle [`Enter`][Kind::Enter]:{[`CodeText`][Name::CodeText],[`MathText`][Name::MathText]}.
 fn on_enter_raw_text(context: &mut CompileContext) {
     context.raw_text_inside = true;
-    if !context.image_alt_inside {
+    if context.events[context.index].name == Name::MathText
+        && mathml(context).is_some()
+        && !context.image_alt_inside
+    {
+        context.encode_html = false;
+    } else if !context.image_alt_inside {
         context.push("<code");
         if context.events[context.index].name == Name::MathText {
             context.push(" class=\"language-math math-inline\"");
@@ -510,7 +701,8 @@ fn on_enter_definition_destination_string(context: &mut CompileContext) {
 /// Handle [`Enter`][Kind::Enter]:[`Emphasis`][Name::Emphasis].
 fn on_enter_emphasis(context: &mut CompileContext) {
     if !context.image_alt_inside {
-        context.push("<em>");
+        push_open(context, "<em>");
+        context.html_scope_enter();
     }
 }
 
@@ -522,6 +714,7 @@ fn on_enter_frontmatter(context: &mut CompileContext) {
 /// Handle [`Enter`][Kind::Enter]:[`GfmFootnoteDefinition`][Name::GfmFootnoteDefinition].
 fn on_enter_gfm_footnote_definition(context: &mut CompileContext) {
     context.tight_stack.push(false);
+    context.html_scope_enter();
 }
 
 /// Handle [`Enter`][Kind::Enter]:[`GfmFootnoteCall`][Name::GfmFootnoteCall].
@@ -539,7 +732,8 @@ fn on_enter_gfm_footnote_call(context: &mut CompileContext) {
 /// Handle [`Enter`][Kind::Enter]:[`GfmStrikethrough`][Name::GfmStrikethrough].
 fn on_enter_gfm_strikethrough(context: &mut CompileContext) {
     if !context.image_alt_inside {
-        context.push("<del>");
+        push_open(context, "<del>");
+        context.html_scope_enter();
     }
 }
 
@@ -548,7 +742,9 @@ fn on_enter_gfm_table(context: &mut CompileContext) {
     let align = gfm_table_align(context.events, context.index);
     context.gfm_table_align = Some(align);
     context.line_ending_if_needed();
-    context.push("<table>");
+
+    let open = format!("<table{}>", sourcepos(context, context.index));
+    push_open(context, &open);
 }
 
 /// Handle [`Enter`][Kind::Enter]:[`GfmTableBody`][Name::GfmTableBody].
@@ -567,22 +763,11 @@ fn on_enter_gfm_table_cell(context: &mut CompileContext) {
     } else {
         let value = align[column];
         context.line_ending_if_needed();
-
-        if context.gfm_table_in_head {
-            context.push("<th");
-        } else {
-            context.push("<td");
-        }
-
-        match value {
-            AlignKind::Left => context.push(" align=\"left\""),
-            AlignKind::Right => context.push(" align=\"right\""),
-            AlignKind::Center => context.push(" align=\"center\""),
-            AlignKind::None => {}
-        }
-
-        context.push(">");
+        let open = gfm_table_cell_open(context, value);
+        push_open(context, &open);
     }
+
+    context.html_scope_enter();
 }
 
 /// Handle [`Enter`][Kind::Enter]:[`GfmTableHead`][Name::GfmTableHead].
@@ -595,7 +780,8 @@ fn on_enter_gfm_table_head(context: &mut CompileContext) {
 /// Handle [`Enter`][Kind::Enter]:[`GfmTableRow`][Name::GfmTableRow].
 fn on_enter_gfm_table_row(context: &mut CompileContext) {
     context.line_ending_if_needed();
-    context.push("<tr>");
+    let open = format!("<tr{}>", sourcepos(context, context.index));
+    push_open(context, &open);
 }
 
 /// Handle [`Enter`][Kind::Enter]:[`GfmTaskListItemCheck`][Name::GfmTaskListItemCheck].
@@ -608,18 +794,27 @@ fn on_enter_gfm_task_list_item_check(context: &mut CompileContext) {
     }
 }
 
+/// Handle [`Enter`][Kind::Enter]:{[`HeadingAtxText`][Name::HeadingAtxText],[`HeadingSetextText`][Name::HeadingSetextText]}.
+fn on_enter_heading_text(context: &mut CompileContext) {
+    context.buffer();
+    context.html_scope_enter();
+}
+
 /// Handle [`Enter`][Kind::Enter]:[`HtmlFlow`][Name::HtmlFlow].
 fn on_enter_html_flow(context: &mut CompileContext) {
     context.line_ending_if_needed();
-    if context.options.allow_dangerous_html {
-        context.encode_html = false;
-    }
+    on_enter_html_text(context);
 }
 
 /// Handle [`Enter`][Kind::Enter]:[`HtmlText`][Name::HtmlText].
 fn on_enter_html_text(context: &mut CompileContext) {
     if context.options.allow_dangerous_html {
         context.encode_html = false;
+
+        // Collect the raw HTML, to sanitize it.
+        if context.html_sanitizer.is_some() {
+            context.buffer();
+        }
     }
 }
 
@@ -636,6 +831,12 @@ fn on_enter_image(context: &mut CompileContext) {
     context.image_alt_inside = true; // Disallow tags.
 }
 
+/// Handle [`Enter`][Kind::Enter]:[`Label`][Name::Label].
+fn on_enter_label(context: &mut CompileContext) {
+    context.buffer();
+    context.html_scope_enter();
+}
+
 /// Handle [`Enter`][Kind::Enter]:[`Link`][Name::Link].
 fn on_enter_link(context: &mut CompileContext) {
     context.media_stack.push(Media {
@@ -654,12 +855,20 @@ fn on_enter_list(context: &mut CompileContext) {
     context.tight_stack.push(!loose);
     context.line_ending_if_needed();
 
+    // Collect the children, for the handler.
+    if context.options.html_handler.is_some() {
+        context.buffer();
+        context.list_start_stack.push(None);
+    }
+
     // Note: no `>`.
     context.push(if context.events[context.index].name == Name::ListOrdered {
         "<ol"
     } else {
         "<ul"
     });
+    let attribute = sourcepos(context, context.index);
+    context.push(&attribute);
     context.list_expect_first_marker = Some(true);
 }
 
@@ -667,11 +876,17 @@ fn on_enter_list(context: &mut CompileContext) {
 fn on_enter_list_item_marker(context: &mut CompileContext) {
     if context.list_expect_first_marker.take().unwrap() {
         context.push(">");
+
+        if context.options.html_handler.is_some() {
+            context.content_start();
+        }
     }
 
     context.line_ending_if_needed();
-
-    context.push("<li>");
+    let item = skip::to_back(context.events, context.index, &[Name::ListItem]);
+    let open = format!("<li{}>", sourcepos(context, item));
+    push_open(context, &open);
+    context.html_scope_enter();
     context.list_expect_first_marker = Some(false);
 }
 
@@ -681,8 +896,11 @@ fn on_enter_paragraph(context: &mut CompileContext) {
 
     if !tight {
         context.line_ending_if_needed();
-        context.push("<p>");
+        let open = format!("<p{}>", sourcepos(context, context.index));
+        push_open(context, &open);
     }
+
+    context.html_scope_enter();
 }
 
 /// Handle [`Enter`][Kind::Enter]:[`Resource`][Name::Resource].
@@ -702,7 +920,8 @@ fn on_enter_resource_destination_string(context: &mut CompileContext) {
 /// Handle [`Enter`][Kind::Enter]:[`Strong`][Name::Strong].
 fn on_enter_strong(context: &mut CompileContext) {
     if !context.image_alt_inside {
-        context.push("<strong>");
+        push_open(context, "<strong>");
+        context.html_scope_enter();
     }
 }
 
@@ -751,9 +970,11 @@ fn on_exit_blank_line_ending(context: &mut CompileContext) {
 /// Handle [`Exit`][Kind::Exit]:[`BlockQuote`][Name::BlockQuote].
 fn on_exit_block_quote(context: &mut CompileContext) {
     context.tight_stack.pop();
+    context.html_scope_exit();
     context.line_ending_if_needed();
     context.slurp_one_line_ending = false;
-    context.push("</blockquote>");
+
+    push_buffered_node(context, &HtmlNode::BlockQuote, "</blockquote>");
 }
 
 /// Handle [`Exit`][Kind::Exit]:[`CharacterReferenceMarker`][Name::CharacterReferenceMarker].
@@ -790,15 +1011,18 @@ fn on_exit_character_reference_value(context: &mut CompileContext) {
 /// Handle [`Exit`][Kind::Exit]:{[`CodeFlowChunk`][Name::CodeFlowChunk],[`MathFlowChunk`][Name::MathFlowChunk]}.
 fn on_exit_raw_flow_chunk(context: &mut CompileContext) {
     context.raw_flow_seen_data = Some(true);
-    context.push(&encode(
-        &Slice::from_position(
-            context.bytes,
-            &Position::from_exit_event(context.events, context.index),
-        )
-        // Must serialize to get virtual spaces.
-        .serialize(),
-        context.encode_html,
-    ));
+    let value = Slice::from_position(
+        context.bytes,
+        &Position::from_exit_event(context.events, context.index),
+    )
+    // Must serialize to get virtual spaces.
+    .serialize();
+
+    if let Some(code_block) = context.code_block.as_mut() {
+        code_block.value.push_str(&value);
+    }
+
+    context.push(&encode(&value, context.encode_html));
 }
 
 /// Handle [`Exit`][Kind::Exit]:{[`CodeFencedFence`][Name::CodeFencedFence],[`MathFlowFence`][Name::MathFlowFence]}.
@@ -808,7 +1032,9 @@ fn on_exit_raw_flow_fence(context: &mut CompileContext) {
         .expect("expected `raw_flow_fences_count`");
 
     if count == 0 {
-        context.push(">");
+        if context.events[context.index].name != Name::MathFlowFence || mathml(context).is_none() {
+            context.push(">");
+        }
         context.slurp_one_line_ending = true;
     }
 
@@ -819,12 +1045,29 @@ fn on_exit_raw_flow_fence(context: &mut CompileContext) {
 ///
 /// Note: math (flow) does not support `info`.
 fn on_exit_raw_flow_fence_info(context: &mut CompileContext) {
-    let value = context.resume();
+    let mut value = context.resume();
+
+    if let Some(code_block) = context.code_block.as_mut() {
+        context.encode_html = true;
+        code_block.lang = Some(value.clone());
+        value = encode(&value, true);
+    }
+
     context.push(" class=\"language-");
     context.push(&value);
     context.push("\"");
 }
 
+/// Handle [`Exit`][Kind::Exit]:[`CodeFencedFenceMeta`][Name::CodeFencedFenceMeta].
+fn on_exit_raw_flow_fence_meta(context: &mut CompileContext) {
+    let value = context.resume();
+
+    if let Some(code_block) = context.code_block.as_mut() {
+        context.encode_html = true;
+        code_block.meta = Some(value);
+    }
+}
+
 /// Handle [`Exit`][Kind::Exit]:{[`CodeFenced`][Name::CodeFenced],[`CodeIndented`][Name::CodeIndented],[`MathFlow`][Name::MathFlow]}.
 fn on_exit_raw_flow(context: &mut CompileContext) {
     // One special case is if we are inside a container, and the raw (flow) was
@@ -854,7 +1097,47 @@ fn on_exit_raw_flow(context: &mut CompileContext) {
         context.line_ending_if_needed();
     }
 
-    context.push("</code></pre>");
+    let compile = if context.events[context.index].name == Name::MathFlow {
+        mathml(context)
+    } else {
+        None
+    };
+
+    if let Some(compile) = compile {
+        let value = context.resume();
+        context.encode_html = true;
+        context.push(&compile(
+            value.trim_end_matches(|d| d == '\n' || d == '\r'),
+            true,
+        ));
+    } else {
+        context.push("</code></pre>");
+    }
+
+    if let Some(code_block) = context.code_block.take() {
+        let html = context.resume();
+        let mut value = code_block.value;
+
+        // Drop the final line ending, like in mdast.
+        if value.ends_with('\n') {
+            value.pop();
+        }
+        if value.ends_with('\r') {
+            value.pop();
+        }
+
+        let render = context
+            .options
+            .code_block_render
+            .as_ref()
+            .expect("expected `code_block_render` if there is a code block");
+        let result = render(
+            code_block.lang.as_deref(),
+            code_block.meta.as_deref(),
+            &value,
+        );
+        context.push(&result.unwrap_or(html));
+    }
 
     if let Some(count) = context.raw_flow_fences_count.take() {
         if count < 2 {
@@ -909,10 +1192,23 @@ fn on_exit_raw_text(context: &mut CompileContext) {
     }
 
     context.raw_text_inside = false;
-    context.push(str::from_utf8(&bytes).unwrap());
 
-    if !context.image_alt_inside {
-        context.push("</code>");
+    let compile =
+        if context.events[context.index].name == Name::MathText && !context.image_alt_inside {
+            mathml(context)
+        } else {
+            None
+        };
+
+    if let Some(compile) = compile {
+        context.encode_html = true;
+        context.push(&compile(str::from_utf8(&bytes).unwrap(), false));
+    } else {
+        context.push(str::from_utf8(&bytes).unwrap());
+
+        if !context.image_alt_inside {
+            context.push("</code>");
+        }
     }
 }
 
@@ -961,6 +1257,7 @@ fn on_exit_definition(context: &mut CompileContext) {
 /// Handle [`Exit`][Kind::Exit]:[`DefinitionDestinationString`][Name::DefinitionDestinationString].
 fn on_exit_definition_destination_string(context: &mut CompileContext) {
     let buf = context.resume();
+    let buf = rewrite_url(context, buf, UrlKind::Definition);
     context.media_stack.last_mut().unwrap().destination = Some(buf);
     context.encode_html = true;
 }
@@ -982,7 +1279,8 @@ fn on_exit_definition_title_string(context: &mut CompileContext) {
 /// Handle [`Exit`][Kind::Exit]:[`Emphasis`][Name::Emphasis].
 fn on_exit_emphasis(context: &mut CompileContext) {
     if !context.image_alt_inside {
-        context.push("</em>");
+        context.html_scope_exit();
+        push_buffered_node(context, &HtmlNode::Emphasis, "</em>");
     }
 }
 
@@ -1139,6 +1437,7 @@ fn on_exit_gfm_footnote_definition_prefix(context: &mut CompileContext) {
 
 /// Handle [`Exit`][Kind::Exit]:[`GfmFootnoteDefinition`][Name::GfmFootnoteDefinition].
 fn on_exit_gfm_footnote_definition(context: &mut CompileContext) {
+    context.html_scope_exit();
     let value = context.resume();
     let indices = context.gfm_footnote_definition_stack.pop().unwrap();
     context.tight_stack.pop();
@@ -1151,15 +1450,17 @@ fn on_exit_gfm_footnote_definition(context: &mut CompileContext) {
 /// Handle [`Exit`][Kind::Exit]:[`GfmStrikethrough`][Name::GfmStrikethrough].
 fn on_exit_gfm_strikethrough(context: &mut CompileContext) {
     if !context.image_alt_inside {
-        context.push("</del>");
+        context.html_scope_exit();
+        push_buffered_node(context, &HtmlNode::Delete, "</del>");
     }
 }
 
 /// Handle [`Exit`][Kind::Exit]:[`GfmTable`][Name::GfmTable].
 fn on_exit_gfm_table(context: &mut CompileContext) {
-    context.gfm_table_align = None;
+    let align = context.gfm_table_align.take().unwrap();
     context.line_ending_if_needed();
-    context.push("</table>");
+
+    push_buffered_node(context, &HtmlNode::Table { align: &align }, "</table>");
 }
 
 /// Handle [`Exit`][Kind::Exit]:[`GfmTableBody`][Name::GfmTableBody].
@@ -1170,14 +1471,17 @@ fn on_exit_gfm_table_body(context: &mut CompileContext) {
 
 /// Handle [`Exit`][Kind::Exit]:[`GfmTableCell`][Name::GfmTableCell].
 fn on_exit_gfm_table_cell(context: &mut CompileContext) {
+    context.html_scope_exit();
     let align = context.gfm_table_align.as_ref().unwrap();
 
     if context.gfm_table_column < align.len() {
-        if context.gfm_table_in_head {
-            context.push("</th>");
-        } else {
-            context.push("</td>");
-        }
+        let value = align[context.gfm_table_column];
+        let head = context.gfm_table_in_head;
+        push_buffered_node(
+            context,
+            &HtmlNode::TableCell { align: value, head },
+            if head { "</th>" } else { "</td>" },
+        );
     } else {
         // Stop capturing.
         context.resume();
@@ -1208,7 +1512,7 @@ fn on_exit_gfm_table_row(context: &mut CompileContext) {
 
     context.gfm_table_column = 0;
     context.line_ending_if_needed();
-    context.push("</tr>");
+    push_buffered_node(context, &HtmlNode::TableRow, "</tr>");
 }
 
 /// Handle [`Exit`][Kind::Exit]:[`GfmTaskListItemCheck`][Name::GfmTaskListItemCheck].
@@ -1232,9 +1536,23 @@ fn on_exit_heading_atx(context: &mut CompileContext) {
         .take()
         .expect("`heading_atx_rank` must be set in headings");
 
-    context.push("</h");
-    context.push(&rank.to_string());
-    context.push(">");
+    if context.options.html_handler.is_some() {
+        let children = context.resume();
+        #[allow(clippy::cast_possible_truncation)]
+        let depth = rank as u8;
+        let attribute = sourcepos(context, context.index);
+        push_node(
+            context,
+            &HtmlNode::Heading { depth },
+            &format!("<h{}{}>", rank, attribute),
+            &children,
+            &format!("</h{}>", rank),
+        );
+    } else {
+        context.push("</h");
+        context.push(&rank.to_string());
+        context.push(">");
+    }
 }
 
 /// Handle [`Exit`][Kind::Exit]:[`HeadingAtxSequence`][Name::HeadingAtxSequence].
@@ -1248,20 +1566,31 @@ fn on_exit_heading_atx_sequence(context: &mut CompileContext) {
         .len();
         context.line_ending_if_needed();
         context.heading_atx_rank = Some(rank);
-        context.push("<h");
-        context.push(&rank.to_string());
-        context.push(">");
+
+        // Collect the children, for the handler.
+        if context.options.html_handler.is_some() {
+            context.buffer();
+        } else {
+            let heading = skip::to_back(context.events, context.index, &[Name::HeadingAtx]);
+            let attribute = sourcepos(context, heading);
+            context.push("<h");
+            context.push(&rank.to_string());
+            context.push(&attribute);
+            context.push(">");
+        }
     }
 }
 
 /// Handle [`Exit`][Kind::Exit]:[`HeadingAtxText`][Name::HeadingAtxText].
 fn on_exit_heading_atx_text(context: &mut CompileContext) {
+    context.html_scope_exit();
     let value = context.resume();
     context.push(&value);
 }
 
 /// Handle [`Exit`][Kind::Exit]:[`HeadingSetextText`][Name::HeadingSetextText].
 fn on_exit_heading_setext_text(context: &mut CompileContext) {
+    context.html_scope_exit();
     let buf = context.resume();
     context.heading_setext_buffer = Some(buf);
     context.slurp_one_line_ending = true;
@@ -1275,21 +1604,38 @@ fn on_exit_heading_setext_underline_sequence(context: &mut CompileContext) {
         .expect("`heading_atx_rank` must be set in headings");
     let position = Position::from_exit_event(context.events, context.index);
     let head = context.bytes[position.start.index];
-    let rank = if head == b'-' { "2" } else { "1" };
+    let rank = if head == b'-' { 2 } else { 1 };
+    let heading = skip::to_back(context.events, context.index, &[Name::HeadingSetext]);
+    let attribute = sourcepos(context, heading);
 
     context.line_ending_if_needed();
-    context.push("<h");
-    context.push(rank);
-    context.push(">");
-    context.push(&text);
-    context.push("</h");
-    context.push(rank);
-    context.push(">");
+    push_node(
+        context,
+        &HtmlNode::Heading { depth: rank },
+        &format!("<h{}{}>", rank, attribute),
+        &text,
+        &format!("</h{}>", rank),
+    );
 }
 
 /// Handle [`Exit`][Kind::Exit]:{[`HtmlFlow`][Name::HtmlFlow],[`HtmlText`][Name::HtmlText]}.
 fn on_exit_html(context: &mut CompileContext) {
     context.encode_html = true;
+
+    if context.html_sanitizer.is_some() {
+        let value = context.resume();
+
+        // Attributes could be injected into the `alt` of images.
+        if !context.image_alt_inside {
+            let schema = context.options.html_sanitize.as_ref().unwrap();
+            let value = context
+                .html_sanitizer
+                .as_mut()
+                .unwrap()
+                .sanitize(schema, &value);
+            context.push(&value);
+        }
+    }
 }
 
 /// Handle [`Exit`][Kind::Exit]:{[`HtmlFlowData`][Name::HtmlFlowData],[`HtmlTextData`][Name::HtmlTextData]}.
@@ -1300,7 +1646,9 @@ fn on_exit_html_data(context: &mut CompileContext) {
     );
     let value = slice.as_str();
 
-    let encoded = if context.options.gfm_tagfilter && context.options.allow_dangerous_html {
+    let encoded = if context.html_sanitizer.is_some() {
+        value.to_string()
+    } else if context.options.gfm_tagfilter && context.options.allow_dangerous_html {
         encode(&gfm_tagfilter(value), context.encode_html)
     } else {
         encode(value, context.encode_html)
@@ -1311,6 +1659,7 @@ fn on_exit_html_data(context: &mut CompileContext) {
 
 /// Handle [`Exit`][Kind::Exit]:[`Label`][Name::Label].
 fn on_exit_label(context: &mut CompileContext) {
+    context.html_scope_exit();
     let buf = context.resume();
     context.media_stack.last_mut().unwrap().label = Some(buf);
 }
@@ -1333,14 +1682,17 @@ fn on_exit_line_ending(context: &mut CompileContext) {
     {
         context.slurp_one_line_ending = false;
     } else {
-        context.push(&encode(
-            Slice::from_position(
-                context.bytes,
-                &Position::from_exit_event(context.events, context.index),
-            )
-            .as_str(),
-            context.encode_html,
-        ));
+        let slice = Slice::from_position(
+            context.bytes,
+            &Position::from_exit_event(context.events, context.index),
+        );
+        let value = slice.as_str();
+
+        if let Some(code_block) = context.code_block.as_mut() {
+            code_block.value.push_str(value);
+        }
+
+        context.push(&encode(value, context.encode_html));
     }
 }
 
@@ -1348,11 +1700,17 @@ fn on_exit_line_ending(context: &mut CompileContext) {
 fn on_exit_list(context: &mut CompileContext) {
     context.tight_stack.pop();
     context.line_ending();
-    context.push(if context.events[context.index].name == Name::ListOrdered {
-        "</ol>"
+    let ordered = context.events[context.index].name == Name::ListOrdered;
+    let close = if ordered { "</ol>" } else { "</ul>" };
+
+    // The start is only collected when there is a handler.
+    let start = context.list_start_stack.pop().flatten();
+    let start = if ordered {
+        Some(start.unwrap_or(1))
     } else {
-        "</ul>"
-    });
+        None
+    };
+    push_buffered_node(context, &HtmlNode::List { ordered, start }, close);
 }
 
 /// Handle [`Exit`][Kind::Exit]:[`ListItem`][Name::ListItem].
@@ -1375,13 +1733,14 @@ fn on_exit_list_item(context: &mut CompileContext) {
     let tight_paragraph = *tight && previous.name == Name::Paragraph;
     let empty_item = previous.name == Name::ListItemPrefix;
 
+    context.html_scope_exit();
     context.slurp_one_line_ending = false;
 
     if !tight_paragraph && !empty_item {
         context.line_ending_if_needed();
     }
 
-    context.push("</li>");
+    push_buffered_node(context, &HtmlNode::ListItem, "</li>");
 }
 
 /// Handle [`Exit`][Kind::Exit]:[`ListItemValue`][Name::ListItemValue].
@@ -1393,6 +1752,10 @@ fn on_exit_list_item_value(context: &mut CompileContext) {
         );
         let value = slice.as_str().parse::<u32>().ok().unwrap();
 
+        if let Some(start) = context.list_start_stack.last_mut() {
+            *start = Some(value);
+        }
+
         if value != 1 {
             context.push(" start=\"");
             context.push(&value.to_string());
@@ -1442,83 +1805,73 @@ fn on_exit_media(context: &mut CompileContext) {
         None
     };
 
-    if !is_in_image {
-        if media.image {
-            context.push("<img src=\"");
-        } else {
-            context.push("<a href=\"");
-        };
-
-        let destination = if let Some(index) = definition_index {
-            context.definitions[index].destination.as_ref()
-        } else {
-            media.destination.as_ref()
-        };
-
-        if let Some(destination) = destination {
-            let url = if context.options.allow_dangerous_protocol {
-                sanitize(destination)
-            } else {
-                sanitize_with_protocols(
-                    destination,
-                    if media.image {
-                        &SAFE_PROTOCOL_SRC
-                    } else {
-                        &SAFE_PROTOCOL_HREF
-                    },
-                )
-            };
-            context.push(&url);
-        }
-
-        if media.image {
-            context.push("\" alt=\"");
-        };
-    }
-
-    if media.image {
+    if is_in_image {
         context.push(&label);
+        return;
     }
 
-    if !is_in_image {
-        context.push("\"");
-
-        let title = if let Some(index) = definition_index {
-            context.definitions[index].title.clone()
-        } else {
-            media.title
-        };
-
-        if let Some(title) = title {
-            context.push(" title=\"");
-            context.push(&title);
-            context.push("\"");
-        };
+    let destination = if let Some(index) = definition_index {
+        context.definitions[index].destination.as_ref()
+    } else {
+        media.destination.as_ref()
+    };
 
-        if media.image {
-            context.push(" /");
-        }
+    let url = if let Some(destination) = destination {
+        sanitize_url(context, destination, media.image)
+    } else {
+        String::new()
+    };
 
-        context.push(">");
-    }
+    let title = if let Some(index) = definition_index {
+        context.definitions[index].title.clone()
+    } else {
+        media.title
+    };
 
-    if !media.image {
-        context.push(&label);
+    let title_attribute = if let Some(title) = &title {
+        format!(" title=\"{}\"", title)
+    } else {
+        String::new()
+    };
 
-        if !is_in_image {
-            context.push("</a>");
-        }
+    if media.image {
+        push_node(
+            context,
+            &HtmlNode::Image {
+                url: &url,
+                alt: &label,
+                title: title.as_deref(),
+            },
+            &format!(
+                "<img src=\"{}\" alt=\"{}\"{} />",
+                url, label, title_attribute
+            ),
+            "",
+            "",
+        );
+    } else {
+        push_node(
+            context,
+            &HtmlNode::Link {
+                url: &url,
+                title: title.as_deref(),
+            },
+            &format!("<a href=\"{}\"{}>", url, title_attribute),
+            &label,
+            "</a>",
+        );
     }
 }
 
 /// Handle [`Exit`][Kind::Exit]:[`Paragraph`][Name::Paragraph].
 fn on_exit_paragraph(context: &mut CompileContext) {
+    context.html_scope_exit();
     let tight = context.tight_stack.last().unwrap_or(&false);
 
     if *tight {
         context.slurp_one_line_ending = true;
     } else {
-        context.push("</p>");
+        push_buffered_node(context, &HtmlNode::Paragraph, "</p>");
     }
 }
 
@@ -1534,6 +1887,12 @@ fn on_exit_reference_string(context: &mut CompileContext) {
 /// Handle [`Exit`][Kind::Exit]:[`ResourceDestinationString`][Name::ResourceDestinationString].
 fn on_exit_resource_destination_string(context: &mut CompileContext) {
     let buf = context.resume();
+    let kind = if context.media_stack.last().unwrap().image {
+        UrlKind::Image
+    } else {
+        UrlKind::Link
+    };
+    let buf = rewrite_url(context, buf, kind);
     context.media_stack.last_mut().unwrap().destination = Some(buf);
     context.encode_html = true;
 }
@@ -1547,14 +1906,40 @@ fn on_exit_resource_title_string(context: &mut CompileContext) {
 /// Handle [`Exit`][Kind::Exit]:[`Strong`][Name::Strong].
 fn on_exit_strong(context: &mut CompileContext) {
     if !context.image_alt_inside {
-        context.push("</strong>");
+        context.html_scope_exit();
+        push_buffered_node(context, &HtmlNode::Strong, "</strong>");
     }
 }
 
 /// Handle [`Exit`][Kind::Exit]:[`ThematicBreak`][Name::ThematicBreak].
 fn on_exit_thematic_break(context: &mut CompileContext) {
     context.line_ending_if_needed();
-    context.push("<hr />");
+    let value = format!("<hr{} />", sourcepos(context, context.index));
+    push_node(context, &HtmlNode::ThematicBreak, &value, "", "");
+}
+
+/// Generate the opening tag of a table cell.
+fn gfm_table_cell_open(context: &CompileContext, align: AlignKind) -> String {
+    let mut open = String::from(if context.gfm_table_in_head {
+        "<th"
+    } else {
+        "<td"
+    });
+
+    // Phantom cells are added at the end of rows.
+    if context.events[context.index].name == Name::GfmTableCell {
+        open.push_str(&sourcepos(context, context.index));
+    }
+
+    match align {
+        AlignKind::Left => open.push_str(" align=\"left\""),
+        AlignKind::Right => open.push_str(" align=\"right\""),
+        AlignKind::Center => open.push_str(" align=\"center\""),
+        AlignKind::None => {}
+    }
+
+    open.push('>');
+    open
 }
 
 /// Generate a footnote section.
@@ -1721,27 +2106,202 @@ fn generate_autolink(
         index += 1;
     }
 
+    let children = encode(value, context.encode_html);
+
     if !context.image_alt_inside && (!is_in_link || !is_gfm_literal) {
-        context.push("<a href=\"");
         let url = if let Some(protocol) = protocol {
             format!("{}{}", protocol, value)
         } else {
             value.into()
         };
 
-        let url = if context.options.allow_dangerous_protocol {
-            sanitize(&url)
+        let url = rewrite_url(context, url, UrlKind::Autolink);
+
+        let url = sanitize_url(context, &url, false);
+
+        push_node(
+            context,
+            &HtmlNode::Link {
+                url: &url,
+                title: None,
+            },
+            &format!("<a href=\"{}\">", url),
+            &children,
+            "</a>",
+        );
+    } else {
+        context.push(&children);
+    }
+}
+
+/// Rewrite a URL with the `url_rewrite` hook, if any.
+fn rewrite_url(context: &CompileContext, url: String, kind: UrlKind) -> String {
+    if let Some(rewrite) = &context.options.url_rewrite {
+        rewrite(&url, &kind).unwrap_or(url)
+    } else {
+        url
+    }
+}
+
+/// Make a URL safe, as `src` on `img` if `image`, or as `href` on `a`
+/// otherwise, with the configured protocols.
+fn sanitize_url(context: &CompileContext, url: &str, image: bool) -> String {
+    let options = context.options;
+
+    if options.allow_dangerous_protocol {
+        sanitize(url)
+    } else if image {
+        if let Some(protocols) = &options.safe_protocol_src {
+            sanitize_with_protocols(url, protocols)
         } else {
-            sanitize_with_protocols(&url, &SAFE_PROTOCOL_HREF)
-        };
+            sanitize_with_protocols(url, &SAFE_PROTOCOL_SRC)
+        }
+    } else if let Some(protocols) = &options.safe_protocol_href {
+        sanitize_with_protocols(url, protocols)
+    } else {
+        sanitize_with_protocols(url, &SAFE_PROTOCOL_HREF)
+    }
+}
 
-        context.push(&url);
-        context.push("\">");
+/// Get a `data-sourcepos` attribute for the construct whose enter or exit is
+/// at `index`, if turned on.
+///
+/// Like `cmark`, the end column is that of the last character, which is
+/// before trailing line endings.
+fn sourcepos(context: &CompileContext, index: usize) -> String {
+    if !context.options.sourcepos {
+        return String::new();
     }
 
-    context.push(&encode(value, context.encode_html));
+    let (enter, exit) = if context.events[index].kind == Kind::Enter {
+        (index, context.pairs[index])
+    } else {
+        (context.pairs[index], index)
+    };
 
-    if !context.image_alt_inside && (!is_in_link || !is_gfm_literal) {
-        context.push("</a>");
+    let start = &context.events[enter].point;
+    let end = &context.events[exit].point;
+    let mut end_line = end.line;
+    let mut end_column = end.column - 1;
+
+    // Move back from after line endings.
+    if end.column == 1 && end.line > start.line {
+        let mut end_index = end.index;
+
+        while end_index > start.index && matches!(context.bytes[end_index - 1], b'\n' | b'\r') {
+            if !(context.bytes[end_index - 1] == b'\r'
+                && end_index < context.bytes.len()
+                && context.bytes[end_index] == b'\n')
+            {
+                end_line -= 1;
+            }
+
+            end_index -= 1;
+        }
+
+        let mut line_start = end_index;
+
+        while line_start > 0 && !matches!(context.bytes[line_start - 1], b'\n' | b'\r') {
+            line_start -= 1;
+        }
+
+        end_column = end_index - line_start;
+    }
+
+    format!(
+        " data-sourcepos=\"{}:{}-{}:{}\"",
+        start.line, start.column, end_line, end_column
+    )
+}
+
+/// Get the index of the matching exit of each enter, and the other way
+/// around.
+fn pairs(events: &[Event]) -> Vec<usize> {
+    let mut pairs = vec![0; events.len()];
+    let mut stack = vec![];
+    let mut index = 0;
+
+    while index < events.len() {
+        if events[index].kind == Kind::Enter {
+            stack.push(index);
+        } else {
+            let enter = stack.pop().expect("expected enter");
+            debug_assert_eq!(
+                events[enter].name, events[index].name,
+                "expected matching exit"
+            );
+            pairs[enter] = index;
+            pairs[index] = enter;
+        }
+
+        index += 1;
+    }
+
+    pairs
+}
+
+/// Push a node, using the handler (if any) for its opening tag, content, and
+/// closing tag.
+fn push_node(
+    context: &mut CompileContext,
+    node: &HtmlNode,
+    open: &str,
+    children: &str,
+    close: &str,
+) {
+    let options = context.options;
+
+    if let Some(handler) = &options.html_handler {
+        let open_custom = handler.enter(node, children);
+        let children_custom = handler.content(node, children);
+        let close_custom = handler.exit(node, children);
+        context.push(open_custom.as_deref().unwrap_or(open));
+        context.push(children_custom.as_deref().unwrap_or(children));
+        context.push(close_custom.as_deref().unwrap_or(close));
+    } else {
+        context.push(open);
+        context.push(children);
+        context.push(close);
+    }
+}
+
+/// Push the opening tag of a node, and, when there is a handler, collect
+/// its children after it.
+///
+/// Closed with [`push_buffered_node`][].
+fn push_open(context: &mut CompileContext, open: &str) {
+    if context.options.html_handler.is_some() {
+        context.buffer();
+        context.push(open);
+        context.content_start();
+    } else {
+        context.push(open);
+    }
+}
+
+/// Push a node whose opening tag and children were buffered, using the
+/// handler (if any).
+fn push_buffered_node(context: &mut CompileContext, node: &HtmlNode, close: &str) {
+    if context.options.html_handler.is_some() {
+        let value = context.resume();
+        let start = context
+            .content_start_stack
+            .pop()
+            .expect("expected content start");
+        push_node(context, node, &value[..start], &value[start..], close);
+    } else {
+        context.push(close);
     }
 }
+
+/// Get the function that turns LaTeX into MathML, if math should be
+/// compiled to MathML.
+fn mathml(context: &CompileContext) -> Option<fn(&str, bool) -> String> {
+    #[cfg(feature = "mathml")]
+    if context.options.math_mathml {
+        return Some(to_mathml);
+    }
+
+    let _ = context;
+    None
+}
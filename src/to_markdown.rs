@@ -0,0 +1,1593 @@
+//! Turn a syntax tree into markdown.
+
+use crate::configuration::FormatOptions;
+use crate::mdast::{
+    AlignKind, AttributeContent, AttributeValue, Code, Definition, Heading, Image, ImageReference,
+    Link, LinkReference, List, ListItem, Math, MdxJsxFlowElement, MdxJsxTextElement, Node,
+    ReferenceKind, Table,
+};
+use crate::unist::Position;
+use crate::util::{
+    char::{classify, Kind as CharacterKind},
+    character_reference::parse as decode_character_references,
+    normalize_identifier::normalize_identifier,
+};
+use crate::visit::{visit_mut, ActionMut};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// Info used while serializing.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug)]
+struct State<'a> {
+    /// Configuration.
+    options: &'a FormatOptions,
+    /// Source the tree came from, used to keep frontmatter and MDX as they
+    /// were.
+    source: Option<&'a str>,
+    /// Whether we are in a table cell, where `|` must be escaped.
+    table_cell: bool,
+    /// Whether we are in the label of a link, whose end also ends GFM
+    /// autolink literals.
+    label: bool,
+    /// Whether we are next to letters, digits, or HTML, where `_` does not
+    /// work for attention.
+    intraword: bool,
+    /// Whether what is around the current link ends a GFM autolink literal,
+    /// so that the link can be one.
+    literal: bool,
+    /// Size of the prefix of containers (block quotes, list items) that
+    /// lines get.
+    indent: usize,
+    /// Bullet and how often it is repeated, when the current line so far
+    /// consists of only the bullets of nested lists, such as `- -`.
+    line_bullets: Option<(char, usize)>,
+}
+
+/// Turn a syntax tree into markdown.
+///
+/// When `source` is given, it must be the document that `tree` was parsed
+/// from.
+pub fn serialize(tree: &Node, options: &FormatOptions, source: Option<&str>) -> String {
+    let mut state = State {
+        options,
+        source,
+        table_cell: false,
+        label: false,
+        intraword: false,
+        literal: false,
+        indent: 0,
+        line_bullets: None,
+    };
+    let mut value = node(&mut state, tree, false);
+    // Some HTML runs to the end of the document, including a final line
+    // ending.
+    let html = matches!(
+        tree.children().and_then(|children| children.last()),
+        Some(Node::Html(_))
+    );
+
+    // A final blank line (such as after a spread list item) needs a line
+    // ending too.
+    if !value.is_empty() && (!value.ends_with('\n') || value.ends_with("\n\n")) && !html {
+        value.push('\n');
+    }
+
+    if let Some(line_ending) = &options.line_ending {
+        value = line_endings(&value, line_ending.as_str());
+    }
+
+    value
+}
+
+/// Turn all line endings (`\r\n`, `\r`, `\n`) in `value` into
+/// `line_ending`.
+pub fn line_endings(value: &str, line_ending: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut result = String::with_capacity(value.len());
+    let mut start = 0;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if matches!(bytes[index], b'\r' | b'\n') {
+            result.push_str(&value[start..index]);
+            result.push_str(line_ending);
+
+            if bytes[index] == b'\r' && index + 1 < bytes.len() && bytes[index + 1] == b'\n' {
+                index += 1;
+            }
+
+            start = index + 1;
+        }
+
+        index += 1;
+    }
+
+    result.push_str(&value[start..]);
+    result
+}
+
+/// Serialize a node.
+fn node(state: &mut State, node: &Node, alternate: bool) -> String {
+    if let Some(value) = verbatim(state, node) {
+        return value;
+    }
+
+    match node {
+        Node::Root(x) => flow(state, &x.children, "\n\n"),
+        Node::BlockQuote(x) => {
+            let value = indented(state, 2, |state| flow(state, &x.children, "\n\n"));
+            indent(&value, "> ", "> ")
+        }
+        Node::FootnoteDefinition(x) => {
+            let value = indented(state, 4, |state| flow(state, &x.children, "\n\n"));
+            let label = format!("[^{}]:", label(x.label.as_deref(), &x.identifier));
+
+            if value.is_empty() {
+                label
+            } else {
+                indent(&value, &format!("{} ", label), "    ")
+            }
+        }
+        Node::MdxJsxFlowElement(x) => jsx_flow(state, x),
+        Node::List(x) => list(state, x, alternate),
+        Node::MdxjsEsm(x) => x.value.clone(),
+        Node::Toml(x) => format!("+++\n{}\n+++", x.value),
+        Node::Yaml(x) => format!("---\n{}\n---", x.value),
+        Node::Break(_) => "\\\n".into(),
+        Node::InlineCode(x) => inline_code(state, &x.value, '`'),
+        Node::InlineMath(x) => inline_code(state, &x.value, '$'),
+        Node::Delete(x) => format!("~~{}~~", inside(state, &x.children)),
+        Node::Emphasis(x) => {
+            let mut marker = if state.intraword {
+                '*'
+            } else {
+                state.options.emphasis
+            };
+            // Nested emphasis would otherwise turn into strong.
+            if matches!(x.children.first(), Some(Node::Emphasis(_)))
+                || matches!(x.children.last(), Some(Node::Emphasis(_)))
+            {
+                marker = if marker == '*' { '_' } else { '*' };
+            }
+            let value = inside(state, &x.children);
+            format!("{}{}{}", marker, value, marker)
+        }
+        Node::Strong(x) => {
+            let marker = if state.intraword {
+                '*'
+            } else {
+                state.options.strong
+            };
+            let value = inside(state, &x.children);
+            format!("{0}{0}{1}{0}{0}", marker, value)
+        }
+        Node::MdxTextExpression(x) => format!("{{{}}}", x.value),
+        Node::FootnoteReference(x) => {
+            format!("[^{}]", label(x.label.as_deref(), &x.identifier))
+        }
+        Node::Html(x) => x.value.clone(),
+        Node::Image(x) => image(state, x),
+        Node::ImageReference(x) => image_reference(state, x),
+        Node::MdxJsxTextElement(x) => jsx_text(state, x),
+        Node::Link(x) => link(state, x),
+        Node::LinkReference(x) => link_reference(state, x),
+        Node::Text(x) => text(state, &x.value, false, false),
+        Node::Code(x) => code(x),
+        Node::Math(x) => math(x),
+        Node::MdxFlowExpression(x) => format!("{{{}}}", x.value),
+        Node::Heading(x) => heading(state, x),
+        Node::Table(x) => table(state, x),
+        Node::ThematicBreak(_) => "***".into(),
+        Node::TableRow(x) => row(state, &x.children),
+        Node::TableCell(x) => cell(state, &x.children),
+        Node::ListItem(x) => list_item(state, x, "-"),
+        Node::Definition(x) => definition(x),
+        Node::Paragraph(x) => match state.options.line_width {
+            Some(width) => {
+                let children = wrap(state, &x.children, width);
+                phrasing(state, &children, true)
+            }
+            None => phrasing(state, &x.children, true),
+        },
+    }
+}
+
+/// Get the source of frontmatter and MDX nodes, if available.
+fn verbatim(state: &State, node: &Node) -> Option<String> {
+    if !matches!(
+        node,
+        Node::Yaml(_)
+            | Node::Toml(_)
+            | Node::MdxjsEsm(_)
+            | Node::MdxFlowExpression(_)
+            | Node::MdxJsxFlowElement(_)
+            | Node::MdxTextExpression(_)
+            | Node::MdxJsxTextElement(_)
+    ) {
+        return None;
+    }
+
+    source(state, node.position())
+}
+
+/// Get the source at `position`, if available.
+///
+/// Multiline source in containers is not used, as it includes the prefixes
+/// of those containers.
+fn source(state: &State, position: Option<&Position>) -> Option<String> {
+    let position = position?;
+    let value = state
+        .source?
+        .get(position.start.offset..position.end.offset)?;
+
+    if state.indent > 0 && value.contains(['\r', '\n']) {
+        None
+    } else {
+        Some(value.into())
+    }
+}
+
+/// Serialize something inside a container that adds a prefix of `size`.
+fn indented(
+    state: &mut State,
+    size: usize,
+    serialize: impl FnOnce(&mut State) -> String,
+) -> String {
+    state.indent += size;
+    let value = serialize(state);
+    state.indent -= size;
+    value
+}
+
+/// Serialize flow children, joined by `join`.
+fn flow(state: &mut State, children: &[Node], join: &str) -> String {
+    let line_bullets = state.line_bullets.take();
+    let mut result = String::new();
+    let mut alternate = false;
+    let mut index = 0;
+
+    while index < children.len() {
+        let child = &children[index];
+
+        // Only a list directly after the bullets continues that line.
+        if index == 0 && matches!(child, Node::List(_)) {
+            state.line_bullets = line_bullets;
+        }
+
+        // Adjacent lists with the same marker would join together.
+        if let (Node::List(current), Some(Node::List(previous))) =
+            (child, index.checked_sub(1).map(|d| &children[d]))
+        {
+            alternate = current.ordered == previous.ordered && !alternate;
+        } else {
+            alternate = false;
+        }
+
+        if index > 0 {
+            result.push_str(join);
+        }
+
+        result.push_str(&node(state, child, alternate));
+        index += 1;
+    }
+
+    result
+}
+
+/// Serialize phrasing children.
+///
+/// When `edges`, the children are at the start and end of lines (such as in
+/// paragraphs and headings), where whitespace is not content.
+fn phrasing(state: &mut State, children: &[Node], edges: bool) -> String {
+    let intraword = state.intraword;
+    let mut result = String::new();
+    let mut index = 0;
+
+    while index < children.len() {
+        let line_start = if result.is_empty() {
+            edges
+        } else {
+            result.ends_with(['\r', '\n'])
+        };
+        let line_end = edges && index + 1 == children.len();
+        let value = match &children[index] {
+            Node::Text(x) => {
+                let mut value = text(state, &x.value, line_start, line_end);
+
+                if index > 0
+                    && matches!(
+                        children[index - 1],
+                        Node::Emphasis(_) | Node::Strong(_) | Node::Delete(_)
+                    )
+                {
+                    closing(&result, &mut value);
+                }
+
+                value
+            }
+            child @ (Node::Emphasis(_) | Node::Strong(_)) => {
+                let before = match index.checked_sub(1).map(|d| &children[d]) {
+                    Some(Node::Text(_)) => {
+                        result.chars().last().map_or(false, char::is_alphanumeric)
+                    }
+                    Some(previous) => is_html(previous),
+                    None => intraword,
+                };
+                let after = match children.get(index + 1) {
+                    Some(Node::Text(x)) => {
+                        x.value.chars().next().map_or(false, char::is_alphanumeric)
+                    }
+                    Some(next) => is_html(next),
+                    None => intraword,
+                };
+                state.intraword = before || after;
+                let mut value = node(state, child, false);
+
+                if !state.intraword {
+                    let size = if matches!(child, Node::Strong(_)) {
+                        2
+                    } else {
+                        1
+                    };
+                    value = separate(&result, value, size);
+                }
+
+                if index > 0 && matches!(children[index - 1], Node::Text(_)) {
+                    opening(&mut result, &value);
+                }
+
+                value
+            }
+            child @ Node::Delete(_) => {
+                state.intraword = false;
+                let value = node(state, child, false);
+
+                if index > 0 && matches!(children[index - 1], Node::Text(_)) {
+                    opening(&mut result, &value);
+                }
+
+                value
+            }
+            child @ Node::Link(link) => {
+                let last = index + 2 == children.len();
+                let after = match children.get(index + 1) {
+                    Some(Node::Text(x)) => literal_end(
+                        &text(state, &x.value, false, edges && last),
+                        edges && last,
+                        link.url.contains('('),
+                    ),
+                    Some(_) => false,
+                    None => edges || state.label,
+                };
+                state.intraword = false;
+                state.literal = after
+                    && result.chars().last().map_or(true, |d| {
+                        d.is_whitespace() || matches!(d, '(' | '*' | '_' | '[' | ']' | '~')
+                    });
+                node(state, child, false)
+            }
+            child => {
+                state.intraword = false;
+                node(state, child, false)
+            }
+        };
+        result.push_str(&value);
+
+        // An exclamation mark before a link would turn it into an image.
+        if result.ends_with('!')
+            && matches!(
+                children.get(index + 1),
+                Some(Node::Link(_) | Node::LinkReference(_))
+            )
+        {
+            result.insert(result.len() - 1, '\\');
+        }
+        index += 1;
+    }
+
+    state.intraword = intraword;
+    result
+}
+
+/// Make sure the attention `value` opens after `result`.
+///
+/// A marker followed by punctuation only opens when it is not preceded by
+/// other characters (such as letters, or `$` and `=`).
+/// Content that starts with an escape (`\\`) or character reference (`&`)
+/// is followed by punctuation, even when the character it stands for is
+/// not, so then the last character in `result` is encoded, as character
+/// references end in punctuation (`;`).
+fn opening(result: &mut String, value: &str) {
+    let marker = value.chars().next();
+    let after = value.chars().find(|d| Some(*d) != marker);
+    let before = match result.chars().last() {
+        Some(char) if classify(char) == CharacterKind::Other => char,
+        _ => return,
+    };
+
+    if after.map_or(true, |d| classify(d) != CharacterKind::Punctuation) {
+        return;
+    }
+
+    let mut end = result.len() - before.len_utf8();
+    let mut size = 0;
+
+    while result[..end - size].ends_with('\\') {
+        size += 1;
+    }
+
+    // Escaped.
+    if size % 2 == 1 {
+        end -= 1;
+    }
+
+    result.truncate(end);
+    result.push_str(&encode(before));
+}
+
+/// Use the other marker for the attention `value` (with markers of `size`),
+/// if it starts with the marker that `result` ends with, as they would join.
+fn separate(result: &str, value: String, size: usize) -> String {
+    let marker = match value.chars().next() {
+        Some(marker) if result.ends_with(marker) && matches!(marker, '*' | '_') => marker,
+        _ => return value,
+    };
+    let other = if marker == '*' { '_' } else { '*' };
+    let inside = &value[size..value.len() - size];
+
+    // Nested attention that uses the other marker would join too.
+    if inside.starts_with(other) || inside.ends_with(other) {
+        return value;
+    }
+
+    let markers = other.to_string().repeat(size);
+    format!("{}{}{}", markers, inside, markers)
+}
+
+/// Make sure the attention that `result` ends with closes before `value`.
+///
+/// A marker preceded by punctuation only closes when it is not followed by
+/// other characters (such as letters, or `|` and `=`), which is the case
+/// when that character was escaped in the source but does not need to be.
+/// Then it is escaped or encoded again.
+fn closing(result: &str, value: &mut String) {
+    let mut chars = result.chars().rev();
+    let marker = chars.next();
+    let before = chars.find(|d| Some(*d) != marker);
+    let after = match value.chars().next() {
+        Some(char) if classify(char) == CharacterKind::Other => char,
+        _ => return,
+    };
+
+    if before.map_or(true, |d| classify(d) != CharacterKind::Punctuation) {
+        return;
+    }
+
+    if after.is_ascii_punctuation() {
+        value.insert(0, '\\');
+    } else {
+        value.replace_range(..after.len_utf8(), &encode(after));
+    }
+}
+
+/// Whether `value`, serialized after a GFM autolink literal, ends it.
+///
+/// Trailing punctuation is not part of a literal when followed by whitespace,
+/// and neither is a closing paren when there is no opening paren.
+/// When `last`, `value` is at the end of a line.
+fn literal_end(value: &str, last: bool, paren: bool) -> bool {
+    let rest = value.trim_start_matches(|d| {
+        matches!(d, '!' | '"' | '\'' | ',' | '.' | ':' | ';' | '?') || (d == ')' && !paren)
+    });
+
+    match rest.chars().next() {
+        Some(char) => matches!(char, ' ' | '\t' | '\r' | '\n'),
+        None => last,
+    }
+}
+
+/// Whether `node` is HTML or JSX.
+fn is_html(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::Html(_) | Node::MdxJsxTextElement(_) | Node::MdxTextExpression(_)
+    )
+}
+
+/// Serialize phrasing children inside attention (emphasis, strong, delete).
+///
+/// Attention cannot start or end with whitespace, so those are encoded.
+fn inside(state: &mut State, children: &[Node]) -> String {
+    let value = phrasing(state, children, false);
+    let mut result = String::new();
+    let mut chars = value.chars().peekable();
+    let mut first = true;
+
+    while let Some(char) = chars.next() {
+        if (first || chars.peek().is_none()) && matches!(char, ' ' | '\t' | '\r' | '\n') {
+            result.push_str(&encode(char));
+        } else {
+            result.push(char);
+        }
+
+        first = false;
+    }
+
+    result
+}
+
+/// Serialize text.
+///
+/// `line_start` and `line_end` are whether `value` is at the start or end of
+/// a line.
+fn text(state: &State, value: &str, line_start: bool, line_end: bool) -> String {
+    let chars = value.chars().collect::<Vec<_>>();
+    let mut result = String::new();
+    let mut line_start = line_start;
+    let mut digits = line_start;
+    let mut index = 0;
+
+    while index < chars.len() {
+        let char = chars[index];
+        let start = line_start;
+        line_start = false;
+
+        match char {
+            // A blank line would end the paragraph.
+            '\r' | '\n' if start => result.push_str(&encode(char)),
+            '\r' | '\n' => {
+                result.push(char);
+
+                if char == '\r' && index + 1 < chars.len() && chars[index + 1] == '\n' {
+                    result.push('\n');
+                    index += 1;
+                }
+
+                line_start = true;
+                digits = true;
+                index += 1;
+                continue;
+            }
+            ' ' | '\t' => {
+                let mut end = index;
+                while end < chars.len() && matches!(chars[end], ' ' | '\t') {
+                    end += 1;
+                }
+
+                // Whitespace at the start or end of lines is not content.
+                if start
+                    || (end == chars.len() && line_end)
+                    || (end < chars.len() && matches!(chars[end], '\r' | '\n'))
+                {
+                    result.push_str(&encode(char));
+                } else {
+                    result.push(char);
+                }
+            }
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '&' | '~' | '$' | '{' | '}' => {
+                result.push('\\');
+                result.push(char);
+            }
+            '|' if state.table_cell => {
+                result.push('\\');
+                result.push(char);
+            }
+            '#' | '>' | '-' | '+' | '=' | '|' | ':' if start => {
+                result.push('\\');
+                result.push(char);
+            }
+            '.' | ')' if digits && index > 0 && chars[index - 1].is_ascii_digit() => {
+                result.push('\\');
+                result.push(char);
+            }
+            // GFM autolink literals would form.
+            '.' | ':' | '@' if literal_start(&chars, index) => {
+                result.push('\\');
+                result.push(char);
+            }
+            _ => result.push(char),
+        }
+
+        digits = digits && char.is_ascii_digit();
+        index += 1;
+    }
+
+    result
+}
+
+/// Whether the character at `index` in `chars` would start a GFM autolink
+/// literal: the dot in `www.`, the colon in `http://`, or the at sign in an
+/// email address.
+fn literal_start(chars: &[char], index: usize) -> bool {
+    let before = |size: usize| {
+        chars[index.saturating_sub(size)..index]
+            .iter()
+            .collect::<String>()
+            .to_ascii_lowercase()
+    };
+    let after = chars.get(index + 1).copied();
+    let atext = |d: char| d.is_ascii_alphanumeric() || matches!(d, '-' | '.' | '_');
+
+    match chars[index] {
+        '.' => before(3) == "www",
+        ':' => after == Some('/') && (before(4) == "http" || before(5) == "https"),
+        '@' => {
+            index > 0
+                && (atext(chars[index - 1]) || chars[index - 1] == '+')
+                && after.map_or(false, atext)
+        }
+        _ => false,
+    }
+}
+
+/// Encode a character as a character reference.
+fn encode(char: char) -> String {
+    format!("&#x{:X};", char as u32)
+}
+
+/// Prefix lines in `value`.
+///
+/// The first line gets `first`, other lines get `rest`, without trailing
+/// whitespace on empty lines.
+fn indent(value: &str, first: &str, rest: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut result = String::new();
+    let mut prefix = first;
+    let mut start = 0;
+    let mut index = 0;
+
+    while index <= bytes.len() {
+        if index == bytes.len() || matches!(bytes[index], b'\r' | b'\n') {
+            if start == index {
+                result.push_str(prefix.trim_end());
+            } else {
+                result.push_str(prefix);
+                result.push_str(&value[start..index]);
+            }
+
+            if index == bytes.len() {
+                break;
+            }
+
+            let mut end = index + 1;
+
+            if bytes[index] == b'\r' && end < bytes.len() && bytes[end] == b'\n' {
+                end += 1;
+            }
+
+            result.push_str(&value[index..end]);
+            prefix = rest;
+            start = end;
+            index = end;
+        } else {
+            index += 1;
+        }
+    }
+
+    result
+}
+
+/// Wrap phrasing children at `width`.
+///
+/// Line endings in text are turned into spaces, and then spaces between
+/// words are turned into line endings where lines would otherwise get too
+/// long.
+/// Other nodes (such as links and code) are not broken.
+fn wrap(state: &mut State, children: &[Node], width: usize) -> Vec<Node> {
+    let width = width.saturating_sub(state.indent).max(1);
+    let mut children = children.to_vec();
+    // Spaces to break at, as indices of children and bytes in their values.
+    let mut breaks = Vec::new();
+    // Space at the end of the previous text.
+    let mut pending = None;
+    let mut column = 0;
+    let mut index = 0;
+
+    while index < children.len() {
+        if let Node::Text(x) = &mut children[index] {
+            x.value = x.value.replace("\r\n", " ").replace(['\r', '\n'], " ");
+            let value = &x.value;
+            let mut offset = 0;
+            let mut previous = false;
+
+            for (position, segment) in value.split(' ').enumerate() {
+                let size = text(state, segment, false, false).chars().count();
+
+                if position == 0 {
+                    column += size;
+                } else if previous && !segment.is_empty() && column > 0 && column + 1 + size > width
+                {
+                    breaks.push((index, offset - 1));
+                    column = size;
+                } else {
+                    column += 1 + size;
+                }
+
+                previous = !segment.is_empty() || (position == 0 && index > 0);
+                offset += segment.len() + 1;
+            }
+
+            pending = value
+                .strip_suffix(' ')
+                .filter(|rest| !rest.ends_with(' ') && (!rest.is_empty() || index > 0))
+                .map(|_| (index, value.len() - 1));
+        } else {
+            let child = &children[index];
+            let value = node(state, child, false);
+            let size = value.chars().count();
+
+            // Line endings before HTML, MDX, and breaks would change them.
+            if let Some(space) = pending.take() {
+                if column > 1
+                    && column + size > width
+                    && matches!(
+                        child,
+                        Node::Emphasis(_)
+                            | Node::Strong(_)
+                            | Node::Delete(_)
+                            | Node::InlineCode(_)
+                            | Node::InlineMath(_)
+                            | Node::Link(_)
+                            | Node::LinkReference(_)
+                            | Node::Image(_)
+                            | Node::ImageReference(_)
+                            | Node::FootnoteReference(_)
+                    )
+                {
+                    breaks.push(space);
+                    column = 0;
+                }
+            }
+
+            match value.rfind('\n') {
+                Some(position) => column = value[position + 1..].chars().count(),
+                None => column += size,
+            }
+        }
+
+        index += 1;
+    }
+
+    for (index, offset) in breaks {
+        if let Node::Text(x) = &mut children[index] {
+            x.value.replace_range(offset..=offset, "\n");
+        }
+    }
+
+    children
+}
+
+/// Serialize a heading.
+fn heading(state: &mut State, heading: &Heading) -> String {
+    let mut value = phrasing(state, &heading.children, true);
+
+    // Setext headings can contain line endings.
+    if heading.depth < 3
+        && (value.contains(['\r', '\n']) || (state.options.setext && !value.is_empty()))
+    {
+        let size = value
+            .rsplit(['\r', '\n'])
+            .next()
+            .map_or(0, |line| line.chars().count())
+            .max(3);
+        let underline = if heading.depth == 1 { "=" } else { "-" };
+        return format!("{}\n{}", value, underline.repeat(size));
+    }
+
+    // A closing sequence would be removed.
+    if value.ends_with('#') && !value.ends_with("\\#") {
+        value.insert(value.len() - 1, '\\');
+    }
+
+    let sequence = "#".repeat(usize::from(heading.depth.clamp(1, 6)));
+
+    if value.is_empty() {
+        sequence
+    } else {
+        format!("{} {}", sequence, value)
+    }
+}
+
+/// Serialize a list.
+fn list(state: &mut State, list: &List, alternate: bool) -> String {
+    let join = if list.spread { "\n\n" } else { "\n" };
+    let mut result = String::new();
+    let mut index = 0;
+
+    let mut bullet = state.options.bullet;
+    let mut bullet_ordered = state.options.bullet_ordered;
+
+    if alternate {
+        bullet = if bullet == '-' { '*' } else { '-' };
+        bullet_ordered = if bullet_ordered == '.' { ')' } else { '.' };
+    }
+
+    // Three of the same bullets on a line would be a thematic break.
+    let mut count = 1;
+    if let Some((previous, size)) = state.line_bullets.take() {
+        if previous == bullet {
+            count = size + 1;
+        }
+    }
+    if !list.ordered && count > 2 && matches!(bullet, '-' | '*') {
+        bullet = '+';
+        count = 1;
+    }
+
+    while index < list.children.len() {
+        let marker = if list.ordered {
+            let number = list.start.unwrap_or(1) as usize + index;
+            format!("{}{}", number, bullet_ordered)
+        } else {
+            bullet.to_string()
+        };
+
+        if index > 0 {
+            result.push_str(join);
+        }
+
+        let value = match &list.children[index] {
+            Node::ListItem(item) => {
+                if !list.ordered {
+                    state.line_bullets = Some((bullet, if index == 0 { count } else { 1 }));
+                }
+                list_item(state, item, &marker)
+            }
+            child => indent(
+                &indented(state, marker.len() + 1, |state| node(state, child, false)),
+                &format!("{} ", marker),
+                &" ".repeat(marker.len() + 1),
+            ),
+        };
+
+        result.push_str(&value);
+        index += 1;
+    }
+
+    result
+}
+
+/// Serialize a list item.
+fn list_item(state: &mut State, item: &ListItem, marker: &str) -> String {
+    let join = if item.spread { "\n\n" } else { "\n" };
+    let mut value = indented(state, marker.len() + 1, |state| {
+        flow(state, &item.children, join)
+    });
+
+    if let (Some(checked), Some(Node::Paragraph(_))) = (item.checked, item.children.first()) {
+        value = format!("[{}] {}", if checked { 'x' } else { ' ' }, value);
+    }
+
+    // A thematic break with the same marker would turn the item into a
+    // thematic break.
+    if marker == "*" && matches!(item.children.first(), Some(Node::ThematicBreak(_))) {
+        value.replace_range(..3, "___");
+    }
+
+    let mut result = if value.is_empty() {
+        marker.into()
+    } else {
+        indent(
+            &value,
+            &format!("{} ", marker),
+            &" ".repeat(marker.len() + 1),
+        )
+    };
+
+    // An item with one child is only spread when two blank lines follow it.
+    if item.spread && item.children.len() == 1 {
+        result.push_str("\n\n");
+    }
+
+    result
+}
+
+/// Serialize a fenced code block.
+fn code(code: &Code) -> String {
+    let mut info = String::new();
+
+    if let Some(lang) = &code.lang {
+        info.push_str(&info_string(lang));
+
+        if let Some(meta) = &code.meta {
+            info.push(' ');
+            info.push_str(&info_string(meta));
+        }
+    }
+
+    // The info of backtick fences cannot contain backticks.
+    let marker = if info.contains('`') { '~' } else { '`' };
+    fenced(&code.value, marker, &info)
+}
+
+/// Serialize a math (flow) block.
+fn math(math: &Math) -> String {
+    fenced(
+        &math.value,
+        '$',
+        &info_string(math.meta.as_deref().unwrap_or("")),
+    )
+}
+
+/// Escape `value` for use in the info string of a fence, where character
+/// escapes and character references work.
+fn info_string(value: &str) -> String {
+    let mut result = String::new();
+
+    for char in value.chars() {
+        if matches!(char, '\\' | '&') {
+            result.push('\\');
+        }
+
+        result.push(char);
+    }
+
+    result
+}
+
+/// Serialize a fenced block of `value`.
+fn fenced(value: &str, marker: char, info: &str) -> String {
+    // Math fences can be two markers, code fences need three.
+    let size = (longest_run(value, marker) + 1).max(if marker == '$' { 2 } else { 3 });
+    let fence = marker.to_string().repeat(size);
+
+    if value.is_empty() {
+        format!("{}{}\n{}", fence, info, fence)
+    } else {
+        // A `\n` after a final `\r` would join it into one line ending.
+        let line_ending = if value.ends_with('\r') { '\r' } else { '\n' };
+        format!("{}{}\n{}{}{}", fence, info, value, line_ending, fence)
+    }
+}
+
+/// Serialize inline code or math.
+fn inline_code(state: &State, value: &str, marker: char) -> String {
+    let fence = marker.to_string().repeat(longest_run(value, marker) + 1);
+    // Pipes in code in tables still end cells, unless escaped.
+    let escaped;
+    let value = if state.table_cell {
+        escaped = value.replace('|', "\\|");
+        &escaped
+    } else {
+        value
+    };
+    if value.starts_with(marker) || value.ends_with(marker) {
+        format!("{} {} {}", fence, value, fence)
+    } else {
+        format!("{}{}{}", fence, value, fence)
+    }
+}
+
+/// Get the size of the longest run of `marker` in `value`.
+fn longest_run(value: &str, marker: char) -> usize {
+    let mut max = 0;
+    let mut size = 0;
+
+    for char in value.chars() {
+        if char == marker {
+            size += 1;
+            max = max.max(size);
+        } else {
+            size = 0;
+        }
+    }
+
+    max
+}
+
+/// Serialize a link.
+fn link(state: &mut State, link: &Link) -> String {
+    let before = state.label;
+    state.label = true;
+    let label = phrasing(state, &link.children, false);
+    state.label = before;
+
+    // Autolink.
+    if let (None, [Node::Text(text)]) = (&link.title, link.children.as_slice()) {
+        let value = &text.value;
+        let scheme = value
+            .split_once(':')
+            .map_or(false, |(scheme, _)| is_scheme(scheme));
+        let plain = !value
+            .chars()
+            .any(|d| d.is_ascii_whitespace() || d.is_control() || d == '<' || d == '>');
+
+        if plain
+            && ((scheme && link.url == *value)
+                || (link.url == format!("mailto:{}", value) && is_email(value)))
+        {
+            return format!("<{}>", value);
+        }
+
+        // GFM autolink literal.
+        if plain
+            && state.literal
+            && value.starts_with("www.")
+            && link.url == format!("http://{}", value)
+        {
+            return value.clone();
+        }
+    }
+
+    format!(
+        "[{}]({})",
+        label,
+        resource(&link.url, link.title.as_deref())
+    )
+}
+
+/// Serialize an image.
+fn image(state: &State, image: &Image) -> String {
+    format!(
+        "![{}]({})",
+        text(state, &image.alt, false, false),
+        resource(&image.url, image.title.as_deref())
+    )
+}
+
+/// Serialize a link reference.
+fn link_reference(state: &mut State, reference: &LinkReference) -> String {
+    let value = phrasing(state, &reference.children, false);
+
+    if let Some(value) = reference_source(
+        state,
+        reference.reference_kind,
+        &reference.identifier,
+        &value,
+        reference.position.as_ref(),
+    ) {
+        return value;
+    }
+
+    if let Some(value) = reference_content(
+        state,
+        "[",
+        reference.reference_kind,
+        &reference.identifier,
+        &value,
+        &Node::LinkReference(reference.clone()),
+    ) {
+        return value;
+    }
+
+    format!(
+        "[{}]{}",
+        value,
+        reference_end(
+            reference.reference_kind,
+            reference.label.as_deref(),
+            &reference.identifier,
+            &value
+        )
+    )
+}
+
+/// Serialize an image reference.
+fn image_reference(state: &State, reference: &ImageReference) -> String {
+    let value = text(state, &reference.alt, false, false);
+
+    if let Some(value) = reference_source(
+        state,
+        reference.reference_kind,
+        &reference.identifier,
+        &value,
+        reference.position.as_ref(),
+    ) {
+        return value;
+    }
+
+    if let Some(value) = reference_content(
+        state,
+        "![",
+        reference.reference_kind,
+        &reference.identifier,
+        &value,
+        &Node::ImageReference(reference.clone()),
+    ) {
+        return value;
+    }
+
+    format!(
+        "![{}]{}",
+        value,
+        reference_end(
+            reference.reference_kind,
+            reference.label.as_deref(),
+            &reference.identifier,
+            &value
+        )
+    )
+}
+
+/// Get the source of a collapsed or shortcut reference, if available and
+/// its serialized content `value` does not match `identifier`.
+///
+/// Such references would otherwise turn into full references.
+fn reference_source(
+    state: &State,
+    kind: ReferenceKind,
+    identifier: &str,
+    value: &str,
+    position: Option<&Position>,
+) -> Option<String> {
+    if kind == ReferenceKind::Full || normalize_identifier(value).to_lowercase() == identifier {
+        None
+    } else {
+        source(state, position)
+    }
+}
+
+/// Serialize a collapsed or shortcut reference whose serialized content
+/// `value` does not match `identifier`, if that is possible without turning
+/// it into a full reference.
+///
+/// The content is written as it could have been in the source: escapes that
+/// `identifier` does not have are dropped, and characters that only
+/// `identifier` has (such as attention markers in the alt of images) are
+/// added.
+/// That is only used when it parses into the same `reference`.
+fn reference_content(
+    state: &State,
+    open: &str,
+    kind: ReferenceKind,
+    identifier: &str,
+    value: &str,
+    reference: &Node,
+) -> Option<String> {
+    if kind == ReferenceKind::Full || normalize_identifier(value).to_lowercase() == identifier {
+        return None;
+    }
+
+    let chars = value.chars().collect::<Vec<_>>();
+    let expected = identifier.chars().collect::<Vec<_>>();
+    let mut result = String::from(open);
+    let mut index = 0;
+    let mut expected_index = 0;
+
+    while index < chars.len() && expected_index < expected.len() {
+        let char = chars[index];
+        let expected_char = expected[expected_index];
+
+        if char.to_lowercase().eq(expected_char.to_lowercase())
+            || (char.is_whitespace() && expected_char.is_whitespace())
+        {
+            result.push(char);
+            index += 1;
+            expected_index += 1;
+        } else if let Some((size, decoded_size)) =
+            character_reference(&expected[expected_index..], &chars[index..])
+        {
+            result.extend(&expected[expected_index..expected_index + size]);
+            index += decoded_size;
+            expected_index += size;
+        } else if char == '\\' {
+            index += 1;
+        } else if char.is_whitespace()
+            && (expected_index == 0 || expected[expected_index - 1].is_whitespace())
+        {
+            // Whitespace is trimmed and collapsed in identifiers.
+            result.push(char);
+            index += 1;
+        } else {
+            result.push(expected_char);
+            expected_index += 1;
+        }
+    }
+
+    result.extend(&chars[index..]);
+    result.extend(&expected[expected_index..]);
+    result.push(']');
+
+    if kind == ReferenceKind::Collapsed {
+        result.push_str("[]");
+    }
+
+    let definition = Definition {
+        position: None,
+        url: String::new(),
+        title: None,
+        identifier: identifier.into(),
+        label: None,
+    };
+    let mut tree = crate::to_mdast_inline(&result, &[definition], &state.options.parse).ok()?;
+    let mut reference = reference.clone();
+    strip(&mut tree);
+    strip(&mut reference);
+
+    if tree.children() == Some(&vec![reference]) {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Get the size of the character reference at the start of `expected`, and
+/// the size of what it decodes to at the start of `chars` (which can be
+/// escaped), if there is one and they match.
+fn character_reference(expected: &[char], chars: &[char]) -> Option<(usize, usize)> {
+    if expected.first() != Some(&'&') {
+        return None;
+    }
+
+    let size = expected.iter().take(40).position(|d| *d == ';')? + 1;
+    let reference = expected[..size].iter().collect::<String>();
+    let decoded = decode_character_references(&reference, true)
+        .chars()
+        .collect::<Vec<_>>();
+    let escape = usize::from(chars.first() == Some(&'\\'));
+
+    if decoded.iter().collect::<String>() != reference && chars[escape..].starts_with(&decoded) {
+        Some((size, escape + decoded.len()))
+    } else {
+        None
+    }
+}
+
+/// Remove positions from `node` and its descendants.
+fn strip(node: &mut Node) {
+    visit_mut(node, &mut |node: &mut Node, _: &[usize]| {
+        node.position_set(None);
+        ActionMut::Continue
+    });
+}
+
+/// Serialize the end of a reference.
+///
+/// Collapsed and shortcut references use their content as label, so when
+/// that does not match, a full reference is used instead.
+fn reference_end(
+    kind: ReferenceKind,
+    label_value: Option<&str>,
+    identifier: &str,
+    value: &str,
+) -> String {
+    let matches = normalize_identifier(value).to_lowercase() == identifier;
+
+    match kind {
+        ReferenceKind::Collapsed if matches => "[]".into(),
+        ReferenceKind::Shortcut if matches => String::new(),
+        _ => format!("[{}]", label(label_value, identifier)),
+    }
+}
+
+/// Serialize a label.
+///
+/// The `label` field in the tree has escapes and character references
+/// decoded, so it is escaped again when that matches `identifier`.
+/// Otherwise, `identifier` (the normalized source) is used.
+fn label(label: Option<&str>, identifier: &str) -> String {
+    if let Some(label) = label {
+        let mut result = String::new();
+
+        for char in label.chars() {
+            if matches!(char, '\\' | '[' | ']') {
+                result.push('\\');
+            }
+
+            result.push(char);
+        }
+
+        if normalize_identifier(&result).to_lowercase() == identifier {
+            return result;
+        }
+    }
+
+    identifier.into()
+}
+
+/// Serialize a definition.
+fn definition(definition: &Definition) -> String {
+    let mut value = format!(
+        "[{}]: {}",
+        label(definition.label.as_deref(), &definition.identifier),
+        destination(&definition.url)
+    );
+
+    if let Some(title) = &definition.title {
+        value.push(' ');
+        value.push_str(&title_string(title));
+    }
+
+    value
+}
+
+/// Serialize a destination and title.
+fn resource(url: &str, title: Option<&str>) -> String {
+    let mut value = destination(url);
+
+    if let Some(title) = title {
+        value.push(' ');
+        value.push_str(&title_string(title));
+    }
+
+    value
+}
+
+/// Serialize a destination.
+fn destination(url: &str) -> String {
+    let enclosed = url.is_empty()
+        || url
+            .chars()
+            .any(|d| d.is_ascii_whitespace() || d.is_control());
+    let mut result = String::new();
+
+    if enclosed {
+        result.push('<');
+    }
+
+    for char in url.chars() {
+        let escape = if enclosed {
+            matches!(char, '<' | '>' | '\\')
+        } else {
+            matches!(char, '(' | ')' | '<' | '\\')
+        };
+
+        if escape {
+            result.push('\\');
+        }
+
+        result.push(char);
+    }
+
+    if enclosed {
+        result.push('>');
+    }
+
+    result
+}
+
+/// Serialize a title.
+fn title_string(title: &str) -> String {
+    let mut result = String::from("\"");
+
+    for char in title.chars() {
+        if matches!(char, '"' | '\\') {
+            result.push('\\');
+        }
+
+        result.push(char);
+    }
+
+    result.push('"');
+    result
+}
+
+/// Whether `value` is an email address that works in an autolink.
+fn is_email(value: &str) -> bool {
+    let (local, domain) = if let Some(parts) = value.split_once('@') {
+        parts
+    } else {
+        return false;
+    };
+
+    !local.is_empty()
+        && local
+            .bytes()
+            .all(|d| d.is_ascii_alphanumeric() || b".!#$%&'*+/=?^_`{|}~-".contains(&d))
+        && domain.split('.').all(|label| {
+            let bytes = label.as_bytes();
+            !bytes.is_empty()
+                && bytes.len() < 64
+                && bytes[0].is_ascii_alphanumeric()
+                && bytes[bytes.len() - 1].is_ascii_alphanumeric()
+                && bytes
+                    .iter()
+                    .all(|d| d.is_ascii_alphanumeric() || *d == b'-')
+        })
+}
+
+/// Whether `value` is a URL scheme.
+fn is_scheme(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() > 1
+        && bytes.len() < 33
+        && bytes[0].is_ascii_alphabetic()
+        && bytes
+            .iter()
+            .all(|d| d.is_ascii_alphanumeric() || matches!(d, b'+' | b'-' | b'.'))
+}
+
+/// Serialize a table.
+fn table(state: &mut State, table: &Table) -> String {
+    let mut rows = Vec::new();
+    let mut widths = vec![3_usize; table.align.len()];
+    let mut index = 0;
+
+    while index < table.children.len() {
+        let cells = match &table.children[index] {
+            Node::TableRow(x) => x
+                .children
+                .iter()
+                .map(|child| match child {
+                    Node::TableCell(x) => cell(state, &x.children),
+                    child => node(state, child, false),
+                })
+                .collect::<Vec<_>>(),
+            child => vec![node(state, child, false)],
+        };
+
+        if state.options.align_tables {
+            let mut column = 0;
+            while column < cells.len().min(widths.len()) {
+                widths[column] = widths[column].max(cells[column].chars().count());
+                column += 1;
+            }
+        }
+
+        rows.push(cells);
+        index += 1;
+    }
+
+    let mut result = String::new();
+    index = 0;
+
+    while index < rows.len() {
+        if index > 0 {
+            result.push('\n');
+        }
+
+        result.push('|');
+        let mut column = 0;
+        while column < rows[index].len() {
+            let value = &rows[index][column];
+            let (before, after) = match (widths.get(column), table.align.get(column)) {
+                (Some(width), Some(align)) if state.options.align_tables => {
+                    let size = width.saturating_sub(value.chars().count());
+                    match align {
+                        AlignKind::Right => (size, 0),
+                        AlignKind::Center => (size / 2, size - size / 2),
+                        AlignKind::Left | AlignKind::None => (0, size),
+                    }
+                }
+                _ => (0, 0),
+            };
+            result.push(' ');
+            result.push_str(&" ".repeat(before));
+            result.push_str(value);
+            result.push_str(&" ".repeat(after));
+            result.push_str(" |");
+            column += 1;
+        }
+
+        if index == 0 {
+            result.push_str("\n|");
+            column = 0;
+            while column < table.align.len() {
+                let size = if state.options.align_tables {
+                    widths[column]
+                } else {
+                    3
+                };
+                let (left, right) = match table.align[column] {
+                    AlignKind::Left => (':', '-'),
+                    AlignKind::Right => ('-', ':'),
+                    AlignKind::Center => (':', ':'),
+                    AlignKind::None => ('-', '-'),
+                };
+                result.push(' ');
+                result.push(left);
+                result.push_str(&"-".repeat(size - 2));
+                result.push(right);
+                result.push_str(" |");
+                column += 1;
+            }
+        }
+
+        index += 1;
+    }
+
+    result
+}
+
+/// Serialize a table row.
+fn row(state: &mut State, children: &[Node]) -> String {
+    let mut result = String::from("|");
+    let mut index = 0;
+
+    while index < children.len() {
+        let value = match &children[index] {
+            Node::TableCell(x) => cell(state, &x.children),
+            child => node(state, child, false),
+        };
+        result.push(' ');
+        result.push_str(&value);
+        result.push_str(" |");
+        index += 1;
+    }
+
+    result
+}
+
+/// Serialize a table cell.
+fn cell(state: &mut State, children: &[Node]) -> String {
+    let before = state.table_cell;
+    state.table_cell = true;
+    let value = phrasing(state, children, true);
+    state.table_cell = before;
+    value
+}
+
+/// Serialize a JSX element (flow).
+fn jsx_flow(state: &mut State, element: &MdxJsxFlowElement) -> String {
+    let open = jsx_open(element.name.as_deref(), &element.attributes);
+
+    if element.children.is_empty() {
+        return match &element.name {
+            Some(_) => format!("{} />", open),
+            None => "<></>".into(),
+        };
+    }
+
+    let value = indented(state, 2, |state| flow(state, &element.children, "\n\n"));
+    format!(
+        "{}>\n{}\n</{}>",
+        open,
+        indent(&value, "  ", "  "),
+        element.name.as_deref().unwrap_or("")
+    )
+}
+
+/// Serialize a JSX element (text).
+fn jsx_text(state: &mut State, element: &MdxJsxTextElement) -> String {
+    let open = jsx_open(element.name.as_deref(), &element.attributes);
+
+    if element.children.is_empty() {
+        return match &element.name {
+            Some(_) => format!("{} />", open),
+            None => "<></>".into(),
+        };
+    }
+
+    format!(
+        "{}>{}</{}>",
+        open,
+        phrasing(state, &element.children, false),
+        element.name.as_deref().unwrap_or("")
+    )
+}
+
+/// Serialize the opening of a JSX element, without `>` or `/>`.
+fn jsx_open(name: Option<&str>, attributes: &[AttributeContent]) -> String {
+    let mut result = format!("<{}", name.unwrap_or(""));
+    let mut index = 0;
+
+    while index < attributes.len() {
+        result.push(' ');
+
+        match &attributes[index] {
+            AttributeContent::Expression { value, .. } => {
+                result.push('{');
+                result.push_str(value);
+                result.push('}');
+            }
+            AttributeContent::Property(property) => {
+                result.push_str(&property.name);
+
+                match &property.value {
+                    Some(AttributeValue::Literal(value)) => {
+                        let quote = if value.contains('"') { '\'' } else { '"' };
+                        result.push('=');
+                        result.push(quote);
+                        result.push_str(value);
+                        result.push(quote);
+                    }
+                    Some(AttributeValue::Expression(value)) => {
+                        result.push_str("={");
+                        result.push_str(&value.value);
+                        result.push('}');
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        index += 1;
+    }
+
+    result
+}
@@ -1,5 +1,6 @@
 //! Turn events into a syntax tree.
 
+use crate::configuration::PositionEncoding;
 use crate::event::{Event, Kind, Name, Point as EventPoint};
 use crate::mdast::{
     AttributeContent, AttributeValue, AttributeValueExpression, BlockQuote, Break, Code,
@@ -15,10 +16,12 @@ use crate::util::{
         decode as decode_character_reference, parse as parse_character_reference,
     },
     infer::{gfm_table_align, list_item_loose, list_loose},
+    location::{format_place, Location},
     mdx_collect::{collect, Result as CollectResult},
     normalize_identifier::normalize_identifier,
     slice::{Position as SlicePosition, Slice},
 };
+use crate::visit::{visit_mut, ActionMut};
 use alloc::{
     format,
     string::{String, ToString},
@@ -87,6 +90,8 @@ struct CompileContext<'a> {
     events: &'a [Event],
     /// List of bytes.
     bytes: &'a [u8],
+    /// How to count places in error messages.
+    encoding: PositionEncoding,
     // Fields used by handlers to track the things they need to track to
     // compile markdown.
     character_reference_marker: u8,
@@ -106,7 +111,7 @@ struct CompileContext<'a> {
 
 impl<'a> CompileContext<'a> {
     /// Create a new compile context.
-    fn new(events: &'a [Event], bytes: &'a [u8]) -> CompileContext<'a> {
+    fn new(events: &'a [Event], bytes: &'a [u8], encoding: PositionEncoding) -> CompileContext<'a> {
         let tree = Node::Root(Root {
             children: vec![],
             position: Some(Position {
@@ -126,6 +131,7 @@ impl<'a> CompileContext<'a> {
         CompileContext {
             events,
             bytes,
+            encoding,
             character_reference_marker: 0,
             gfm_table_inside: false,
             hard_break_after: false,
@@ -202,6 +208,18 @@ impl<'a> CompileContext<'a> {
         event_stack.push(self.index);
     }
 
+    /// Format the place of `point`, as `line:column`, for use in error
+    /// messages.
+    fn place(&self, point: &Point) -> String {
+        format_place(
+            self.bytes,
+            point.line,
+            point.column,
+            point.offset,
+            self.encoding,
+        )
+    }
+
     fn tail_pop(&mut self) -> Result<(), String> {
         let ev = &self.events[self.index];
         let end = point_from_event(ev);
@@ -222,8 +240,11 @@ impl<'a> CompileContext<'a> {
 }
 
 /// Turn events and bytes into a syntax tree.
-pub fn compile(events: &[Event], bytes: &[u8]) -> Result<Node, String> {
-    let mut context = CompileContext::new(events, bytes);
+///
+/// `encoding` is used to count places in error messages, positions of nodes
+/// are always in bytes (see [`encode_positions()`][]).
+pub fn compile(events: &[Event], bytes: &[u8], encoding: PositionEncoding) -> Result<Node, String> {
+    let mut context = CompileContext::new(events, bytes, encoding);
 
     let mut index = 0;
     while index < events.len() {
@@ -242,6 +263,29 @@ pub fn compile(events: &[Event], bytes: &[u8]) -> Result<Node, String> {
     Ok(tree)
 }
 
+/// Count the columns and offsets of positions in `tree`, which are in
+/// `bytes`, in `encoding`.
+pub fn encode_positions(tree: &mut Node, bytes: &[u8], encoding: PositionEncoding) {
+    if encoding == PositionEncoding::Byte {
+        return;
+    }
+
+    let location = Location::new(bytes);
+
+    visit_mut(tree, &mut |node: &mut Node, _: &[usize]| {
+        if let Some(position) = node.position_mut() {
+            if let Some(start) = location.to_encoded_point(bytes, position.start.offset, encoding) {
+                position.start = start;
+            }
+            if let Some(end) = location.to_encoded_point(bytes, position.end.offset, encoding) {
+                position.end = end;
+            }
+        }
+
+        ActionMut::Continue
+    });
+}
+
 /// Handle the event at `index`.
 fn handle(context: &mut CompileContext, index: usize) -> Result<(), String> {
     context.index = index;
@@ -791,9 +835,8 @@ fn on_enter_mdx_jsx_tag_closing_marker(context: &mut CompileContext) -> Result<(
     if context.jsx_tag_stack.is_empty() {
         let event = &context.events[context.index];
         Err(format!(
-            "{}:{}: Unexpected closing slash `/` in tag, expected an open tag first (mdx-jsx:unexpected-closing-slash)",
-            event.point.line,
-            event.point.column,
+            "{}: Unexpected closing slash `/` in tag, expected an open tag first (mdx-jsx:unexpected-closing-slash)",
+            context.place(&point_from_event(event)),
         ))
     } else {
         Ok(())
@@ -805,9 +848,8 @@ fn on_enter_mdx_jsx_tag_any_attribute(context: &mut CompileContext) -> Result<()
     if context.jsx_tag.as_ref().expect("expected tag").close {
         let event = &context.events[context.index];
         Err(format!(
-            "{}:{}: Unexpected attribute in closing tag, expected the end of the tag (mdx-jsx:unexpected-attribute)",
-            event.point.line,
-            event.point.column,
+            "{}: Unexpected attribute in closing tag, expected the end of the tag (mdx-jsx:unexpected-attribute)",
+            context.place(&point_from_event(event)),
         ))
     } else {
         Ok(())
@@ -888,9 +930,8 @@ fn on_enter_mdx_jsx_tag_self_closing_marker(context: &mut CompileContext) -> Res
     if tag.close {
         let event = &context.events[context.index];
         Err(format!(
-            "{}:{}: Unexpected self-closing slash `/` in closing tag, expected the end of the tag (mdx-jsx:unexpected-self-closing-slash)",
-            event.point.line,
-            event.point.column,
+            "{}: Unexpected self-closing slash `/` in closing tag, expected the end of the tag (mdx-jsx:unexpected-self-closing-slash)",
+            context.place(&point_from_event(event)),
         ))
     } else {
         Ok(())
@@ -1460,13 +1501,11 @@ fn on_exit_mdx_jsx_tag(context: &mut CompileContext) -> Result<(), String> {
 
         if tail.name != tag.name {
             return Err(format!(
-                "{}:{}: Unexpected closing tag `{}`, expected corresponding closing tag for `{}` ({}:{}) (mdx-jsx:end-tag-mismatch)",
-                tag.start.line,
-                tag.start.column,
+                "{}: Unexpected closing tag `{}`, expected corresponding closing tag for `{}` ({}) (mdx-jsx:end-tag-mismatch)",
+                context.place(&tag.start),
                 serialize_abbreviated_tag(&tag),
                 serialize_abbreviated_tag(tail),
-                tail.start.line,
-                tail.start.column,
+                context.place(&tail.start),
             ));
         }
 
@@ -1618,6 +1657,7 @@ fn on_exit_mdx_jsx_tag_attribute_value_literal(context: &mut CompileContext) {
     {
         node.value = Some(AttributeValue::Literal(parse_character_reference(
             &value.to_string(),
+            false,
         )));
     } else {
         unreachable!("expected property")
@@ -1750,12 +1790,10 @@ fn on_mismatch_error(
         let tag = context.jsx_tag.as_ref().unwrap();
 
         return Err(format!(
-            "{}:{}: Expected a closing tag for `{}` ({}:{}){} (mdx-jsx:end-tag-mismatch)",
-            point.line,
-            point.column,
+            "{}: Expected a closing tag for `{}` ({}){} (mdx-jsx:end-tag-mismatch)",
+            context.place(&point_from_event_point(point)),
             serialize_abbreviated_tag(tag),
-            tag.start.line,
-            tag.start.column,
+            context.place(&tag.start),
             if let Some(left) = left {
                 format!(" before the end of `{:?}`", left.name)
             } else {
@@ -1769,13 +1807,11 @@ fn on_mismatch_error(
             let tag = context.jsx_tag.as_ref().unwrap();
 
             return Err(format!(
-                "{}:{}: Expected the closing tag `{}` either before the start of `{:?}` ({}:{}), or another opening tag after that start (mdx-jsx:end-tag-mismatch)",
-                tag.start.line,
-                tag.start.column,
+                "{}: Expected the closing tag `{}` either before the start of `{:?}` ({}), or another opening tag after that start (mdx-jsx:end-tag-mismatch)",
+                context.place(&tag.start),
                 serialize_abbreviated_tag(tag),
                 &right.name,
-                &right.point.line,
-                &right.point.column,
+                context.place(&point_from_event(right)),
             ));
         }
         unreachable!("mismatched (non-jsx): {:?} / {:?}", left.name, right.name);
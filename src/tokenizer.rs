@@ -9,7 +9,7 @@
 //! [`attempt`]: Tokenizer::attempt
 
 use crate::event::{Content, Event, Kind, Link, Name, Point, VOID_EVENTS};
-use crate::parser::ParseState;
+use crate::parser::{limit_error, ParseState};
 use crate::resolve::{call as call_resolve, Name as ResolveName};
 use crate::state::{call, State};
 use crate::subtokenize::Subresult;
@@ -17,8 +17,12 @@ use crate::subtokenize::Subresult;
 #[cfg(feature = "log")]
 use crate::util::char::format_byte_opt;
 
-use crate::util::{constant::TAB_SIZE, edit_map::EditMap};
-use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use crate::util::{
+    constant::{ABORT_CHECK_INTERVAL, TAB_SIZE},
+    edit_map::EditMap,
+    location::format_place,
+};
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
 
 /// Containers.
 ///
@@ -117,6 +121,8 @@ pub struct LabelStart {
     /// That link start is still looking for a balanced closing bracket though,
     /// so we can’t remove it just yet.
     pub inactive: bool,
+    /// How deep labels that closed in this label so far are nested.
+    pub depth: usize,
 }
 
 /// Valid label.
@@ -127,6 +133,8 @@ pub struct Label {
     pub start: (usize, usize),
     /// Indices of label end.
     pub end: (usize, usize),
+    /// How deep labels are nested in each other here, including this one.
+    pub depth: usize,
 }
 
 /// Different kinds of attempts.
@@ -476,7 +484,18 @@ impl<'a> Tokenizer<'a> {
                     self.point.line += 1;
                     self.point.column = 1;
 
-                    if self.point.line - self.first_line + 1 > self.column_start.len() {
+                    let at = self.point.line - self.first_line;
+
+                    if at >= self.column_start.len() {
+                        // Store each line at its own index, so that the
+                        // right start is used when lines are consumed again
+                        // after an attempt is reverted.
+                        // Lines before it without start (such as the first)
+                        // get `(0, 0)`, which does not move.
+                        while self.column_start.len() < at {
+                            self.column_start.push((0, 0));
+                        }
+
                         self.column_start.push((self.point.index, self.point.vs));
                     }
 
@@ -501,6 +520,30 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
+    /// Format the place of the current point, as `line:column`, for use in
+    /// error messages.
+    pub fn place(&self) -> String {
+        format_place(
+            self.parse_state.bytes,
+            self.point.line,
+            self.point.column,
+            self.point.index,
+            self.parse_state.options.position_encoding,
+        )
+    }
+
+    /// Count a step, and check whether to abort every so often.
+    fn should_abort(&self) -> bool {
+        let steps = self.parse_state.steps.get();
+        self.parse_state.steps.set(steps + 1);
+
+        if let Some(should_abort) = &self.parse_state.options.should_abort {
+            steps % ABORT_CHECK_INTERVAL == 0 && should_abort()
+        } else {
+            false
+        }
+    }
+
     /// Mark the start of a semantic label.
     pub fn enter(&mut self, name: Name) {
         enter_impl(self, name, None);
@@ -552,6 +595,7 @@ impl<'a> Tokenizer<'a> {
             link: None,
         };
         self.events.push(event);
+        count_event(self);
     }
 
     /// Capture the tokenizer progress.
@@ -684,6 +728,13 @@ fn enter_impl(tokenizer: &mut Tokenizer, name: Name, link: Option<Link>) {
         point,
         link,
     });
+    count_event(tokenizer);
+}
+
+/// Count a made event, for the `events` limit.
+fn count_event(tokenizer: &Tokenizer) {
+    let made = &tokenizer.parse_state.events_made;
+    made.set(made.get() + 1);
 }
 
 /// Run the tokenizer.
@@ -703,6 +754,26 @@ fn push_impl(
     tokenizer.move_to(from);
 
     loop {
+        if !matches!(state, State::Error(_)) && tokenizer.should_abort() {
+            state = State::Error(format!(
+                "{}: Unexpected abort, expected `should_abort` to return `false` (aborted)",
+                tokenizer.place()
+            ));
+        }
+
+        if !matches!(state, State::Error(_)) {
+            if let Some(max) = tokenizer.parse_state.options.limits.events {
+                if tokenizer.parse_state.events_made.get() > max {
+                    state = State::Error(limit_error(
+                        tokenizer.parse_state,
+                        &tokenizer.point,
+                        "event",
+                        max,
+                    ));
+                }
+            }
+        }
+
         match state {
             State::Error(_) => break,
             State::Ok | State::Nok => {
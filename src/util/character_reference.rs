@@ -155,12 +155,10 @@ pub fn value_test(marker: u8) -> fn(&u8) -> bool {
 
 /// Decode character references in a string.
 ///
-/// > 👉 **Note**: this currently only supports the 252 named character
-/// > references from HTML 4, as it’s only used for JSX.
-/// >
-/// > If it’s ever needed to support HTML 5 (which is what normal markdown
-/// > uses), a boolean parameter can be added here.
-pub fn parse(value: &str) -> String {
+/// The `html5` boolean specifies whether the 2125 named character references
+/// from HTML 5 (used in HTML and markdown) or the 252 names from HTML 4 (used
+/// in JSX) are supported.
+pub fn parse(value: &str, html5: bool) -> String {
     let bytes = value.as_bytes();
     let mut index = 0;
     let len = bytes.len();
@@ -193,11 +191,11 @@ pub fn parse(value: &str) -> String {
             let value_end = value_start + value_index;
 
             // Non empty and terminated.
-            if value_index > 0 && bytes[value_end] == b';' {
+            if value_index > 0 && value_end < len && bytes[value_end] == b';' {
                 if let Some(decoded) = decode(
                     str::from_utf8(&bytes[value_start..value_end]).unwrap(),
                     marker,
-                    false,
+                    html5,
                 ) {
                     result.push_str(&value[start..index]);
                     result.push_str(&decoded);
@@ -15,6 +15,25 @@
 //! [heading_atx]: crate::construct::heading_atx
 //! [html_flow]: crate::construct::html_flow
 
+/// The number of steps the tokenizer takes between calls to
+/// [`should_abort`][crate::configuration::ParseOptions::should_abort].
+///
+/// A step is feeding one byte to a state function, or retrying one.
+/// Calling the function is likely much slower than a step, so it’s not done
+/// on every step, but often enough that parsing stops soon after it returns
+/// `true`.
+pub const ABORT_CHECK_INTERVAL: usize = 1024;
+
+/// The number of bytes of unfinished markdown that
+/// [`Parser::push`][crate::Parser::push] parses again on every completed
+/// line.
+///
+/// Past this, unfinished markdown is parsed again once it has doubled in
+/// size since it was last parsed.
+/// That keeps pushing linear when a node (such as a long code block) stays
+/// unfinished for a long time, at the cost of reporting finished nodes later.
+pub const STREAM_REPARSE_SIZE_MAX: usize = 4096;
+
 /// The number of characters allowed in a protocol of an [autolink][].
 ///
 /// The protocol part is the `xxx` in `<xxx://example.com>`.
@@ -226,6 +245,18 @@ pub const HTML_CDATA_PREFIX: [u8; 6] = [b'C', b'D', b'A', b'T', b'A', b'['];
 /// [html_flow]: crate::construct::html_flow
 pub const HTML_RAW_NAMES: [&str; 4] = ["pre", "script", "style", "textarea"];
 
+/// List of HTML tag names of void elements, which have no closing tag.
+///
+/// Used when sanitizing HTML.
+///
+/// ## References
+///
+/// *   [*§ 13.1.2 Elements* in the HTML spec](https://html.spec.whatwg.org/multipage/syntax.html#void-elements)
+pub const HTML_VOID_NAMES: [&str; 13] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
 /// The number of the longest tag name in [`HTML_RAW_NAMES`][].
 ///
 /// This is currently the size of `textarea`.
@@ -248,6 +279,14 @@ pub const LINK_REFERENCE_SIZE_MAX: usize = 999;
 /// [list-item]: crate::construct::list_item
 pub const LIST_ITEM_VALUE_SIZE_MAX: usize = 10;
 
+/// Maximum nesting of groups, arguments, and scripts in math, when compiled
+/// to MathML.
+///
+/// Math nested deeper than this is shown as text instead, as the parser and
+/// serializer are recursive.
+#[cfg(feature = "mathml")]
+pub const MATHML_DEPTH_MAX: usize = 64;
+
 /// The number of markers needed for [math (flow)][raw_flow] to form.
 ///
 /// Unlike code (fenced), this number is `2`.
@@ -0,0 +1,183 @@
+//! Customize how certain nodes are compiled to HTML.
+
+use crate::mdast::AlignKind;
+use alloc::string::String;
+
+/// Node that can be customized with an [`HtmlHandler`][].
+///
+/// Values are already sanitized and encoded, so they can be used in
+/// attributes as they are.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HtmlNode<'a> {
+    /// Block quote.
+    ///
+    /// ```markdown
+    /// > | > a
+    ///     ^^^
+    /// ```
+    BlockQuote,
+    /// GFM: delete (strikethrough).
+    ///
+    /// ```markdown
+    /// > | ~~a~~
+    ///     ^^^^^
+    /// ```
+    Delete,
+    /// Emphasis.
+    ///
+    /// ```markdown
+    /// > | *a*
+    ///     ^^^
+    /// ```
+    Emphasis,
+    /// Heading (atx or setext).
+    ///
+    /// ```markdown
+    /// > | # a
+    ///     ^^^
+    /// ```
+    Heading {
+        /// Rank (between `1` and `6`, both including).
+        depth: u8,
+    },
+    /// Image (resource or reference).
+    ///
+    /// ```markdown
+    /// > | ![a](b "c")
+    ///     ^^^^^^^^^^^
+    /// ```
+    Image {
+        /// Sanitized and encoded URL.
+        url: &'a str,
+        /// Encoded alt text.
+        alt: &'a str,
+        /// Encoded title, if any.
+        title: Option<&'a str>,
+    },
+    /// Link (resource, reference, autolink, or GFM autolink literal).
+    ///
+    /// ```markdown
+    /// > | [a](b "c") <https://d>
+    ///     ^^^^^^^^^^ ^^^^^^^^^^^
+    /// ```
+    Link {
+        /// Sanitized and encoded URL.
+        url: &'a str,
+        /// Encoded title, if any.
+        title: Option<&'a str>,
+    },
+    /// List (ordered or unordered).
+    ///
+    /// ```markdown
+    /// > | * a
+    ///     ^^^
+    /// ```
+    List {
+        /// Whether the list is ordered.
+        ordered: bool,
+        /// Number of the first item, if ordered.
+        start: Option<u32>,
+    },
+    /// List item.
+    ///
+    /// ```markdown
+    /// > | * a
+    ///     ^^^
+    /// ```
+    ListItem,
+    /// Paragraph.
+    ///
+    /// Not used for paragraphs in tight lists, which have no element.
+    ///
+    /// ```markdown
+    /// > | a
+    ///     ^
+    /// ```
+    Paragraph,
+    /// Strong.
+    ///
+    /// ```markdown
+    /// > | **a**
+    ///     ^^^^^
+    /// ```
+    Strong,
+    /// GFM: table.
+    ///
+    /// ```markdown
+    /// > | | a |
+    ///     ^^^^^
+    /// > | | - |
+    ///     ^^^^^
+    /// ```
+    Table {
+        /// How cells in columns are aligned.
+        align: &'a [AlignKind],
+    },
+    /// GFM: table cell.
+    ///
+    /// ```markdown
+    /// > | | a |
+    ///     ^^^^^
+    /// ```
+    TableCell {
+        /// How the cell is aligned.
+        align: AlignKind,
+        /// Whether the cell is in the head row (`th`) or not (`td`).
+        head: bool,
+    },
+    /// GFM: table row.
+    ///
+    /// ```markdown
+    /// > | | a |
+    ///     ^^^^^
+    /// ```
+    TableRow,
+    /// Thematic break.
+    ///
+    /// ```markdown
+    /// > | ***
+    ///     ^^^
+    /// ```
+    ThematicBreak,
+}
+
+/// Handler to customize how certain nodes are compiled to HTML.
+///
+/// Can be passed as `html_handler` in
+/// [`CompileOptions`][crate::configuration::CompileOptions].
+///
+/// All methods get the node, and the compiled HTML of its children (which
+/// is empty for images and thematic breaks).
+/// They are called when the whole node is compiled, so the children can be
+/// used to generate, say, an anchor for a heading.
+/// Returning `None` uses the default.
+///
+/// For example, the default for a link is `<a href="b" title="c">` as the
+/// opening tag, the compiled children as the content, and `</a>` as the
+/// closing tag.
+/// The default for images is `<img src="b" alt="a" title="c" />` as the
+/// opening tag, and nothing as the content and closing tag.
+///
+/// The nodes in [`HtmlNode`][] are the ones that compile to elements.
+/// Other things, such as code (use `code_block_render` for that), math,
+/// HTML, footnotes, breaks, and text, are not passed to the handler.
+/// Neither are nodes in the alt text of images, as that is text.
+///
+/// > 👉 **Note**: what the handler returns is not sanitized.
+/// > Make sure to encode values yourself.
+pub trait HtmlHandler {
+    /// Get the opening tag of `node`.
+    fn enter(&self, _node: &HtmlNode, _children: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the content of `node`.
+    fn content(&self, _node: &HtmlNode, _children: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the closing tag of `node`.
+    fn exit(&self, _node: &HtmlNode, _children: &str) -> Option<String> {
+        None
+    }
+}
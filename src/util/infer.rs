@@ -35,43 +35,45 @@ pub fn list_loose(events: &[Event], mut index: usize, include_items: bool) -> bo
             balance -= 1;
 
             if balance == 1 && event.name == Name::BlankLineEnding {
-                // Blank line directly after item, which is just a prefix.
+                // Blank line directly after item, which is just a prefix,
+                // maybe with empty containers in it:
                 //
                 // ```markdown
                 // > | -␊
                 //      ^
-                //   | - a
-                // ```
-                let mut at_empty_list_item = false;
-                // Blank line at block quote prefix:
-                //
-                // ```markdown
                 // > | * >␊
                 //        ^
-                //   | * a
+                // > | - +␊
+                //        ^
+                //   | - a
                 // ```
-                let mut at_empty_block_quote = false;
-
-                // List.
                 let mut before = index - 2;
 
-                if events[before].name == Name::ListItem {
-                    before -= 1;
-
-                    if events[before].name == Name::SpaceOrTab {
-                        before -= 2;
-                    }
-
-                    if events[before].name == Name::BlockQuote
-                        && events[before - 1].name == Name::BlockQuotePrefix
-                    {
-                        at_empty_block_quote = true;
-                    } else if events[before].name == Name::ListItemPrefix {
-                        at_empty_list_item = true;
-                    }
+                while events[before].kind == Kind::Exit
+                    && matches!(
+                        events[before].name,
+                        Name::BlockQuote
+                            | Name::ListItem
+                            | Name::ListOrdered
+                            | Name::ListUnordered
+                            | Name::SpaceOrTab
+                    )
+                {
+                    before -= if events[before].name == Name::SpaceOrTab {
+                        2
+                    } else {
+                        1
+                    };
                 }
 
-                if !at_empty_list_item && !at_empty_block_quote {
+                let at_prefix = before < index - 2
+                    && matches!(
+                        events[before].name,
+                        Name::BlockQuotePrefix | Name::ListItemPrefix
+                    )
+                    && events[before].point.line == events[index - 1].point.line;
+
+                if !at_prefix {
                     return true;
                 }
             }
@@ -4,8 +4,9 @@
 //! * Convert between byte indices into a string which is built up of several
 //!   slices in a whole document, and byte indices into that whole document.
 
+use crate::configuration::PositionEncoding;
 use crate::unist::Point;
-use alloc::{vec, vec::Vec};
+use alloc::{format, string::String, vec, vec::Vec};
 
 /// Each stop represents a new slice, which contains the byte index into the
 /// corresponding string where the slice starts (`0`), and the byte index into
@@ -17,6 +18,14 @@ pub struct Location {
     /// List, where each index is a line number (0-based), and each value is
     /// the byte index *after* where the line ends.
     indices: Vec<usize>,
+    /// List, where each index is a line number (0-based), and each value is
+    /// the number of code points before the corresponding index in
+    /// `indices`.
+    code_points: Vec<usize>,
+    /// List, where each index is a line number (0-based), and each value is
+    /// the number of UTF-16 code units before the corresponding index in
+    /// `indices`.
+    utf16: Vec<usize>,
 }
 
 impl Location {
@@ -26,24 +35,43 @@ impl Location {
     #[must_use]
     pub fn new(bytes: &[u8]) -> Self {
         let mut index = 0;
-        let mut location_index = Self { indices: vec![] };
+        let mut code_points = 0;
+        let mut utf16 = 0;
+        let mut location_index = Self {
+            indices: vec![],
+            code_points: vec![],
+            utf16: vec![],
+        };
 
         while index < bytes.len() {
+            code_points += count(&bytes[index..=index], PositionEncoding::CodePoint);
+            utf16 += count(&bytes[index..=index], PositionEncoding::Utf16);
+
             if bytes[index] == b'\r' {
                 if index + 1 < bytes.len() && bytes[index + 1] == b'\n' {
                     location_index.indices.push(index + 2);
+                    location_index.code_points.push(code_points + 1);
+                    location_index.utf16.push(utf16 + 1);
                     index += 1;
+                    code_points += 1;
+                    utf16 += 1;
                 } else {
                     location_index.indices.push(index + 1);
+                    location_index.code_points.push(code_points);
+                    location_index.utf16.push(utf16);
                 }
             } else if bytes[index] == b'\n' {
                 location_index.indices.push(index + 1);
+                location_index.code_points.push(code_points);
+                location_index.utf16.push(utf16);
             }
 
             index += 1;
         }
 
         location_index.indices.push(index + 1);
+        location_index.code_points.push(code_points + 1);
+        location_index.utf16.push(utf16 + 1);
         location_index
     }
 
@@ -82,6 +110,61 @@ impl Location {
         None
     }
 
+    /// Like `to_point`, but with the column and offset of the point counted
+    /// in `encoding`.
+    ///
+    /// `bytes` must be the bytes this was made for.
+    /// Columns of tabs are not virtual: with [`PositionEncoding::Byte`][],
+    /// this is the same as `to_point`.
+    ///
+    /// Returns `None` when given out of bounds input.
+    #[must_use]
+    pub fn to_encoded_point(
+        &self,
+        bytes: &[u8],
+        offset: usize,
+        encoding: PositionEncoding,
+    ) -> Option<Point> {
+        let mut point = self.to_point(offset)?;
+
+        if encoding != PositionEncoding::Byte {
+            let line_start = offset + 1 - point.column;
+            let before = if point.line > 1 {
+                match encoding {
+                    PositionEncoding::CodePoint => self.code_points[point.line - 2],
+                    _ => self.utf16[point.line - 2],
+                }
+            } else {
+                0
+            };
+            let column = count(&bytes[line_start..offset], encoding);
+            point.column = column + 1;
+            point.offset = before + column;
+        }
+
+        Some(point)
+    }
+
+    /// Get the `offset` for a line and column-based point in the bound
+    /// indices.
+    ///
+    /// Returns `None` when given out of bounds input.
+    ///
+    /// Port of <https://github.com/vfile/vfile-location/blob/main/index.js>
+    #[must_use]
+    pub fn to_offset(&self, line: usize, column: usize) -> Option<usize> {
+        if line > 0 && column > 0 && line <= self.indices.len() {
+            let start = if line > 1 { self.indices[line - 2] } else { 0 };
+            let offset = start + column - 1;
+
+            if offset < self.indices[line - 1] {
+                return Some(offset);
+            }
+        }
+
+        None
+    }
+
     /// Like `to_point`, but takes a relative offset from a certain string
     /// instead of an absolute offset into the whole document.
     ///
@@ -111,6 +194,57 @@ impl Location {
     }
 }
 
+/// Format the place of a point, as `line:column`, for use in error
+/// messages.
+///
+/// With [`PositionEncoding::Byte`][], `line` and `column` (which can be
+/// virtual) are used; otherwise, the place is counted at `index` in `bytes`.
+pub fn format_place(
+    bytes: &[u8],
+    line: usize,
+    column: usize,
+    index: usize,
+    encoding: PositionEncoding,
+) -> String {
+    match Location::new(bytes).to_encoded_point(bytes, index, encoding) {
+        Some(point) if encoding != PositionEncoding::Byte => {
+            format!("{}:{}", point.line, point.column)
+        }
+        _ => format!("{}:{}", line, column),
+    }
+}
+
+/// Count code units of `encoding` in `bytes`.
+///
+/// Characters are counted at their first byte.
+pub fn count(bytes: &[u8], encoding: PositionEncoding) -> usize {
+    if encoding == PositionEncoding::Byte {
+        return bytes.len();
+    }
+
+    let mut index = 0;
+    let mut count = 0;
+
+    while index < bytes.len() {
+        let byte = bytes[index];
+
+        // Continuation bytes are part of the character before them.
+        if byte & 0xC0 != 0x80 {
+            // Characters of four bytes are outside the BMP, so they are
+            // two UTF-16 code units.
+            count += if encoding == PositionEncoding::Utf16 && byte >= 0xF0 {
+                2
+            } else {
+                1
+            };
+        }
+
+        index += 1;
+    }
+
+    count
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,6 +286,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_location_to_offset() {
+        let location = Location::new("ab\r\nc".as_bytes());
+        assert_eq!(
+            location.to_offset(1, 1),
+            Some(0),
+            "should support offsets (1)"
+        );
+        assert_eq!(
+            location.to_offset(1, 4),
+            Some(3),
+            "should support offsets (2)"
+        );
+        assert_eq!(
+            location.to_offset(2, 1),
+            Some(4),
+            "should support offsets (3)"
+        );
+        assert_eq!(
+            location.to_offset(2, 2),
+            Some(5),
+            "should support offsets (4)"
+        );
+        assert_eq!(location.to_offset(1, 5), None, "should support offsets (5)");
+        assert_eq!(location.to_offset(2, 3), None, "should support offsets (6)");
+        assert_eq!(location.to_offset(3, 1), None, "should support offsets (7)");
+        assert_eq!(location.to_offset(0, 1), None, "should support offsets (8)");
+    }
+
     #[test]
     fn test_location_cr() {
         let location = Location::new("a\rb".as_bytes());
@@ -196,6 +359,44 @@ mod tests {
             "should support some points (4)"
         );
     }
+    #[test]
+    fn test_location_encoded() {
+        let value = "a😀\r\nä\tb";
+        let location = Location::new(value.as_bytes());
+        let point =
+            |offset, encoding| location.to_encoded_point(value.as_bytes(), offset, encoding);
+        assert_eq!(
+            point(5, PositionEncoding::Byte), // `\r`
+            Some(Point::new(1, 6, 5)),
+            "should support bytes"
+        );
+        assert_eq!(
+            point(5, PositionEncoding::Utf16), // `\r`
+            Some(Point::new(1, 4, 3)),
+            "should support UTF-16 (1)"
+        );
+        assert_eq!(
+            point(10, PositionEncoding::Utf16), // `b`
+            Some(Point::new(2, 3, 7)),
+            "should support UTF-16 (2)"
+        );
+        assert_eq!(
+            point(5, PositionEncoding::CodePoint), // `\r`
+            Some(Point::new(1, 3, 2)),
+            "should support code points (1)"
+        );
+        assert_eq!(
+            point(11, PositionEncoding::CodePoint), // EOF
+            Some(Point::new(2, 4, 7)),
+            "should support code points (2)"
+        );
+        assert_eq!(
+            point(12, PositionEncoding::CodePoint), // Out of bounds
+            None,
+            "should support code points (3)"
+        );
+    }
+
     #[test]
     fn test_empty() {
         let location = Location::new("".as_bytes());
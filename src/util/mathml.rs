@@ -0,0 +1,1153 @@
+//! Turn a subset of LaTeX into MathML.
+//!
+//! This is used when compiling [math (flow)][math_flow] and
+//! [math (text)][math_text] to HTML, with the `mathml` feature turned on, so
+//! that math can be shown without client-side JavaScript.
+//!
+//! Only a reasonable subset of LaTeX is supported: identifiers, numbers,
+//! operators, groups, sub- and superscripts, fractions, roots, common
+//! symbols, fonts, accents, spacing, delimiters, and a couple of matrix-like
+//! environments.
+//! Unsupported commands are not dropped: they are shown as text, so that the
+//! reader can still see what the author wrote.
+//!
+//! [math_flow]: crate::construct::raw_flow
+//! [math_text]: crate::construct::raw_text
+
+use crate::util::{constant::MATHML_DEPTH_MAX, encode::encode};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// A MathML node.
+#[derive(Debug)]
+enum Node {
+    /// Identifier (`<mi>`), with an optional `mathvariant`.
+    Identifier(String, Option<&'static str>),
+    /// Number (`<mn>`).
+    Number(String),
+    /// Operator (`<mo>`), and whether it takes limits (such as `\sum` in
+    /// display mode).
+    Operator(String, bool),
+    /// Stretchy fence (`<mo>`), as used by `\left` and `\right`.
+    Fence(String),
+    /// Text (`<mtext>`).
+    Text(String),
+    /// Space (`<mspace>`), with a width in `em`.
+    Space(&'static str),
+    /// Group (`<mrow>`).
+    Row(Vec<Node>),
+    /// Fraction (`<mfrac>`), optionally without a line (for `\binom`).
+    Fraction(Box<Node>, Box<Node>, bool),
+    /// Square root (`<msqrt>`).
+    SquareRoot(Box<Node>),
+    /// Root with an index (`<mroot>`).
+    Root(Box<Node>, Box<Node>),
+    /// Base with optional subscript and superscript.
+    Scripts(Box<Node>, Option<Box<Node>>, Option<Box<Node>>),
+    /// Base with something above it (`<mover>`), and whether that is an
+    /// accent.
+    Over(Box<Node>, Box<Node>, bool),
+    /// Base with something below it (`<munder>`).
+    Under(Box<Node>, Box<Node>),
+    /// Table (`<mtable>`), with its rows of cells, and column alignment.
+    Table(Vec<Vec<Node>>, &'static str),
+}
+
+/// Parser state.
+struct Parser<'a> {
+    /// Characters of the input.
+    chars: Vec<char>,
+    /// Current index into `chars`.
+    index: usize,
+    /// Whether this is display (flow) math.
+    display: bool,
+    /// Source, used for the annotation.
+    source: &'a str,
+    /// Current nesting of groups, arguments, and scripts.
+    depth: usize,
+    /// Whether the nesting went above [`MATHML_DEPTH_MAX`][].
+    too_deep: bool,
+}
+
+/// Turn LaTeX into a MathML `<math>` element.
+///
+/// Pass `display: true` for math (flow), which is shown as a block, and
+/// `false` for math (text), which is shown inline.
+///
+/// ## Examples
+///
+/// ```rust ignore
+/// use markdown::util::mathml::to_mathml;
+///
+/// assert_eq!(
+///     to_mathml("x^2", false),
+///     "<math><semantics><mrow><msup><mi>x</mi><mn>2</mn></msup></mrow><annotation encoding=\"application/x-tex\">x^2</annotation></semantics></math>"
+/// );
+/// ```
+pub fn to_mathml(value: &str, display: bool) -> String {
+    let mut parser = Parser {
+        chars: value.chars().collect(),
+        index: 0,
+        display,
+        source: value,
+        depth: 0,
+        too_deep: false,
+    };
+    let mut nodes = parser.parse_list(&[]);
+
+    // Too deep to turn into MathML: show the source instead.
+    if parser.too_deep {
+        nodes = vec![Node::Text(value.into())];
+    }
+
+    let mut result = String::new();
+
+    result.push_str(if display {
+        "<math display=\"block\">"
+    } else {
+        "<math>"
+    });
+    result.push_str("<semantics><mrow>");
+
+    let mut index = 0;
+    while index < nodes.len() {
+        serialize(&nodes[index], &mut result);
+        index += 1;
+    }
+
+    result.push_str("</mrow><annotation encoding=\"application/x-tex\">");
+    result.push_str(&encode(parser.source, true));
+    result.push_str("</annotation></semantics></math>");
+    result
+}
+
+impl Parser<'_> {
+    /// Parse nodes until the end, or until a command or character in `ends`
+    /// (which is not consumed).
+    fn parse_list(&mut self, ends: &[&str]) -> Vec<Node> {
+        let mut nodes = vec![];
+        // Scripts on the last node, which nest when doubled.
+        let mut scripts = 0;
+
+        loop {
+            self.skip_whitespace();
+
+            if self.index >= self.chars.len() || self.at_end(ends) {
+                break;
+            }
+
+            let char = self.chars[self.index];
+
+            if (char == '^' || char == '_' || char == '\'')
+                && self.depth + scripts >= MATHML_DEPTH_MAX
+            {
+                self.give_up();
+                break;
+            }
+
+            if char == '^' || char == '_' {
+                scripts += 1;
+                self.index += 1;
+                let script = self.parse_argument();
+                let base = nodes.pop().unwrap_or(Node::Row(vec![]));
+                nodes.push(attach_script(base, script, char == '^'));
+            } else if char == '\'' {
+                scripts += 1;
+                self.index += 1;
+                let prime = Node::Operator("′".into(), false);
+                let base = nodes.pop().unwrap_or(Node::Row(vec![]));
+                nodes.push(attach_script(base, prime, true));
+            } else if let Some(node) = self.parse_atom() {
+                scripts = 0;
+                nodes.push(node);
+            }
+        }
+
+        nodes
+    }
+
+    /// Check if we are at one of `ends`.
+    fn at_end(&self, ends: &[&str]) -> bool {
+        let mut index = 0;
+
+        while index < ends.len() {
+            let end = ends[index];
+
+            if let Some(name) = end.strip_prefix('\\') {
+                if self.peek_command().as_deref() == Some(name) {
+                    return true;
+                }
+            } else if end.starts_with(self.chars[self.index]) {
+                return true;
+            }
+
+            index += 1;
+        }
+
+        false
+    }
+
+    /// Skip whitespace.
+    fn skip_whitespace(&mut self) {
+        while self.index < self.chars.len() && self.chars[self.index].is_whitespace() {
+            self.index += 1;
+        }
+    }
+
+    /// Get the name of the command at the current position, if any, without
+    /// consuming it.
+    fn peek_command(&self) -> Option<String> {
+        if self.index < self.chars.len() && self.chars[self.index] == '\\' {
+            let mut end = self.index + 1;
+
+            while end < self.chars.len() && self.chars[end].is_ascii_alphabetic() {
+                end += 1;
+            }
+
+            // Single non-letter commands, such as `\,` or `\\`.
+            if end == self.index + 1 && end < self.chars.len() {
+                end += 1;
+            }
+
+            Some(self.chars[self.index + 1..end].iter().collect())
+        } else {
+            None
+        }
+    }
+
+    /// Parse one thing, unless nested too deep.
+    fn parse_atom(&mut self) -> Option<Node> {
+        if self.depth >= MATHML_DEPTH_MAX {
+            self.give_up();
+            return None;
+        }
+
+        self.depth += 1;
+        let node = self.parse_atom_inner();
+        self.depth -= 1;
+        node
+    }
+
+    /// Stop parsing, because the math is nested too deep.
+    fn give_up(&mut self) {
+        self.too_deep = true;
+        self.index = self.chars.len();
+    }
+
+    /// Parse one thing, without checking the depth.
+    fn parse_atom_inner(&mut self) -> Option<Node> {
+        let char = self.chars[self.index];
+
+        match char {
+            '{' => {
+                self.index += 1;
+                let nodes = self.parse_list(&["}"]);
+                // Closing brace, if there.
+                if self.index < self.chars.len() {
+                    self.index += 1;
+                }
+                Some(Node::Row(nodes))
+            }
+            '\\' => self.parse_command(),
+            '0'..='9' | '.' => {
+                let start = self.index;
+                while self.index < self.chars.len()
+                    && (self.chars[self.index].is_ascii_digit()
+                        || (self.chars[self.index] == '.'
+                            && self.index + 1 < self.chars.len()
+                            && self.chars[self.index + 1].is_ascii_digit()))
+                {
+                    self.index += 1;
+                }
+
+                // A lone dot.
+                if self.index == start {
+                    self.index += 1;
+                    return Some(Node::Operator(".".into(), false));
+                }
+
+                Some(Node::Number(self.chars[start..self.index].iter().collect()))
+            }
+            '~' => {
+                self.index += 1;
+                Some(Node::Space("0.3333em"))
+            }
+            _ => {
+                self.index += 1;
+                if char.is_alphabetic() {
+                    Some(Node::Identifier(char.to_string(), None))
+                } else if char == '}' || char == '&' {
+                    // Unbalanced: show as is.
+                    Some(Node::Operator(char.to_string(), false))
+                } else {
+                    Some(Node::Operator(
+                        match char {
+                            '-' => "−".into(),
+                            '*' => "∗".into(),
+                            _ => char.to_string(),
+                        },
+                        false,
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Parse a required argument: a group, a command, or a single character.
+    fn parse_argument(&mut self) -> Node {
+        self.skip_whitespace();
+
+        if self.index >= self.chars.len() {
+            return Node::Row(vec![]);
+        }
+
+        if self.chars[self.index].is_ascii_digit() {
+            // Only one digit: `x^23` is `x` to the power of `2`, times `3`.
+            self.index += 1;
+            return Node::Number(self.chars[self.index - 1].to_string());
+        }
+
+        self.parse_atom().unwrap_or(Node::Row(vec![]))
+    }
+
+    /// Parse a raw argument in braces (or a single character), as text.
+    fn parse_raw_argument(&mut self) -> String {
+        self.skip_whitespace();
+
+        if self.index >= self.chars.len() {
+            return String::new();
+        }
+
+        if self.chars[self.index] == '{' {
+            let start = self.index + 1;
+            let mut depth = 0;
+
+            while self.index < self.chars.len() {
+                match self.chars[self.index] {
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    '\\' => self.index += 1,
+                    _ => {}
+                }
+                self.index += 1;
+            }
+
+            let end = self.index.min(self.chars.len());
+            // Closing brace, if there.
+            if self.index < self.chars.len() {
+                self.index += 1;
+            }
+            self.chars[start..end].iter().collect()
+        } else {
+            self.index += 1;
+            self.chars[self.index - 1].to_string()
+        }
+    }
+
+    /// Parse an optional argument in brackets (`[x]`).
+    fn parse_optional_argument(&mut self) -> Option<Node> {
+        self.skip_whitespace();
+
+        if self.index < self.chars.len() && self.chars[self.index] == '[' {
+            self.index += 1;
+            let nodes = self.parse_list(&["]"]);
+            // Closing bracket, if there.
+            if self.index < self.chars.len() {
+                self.index += 1;
+            }
+            Some(Node::Row(nodes))
+        } else {
+            None
+        }
+    }
+
+    /// Parse a delimiter after `\left`, `\right`, `\big`, and the like.
+    fn parse_delimiter(&mut self) -> String {
+        self.skip_whitespace();
+
+        if self.index >= self.chars.len() {
+            return String::new();
+        }
+
+        if let Some(name) = self.peek_command() {
+            self.index += name.chars().count() + 1;
+            delimiter(&name).unwrap_or("").into()
+        } else {
+            let char = self.chars[self.index];
+            self.index += 1;
+            if char == '.' {
+                String::new()
+            } else {
+                char.to_string()
+            }
+        }
+    }
+
+    /// Parse a command (`\x`).
+    fn parse_command(&mut self) -> Option<Node> {
+        let name = self.peek_command().unwrap_or_default();
+        self.index += name.chars().count() + 1;
+
+        if name.is_empty() {
+            // A trailing backslash.
+            return Some(Node::Operator("\\".into(), false));
+        }
+
+        if let Some(value) = identifier(&name) {
+            return Some(Node::Identifier(value.into(), None));
+        }
+
+        if let Some(value) = operator(&name) {
+            return Some(Node::Operator(value.into(), false));
+        }
+
+        if let Some(value) = big_operator(&name) {
+            return Some(Node::Operator(value.into(), self.display));
+        }
+
+        if let Some(value) = delimiter(&name) {
+            return Some(Node::Operator(value.into(), false));
+        }
+
+        if let Some(value) = space(&name) {
+            return Some(Node::Space(value));
+        }
+
+        if FUNCTIONS.contains(&name.as_str()) {
+            let limits = FUNCTIONS_WITH_LIMITS.contains(&name.as_str());
+            return Some(if limits && self.display {
+                Node::Operator(name, true)
+            } else {
+                Node::Identifier(name, None)
+            });
+        }
+
+        if let Some(value) = accent(&name) {
+            let base = self.parse_argument();
+            return Some(Node::Over(
+                Box::new(base),
+                Box::new(Node::Operator(value.into(), false)),
+                true,
+            ));
+        }
+
+        if let Some(variant) = font(&name) {
+            let argument = self.parse_argument();
+            return Some(with_variant(argument, variant));
+        }
+
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" | "cfrac" => {
+                let numerator = self.parse_argument();
+                let denominator = self.parse_argument();
+                Some(Node::Fraction(
+                    Box::new(numerator),
+                    Box::new(denominator),
+                    true,
+                ))
+            }
+            "binom" | "dbinom" | "tbinom" => {
+                let top = self.parse_argument();
+                let bottom = self.parse_argument();
+                Some(Node::Row(vec![
+                    Node::Fence("(".into()),
+                    Node::Fraction(Box::new(top), Box::new(bottom), false),
+                    Node::Fence(")".into()),
+                ]))
+            }
+            "sqrt" => {
+                let index = self.parse_optional_argument();
+                let radicand = self.parse_argument();
+                Some(if let Some(index) = index {
+                    Node::Root(Box::new(radicand), Box::new(index))
+                } else {
+                    Node::SquareRoot(Box::new(radicand))
+                })
+            }
+            "text" | "textrm" | "textnormal" | "mbox" | "hbox" => {
+                Some(Node::Text(self.parse_raw_argument()))
+            }
+            "operatorname" => Some(Node::Identifier(self.parse_raw_argument(), None)),
+            "overline" => {
+                let base = self.parse_argument();
+                Some(Node::Over(
+                    Box::new(base),
+                    Box::new(Node::Operator("‾".into(), false)),
+                    true,
+                ))
+            }
+            "overbrace" => {
+                let base = self.parse_argument();
+                Some(Node::Over(
+                    Box::new(base),
+                    Box::new(Node::Operator("⏞".into(), false)),
+                    false,
+                ))
+            }
+            "underline" => {
+                let base = self.parse_argument();
+                Some(Node::Under(
+                    Box::new(base),
+                    Box::new(Node::Operator("_".into(), false)),
+                ))
+            }
+            "underbrace" => {
+                let base = self.parse_argument();
+                Some(Node::Under(
+                    Box::new(base),
+                    Box::new(Node::Operator("⏟".into(), false)),
+                ))
+            }
+            "left" => {
+                let open = self.parse_delimiter();
+                let mut nodes = self.parse_list(&["\\right"]);
+                let mut close = String::new();
+
+                if self.peek_command().as_deref() == Some("right") {
+                    self.index += "right".len() + 1;
+                    close = self.parse_delimiter();
+                }
+
+                nodes.insert(0, Node::Fence(open));
+                nodes.push(Node::Fence(close));
+                Some(Node::Row(nodes))
+            }
+            "big" | "Big" | "bigg" | "Bigg" | "bigl" | "Bigl" | "biggl" | "Biggl" | "bigr"
+            | "Bigr" | "biggr" | "Biggr" | "bigm" | "Bigm" => {
+                Some(Node::Operator(self.parse_delimiter(), false))
+            }
+            "begin" => Some(self.parse_environment()),
+            // Ignored sizing and style commands.
+            "displaystyle" | "textstyle" | "scriptstyle" | "limits" | "nolimits" => None,
+            // Escapes.
+            "{" | "}" | "$" | "%" | "&" | "#" | "_" | "|" => Some(Node::Operator(
+                if name == "|" { "‖".into() } else { name },
+                false,
+            )),
+            // Unknown: show the command as text, so nothing is lost.
+            _ => Some(Node::Text(format!("\\{}", name))),
+        }
+    }
+
+    /// Parse an environment, after `\begin`.
+    fn parse_environment(&mut self) -> Node {
+        let name = self.parse_raw_argument();
+
+        // Column spec of arrays, which we ignore.
+        if name == "array" {
+            self.parse_raw_argument();
+        }
+
+        let mut rows = vec![];
+        let mut row = vec![];
+
+        loop {
+            let cell = self.parse_list(&["&", "\\\\", "\\end"]);
+            row.push(Node::Row(cell));
+
+            if self.index >= self.chars.len() {
+                break;
+            }
+
+            if self.chars[self.index] == '&' {
+                self.index += 1;
+            } else if self.peek_command().as_deref() == Some("\\") {
+                self.index += 2;
+                rows.push(row);
+                row = vec![];
+            } else {
+                // `\end{x}`.
+                self.index += "end".len() + 1;
+                self.parse_raw_argument();
+                break;
+            }
+        }
+
+        rows.push(row);
+
+        let align = match name.as_str() {
+            "cases" => "left",
+            "aligned" | "align" | "align*" | "split" => "right left",
+            _ => "center",
+        };
+        let fences = match name.as_str() {
+            "pmatrix" => Some(("(", ")")),
+            "bmatrix" => Some(("[", "]")),
+            "Bmatrix" => Some(("{", "}")),
+            "vmatrix" => Some(("|", "|")),
+            "Vmatrix" => Some(("‖", "‖")),
+            "cases" => Some(("{", "")),
+            _ => None,
+        };
+        let table = Node::Table(rows, align);
+
+        if let Some((open, close)) = fences {
+            Node::Row(vec![
+                Node::Fence(open.into()),
+                table,
+                Node::Fence(close.into()),
+            ])
+        } else {
+            table
+        }
+    }
+}
+
+/// Attach a sub- or superscript to a base.
+fn attach_script(base: Node, script: Node, superscript: bool) -> Node {
+    match base {
+        Node::Scripts(base, sub, sup) => {
+            if superscript && sup.is_none() {
+                Node::Scripts(base, sub, Some(Box::new(script)))
+            } else if !superscript && sub.is_none() {
+                Node::Scripts(base, Some(Box::new(script)), sup)
+            } else {
+                // Double script, such as `x^a^b`: nest.
+                let base = Node::Scripts(base, sub, sup);
+                attach_script(Node::Row(vec![base]), script, superscript)
+            }
+        }
+        base => {
+            if superscript {
+                Node::Scripts(Box::new(base), None, Some(Box::new(script)))
+            } else {
+                Node::Scripts(Box::new(base), Some(Box::new(script)), None)
+            }
+        }
+    }
+}
+
+/// Apply a font variant to identifiers in a node.
+fn with_variant(node: Node, variant: &'static str) -> Node {
+    match node {
+        Node::Identifier(value, _) => Node::Identifier(value, Some(variant)),
+        Node::Row(nodes) => Node::Row(
+            nodes
+                .into_iter()
+                .map(|node| with_variant(node, variant))
+                .collect(),
+        ),
+        node => node,
+    }
+}
+
+/// Serialize a node.
+fn serialize(node: &Node, result: &mut String) {
+    match node {
+        Node::Identifier(value, variant) => {
+            result.push_str("<mi");
+            if let Some(variant) = variant {
+                result.push_str(" mathvariant=\"");
+                result.push_str(variant);
+                result.push('"');
+            }
+            result.push('>');
+            result.push_str(&encode(value, true));
+            result.push_str("</mi>");
+        }
+        Node::Number(value) => {
+            result.push_str("<mn>");
+            result.push_str(value);
+            result.push_str("</mn>");
+        }
+        Node::Operator(value, _) => {
+            result.push_str("<mo>");
+            result.push_str(&encode(value, true));
+            result.push_str("</mo>");
+        }
+        Node::Fence(value) => {
+            result.push_str("<mo fence=\"true\" stretchy=\"true\">");
+            result.push_str(&encode(value, true));
+            result.push_str("</mo>");
+        }
+        Node::Text(value) => {
+            result.push_str("<mtext>");
+            result.push_str(&encode(value, true));
+            result.push_str("</mtext>");
+        }
+        Node::Space(width) => {
+            result.push_str("<mspace width=\"");
+            result.push_str(width);
+            result.push_str("\"></mspace>");
+        }
+        Node::Row(nodes) => {
+            result.push_str("<mrow>");
+            let mut index = 0;
+            while index < nodes.len() {
+                serialize(&nodes[index], result);
+                index += 1;
+            }
+            result.push_str("</mrow>");
+        }
+        Node::Fraction(numerator, denominator, line) => {
+            result.push_str(if *line {
+                "<mfrac>"
+            } else {
+                "<mfrac linethickness=\"0\">"
+            });
+            serialize(numerator, result);
+            serialize(denominator, result);
+            result.push_str("</mfrac>");
+        }
+        Node::SquareRoot(radicand) => {
+            result.push_str("<msqrt>");
+            serialize(radicand, result);
+            result.push_str("</msqrt>");
+        }
+        Node::Root(radicand, index) => {
+            result.push_str("<mroot>");
+            serialize(radicand, result);
+            serialize(index, result);
+            result.push_str("</mroot>");
+        }
+        Node::Scripts(base, sub, sup) => {
+            // Big operators take limits (above and below).
+            let limits = matches!(**base, Node::Operator(_, true));
+            let name = match (sub.is_some(), sup.is_some(), limits) {
+                (true, true, false) => "msubsup",
+                (true, false, false) => "msub",
+                (false, _, false) => "msup",
+                (true, true, true) => "munderover",
+                (true, false, true) => "munder",
+                (false, _, true) => "mover",
+            };
+            result.push('<');
+            result.push_str(name);
+            result.push('>');
+            serialize(base, result);
+            if let Some(sub) = sub {
+                serialize(sub, result);
+            }
+            if let Some(sup) = sup {
+                serialize(sup, result);
+            }
+            result.push_str("</");
+            result.push_str(name);
+            result.push('>');
+        }
+        Node::Over(base, over, accent) => {
+            result.push_str(if *accent {
+                "<mover accent=\"true\">"
+            } else {
+                "<mover>"
+            });
+            serialize(base, result);
+            serialize(over, result);
+            result.push_str("</mover>");
+        }
+        Node::Under(base, under) => {
+            result.push_str("<munder>");
+            serialize(base, result);
+            serialize(under, result);
+            result.push_str("</munder>");
+        }
+        Node::Table(rows, align) => {
+            result.push_str("<mtable");
+            if *align != "center" {
+                result.push_str(" columnalign=\"");
+                result.push_str(align);
+                result.push('"');
+            }
+            result.push('>');
+            let mut row_index = 0;
+            while row_index < rows.len() {
+                result.push_str("<mtr>");
+                let mut cell_index = 0;
+                while cell_index < rows[row_index].len() {
+                    result.push_str("<mtd>");
+                    serialize(&rows[row_index][cell_index], result);
+                    result.push_str("</mtd>");
+                    cell_index += 1;
+                }
+                result.push_str("</mtr>");
+                row_index += 1;
+            }
+            result.push_str("</mtable>");
+        }
+    }
+}
+
+/// Functions that are shown upright.
+const FUNCTIONS: [&str; 32] = [
+    "arccos", "arcsin", "arctan", "arg", "cos", "cosh", "cot", "coth", "csc", "deg", "det", "dim",
+    "exp", "gcd", "hom", "inf", "ker", "lg", "lim", "liminf", "limsup", "ln", "log", "max", "min",
+    "Pr", "sec", "sin", "sinh", "sup", "tan", "tanh",
+];
+
+/// Functions that take limits in display mode.
+const FUNCTIONS_WITH_LIMITS: [&str; 9] = [
+    "det", "gcd", "inf", "lim", "liminf", "limsup", "max", "min", "sup",
+];
+
+/// Identifiers (letters and letter-like symbols).
+fn identifier(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "varpi" => "ϖ",
+        "rho" => "ρ",
+        "varrho" => "ϱ",
+        "sigma" => "σ",
+        "varsigma" => "ς",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Upsilon" => "Υ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "emptyset" | "varnothing" => "∅",
+        "hbar" => "ℏ",
+        "ell" => "ℓ",
+        "Re" => "ℜ",
+        "Im" => "ℑ",
+        "aleph" => "ℵ",
+        "wp" => "℘",
+        _ => return None,
+    })
+}
+
+/// Operators and relations.
+fn operator(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "pm" => "±",
+        "mp" => "∓",
+        "times" => "×",
+        "div" => "÷",
+        "cdot" => "⋅",
+        "ast" => "∗",
+        "star" => "⋆",
+        "circ" => "∘",
+        "bullet" => "∙",
+        "cap" => "∩",
+        "cup" => "∪",
+        "wedge" | "land" => "∧",
+        "vee" | "lor" => "∨",
+        "neg" | "lnot" => "¬",
+        "setminus" => "∖",
+        "oplus" => "⊕",
+        "ominus" => "⊖",
+        "otimes" => "⊗",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "cong" => "≅",
+        "propto" => "∝",
+        "ll" => "≪",
+        "gg" => "≫",
+        "in" => "∈",
+        "notin" => "∉",
+        "ni" => "∋",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "supseteq" => "⊇",
+        "to" | "rightarrow" => "→",
+        "leftarrow" | "gets" => "←",
+        "Rightarrow" => "⇒",
+        "Leftarrow" => "⇐",
+        "leftrightarrow" => "↔",
+        "Leftrightarrow" => "⇔",
+        "iff" => "⟺",
+        "implies" => "⟹",
+        "mapsto" => "↦",
+        "uparrow" => "↑",
+        "downarrow" => "↓",
+        "perp" => "⊥",
+        "parallel" => "∥",
+        "mid" => "∣",
+        "forall" => "∀",
+        "exists" => "∃",
+        "nexists" => "∄",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "prime" => "′",
+        "angle" => "∠",
+        "triangle" => "△",
+        "degree" => "°",
+        "colon" => ":",
+        _ => return None,
+    })
+}
+
+/// Big operators, which take limits in display mode.
+fn big_operator(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "sum" => "∑",
+        "prod" => "∏",
+        "coprod" => "∐",
+        "int" => "∫",
+        "iint" => "∬",
+        "iiint" => "∭",
+        "oint" => "∮",
+        "bigcup" => "⋃",
+        "bigcap" => "⋂",
+        "bigoplus" => "⨁",
+        "bigotimes" => "⨂",
+        "bigvee" => "⋁",
+        "bigwedge" => "⋀",
+        _ => return None,
+    })
+}
+
+/// Delimiters.
+fn delimiter(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "{" | "lbrace" => "{",
+        "}" | "rbrace" => "}",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lvert" | "rvert" | "vert" => "|",
+        "lVert" | "rVert" | "Vert" | "|" => "‖",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lbrack" => "[",
+        "rbrack" => "]",
+        _ => return None,
+    })
+}
+
+/// Spacing, as a width in `em`.
+fn space(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "," | "thinspace" => "0.1667em",
+        ":" | ">" | "medspace" => "0.2222em",
+        ";" | "thickspace" => "0.2778em",
+        "!" | "negthinspace" => "-0.1667em",
+        " " => "0.3333em",
+        "quad" => "1em",
+        "qquad" => "2em",
+        _ => return None,
+    })
+}
+
+/// Accents.
+fn accent(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "hat" | "widehat" => "^",
+        "bar" => "¯",
+        "vec" => "→",
+        "dot" => "˙",
+        "ddot" => "¨",
+        "tilde" | "widetilde" => "~",
+        "acute" => "´",
+        "grave" => "`",
+        "breve" => "˘",
+        "check" => "ˇ",
+        _ => return None,
+    })
+}
+
+/// Fonts, as `mathvariant`s.
+fn font(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "mathrm" | "rm" => "normal",
+        "mathbf" | "bf" | "boldsymbol" => "bold",
+        "mathit" | "it" => "italic",
+        "mathbb" => "double-struck",
+        "mathcal" => "script",
+        "mathfrak" => "fraktur",
+        "mathsf" => "sans-serif",
+        "mathtt" => "monospace",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Get the MathML inside the `<semantics><mrow>`.
+    fn inner(value: &str) -> String {
+        let result = to_mathml(value, false);
+        let start = "<math><semantics><mrow>".len();
+        let end = result.find("</mrow><annotation").unwrap();
+        result[start..end].into()
+    }
+
+    #[test]
+    fn test_to_mathml() {
+        assert_eq!(
+            to_mathml("x", false),
+            "<math><semantics><mrow><mi>x</mi></mrow><annotation encoding=\"application/x-tex\">x</annotation></semantics></math>",
+            "should support inline math"
+        );
+
+        assert_eq!(
+            to_mathml("x", true),
+            "<math display=\"block\"><semantics><mrow><mi>x</mi></mrow><annotation encoding=\"application/x-tex\">x</annotation></semantics></math>",
+            "should support display math"
+        );
+
+        assert_eq!(
+            inner("a < b"),
+            "<mi>a</mi><mo>&lt;</mo><mi>b</mi>",
+            "should encode operators"
+        );
+
+        assert_eq!(
+            inner("12.5x - 3"),
+            "<mn>12.5</mn><mi>x</mi><mo>−</mo><mn>3</mn>",
+            "should support numbers"
+        );
+
+        assert_eq!(
+            inner("x_i^2"),
+            "<msubsup><mi>x</mi><mi>i</mi><mn>2</mn></msubsup>",
+            "should support sub- and superscripts"
+        );
+
+        assert_eq!(
+            inner("e^{i\\pi}"),
+            "<msup><mi>e</mi><mrow><mi>i</mi><mi>π</mi></mrow></msup>",
+            "should support groups"
+        );
+
+        assert_eq!(
+            inner("\\frac{1}{2}"),
+            "<mfrac><mrow><mn>1</mn></mrow><mrow><mn>2</mn></mrow></mfrac>",
+            "should support fractions"
+        );
+
+        assert_eq!(
+            inner("\\sqrt[3]{x}"),
+            "<mroot><mrow><mi>x</mi></mrow><mrow><mn>3</mn></mrow></mroot>",
+            "should support roots"
+        );
+
+        assert_eq!(
+            inner("\\sum_{i=0}^n i"),
+            "<msubsup><mo>∑</mo><mrow><mi>i</mi><mo>=</mo><mn>0</mn></mrow><mi>n</mi></msubsup><mi>i</mi>",
+            "should support big operators in inline math"
+        );
+
+        assert_eq!(
+            to_mathml("\\sum_{i=0}^n i", true),
+            "<math display=\"block\"><semantics><mrow><munderover><mo>∑</mo><mrow><mi>i</mi><mo>=</mo><mn>0</mn></mrow><mi>n</mi></munderover><mi>i</mi></mrow><annotation encoding=\"application/x-tex\">\\sum_{i=0}^n i</annotation></semantics></math>",
+            "should support big operators with limits in display math"
+        );
+
+        assert_eq!(
+            inner("\\text{if } x"),
+            "<mtext>if </mtext><mi>x</mi>",
+            "should support text"
+        );
+
+        assert_eq!(
+            inner("\\mathbf{v}"),
+            "<mrow><mi mathvariant=\"bold\">v</mi></mrow>",
+            "should support fonts"
+        );
+
+        assert_eq!(
+            inner("\\left( x \\right)"),
+            "<mrow><mo fence=\"true\" stretchy=\"true\">(</mo><mi>x</mi><mo fence=\"true\" stretchy=\"true\">)</mo></mrow>",
+            "should support fences"
+        );
+
+        assert_eq!(
+            inner("\\begin{matrix}a & b \\\\ c & d\\end{matrix}"),
+            "<mtable><mtr><mtd><mrow><mi>a</mi></mrow></mtd><mtd><mrow><mi>b</mi></mrow></mtd></mtr><mtr><mtd><mrow><mi>c</mi></mrow></mtd><mtd><mrow><mi>d</mi></mrow></mtd></mtr></mtable>",
+            "should support matrices"
+        );
+
+        assert_eq!(
+            inner("\\unknown{x}"),
+            "<mtext>\\unknown</mtext><mrow><mi>x</mi></mrow>",
+            "should show unknown commands as text"
+        );
+
+        assert_eq!(
+            inner("{a"),
+            "<mrow><mi>a</mi></mrow>",
+            "should support unclosed groups"
+        );
+
+        assert_eq!(
+            inner("a}"),
+            "<mi>a</mi><mo>}</mo>",
+            "should support stray braces"
+        );
+
+        assert_eq!(
+            inner(&format!("{}a{}", "{".repeat(10_000), "}".repeat(10_000))),
+            format!(
+                "<mtext>{}a{}</mtext>",
+                "{".repeat(10_000),
+                "}".repeat(10_000)
+            ),
+            "should show deeply nested groups as text"
+        );
+
+        assert_eq!(
+            inner(&"x^".repeat(10_000)),
+            format!("<mtext>{}</mtext>", "x^".repeat(10_000)),
+            "should show deeply nested scripts as text"
+        );
+
+        assert_eq!(
+            inner(&format!("x{}", "'".repeat(10_000))),
+            format!("<mtext>x{}</mtext>", "\'".repeat(10_000)),
+            "should show deeply nested primes as text"
+        );
+
+        assert_eq!(
+            inner(&"\\sqrt".repeat(10_000)),
+            format!("<mtext>{}</mtext>", "\\sqrt".repeat(10_000)),
+            "should show deeply nested arguments as text"
+        );
+
+        assert_eq!(
+            inner(&format!("{}a{}", "{".repeat(32), "}".repeat(32))),
+            format!("{}<mi>a</mi>{}", "<mrow>".repeat(32), "</mrow>".repeat(32)),
+            "should support somewhat nested groups"
+        );
+    }
+}
@@ -6,13 +6,17 @@ pub mod constant;
 pub mod edit_map;
 pub mod encode;
 pub mod gfm_tagfilter;
+pub mod html_handler;
 pub mod identifier;
 pub mod infer;
 pub mod line_ending;
 pub mod location;
+#[cfg(feature = "mathml")]
+pub mod mathml;
 pub mod mdx;
 pub mod mdx_collect;
 pub mod normalize_identifier;
+pub mod sanitize_html;
 pub mod sanitize_uri;
 pub mod skip;
 pub mod slice;
@@ -0,0 +1,631 @@
+//! Make raw HTML safe.
+
+use crate::util::{
+    character_reference::parse, constant::HTML_VOID_NAMES, encode::encode,
+    sanitize_uri::sanitize_with_protocols,
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// Schema that defines which HTML is allowed when sanitizing.
+///
+/// Can be passed as `html_sanitize` in
+/// [`CompileOptions`][crate::configuration::CompileOptions].
+///
+/// The default is the schema GitHub uses (as described by
+/// [`hast-util-sanitize`][hast-util-sanitize]): basic formatting elements,
+/// without `class` or `style` attributes, links to `http`, `https`,
+/// `mailto`, `xmpp`, `irc`, and `ircs`, and clobbered `id` and `name`
+/// attributes.
+///
+/// All names are lowercase.
+///
+/// [hast-util-sanitize]: https://github.com/syntax-tree/hast-util-sanitize
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SanitizeSchema {
+    /// Names of allowed elements.
+    ///
+    /// Tags of other elements are dropped, but what is in them is kept.
+    pub tag_names: Vec<String>,
+    /// Names of allowed attributes, per element name.
+    ///
+    /// Attributes allowed on all elements use `*` as the element name.
+    pub attributes: Vec<(String, Vec<String>)>,
+    /// Allowed protocols, per attribute name.
+    ///
+    /// Values of these attributes are dropped if they use other protocols.
+    /// Protocols are compared case-insensitively; protocols that include a
+    /// colon (such as `data:image/png`) are matched as a prefix, which has to
+    /// be followed by the end, `;`, or `,`.
+    pub protocols: Vec<(String, Vec<String>)>,
+    /// Names of elements that are dropped with what is in them.
+    ///
+    /// Only what is in the same HTML (flow) or HTML (text) is dropped.
+    pub strip: Vec<String>,
+    /// Names of attributes whose values are prefixed with `clobber_prefix`.
+    ///
+    /// This prevents user content from overwriting global variables
+    /// (DOM clobbering), and from colliding with `id`s on the page.
+    pub clobber: Vec<String>,
+    /// Prefix to use for `clobber` attributes.
+    pub clobber_prefix: String,
+}
+
+impl Default for SanitizeSchema {
+    /// GitHub.
+    fn default() -> Self {
+        Self {
+            tag_names: to_strings(&[
+                "a",
+                "b",
+                "blockquote",
+                "br",
+                "code",
+                "dd",
+                "del",
+                "details",
+                "div",
+                "dl",
+                "dt",
+                "em",
+                "h1",
+                "h2",
+                "h3",
+                "h4",
+                "h5",
+                "h6",
+                "hr",
+                "i",
+                "img",
+                "ins",
+                "kbd",
+                "li",
+                "ol",
+                "p",
+                "picture",
+                "pre",
+                "q",
+                "rp",
+                "rt",
+                "ruby",
+                "s",
+                "samp",
+                "section",
+                "source",
+                "span",
+                "strike",
+                "strong",
+                "sub",
+                "summary",
+                "sup",
+                "table",
+                "tbody",
+                "td",
+                "tfoot",
+                "th",
+                "thead",
+                "tr",
+                "tt",
+                "ul",
+                "var",
+            ]),
+            attributes: vec![
+                ("a".into(), to_strings(&["href"])),
+                ("blockquote".into(), to_strings(&["cite"])),
+                ("del".into(), to_strings(&["cite"])),
+                ("img".into(), to_strings(&["src", "longdesc"])),
+                ("ins".into(), to_strings(&["cite"])),
+                ("q".into(), to_strings(&["cite"])),
+                ("source".into(), to_strings(&["srcset"])),
+                (
+                    "*".into(),
+                    to_strings(&[
+                        "abbr",
+                        "accept",
+                        "accept-charset",
+                        "accesskey",
+                        "action",
+                        "align",
+                        "alt",
+                        "axis",
+                        "border",
+                        "cellpadding",
+                        "cellspacing",
+                        "char",
+                        "charoff",
+                        "charset",
+                        "checked",
+                        "clear",
+                        "color",
+                        "cols",
+                        "colspan",
+                        "compact",
+                        "coords",
+                        "datetime",
+                        "dir",
+                        "enctype",
+                        "frame",
+                        "headers",
+                        "height",
+                        "hreflang",
+                        "hspace",
+                        "id",
+                        "ismap",
+                        "itemprop",
+                        "label",
+                        "lang",
+                        "maxlength",
+                        "media",
+                        "method",
+                        "multiple",
+                        "name",
+                        "nohref",
+                        "noshade",
+                        "nowrap",
+                        "open",
+                        "prompt",
+                        "readonly",
+                        "rev",
+                        "rows",
+                        "rowspan",
+                        "rules",
+                        "scope",
+                        "selected",
+                        "shape",
+                        "size",
+                        "span",
+                        "start",
+                        "summary",
+                        "tabindex",
+                        "title",
+                        "valign",
+                        "value",
+                        "width",
+                    ]),
+                ),
+            ],
+            protocols: vec![
+                (
+                    "href".into(),
+                    to_strings(&["http", "https", "mailto", "xmpp", "irc", "ircs"]),
+                ),
+                ("cite".into(), to_strings(&["http", "https"])),
+                ("longdesc".into(), to_strings(&["http", "https"])),
+                ("src".into(), to_strings(&["http", "https"])),
+            ],
+            strip: to_strings(&["script", "style"]),
+            clobber: to_strings(&["id", "name"]),
+            clobber_prefix: "user-content-".into(),
+        }
+    }
+}
+
+/// Sanitizer of raw HTML, which tracks open elements across HTML (flow) and
+/// HTML (text).
+///
+/// Elements are closed when the scope they were opened in is exited (such
+/// as a paragraph), so that they don’t leak into the rest of the document.
+#[derive(Debug, Default)]
+pub struct Sanitizer {
+    /// Stack of open elements, where `None` marks the start of a scope.
+    stack: Vec<Option<String>>,
+}
+
+impl Sanitizer {
+    /// Enter a scope.
+    pub fn enter(&mut self) {
+        self.stack.push(None);
+    }
+
+    /// Exit a scope, returning the closing tags of elements that are still
+    /// open in it.
+    pub fn exit(&mut self) -> String {
+        let mut result = String::new();
+
+        while let Some(entry) = self.stack.pop() {
+            if let Some(name) = entry {
+                close(&mut result, &name);
+            } else {
+                break;
+            }
+        }
+
+        result
+    }
+
+    /// Close all scopes, returning the closing tags of elements that are
+    /// still open.
+    pub fn close(&mut self) -> String {
+        let mut result = String::new();
+
+        while let Some(entry) = self.stack.pop() {
+            if let Some(name) = entry {
+                close(&mut result, &name);
+            }
+        }
+
+        result
+    }
+
+    /// Sanitize raw HTML.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust ignore
+    /// use markdown::util::sanitize_html::{Sanitizer, SanitizeSchema};
+    ///
+    /// let mut sanitizer = Sanitizer::default();
+    /// let schema = SanitizeSchema::default();
+    /// assert_eq!(sanitizer.sanitize(&schema, "<b onclick=\"a\">"), "<b>");
+    /// assert_eq!(sanitizer.close(), "</b>");
+    /// ```
+    ///
+    /// ## References
+    ///
+    /// *   [`hast-util-sanitize`](https://github.com/syntax-tree/hast-util-sanitize)
+    pub fn sanitize(&mut self, schema: &SanitizeSchema, value: &str) -> String {
+        let bytes = value.as_bytes();
+        let len = bytes.len();
+        let mut result = String::with_capacity(len);
+        let mut index = 0;
+        let mut start = 0;
+
+        while index < len {
+            if bytes[index] != b'<' {
+                index += 1;
+                continue;
+            }
+
+            result.push_str(&value[start..index]);
+
+            if let Some(end) = other(bytes, index) {
+                // Drop comments, declarations, instructions, and CDATA.
+                index = end;
+            } else if let Some(tag) = tag(value, index) {
+                index = tag.end;
+
+                if tag.close {
+                    self.close_tag(schema, &mut result, &tag.name);
+                } else if includes(&schema.strip, &tag.name) {
+                    index = strip(value, index, &tag.name);
+                } else if includes(&schema.tag_names, &tag.name) {
+                    open(schema, &mut result, &tag);
+
+                    if !HTML_VOID_NAMES.contains(&tag.name.as_str()) {
+                        self.stack.push(Some(tag.name));
+                    }
+                }
+            } else {
+                result.push_str("&lt;");
+                index += 1;
+            }
+
+            start = index;
+        }
+
+        result.push_str(&value[start..]);
+        result
+    }
+
+    /// Close the element `name`, and the elements opened in it, if it is
+    /// open in the current scope.
+    fn close_tag(&mut self, schema: &SanitizeSchema, result: &mut String, name: &str) {
+        if !includes(&schema.tag_names, name) {
+            return;
+        }
+
+        let mut index = self.stack.len();
+
+        while index > 0 {
+            index -= 1;
+
+            match &self.stack[index] {
+                None => return,
+                Some(open) if open == name => {
+                    while self.stack.len() > index {
+                        let open = self.stack.pop().unwrap().unwrap();
+                        close(result, &open);
+                    }
+                    return;
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// Tag.
+#[derive(Debug)]
+struct Tag {
+    /// Lowercase tag name.
+    name: String,
+    /// Whether this is a closing tag.
+    close: bool,
+    /// Attributes (lowercase name, decoded value).
+    attributes: Vec<(String, Option<String>)>,
+    /// Index after the tag.
+    end: usize,
+}
+
+/// Add a sanitized opening tag to `result`.
+fn open(schema: &SanitizeSchema, result: &mut String, tag: &Tag) {
+    let mut seen: Vec<&str> = vec![];
+
+    result.push('<');
+    result.push_str(&tag.name);
+
+    for (name, value) in &tag.attributes {
+        if seen.contains(&name.as_str()) {
+            continue;
+        }
+
+        seen.push(name);
+
+        let allowed = schema.attributes.iter().any(|(tag_name, names)| {
+            (tag_name == &tag.name || tag_name == "*") && includes(names, name)
+        });
+
+        if !allowed {
+            continue;
+        }
+
+        let mut value = value.clone().unwrap_or_default();
+
+        if includes(&schema.clobber, name) {
+            value = format!("{}{}", schema.clobber_prefix, value);
+        }
+
+        let value = if let Some((_, protocols)) = schema.protocols.iter().find(|d| &d.0 == name) {
+            let url = sanitize_with_protocols(&value, protocols);
+
+            if url.is_empty() && !value.is_empty() {
+                continue;
+            }
+
+            url
+        } else {
+            encode(&value, true)
+        };
+
+        result.push(' ');
+        result.push_str(name);
+        result.push_str("=\"");
+        result.push_str(&value);
+        result.push('"');
+    }
+
+    if HTML_VOID_NAMES.contains(&tag.name.as_str()) {
+        result.push_str(" />");
+    } else {
+        result.push('>');
+    }
+}
+
+/// Add a closing tag to `result`.
+fn close(result: &mut String, name: &str) {
+    result.push_str("</");
+    result.push_str(name);
+    result.push('>');
+}
+
+/// Get the index after a comment, declaration, instruction, or CDATA at
+/// `index`.
+///
+/// Unclosed ones run to the end.
+fn other(bytes: &[u8], index: usize) -> Option<usize> {
+    let rest = &bytes[index..];
+
+    let close: &[u8] = if rest.starts_with(b"<!--") {
+        b"-->"
+    } else if rest.starts_with(b"<![CDATA[") {
+        b"]]>"
+    } else if rest.starts_with(b"<!") || rest.starts_with(b"<?") {
+        b">"
+    } else {
+        return None;
+    };
+
+    let mut end = index + 2;
+
+    while end < bytes.len() {
+        if bytes[end..].starts_with(close) {
+            return Some(end + close.len());
+        }
+
+        end += 1;
+    }
+
+    Some(bytes.len())
+}
+
+/// Parse a complete opening or closing tag at `index`.
+fn tag(value: &str, index: usize) -> Option<Tag> {
+    let bytes = value.as_bytes();
+    let len = bytes.len();
+    let mut index = index + 1;
+    let close = index < len && bytes[index] == b'/';
+
+    if close {
+        index += 1;
+    }
+
+    if index == len || !bytes[index].is_ascii_alphabetic() {
+        return None;
+    }
+
+    let name_start = index;
+
+    while index < len && (bytes[index].is_ascii_alphanumeric() || bytes[index] == b'-') {
+        index += 1;
+    }
+
+    let name = value[name_start..index].to_ascii_lowercase();
+    let mut attributes = vec![];
+
+    loop {
+        while index < len && (bytes[index].is_ascii_whitespace() || bytes[index] == b'/') {
+            index += 1;
+        }
+
+        if index == len {
+            return None;
+        }
+
+        if bytes[index] == b'>' {
+            return Some(Tag {
+                name,
+                close,
+                attributes,
+                end: index + 1,
+            });
+        }
+
+        let attribute_start = index;
+
+        while index < len
+            && !bytes[index].is_ascii_whitespace()
+            && !matches!(bytes[index], b'/' | b'>' | b'=')
+        {
+            index += 1;
+        }
+
+        // An attribute name starting with `=`.
+        if index == attribute_start {
+            index += 1;
+        }
+
+        let attribute_name = value[attribute_start..index].to_ascii_lowercase();
+        let mut end = index;
+
+        while end < len && bytes[end].is_ascii_whitespace() {
+            end += 1;
+        }
+
+        if end < len && bytes[end] == b'=' {
+            end += 1;
+
+            while end < len && bytes[end].is_ascii_whitespace() {
+                end += 1;
+            }
+
+            if end == len {
+                return None;
+            }
+
+            let quote = bytes[end];
+            let value_start;
+
+            if quote == b'"' || quote == b'\'' {
+                end += 1;
+                value_start = end;
+
+                while end < len && bytes[end] != quote {
+                    end += 1;
+                }
+
+                if end == len {
+                    return None;
+                }
+
+                index = end + 1;
+            } else {
+                value_start = end;
+
+                while end < len && !bytes[end].is_ascii_whitespace() && bytes[end] != b'>' {
+                    end += 1;
+                }
+
+                index = end;
+            }
+
+            attributes.push((attribute_name, Some(parse(&value[value_start..end], true))));
+        } else {
+            attributes.push((attribute_name, None));
+        }
+    }
+}
+
+/// Get the index after the closing tag of `name`, starting at `index`.
+///
+/// Without closing tag, everything is dropped.
+fn strip(value: &str, index: usize, name: &str) -> usize {
+    let bytes = value.as_bytes();
+    let mut index = index;
+
+    while index < bytes.len() {
+        if bytes[index] == b'<' {
+            if let Some(tag) = tag(value, index) {
+                if tag.close && tag.name == name {
+                    return tag.end;
+                }
+            }
+        }
+
+        index += 1;
+    }
+
+    index
+}
+
+/// Check whether `list` includes `value`.
+fn includes(list: &[String], value: &str) -> bool {
+    list.iter().any(|d| d == value)
+}
+
+/// Turn a list of strs into strings.
+fn to_strings(list: &[&str]) -> Vec<String> {
+    list.iter().map(ToString::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        let schema = SanitizeSchema::default();
+        let mut sanitizer = Sanitizer::default();
+
+        assert_eq!(
+            sanitizer.sanitize(&schema, "<b onclick=\"a\" title='&amp;\"'>"),
+            "<b title=\"&amp;&quot;\">",
+            "should drop unknown attributes, and encode values"
+        );
+        assert_eq!(
+            sanitizer.sanitize(&schema, "<a href=\"javascript:alert(1)\" id=a>"),
+            "<a id=\"user-content-a\">",
+            "should drop dangerous URLs, and clobber names"
+        );
+        assert_eq!(
+            sanitizer.sanitize(&schema, "</b>"),
+            "</a></b>",
+            "should close elements opened in the closed element"
+        );
+        assert_eq!(
+            sanitizer.sanitize(&schema, "<iframe>a</iframe><script>b</script>c"),
+            "ac",
+            "should drop unknown elements, and strip some"
+        );
+        assert_eq!(
+            sanitizer.sanitize(&schema, "<!--a--><br>< b <c"),
+            "<br />&lt; b &lt;c",
+            "should drop comments, and encode incomplete tags"
+        );
+
+        sanitizer.enter();
+        assert_eq!(sanitizer.sanitize(&schema, "<i><s>"), "<i><s>");
+        sanitizer.enter();
+        assert_eq!(
+            sanitizer.sanitize(&schema, "</i>"),
+            "",
+            "should not close elements outside the current scope"
+        );
+        assert_eq!(sanitizer.exit(), "", "should exit a scope");
+        assert_eq!(sanitizer.exit(), "</s></i>", "should close on exit");
+        assert_eq!(sanitizer.close(), "", "should close everything");
+    }
+}
@@ -30,14 +30,20 @@ pub fn sanitize(value: &str) -> String {
 /// Make a value safe for injection as a URL, and check protocols.
 ///
 /// This first uses [`sanitize`][sanitize].
-/// Then, a vec of (lowercase) allowed protocols can be given, in which case
-/// the URL is ignored or kept.
+/// Then, a vec of allowed protocols can be given, in which case the URL is
+/// ignored or kept.
+/// Protocols are compared case-insensitively.
 ///
 /// For example, `&["http", "https", "irc", "ircs", "mailto", "xmpp"]`
 /// can be used for `a[href]`, or `&["http", "https"]` for `img[src]`.
 /// If the URL includes an unknown protocol (one not matched by `protocol`, such
 /// as a dangerous example, `javascript:`), the value is ignored.
 ///
+/// Protocols that include a colon are matched as a prefix instead, which
+/// has to be followed by the end, `;`, or `,`.
+/// For example, `data:image/png` allows `data:image/png;base64,…`, but not
+/// `data:text/html,…`.
+///
 /// ## Examples
 ///
 /// ```rust ignore
@@ -45,13 +51,16 @@ pub fn sanitize(value: &str) -> String {
 ///
 /// assert_eq!(sanitize_with_protocols("javascript:alert(1)", &["http", "https"]), "");
 /// assert_eq!(sanitize_with_protocols("https://example.com", &["http", "https"]), "https://example.com");
+/// assert_eq!(sanitize_with_protocols("HTTPS://example.com", &["Http", "Https"]), "HTTPS://example.com");
 /// assert_eq!(sanitize_with_protocols("https://a👍b.c/%20/%", &["http", "https"]), "https://a%F0%9F%91%8Db.c/%20/%25");
+/// assert_eq!(sanitize_with_protocols("data:image/png;base64,a", &["data:image/png"]), "data:image/png;base64,a");
+/// assert_eq!(sanitize_with_protocols("data:text/html,a", &["data:image/png"]), "");
 /// ```
 ///
 /// ## References
 ///
 /// *   [`micromark-util-sanitize-uri` in `micromark`](https://github.com/micromark/micromark/tree/main/packages/micromark-util-sanitize-uri)
-pub fn sanitize_with_protocols(value: &str, protocols: &[&str]) -> String {
+pub fn sanitize_with_protocols<S: AsRef<str>>(value: &str, protocols: &[S]) -> String {
     let value = sanitize(value);
 
     let end = value.find(|c| matches!(c, '?' | '#' | '/'));
@@ -70,7 +79,31 @@ pub fn sanitize_with_protocols(value: &str, protocols: &[&str]) -> String {
     if let Some(colon) = colon {
         // If it is a protocol, it should be allowed.
         let protocol = value[0..colon].to_lowercase();
-        if !protocols.contains(&protocol.as_str()) {
+        let mut index = 0;
+
+        while index < protocols.len() {
+            let allowed = protocols[index].as_ref();
+
+            if allowed.contains(':') {
+                // A prefix, such as `data:image/png`.
+                if value.len() >= allowed.len()
+                    && value.is_char_boundary(allowed.len())
+                    && value[0..allowed.len()].eq_ignore_ascii_case(allowed)
+                    && matches!(
+                        value.as_bytes().get(allowed.len()),
+                        None | Some(b';' | b',')
+                    )
+                {
+                    break;
+                }
+            } else if allowed.to_lowercase() == protocol {
+                break;
+            }
+
+            index += 1;
+        }
+
+        if index == protocols.len() {
             return String::new();
         }
     }
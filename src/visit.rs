@@ -0,0 +1,286 @@
+//! Walk syntax trees.
+//!
+//! [`visit()`][] walks a tree depth-first, calling a [`Visitor`][] when
+//! entering and exiting each node, with the ancestors of that node.
+//! [`visit_mut()`][] does the same with a [`VisitorMut`][], which can change,
+//! replace, or remove nodes as it goes.
+//! What happens next is controlled by returning an [`Action`][] (or an
+//! [`ActionMut`][]) from the visitor.
+//!
+//! This is modelled after
+//! [`unist-util-visit-parents`](https://github.com/syntax-tree/unist-util-visit-parents).
+//!
+//! ## Examples
+//!
+//! ```
+//! use markdown::{mdast::Node, to_mdast, visit::{visit, Action}};
+//! # fn main() -> Result<(), String> {
+//! let tree = to_mdast("# a *b*\n\nc *d*", &Default::default())?;
+//! let mut found = vec![];
+//!
+//! visit(&tree, &mut |node: &Node, ancestors: &[&Node], _: Option<usize>| {
+//!     if let (Node::Emphasis(_), Some(Node::Heading(_))) = (node, ancestors.last()) {
+//!         found.push(node.to_string());
+//!     }
+//!     Action::Continue
+//! });
+//!
+//! assert_eq!(found, vec!["b"]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::mdast::Node;
+use alloc::vec::Vec;
+
+/// What to do after visiting a node.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Action {
+    /// Go on: visit the children of the node (when entering), then its
+    /// following siblings.
+    Continue,
+    /// Do not visit the children of the node.
+    ///
+    /// The node is still exited.
+    /// When exiting, this is the same as `Continue`.
+    Skip,
+    /// Stop visiting.
+    Exit,
+}
+
+/// What to do after visiting a node, when it can be changed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ActionMut {
+    /// Go on: visit the children of the node (when entering), then its
+    /// following siblings.
+    Continue,
+    /// Do not visit the children of the node.
+    ///
+    /// The node is still exited.
+    /// When exiting, this is the same as `Continue`.
+    Skip,
+    /// Stop visiting.
+    Exit,
+    /// Remove the node from its parent, and go on with its next sibling.
+    ///
+    /// The node is not exited.
+    /// Ignored for the root.
+    Remove,
+    /// Replace the node with another node.
+    ///
+    /// When entering, the children of the new node are visited next, and the
+    /// new node is exited.
+    /// When exiting, the new node is not visited.
+    Replace(Node),
+}
+
+/// Visitor for [`visit()`][].
+///
+/// Both methods get the node, its ancestors (the root first, its parent
+/// last), and its index in its parent (`None` for the root).
+/// They default to `Action::Continue`.
+///
+/// Closures that take the same arguments as `enter` are visitors too.
+pub trait Visitor {
+    /// Enter `node`, before its children.
+    fn enter(&mut self, _node: &Node, _ancestors: &[&Node], _index: Option<usize>) -> Action {
+        Action::Continue
+    }
+
+    /// Exit `node`, after its children.
+    fn exit(&mut self, _node: &Node, _ancestors: &[&Node], _index: Option<usize>) -> Action {
+        Action::Continue
+    }
+}
+
+impl<F> Visitor for F
+where
+    F: FnMut(&Node, &[&Node], Option<usize>) -> Action,
+{
+    fn enter(&mut self, node: &Node, ancestors: &[&Node], index: Option<usize>) -> Action {
+        self(node, ancestors, index)
+    }
+}
+
+/// Visitor for [`visit_mut()`][].
+///
+/// Ancestors cannot be borrowed while a node is changed, so instead of them
+/// both methods get the path to the node: the index of each ancestor in its
+/// parent, and then the index of the node in its parent (so it is empty for
+/// the root).
+/// They default to `ActionMut::Continue`.
+///
+/// Closures that take the same arguments as `enter` are visitors too.
+pub trait VisitorMut {
+    /// Enter `node`, before its children.
+    fn enter(&mut self, _node: &mut Node, _path: &[usize]) -> ActionMut {
+        ActionMut::Continue
+    }
+
+    /// Exit `node`, after its children.
+    fn exit(&mut self, _node: &mut Node, _path: &[usize]) -> ActionMut {
+        ActionMut::Continue
+    }
+}
+
+impl<F> VisitorMut for F
+where
+    F: FnMut(&mut Node, &[usize]) -> ActionMut,
+{
+    fn enter(&mut self, node: &mut Node, path: &[usize]) -> ActionMut {
+        self(node, path)
+    }
+}
+
+/// What a parent does with a visited child.
+enum Flow {
+    /// Go to the next sibling.
+    Continue,
+    /// Remove the child.
+    Remove,
+    /// Stop.
+    Exit,
+}
+
+/// Walk `tree` depth-first with `visitor`.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{mdast::Node, to_mdast, visit::{visit, Action, Visitor}};
+/// # fn main() -> Result<(), String> {
+///
+/// /// Get the text of the first heading.
+/// struct Title(Option<String>);
+///
+/// impl Visitor for Title {
+///     fn enter(&mut self, node: &Node, _: &[&Node], _: Option<usize>) -> Action {
+///         if let Node::Heading(_) = node {
+///             self.0 = Some(node.to_string());
+///             Action::Exit
+///         } else {
+///             Action::Continue
+///         }
+///     }
+/// }
+///
+/// let tree = to_mdast("> # a *b*\n\n# c", &Default::default())?;
+/// let mut title = Title(None);
+/// visit(&tree, &mut title);
+///
+/// assert_eq!(title.0, Some("a b".into()));
+/// # Ok(())
+/// # }
+/// ```
+pub fn visit<V: Visitor + ?Sized>(tree: &Node, visitor: &mut V) {
+    let mut ancestors = Vec::new();
+    one(tree, &mut ancestors, None, visitor);
+}
+
+/// Walk `tree` depth-first with `visitor`, which can change it.
+///
+/// ## Examples
+///
+/// ```
+/// use markdown::{mdast::Node, to_markdown, to_mdast, visit::{visit_mut, ActionMut}};
+/// # fn main() -> Result<(), String> {
+/// let mut tree = to_mdast("a *b* <c> d", &Default::default())?;
+///
+/// visit_mut(&mut tree, &mut |node: &mut Node, _: &[usize]| match node {
+///     Node::Html(_) => ActionMut::Remove,
+///     Node::Emphasis(x) => ActionMut::Replace(Node::Strong(markdown::mdast::Strong {
+///         children: x.children.clone(),
+///         position: None,
+///     })),
+///     _ => ActionMut::Continue,
+/// });
+///
+/// assert_eq!(to_markdown(&tree), "a **b**  d\n");
+/// # Ok(())
+/// # }
+/// ```
+pub fn visit_mut<V: VisitorMut + ?Sized>(tree: &mut Node, visitor: &mut V) {
+    let mut path = Vec::new();
+    one_mut(tree, &mut path, visitor);
+}
+
+/// Visit a node and its descendants.
+///
+/// Returns whether to stop.
+fn one<'a, V: Visitor + ?Sized>(
+    node: &'a Node,
+    ancestors: &mut Vec<&'a Node>,
+    index: Option<usize>,
+    visitor: &mut V,
+) -> bool {
+    match visitor.enter(node, ancestors, index) {
+        Action::Continue => {
+            if let Some(children) = node.children() {
+                ancestors.push(node);
+                let mut child = 0;
+
+                while child < children.len() {
+                    if one(&children[child], ancestors, Some(child), visitor) {
+                        return true;
+                    }
+
+                    child += 1;
+                }
+
+                ancestors.pop();
+            }
+        }
+        Action::Skip => {}
+        Action::Exit => return true,
+    }
+
+    visitor.exit(node, ancestors, index) == Action::Exit
+}
+
+/// Visit a node and its descendants, which can be changed.
+fn one_mut<V: VisitorMut + ?Sized>(
+    node: &mut Node,
+    path: &mut Vec<usize>,
+    visitor: &mut V,
+) -> Flow {
+    let descend = match visitor.enter(node, path) {
+        ActionMut::Continue => true,
+        ActionMut::Skip => false,
+        ActionMut::Exit => return Flow::Exit,
+        ActionMut::Remove => return Flow::Remove,
+        ActionMut::Replace(replacement) => {
+            *node = replacement;
+            true
+        }
+    };
+
+    if descend {
+        if let Some(children) = node.children_mut() {
+            let mut index = 0;
+
+            while index < children.len() {
+                path.push(index);
+                let flow = one_mut(&mut children[index], path, visitor);
+                path.pop();
+
+                match flow {
+                    Flow::Continue => index += 1,
+                    Flow::Remove => {
+                        children.remove(index);
+                    }
+                    Flow::Exit => return Flow::Exit,
+                }
+            }
+        }
+    }
+
+    match visitor.exit(node, path) {
+        ActionMut::Continue | ActionMut::Skip => Flow::Continue,
+        ActionMut::Exit => Flow::Exit,
+        ActionMut::Remove => Flow::Remove,
+        ActionMut::Replace(replacement) => {
+            *node = replacement;
+            Flow::Continue
+        }
+    }
+}
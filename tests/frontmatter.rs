@@ -121,6 +121,12 @@ fn frontmatter() -> Result<(), String> {
         "should not support a missing closing fence"
     );
 
+    assert_eq!(
+        to_html_with_options("---\na: b\n\nc\n", &frontmatter)?,
+        "<hr />\n<p>a: b</p>\n<p>c</p>\n",
+        "should not support a missing closing fence (multiple lines)"
+    );
+
     assert_eq!(
         to_html_with_options("---\na\n\nb\n \t\nc\n---", &frontmatter)?,
         "",
@@ -67,6 +67,12 @@ fn fuzz() -> Result<(), String> {
         "6-b: container close after unclosed fenced code, with eol (list, GH-16)"
     );
 
+    assert_eq!(
+        to_html("- ```\n- a\n\n1. b"),
+        "<ul>\n<li>\n<pre><code>\n</code></pre>\n</li>\n<li>a</li>\n</ul>\n<ol>\n<li>b</li>\n</ol>",
+        "6-c: list items after unclosed fenced code, with eol"
+    );
+
     assert_eq!(
         to_html_with_options("> x\n``", &Options::gfm()),
         Ok("<blockquote>\n<p>x</p>\n</blockquote>\n<p>``</p>".into()),
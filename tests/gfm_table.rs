@@ -170,6 +170,12 @@ fn gfm_table() -> Result<(), String> {
         "should not support a piercing delimiter row"
     );
 
+    assert_eq!(
+        to_html_with_options("a|\n- *b*\n\n| c |\n| - |", &Options::gfm())?,
+        "<p>a|</p>\n<ul>\n<li><em>b</em></li>\n</ul>\n<table>\n<thead>\n<tr>\n<th>c</th>\n</tr>\n</thead>\n</table>",
+        "should support a table after a piercing delimiter row"
+    );
+
     assert_eq!(
         to_html_with_options("> a\n> | b |\n|-", &Options::gfm())?,
         "<blockquote>\n<p>a\n| b |\n|-</p>\n</blockquote>",
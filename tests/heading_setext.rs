@@ -261,6 +261,12 @@ fn heading_setext() -> Result<(), String> {
         "should not support lazyness (1)"
     );
 
+    assert_eq!(
+        to_html("a\n=\n=\nb\n---"),
+        "<h1>a</h1>\n<h2>=\nb</h2>",
+        "should support an underline moved into a paragraph, which is then a setext heading"
+    );
+
     assert_eq!(
         to_html("> a\n==="),
         "<blockquote>\n<p>a\n===</p>\n</blockquote>",
@@ -389,6 +389,12 @@ fn link_reference() -> Result<(), String> {
         "should not fail on a missing colon in a definition"
     );
 
+    assert_eq!(
+        to_html("[a b]: c\n\n[ab] [a\tb] [ a  b ]"),
+        "<p>[ab] <a href=\"c\">a\tb</a> <a href=\"c\"> a  b </a></p>",
+        "should keep whitespace between words in identifiers"
+    );
+
     assert_eq!(
         to_html_with_options(
             "[x]()",
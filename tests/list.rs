@@ -210,6 +210,12 @@ fn list() -> Result<(), String> {
         "should not support empty items to interrupt paragraphs"
     );
 
+    assert_eq!(
+        to_html("- a\n\nb\n-\nc"),
+        "<ul>\n<li>a</li>\n</ul>\n<h2>b</h2>\n<p>c</p>",
+        "should not support empty items to interrupt paragraphs after a closed list"
+    );
+
     assert_eq!(
         to_html(
             " 1.  A paragraph\n     with two lines.\n\n         indented code\n\n     > A block quote."),
@@ -467,6 +473,12 @@ fn list() -> Result<(), String> {
         "should support tight lists w/ a sublist"
     );
 
+    assert_eq!(
+        to_html("- +\n- a\n- > *\n- b"),
+        "<ul>\n<li>\n<ul>\n<li></li>\n</ul>\n</li>\n<li>a</li>\n<li>\n<blockquote>\n<ul>\n<li></li>\n</ul>\n</blockquote>\n</li>\n<li>b</li>\n</ul>",
+        "should support tight lists w/ empty sublists"
+    );
+
     assert_eq!(
         to_html("1. ```\n   foo\n   ```\n\n   bar"),
         "<ol>\n<li>\n<pre><code>foo\n</code></pre>\n<p>bar</p>\n</li>\n</ol>",
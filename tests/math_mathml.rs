@@ -0,0 +1,100 @@
+#![cfg(feature = "mathml")]
+
+use markdown::{to_html_with_options, CompileOptions, Constructs, Options, ParseOptions};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn math_mathml() -> Result<(), String> {
+    let math = Options {
+        parse: ParseOptions {
+            constructs: Constructs {
+                math_text: true,
+                math_flow: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        compile: CompileOptions {
+            math_mathml: true,
+            ..Default::default()
+        },
+    };
+
+    assert_eq!(
+        to_html_with_options("$a$", &math)?,
+        "<p><math><semantics><mrow><mi>a</mi></mrow><annotation encoding=\"application/x-tex\">a</annotation></semantics></math></p>",
+        "should support math (text) as MathML"
+    );
+
+    assert_eq!(
+        to_html_with_options("$$\na\n$$", &math)?,
+        "<math display=\"block\"><semantics><mrow><mi>a</mi></mrow><annotation encoding=\"application/x-tex\">a</annotation></semantics></math>",
+        "should support math (flow) as MathML"
+    );
+
+    assert_eq!(
+        to_html_with_options("$$\na\nb\n$$\n\nc", &math)?,
+        "<math display=\"block\"><semantics><mrow><mi>a</mi><mi>b</mi></mrow><annotation encoding=\"application/x-tex\">a\nb</annotation></semantics></math>\n<p>c</p>",
+        "should support multiline math (flow) as MathML"
+    );
+
+    assert_eq!(
+        to_html_with_options("$$\n$$", &math)?,
+        "<math display=\"block\"><semantics><mrow></mrow><annotation encoding=\"application/x-tex\"></annotation></semantics></math>",
+        "should support empty math (flow) as MathML"
+    );
+
+    assert_eq!(
+        to_html_with_options("$$\na", &math)?,
+        "<math display=\"block\"><semantics><mrow><mi>a</mi></mrow><annotation encoding=\"application/x-tex\">a</annotation></semantics></math>\n",
+        "should support unclosed math (flow) as MathML"
+    );
+
+    assert_eq!(
+        to_html_with_options("> $$\n> a\n\nb", &math)?,
+        "<blockquote>\n<math display=\"block\"><semantics><mrow><mi>a</mi></mrow><annotation encoding=\"application/x-tex\">a</annotation></semantics></math>\n</blockquote>\n<p>b</p>",
+        "should support math (flow) in containers as MathML"
+    );
+
+    assert_eq!(
+        to_html_with_options("$a < b \\& c$", &math)?,
+        "<p><math><semantics><mrow><mi>a</mi><mo>&lt;</mo><mi>b</mi><mo>&amp;</mo><mi>c</mi></mrow><annotation encoding=\"application/x-tex\">a &lt; b \\&amp; c</annotation></semantics></math></p>",
+        "should encode HTML in MathML"
+    );
+
+    assert_eq!(
+        to_html_with_options("$\\foo{x}$", &math)?,
+        "<p><math><semantics><mrow><mtext>\\foo</mtext><mrow><mi>x</mi></mrow></mrow><annotation encoding=\"application/x-tex\">\\foo{x}</annotation></semantics></math></p>",
+        "should show unsupported commands as text"
+    );
+
+    assert_eq!(
+        to_html_with_options("![$a$](b)", &math)?,
+        "<p><img src=\"b\" alt=\"a\" /></p>",
+        "should not use MathML in image alt"
+    );
+
+    assert_eq!(
+        to_html_with_options("```js\na\n```", &math)?,
+        "<pre><code class=\"language-js\">a\n</code></pre>",
+        "should not affect code (fenced)"
+    );
+
+    assert_eq!(
+        to_html_with_options("`a`", &math)?,
+        "<p><code>a</code></p>",
+        "should not affect code (text)"
+    );
+
+    let deep = "{x^".repeat(5000);
+    assert_eq!(
+        to_html_with_options(&format!("${}$", deep), &math)?,
+        format!(
+            "<p><math><semantics><mrow><mtext>{}</mtext></mrow><annotation encoding=\"application/x-tex\">{}</annotation></semantics></math></p>",
+            deep, deep
+        ),
+        "should show deeply nested math as text"
+    );
+
+    Ok(())
+}
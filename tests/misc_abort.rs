@@ -0,0 +1,59 @@
+use markdown::{to_html_with_options, to_mdast, Options, ParseOptions};
+use pretty_assertions::assert_eq;
+use std::{cell::Cell, rc::Rc};
+
+/// Get options that abort after `calls` calls to `should_abort`, and the
+/// number of calls.
+fn abort_after(calls: usize) -> (ParseOptions, Rc<Cell<usize>>) {
+    let count = Rc::new(Cell::new(0));
+    let inner = count.clone();
+    let options = ParseOptions {
+        should_abort: Some(Box::new(move || {
+            inner.set(inner.get() + 1);
+            inner.get() > calls
+        })),
+        ..ParseOptions::gfm()
+    };
+
+    (options, count)
+}
+
+#[test]
+fn abort() -> Result<(), String> {
+    let value = "> # a *b* [c](d)\n\n- e\n\n| f |\n| - |\n".repeat(50);
+
+    let (options, count) = abort_after(0);
+    assert_eq!(
+        to_mdast(&value, &options),
+        Err("1:1: Unexpected abort, expected `should_abort` to return `false` (aborted)".into()),
+        "should abort at the start"
+    );
+    assert_eq!(count.get(), 1, "should stop checking after aborting");
+
+    let (options, count) = abort_after(3);
+    assert_eq!(
+        to_html_with_options(
+            &value,
+            &Options {
+                parse: options,
+                ..Options::default()
+            }
+        ),
+        Err("53:2: Unexpected abort, expected `should_abort` to return `false` (aborted)".into()),
+        "should abort while parsing"
+    );
+    assert_eq!(count.get(), 4, "should check every so often");
+
+    let (options, count) = abort_after(usize::MAX);
+    to_mdast(&value, &options)?;
+    assert!(count.get() > 1, "should check while parsing");
+
+    let (options, _) = abort_after(0);
+    assert_eq!(
+        to_mdast("", &options),
+        Err("1:1: Unexpected abort, expected `should_abort` to return `false` (aborted)".into()),
+        "should abort empty documents"
+    );
+
+    Ok(())
+}
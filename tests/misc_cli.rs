@@ -0,0 +1,118 @@
+#![cfg(feature = "cli")]
+
+use pretty_assertions::assert_eq;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Run the `markdown` binary with `args`, passing `stdin`.
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_markdown"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("should spawn");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().expect("should run")
+}
+
+/// Run the `markdown` binary and get stdout.
+fn stdout(args: &[&str], stdin: &str) -> String {
+    let output = run(args, stdin);
+    assert!(output.status.success(), "should exit successfully");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn cli() {
+    assert_eq!(
+        stdout(&[], "# a *b*"),
+        "<h1>a <em>b</em></h1>",
+        "should turn stdin into HTML by default"
+    );
+
+    assert_eq!(
+        stdout(&["--gfm", "-"], "~a~ <b>"),
+        "<p><del>a</del> &lt;b&gt;</p>",
+        "should support `--gfm` and `-`"
+    );
+
+    assert_eq!(
+        stdout(&["--allow-dangerous-html"], "<b>\n"),
+        "<b>\n",
+        "should support `--allow-dangerous-html`"
+    );
+
+    assert_eq!(
+        stdout(&["--line-ending", "crlf"], "> a"),
+        "<blockquote>\r\n<p>a</p>\r\n</blockquote>",
+        "should support `--line-ending` for HTML when there are no line endings"
+    );
+
+    assert_eq!(
+        stdout(&["--allow-dangerous-protocol"], "<javascript:a>"),
+        "<p><a href=\"javascript:a\">javascript:a</a></p>",
+        "should support `--allow-dangerous-protocol`"
+    );
+
+    assert_eq!(
+        stdout(&["--math", "--frontmatter"], "---\na: b\n---\n$c$"),
+        "<p><code class=\"language-math math-inline\">c</code></p>",
+        "should support `--math` and `--frontmatter`"
+    );
+
+    assert_eq!(
+        stdout(&["--mdx"], "{a} <b>c</b>"),
+        "<p> c</p>",
+        "should support `--mdx`"
+    );
+
+    assert_eq!(
+        stdout(
+            &["-f", "markdown", "--gfm", "--line-ending", "crlf"],
+            "* a\n* b"
+        ),
+        "- a\r\n- b\r\n",
+        "should support `-f markdown`"
+    );
+
+    assert_eq!(
+        stdout(
+            &["-f", "markdown", "--line-ending", "crlf"],
+            "a\r\nb\r\n\r\n```\r\nc\rd\n```"
+        ),
+        "a\r\nb\r\n\r\n```\r\nc\r\nd\r\n```\r\n",
+        "should support `--line-ending` for markdown with line endings in the input"
+    );
+
+    let json = stdout(&["--format", "mdast"], "a");
+    assert!(
+        json.contains("\"value\": \"a\"") && json.contains("\"offset\": 1"),
+        "should support `--format mdast`"
+    );
+
+    let output = run(&["--format", "b"], "");
+    assert!(!output.status.success(), "should fail on unknown formats");
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "markdown: Unknown format `b`, expected `html`, `mdast`, or `markdown`\n",
+        "should explain unknown formats"
+    );
+
+    let output = run(&["--c"], "");
+    assert!(!output.status.success(), "should fail on unknown options");
+
+    let output = run(&["missing.md"], "");
+    assert!(!output.status.success(), "should fail on missing files");
+
+    assert!(
+        stdout(&["--help"], "").starts_with("Usage: markdown"),
+        "should support `--help`"
+    );
+}
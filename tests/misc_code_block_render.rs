@@ -0,0 +1,119 @@
+use markdown::{to_html_with_options, CompileOptions, Constructs, Options, ParseOptions};
+use pretty_assertions::assert_eq;
+use std::sync::Arc;
+
+#[test]
+fn code_block_render() -> Result<(), String> {
+    let debug = Options {
+        compile: CompileOptions {
+            code_block_render: Some(Arc::new(|lang, meta, code| {
+                Some(format!("[{:?} {:?} {:?}]", lang, meta, code))
+            })),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mermaid = Options {
+        compile: CompileOptions {
+            code_block_render: Some(Arc::new(|lang, _meta, code| {
+                if lang == Some("mermaid") {
+                    Some(format!("<svg>{}</svg>", code.len()))
+                } else {
+                    None
+                }
+            })),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    assert_eq!(
+        to_html_with_options("```js\na\n```", &debug)?,
+        "[Some(\"js\") None \"a\"]",
+        "should support a hook for code (fenced)"
+    );
+
+    assert_eq!(
+        to_html_with_options("```js title=\"b\"\na\n```", &debug)?,
+        "[Some(\"js\") Some(\"title=\\\"b\\\"\") \"a\"]",
+        "should pass meta to the hook"
+    );
+
+    assert_eq!(
+        to_html_with_options("```\na\n```", &debug)?,
+        "[None None \"a\"]",
+        "should pass no language if there is none"
+    );
+
+    assert_eq!(
+        to_html_with_options("```a&amp;b c\\&d\n<\n```", &debug)?,
+        "[Some(\"a&b\") Some(\"c&d\") \"<\"]",
+        "should pass decoded, unencoded values to the hook"
+    );
+
+    assert_eq!(
+        to_html_with_options("```\na\n\n  b\n\n```", &debug)?,
+        "[None None \"a\\n\\n  b\\n\"]",
+        "should pass the code without the final line ending to the hook"
+    );
+
+    assert_eq!(
+        to_html_with_options("```\r\na\r\nb\r\n```", &debug)?,
+        "[None None \"a\\r\\nb\"]",
+        "should support CR+LF line endings"
+    );
+
+    assert_eq!(
+        to_html_with_options("    a\n\n    b", &debug)?,
+        "[None None \"a\\n\\nb\"]",
+        "should support a hook for code (indented)"
+    );
+
+    assert_eq!(
+        to_html_with_options("> ```\n> a\n\nb", &debug)?,
+        "<blockquote>\n[None None \"a\"]\n</blockquote>\n<p>b</p>",
+        "should support a hook for code (fenced) in containers"
+    );
+
+    assert_eq!(
+        to_html_with_options("a\n```mermaid\nb\n```\nc", &mermaid)?,
+        "<p>a</p>\n<svg>1</svg>\n<p>c</p>",
+        "should use the result of the hook"
+    );
+
+    assert_eq!(
+        to_html_with_options("```js x\n<a>\n```", &mermaid)?,
+        "<pre><code class=\"language-js\">&lt;a&gt;\n</code></pre>",
+        "should use the default if the hook returns `None`"
+    );
+
+    assert_eq!(
+        to_html_with_options("```a&amp;b\n```", &mermaid)?,
+        "<pre><code class=\"language-a&amp;b\"></code></pre>",
+        "should encode the language in the default"
+    );
+
+    assert_eq!(
+        to_html_with_options(
+            "$$\na\n$$",
+            &Options {
+                parse: ParseOptions {
+                    constructs: Constructs {
+                        math_flow: true,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                compile: CompileOptions {
+                    code_block_render: Some(Arc::new(|_lang, _meta, _code| Some("x".into()))),
+                    ..Default::default()
+                }
+            }
+        )?,
+        "<pre><code class=\"language-math math-display\">a\n</code></pre>",
+        "should not use the hook for math (flow)"
+    );
+
+    Ok(())
+}
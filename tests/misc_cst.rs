@@ -0,0 +1,226 @@
+use markdown::{
+    cst::{
+        parse, CharacterEscape, CharacterReference, CodeFenced, Emphasis, HeadingAtx, ListItem,
+        Strong, SyntaxKind, SyntaxTree,
+    },
+    Constructs, ParseOptions,
+};
+use pretty_assertions::assert_eq;
+
+/// Check that the tokens of `tree` are the whole of `value`, and return
+/// the source of nodes of `kind`.
+fn texts<'a>(tree: &SyntaxTree, value: &'a str, kind: SyntaxKind) -> Vec<&'a str> {
+    let mut at = 0;
+
+    for token in tree.tokens() {
+        assert_eq!(token.start, at, "expected tokens to be adjacent");
+        assert!(token.end > token.start, "expected tokens to not be empty");
+        at = token.end;
+    }
+
+    assert_eq!(at, value.len(), "expected tokens to cover the document");
+
+    tree.find_all(kind).iter().map(|d| d.text(value)).collect()
+}
+
+#[test]
+fn cst_lossless() -> Result<(), String> {
+    let gfm = ParseOptions {
+        constructs: Constructs {
+            frontmatter: true,
+            math_flow: true,
+            math_text: true,
+            ..Constructs::gfm()
+        },
+        ..ParseOptions::gfm()
+    };
+
+    for value in [
+        "",
+        "a",
+        "---\nb: c\n---\n\n# d\n",
+        "- e\n\n\t- f\n  \t* g\n\n1)\th\n",
+        "> ```\n>\n>\n\ni",
+        "![^]()",
+        "| j | k |\n| :- | -: |\n| l | \\| |\n",
+        "m  \nn\\\no\r\np\r\n",
+        "$$\nq\n$$ `r` $s$ ~~t~~ <u> &v; w@x.y",
+        "[z]: <a> 'b'\n\n[c][z] [z]\n",
+        "\u{feff}ä\t😀\n",
+    ] {
+        let tree = parse(value, &gfm)?;
+        texts(&tree, value, SyntaxKind::Data);
+    }
+
+    let value = "<X>\n  {a}\n</X>\n";
+    let tree = parse(value, &ParseOptions::mdx())?;
+    assert_eq!(
+        texts(&tree, value, SyntaxKind::MdxJsxTagName),
+        vec!["X", "X"],
+        "should support MDX"
+    );
+
+    assert_eq!(
+        parse("a {b", &ParseOptions::mdx()).unwrap_err(),
+        "1:5: Unexpected end of file in expression, expected a corresponding closing brace for `{`",
+        "should error on invalid MDX"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn cst_kinds() -> Result<(), String> {
+    let value = "* a\n- b\n\n1. c\n\n## d ##\n\n```js x\ne\n```\n\n\\* &amp; *f* __g__";
+    let tree = parse(value, &Default::default())?;
+
+    assert_eq!(
+        texts(&tree, value, SyntaxKind::ListItemMarker),
+        vec!["*", "-", "."],
+        "should keep list item markers"
+    );
+    assert_eq!(
+        texts(&tree, value, SyntaxKind::HeadingAtxSequence),
+        vec!["##", "##"],
+        "should keep heading sequences"
+    );
+    assert_eq!(
+        texts(&tree, value, SyntaxKind::CodeFencedFenceInfo),
+        vec!["js"],
+        "should keep info strings"
+    );
+    assert_eq!(
+        texts(&tree, value, SyntaxKind::CharacterEscape),
+        vec!["\\*"],
+        "should keep character escapes"
+    );
+    assert_eq!(
+        texts(&tree, value, SyntaxKind::LineEnding).len(),
+        7,
+        "should keep line endings"
+    );
+    assert_eq!(
+        tree.children.iter().map(|d| d.kind).collect::<Vec<_>>(),
+        vec![
+            SyntaxKind::ListUnordered,
+            SyntaxKind::LineEnding,
+            SyntaxKind::ListUnordered,
+            SyntaxKind::BlankLineEnding,
+            SyntaxKind::ListOrdered,
+            SyntaxKind::BlankLineEnding,
+            SyntaxKind::HeadingAtx,
+            SyntaxKind::LineEnding,
+            SyntaxKind::BlankLineEnding,
+            SyntaxKind::CodeFenced,
+            SyntaxKind::LineEnding,
+            SyntaxKind::BlankLineEnding,
+            SyntaxKind::Paragraph
+        ],
+        "should have top-level nodes"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn cst_typed() -> Result<(), String> {
+    let value = "1. [x] a\n\n### b\n\n~~~js eval\nc\nd\n~~~\n\n\\* &amp; *e* __f__";
+    let tree = parse(value, &ParseOptions::gfm())?;
+    let text = |node: Option<&markdown::cst::SyntaxNode>| node.map(|d| d.text(value));
+
+    let item = ListItem::cast(tree.find(SyntaxKind::ListItem).unwrap()).unwrap();
+    assert_eq!(
+        text(item.marker()),
+        Some("."),
+        "should get list item markers"
+    );
+    assert_eq!(text(item.value()), Some("1"), "should get list item values");
+    assert_eq!(
+        text(item.prefix()),
+        Some("1. "),
+        "should get list item prefixes"
+    );
+    assert_eq!(
+        text(item.check()),
+        Some("[x]"),
+        "should get task list item checks"
+    );
+    assert_eq!(
+        ListItem::cast(tree.find(SyntaxKind::Paragraph).unwrap()),
+        None,
+        "should not cast other nodes"
+    );
+
+    let heading = HeadingAtx::cast(tree.find(SyntaxKind::HeadingAtx).unwrap()).unwrap();
+    assert_eq!(heading.depth(), 3, "should get heading ranks");
+    assert_eq!(text(heading.text()), Some("b"), "should get heading text");
+    assert_eq!(
+        text(heading.closing_sequence()),
+        None,
+        "should get missing closing sequences"
+    );
+
+    let code = CodeFenced::cast(tree.find(SyntaxKind::CodeFenced).unwrap()).unwrap();
+    assert_eq!(
+        text(code.sequence()),
+        Some("~~~"),
+        "should get fence sequences"
+    );
+    assert_eq!(text(code.info()), Some("js"), "should get info strings");
+    assert_eq!(text(code.meta()), Some("eval"), "should get meta strings");
+    assert_eq!(
+        code.chunks()
+            .iter()
+            .map(|d| d.text(value))
+            .collect::<Vec<_>>(),
+        vec!["c", "d"],
+        "should get lines of code"
+    );
+    assert_eq!(
+        text(code.closing_fence()),
+        Some("~~~"),
+        "should get closing fences"
+    );
+
+    let escape = CharacterEscape::cast(tree.find(SyntaxKind::CharacterEscape).unwrap()).unwrap();
+    assert_eq!(
+        text(escape.marker()),
+        Some("\\"),
+        "should get escape markers"
+    );
+    assert_eq!(
+        text(escape.value()),
+        Some("*"),
+        "should get escaped characters"
+    );
+
+    let reference =
+        CharacterReference::cast(tree.find(SyntaxKind::CharacterReference).unwrap()).unwrap();
+    assert_eq!(
+        text(reference.value()),
+        Some("amp"),
+        "should get reference values"
+    );
+
+    let emphasis = Emphasis::cast(tree.find(SyntaxKind::Emphasis).unwrap()).unwrap();
+    assert_eq!(
+        text(emphasis.sequence()),
+        Some("*"),
+        "should get emphasis markers"
+    );
+    assert_eq!(text(emphasis.text()), Some("e"), "should get emphasis text");
+
+    let strong = Strong::cast(tree.find(SyntaxKind::Strong).unwrap()).unwrap();
+    assert_eq!(
+        text(strong.sequence()),
+        Some("__"),
+        "should get strong markers"
+    );
+    assert_eq!(
+        strong.syntax().tokens().len(),
+        3,
+        "should get the tokens of a node"
+    );
+
+    Ok(())
+}
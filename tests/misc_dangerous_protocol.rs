@@ -1,4 +1,4 @@
-use markdown::to_html;
+use markdown::{to_html, to_html_with_options, CompileOptions, Options};
 use pretty_assertions::assert_eq;
 
 #[test]
@@ -195,3 +195,77 @@ fn dangerous_protocol_link() {
         "should allow a colon in a path"
     );
 }
+
+#[test]
+fn dangerous_protocol_custom() -> Result<(), String> {
+    let custom = Options {
+        compile: CompileOptions {
+            safe_protocol_href: Some(vec!["https".into(), "tel".into(), "vscode".into()]),
+            safe_protocol_src: Some(vec!["https".into(), "data:image/png".into()]),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    assert_eq!(
+        to_html_with_options("[a](tel:123) [b](vscode://c) <obsidian://d>", &custom)?,
+        "<p><a href=\"tel:123\">a</a> <a href=\"vscode://c\">b</a> <a href=\"\">obsidian://d</a></p>",
+        "should support custom protocols for links"
+    );
+
+    assert_eq!(
+        to_html_with_options("[a](http://b) [c](TEL:1) [d](e:f) [g](./h:i)", &custom)?,
+        "<p><a href=\"\">a</a> <a href=\"TEL:1\">c</a> <a href=\"\">d</a> <a href=\"./h:i\">g</a></p>",
+        "should replace the default protocols for links"
+    );
+
+    assert_eq!(
+        to_html_with_options("[a](javascript:alert(1))", &custom)?,
+        "<p><a href=\"\">a</a></p>",
+        "should not allow dangerous protocols for links"
+    );
+
+    assert_eq!(
+        to_html_with_options("![a](data:image/png;base64,b) ![c](DATA:image/PNG,d) ![e](data:image/pngx,f)", &custom)?,
+        "<p><img src=\"data:image/png;base64,b\" alt=\"a\" /> <img src=\"DATA:image/PNG,d\" alt=\"c\" /> <img src=\"\" alt=\"e\" /></p>",
+        "should support protocol prefixes for images"
+    );
+
+    assert_eq!(
+        to_html_with_options("![a](data:text/html,b) ![c](http://d) ![e](https://f)", &custom)?,
+        "<p><img src=\"\" alt=\"a\" /> <img src=\"\" alt=\"c\" /> <img src=\"https://f\" alt=\"e\" /></p>",
+        "should replace the default protocols for images"
+    );
+
+    assert_eq!(
+        to_html_with_options(
+            "[a](data:image/png,b)",
+            &Options {
+                compile: CompileOptions {
+                    safe_protocol_src: Some(vec!["data:image/png".into()]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        )?,
+        "<p><a href=\"\">a</a></p>",
+        "should not use image protocols for links"
+    );
+
+    assert_eq!(
+        to_html_with_options(
+            "[a](tel:123) [b](TEL:456) [c](https://d)",
+            &Options {
+                compile: CompileOptions {
+                    safe_protocol_href: Some(vec!["Tel".into(), "HTTPS".into()]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        )?,
+        "<p><a href=\"tel:123\">a</a> <a href=\"TEL:456\">b</a> <a href=\"https://d\">c</a></p>",
+        "should compare protocols case-insensitively"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use markdown::{format, try_format, Constructs, FormatOptions, LineEnding, ParseOptions};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn format_markers() {
+    let options = FormatOptions::default();
+
+    assert_eq!(format("", &options), "", "should support an empty document");
+
+    assert_eq!(
+        format("Alpha\n=====\n\n+ _b_\n+ __c__\n\n* d\n\n1) e", &options),
+        "# Alpha\n\n- *b*\n- **c**\n\n* d\n\n1. e\n",
+        "should normalize markers"
+    );
+
+    let options = FormatOptions {
+        bullet: '*',
+        bullet_ordered: ')',
+        emphasis: '_',
+        strong: '_',
+        setext: true,
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        format("## a\n\n### b\n\n- c\n\n+ d\n\n1. e", &options),
+        "a\n---\n\n### b\n\n* c\n\n- d\n\n1) e\n",
+        "should support other markers"
+    );
+
+    assert_eq!(
+        format("*a* b*c* **d**e <b>*f*</b> foo***bar***baz", &options),
+        "_a_ b*c* **d**e <b>*f*</b> foo***bar***baz\n",
+        "should not use `_` intraword or next to HTML"
+    );
+
+    assert_eq!(
+        format("- ***", &options),
+        "* ___\n",
+        "should not turn list items into thematic breaks"
+    );
+}
+
+#[test]
+fn format_line_endings() {
+    assert_eq!(
+        format("a\r\nb\n\n> c\r\nd", &FormatOptions::default()),
+        "a\r\nb\n\n> c\r\n> d\n",
+        "should keep line endings in text by default"
+    );
+
+    for (line_ending, expected) in [
+        (LineEnding::LineFeed, "a\nb\n\n> c\n> d\n"),
+        (
+            LineEnding::CarriageReturnLineFeed,
+            "a\r\nb\r\n\r\n> c\r\n> d\r\n",
+        ),
+        (LineEnding::CarriageReturn, "a\rb\r\r> c\r> d\r"),
+    ] {
+        assert_eq!(
+            try_format(
+                "a\r\nb\n\n> c\r\nd",
+                &FormatOptions {
+                    line_ending: Some(line_ending.clone()),
+                    ..FormatOptions::default()
+                }
+            ),
+            Ok(expected.into()),
+            "should support `line_ending: Some({:?})`",
+            line_ending
+        );
+    }
+}
+
+#[test]
+fn format_tables() {
+    let options = FormatOptions {
+        parse: ParseOptions::gfm(),
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        format(
+            "a|b|c\n:-|-:|:-:\nlong cell|d|ef\n|`g\\|h`",
+            &options
+        ),
+        "| a         |   b |  c  |\n| :-------- | --: | :-: |\n| long cell |   d | ef  |\n| `g\\|h`    |\n",
+        "should align tables"
+    );
+
+    assert_eq!(
+        format(
+            "a|b\n-|-\nlong cell|c",
+            &FormatOptions {
+                align_tables: false,
+                ..options
+            }
+        ),
+        "| a | b |\n| --- | --- |\n| long cell | c |\n",
+        "should support `align_tables: false`"
+    );
+}
+
+#[test]
+fn format_wrap() {
+    let options = FormatOptions {
+        line_width: Some(20),
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        format(
+            "Alpha bravo charlie delta echo foxtrot\ngolf hotel",
+            &options
+        ),
+        "Alpha bravo charlie\ndelta echo foxtrot\ngolf hotel\n",
+        "should wrap paragraphs"
+    );
+
+    assert_eq!(
+        format(
+            "> - alpha bravo *charlie delta* [echo](https://foxtrot.golf) `hotel`",
+            &options
+        ),
+        "> - alpha bravo\n>   *charlie delta*\n>   [echo](https://foxtrot.golf)\n>   `hotel`\n",
+        "should include the indent of containers, and not break other nodes"
+    );
+
+    assert_eq!(
+        format("alpha bravo 1. charlie # delta", &options),
+        "alpha bravo 1.\ncharlie # delta\n",
+        "should escape what would start constructs"
+    );
+
+    assert_eq!(
+        format("alpha bravo charlie 1. delta", &options),
+        "alpha bravo charlie\n1\\. delta\n",
+        "should escape what would start constructs at the start of lines"
+    );
+
+    assert_eq!(
+        format("alpha bravo charlie <div>", &options),
+        "alpha bravo charlie <div>\n",
+        "should not break before HTML"
+    );
+
+    assert_eq!(
+        format("alpha\\\nbravo charlie delta echo", &options),
+        "alpha\\\nbravo charlie delta\necho\n",
+        "should support hard breaks"
+    );
+}
+
+#[test]
+fn format_definitions() {
+    let options = FormatOptions {
+        sort_definitions: true,
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        format(
+            "[a]: b\n[c]: d\n[e]: f\n\n![c] [x][A]\n\n> [a]: g",
+            &options
+        ),
+        "![c] [x][A]\n\n> [a]: g\n\n[c]: d\n\n[a]: b\n\n[e]: f\n",
+        "should move definitions to the end, in the order they are used"
+    );
+}
+
+#[test]
+fn format_verbatim() {
+    let options = FormatOptions {
+        parse: ParseOptions {
+            constructs: Constructs {
+                frontmatter: true,
+                ..Constructs::default()
+            },
+            ..ParseOptions::default()
+        },
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        format("---\na:   b\n---\n* c", &options),
+        "---\na:   b\n---\n\n- c\n",
+        "should keep frontmatter"
+    );
+
+    let options = FormatOptions {
+        parse: ParseOptions::mdx(),
+        line_width: Some(10),
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        format("<A  b={c}>\n* d\n</A>\n\n{1 +\n  1} <B\n  c />", &options),
+        "<A  b={c}>\n* d\n</A>\n\n{1 +\n  1} <B\n  c />\n",
+        "should keep MDX"
+    );
+
+    assert_eq!(
+        format("> * a {b} <C  />", &options),
+        "> - a {b} <C  />\n",
+        "should keep MDX in containers"
+    );
+
+    assert_eq!(
+        format("a {b", &options),
+        "a {b",
+        "should return the input if it cannot be parsed"
+    );
+
+    assert_eq!(
+        try_format("a {b", &options),
+        Err("1:5: Unexpected end of file in expression, expected a corresponding closing brace for `{`".into()),
+        "should error if the input cannot be parsed"
+    );
+
+    let options = FormatOptions {
+        parse: ParseOptions::gfm(),
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        format("* a\n\n  *   b\n\n* [www.c~](d)", &options),
+        "* a\n\n  *   b\n\n* [www.c~](d)",
+        "should return the input if the result would be different"
+    );
+
+    assert_eq!(
+        try_format("a\n\n[www.c~](d)", &options),
+        Err(
+            "3:1: Unexpected different syntax tree after formatting, expected the same tree".into()
+        ),
+        "should error with the place of the first node that would be different"
+    );
+
+    assert_eq!(
+        try_format("* a", &options),
+        Ok("- a\n".into()),
+        "should format when the result is the same"
+    );
+}
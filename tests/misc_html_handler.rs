@@ -0,0 +1,185 @@
+use markdown::{
+    mdast::AlignKind, to_html_with_options, CompileOptions, HtmlHandler, HtmlNode, Options,
+    ParseOptions,
+};
+use pretty_assertions::assert_eq;
+use std::sync::Arc;
+
+struct Handler;
+
+impl HtmlHandler for Handler {
+    fn enter(&self, node: &HtmlNode, children: &str) -> Option<String> {
+        match node {
+            HtmlNode::BlockQuote => Some("<blockquote class=\"note\">".into()),
+            HtmlNode::Heading { depth } => Some(format!("<h{} id=\"{}\">", depth, children)),
+            HtmlNode::Image { url, alt, title } => Some(format!(
+                "<img src=\"{}\" alt=\"{}\" title=\"{}\" loading=\"lazy\" />",
+                url,
+                alt,
+                title.unwrap_or_default()
+            )),
+            HtmlNode::Link { url, title } if url.starts_with("https://") => Some(format!(
+                "<a href=\"{}\" title=\"{}\" rel=\"noopener\">",
+                url,
+                title.unwrap_or_default()
+            )),
+            HtmlNode::Table { align } => Some(format!(
+                "<div class=\"table\" data-columns=\"{}\"><table>",
+                align
+                    .iter()
+                    .map(|d| match d {
+                        AlignKind::Left => "l",
+                        AlignKind::Right => "r",
+                        AlignKind::Center => "c",
+                        AlignKind::None => "-",
+                    })
+                    .collect::<String>()
+            )),
+            HtmlNode::List { ordered, start } => Some(format!(
+                "<{} data-start=\"{}\">",
+                if *ordered { "ol" } else { "ul" },
+                start.unwrap_or_default()
+            )),
+            HtmlNode::TableCell { align, head } => Some(format!(
+                "<{} data-align=\"{:?}\">",
+                if *head { "th" } else { "td" },
+                align
+            )),
+            HtmlNode::ThematicBreak => Some("<hr class=\"rule\" />".into()),
+            _ => None,
+        }
+    }
+
+    fn content(&self, node: &HtmlNode, children: &str) -> Option<String> {
+        match node {
+            HtmlNode::Emphasis | HtmlNode::Strong | HtmlNode::Delete => {
+                Some(children.to_uppercase())
+            }
+            _ => None,
+        }
+    }
+
+    fn exit(&self, node: &HtmlNode, _children: &str) -> Option<String> {
+        match node {
+            HtmlNode::Table { .. } => Some("</table></div>".into()),
+            _ => None,
+        }
+    }
+}
+
+/// Handler that uses the defaults.
+struct Defaults;
+
+impl HtmlHandler for Defaults {}
+
+#[test]
+fn html_handler() -> Result<(), String> {
+    let handler = Options {
+        parse: ParseOptions::gfm(),
+        compile: CompileOptions {
+            html_handler: Some(Arc::new(Handler)),
+            ..Default::default()
+        },
+    };
+
+    assert_eq!(
+        to_html_with_options("# a\n\nb\n=", &handler)?,
+        "<h1 id=\"a\">a</h1>\n<h1 id=\"b\">b</h1>",
+        "should support headings"
+    );
+
+    assert_eq!(
+        to_html_with_options("#", &handler)?,
+        "<h1 id=\"\"></h1>",
+        "should support empty headings"
+    );
+
+    assert_eq!(
+        to_html_with_options("> a\n>\n> b", &handler)?,
+        "<blockquote class=\"note\">\n<p>a</p>\n<p>b</p>\n</blockquote>",
+        "should support block quotes"
+    );
+
+    assert_eq!(
+        to_html_with_options("| a | b |\n| :- | -: |\n| c | d |", &handler)?,
+        "<div class=\"table\" data-columns=\"lr\"><table>\n<thead>\n<tr>\n<th data-align=\"Left\">a</th>\n<th data-align=\"Right\">b</th>\n</tr>\n</thead>\n<tbody>\n<tr>\n<td data-align=\"Left\">c</td>\n<td data-align=\"Right\">d</td>\n</tr>\n</tbody>\n</table></div>",
+        "should support tables"
+    );
+
+    assert_eq!(
+        to_html_with_options("[a](https://b \"c\") [d](e)", &handler)?,
+        "<p><a href=\"https://b\" title=\"c\" rel=\"noopener\">a</a> <a href=\"e\">d</a></p>",
+        "should support links, and fall back to the default"
+    );
+
+    assert_eq!(
+        to_html_with_options("[a]\n\n[a]: https://b", &handler)?,
+        "<p><a href=\"https://b\" title=\"\" rel=\"noopener\">a</a></p>\n",
+        "should support link references"
+    );
+
+    assert_eq!(
+        to_html_with_options("<https://a> www.b.com", &handler)?,
+        "<p><a href=\"https://a\" title=\"\" rel=\"noopener\">https://a</a> <a href=\"http://www.b.com\">www.b.com</a></p>",
+        "should support autolinks"
+    );
+
+    assert_eq!(
+        to_html_with_options("![a *b*](c&d \"e\")", &handler)?,
+        "<p><img src=\"c&amp;d\" alt=\"a b\" title=\"e\" loading=\"lazy\" /></p>",
+        "should support images, with encoded values"
+    );
+
+    assert_eq!(
+        to_html_with_options("[a](javascript:alert(1))", &handler)?,
+        "<p><a href=\"\">a</a></p>",
+        "should pass sanitized URLs"
+    );
+
+    assert_eq!(
+        to_html_with_options("a *b* **c** ~d~\n\n***", &handler)?,
+        "<p>a <em>B</em> <strong>C</strong> <del>D</del></p>\n<hr class=\"rule\" />",
+        "should support replacing content, and void elements"
+    );
+
+    assert_eq!(
+        to_html_with_options("![*a*](b)", &handler)?,
+        "<p><img src=\"b\" alt=\"a\" title=\"\" loading=\"lazy\" /></p>",
+        "should not call the handler for nodes in alt text"
+    );
+
+    assert_eq!(
+        to_html_with_options("3. a\n4. b\n\n- c\n\n  d", &handler)?,
+        "<ol data-start=\"3\">\n<li>a</li>\n<li>b</li>\n</ol>\n<ul data-start=\"0\">\n<li>\n<p>c</p>\n<p>d</p>\n</li>\n</ul>",
+        "should support lists, list items, and paragraphs"
+    );
+
+    assert_eq!(
+        to_html_with_options("| a | b |\n| :- | - |\n| c |", &handler)?,
+        "<div class=\"table\" data-columns=\"l-\"><table>\n<thead>\n<tr>\n<th data-align=\"Left\">a</th>\n<th data-align=\"None\">b</th>\n</tr>\n</thead>\n<tbody>\n<tr>\n<td data-align=\"Left\">c</td>\n<td data-align=\"None\"></td>\n</tr>\n</tbody>\n</table></div>",
+        "should support table cells, including phantom ones"
+    );
+
+    assert_eq!(
+        to_html_with_options("# a\n> b\n\n[c](https://d)", &Options::gfm())?,
+        "<h1>a</h1>\n<blockquote>\n<p>b</p>\n</blockquote>\n<p><a href=\"https://d\">c</a></p>",
+        "should use the default without handler"
+    );
+
+    let value = "> 3. a\n>    *b*\n> 4. | c |\n>    | - |\n>    | **d** |\n\n- e\n\n  ~f~";
+    let sourcepos = |html_handler| Options {
+        parse: ParseOptions::gfm(),
+        compile: CompileOptions {
+            html_handler,
+            sourcepos: true,
+            ..Default::default()
+        },
+    };
+    assert_eq!(
+        to_html_with_options(value, &sourcepos(Some(Arc::new(Defaults))))?,
+        to_html_with_options(value, &sourcepos(None))?,
+        "should pass the default opening tags, with `sourcepos`"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use markdown::{
+    mdast::{Definition, Emphasis, LinkReference, Node, ReferenceKind, Root, Text},
+    to_html_inline, to_mdast_inline,
+    unist::Position,
+    CompileOptions, Options, ParseOptions,
+};
+use pretty_assertions::assert_eq;
+use std::sync::Arc;
+
+#[test]
+fn inline() -> Result<(), String> {
+    let definitions = vec![Definition {
+        url: "https://b".into(),
+        title: Some("c\"".into()),
+        identifier: "a".into(),
+        label: Some("A".into()),
+        position: None,
+    }];
+
+    assert_eq!(
+        to_html_inline("a *b* `c`", &[], &Options::default())?,
+        "a <em>b</em> <code>c</code>",
+        "should support text without paragraph"
+    );
+
+    assert_eq!(
+        to_html_inline("# a", &[], &Options::default())?,
+        "# a",
+        "should not support headings"
+    );
+
+    assert_eq!(
+        to_html_inline("- a\n> b\n    c\n***", &[], &Options::default())?,
+        "- a\n&gt; b\nc\n***",
+        "should not support other flow constructs"
+    );
+
+    assert_eq!(
+        to_html_inline("  a  ", &[], &Options::default())?,
+        "a",
+        "should trim whitespace"
+    );
+
+    assert_eq!(
+        to_html_inline("", &[], &Options::default())?,
+        "",
+        "should support empty input"
+    );
+
+    assert_eq!(
+        to_html_inline("[a]: b", &[], &Options::default())?,
+        "[a]: b",
+        "should not support definitions"
+    );
+
+    assert_eq!(
+        to_html_inline("[a] [A][] [x][a] [b]", &definitions, &Options::default())?,
+        "<a href=\"https://b\" title=\"c&quot;\">a</a> <a href=\"https://b\" title=\"c&quot;\">A</a> <a href=\"https://b\" title=\"c&quot;\">x</a> [b]",
+        "should support references to given definitions"
+    );
+
+    assert_eq!(
+        to_html_inline(
+            "[a]",
+            &definitions,
+            &Options {
+                compile: CompileOptions {
+                    url_rewrite: Some(Arc::new(|url, kind| Some(format!("{:?}/{}", kind, url)))),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        )?,
+        "<a href=\"Definition/https://b\" title=\"c&quot;\">a</a>",
+        "should rewrite URLs of given definitions"
+    );
+
+    assert_eq!(
+        to_html_inline("~a~ www.b.com", &[], &Options::gfm())?,
+        "<del>a</del> <a href=\"http://www.b.com\">www.b.com</a>",
+        "should support GFM"
+    );
+
+    assert_eq!(
+        to_html_inline("a\rb\r\nc", &[], &Options::default())?,
+        "a\rb\r\nc",
+        "should support CR and CRLF line endings"
+    );
+
+    assert_eq!(
+        to_html_inline("`\n\r", &[], &Options::default())?,
+        "`",
+        "should support a CR after an LF in code (text) that is not closed"
+    );
+
+    assert_eq!(
+        to_html_inline("`\r\r", &[], &Options::default())?,
+        "`",
+        "should support a CR after a CR in code (text) that is not closed"
+    );
+
+    assert_eq!(
+        to_html_inline("`a\r\nb` `c\rd`", &[], &Options::default())?,
+        "<code>a b</code> <code>c d</code>",
+        "should support CR and CRLF in code (text)"
+    );
+
+    assert_eq!(
+        to_html_inline("*a\r\n\r\nb* <c\r\rd>", &[], &Options::default())?,
+        "*a\r\n\r\nb* &lt;c\r\rd&gt;",
+        "should not form constructs across blank lines"
+    );
+
+    assert_eq!(
+        to_html_inline("\r\n a \r\n\t\r\n", &[], &Options::default())?,
+        "a",
+        "should ignore blank lines at the start and end"
+    );
+
+    assert_eq!(
+        to_mdast_inline("*a* [a]", &definitions, &ParseOptions::default())?,
+        Node::Root(Root {
+            children: vec![
+                Node::Emphasis(Emphasis {
+                    children: vec![Node::Text(Text {
+                        value: "a".into(),
+                        position: Some(Position::new(1, 2, 1, 1, 3, 2))
+                    })],
+                    position: Some(Position::new(1, 1, 0, 1, 4, 3))
+                }),
+                Node::Text(Text {
+                    value: " ".into(),
+                    position: Some(Position::new(1, 4, 3, 1, 5, 4))
+                }),
+                Node::LinkReference(LinkReference {
+                    children: vec![Node::Text(Text {
+                        value: "a".into(),
+                        position: Some(Position::new(1, 6, 5, 1, 7, 6))
+                    })],
+                    reference_kind: ReferenceKind::Shortcut,
+                    identifier: "a".into(),
+                    label: Some("a".into()),
+                    position: Some(Position::new(1, 5, 4, 1, 8, 7))
+                })
+            ],
+            position: Some(Position::new(1, 1, 0, 1, 8, 7))
+        }),
+        "should support inline mdast"
+    );
+
+    Ok(())
+}
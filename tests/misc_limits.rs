@@ -0,0 +1,167 @@
+use markdown::{to_html_with_options, to_mdast, Limits, Options, ParseOptions};
+use pretty_assertions::assert_eq;
+
+/// Get options with `limits`.
+fn limits(limits: Limits) -> Options {
+    Options {
+        parse: ParseOptions {
+            limits,
+            ..ParseOptions::gfm()
+        },
+        ..Options::gfm()
+    }
+}
+
+#[test]
+fn limits_errors() -> Result<(), String> {
+    assert_eq!(
+        to_html_with_options(
+            "abc",
+            &limits(Limits {
+                size: Some(2),
+                ..Limits::default()
+            })
+        ),
+        Err("1:1: Unexpected document of 3 bytes, expected at most 2 bytes (limit:size)".into()),
+        "should error on large documents"
+    );
+
+    assert_eq!(
+        to_html_with_options(
+            "ab",
+            &limits(Limits {
+                size: Some(2),
+                ..Limits::default()
+            })
+        )?,
+        "<p>ab</p>",
+        "should support documents at the size limit"
+    );
+
+    assert_eq!(
+        to_mdast(
+            "[a]: b\n> [c]: d\n\n[e]: f",
+            &limits(Limits {
+                definitions: Some(1),
+                ..Limits::default()
+            })
+            .parse
+        ),
+        Err("2:3: Unexpected definition, expected the number of definitions to be at most 1 (limit:definitions)".into()),
+        "should error on too many definitions"
+    );
+
+    assert_eq!(
+        to_mdast(
+            "a *b*",
+            &limits(Limits {
+                events: Some(3),
+                ..Limits::default()
+            })
+            .parse
+        ),
+        Err(
+            "1:1: Unexpected event, expected the number of events to be at most 3 (limit:events)"
+                .into()
+        ),
+        "should error on too many events"
+    );
+
+    assert_eq!(
+        to_mdast(
+            &"a *b* ".repeat(100_000),
+            &limits(Limits {
+                events: Some(1000),
+                ..Limits::default()
+            })
+            .parse
+        ),
+        Err(
+            "1:744: Unexpected event, expected the number of events to be at most 1000 (limit:events)"
+                .into()
+        ),
+        "should stop parsing when there are too many events"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn limits_fallback() -> Result<(), String> {
+    let container = limits(Limits {
+        container_depth: Some(2),
+        ..Limits::default()
+    });
+    let inline = limits(Limits {
+        inline_depth: Some(1),
+        ..Limits::default()
+    });
+
+    assert_eq!(
+        to_html_with_options("> - > a\n> - b", &container)?,
+        "<blockquote>\n<ul>\n<li>&gt; a</li>\n<li>b</li>\n</ul>\n</blockquote>",
+        "should not nest containers too deep"
+    );
+
+    assert_eq!(
+        to_html_with_options(&"> ".repeat(10_000), &container)?
+            .matches("<blockquote>")
+            .count(),
+        2,
+        "should not nest many containers too deep"
+    );
+
+    assert_eq!(
+        to_html_with_options("*a **b** c* ~d~ **e**", &inline)?,
+        "<p>*a <strong>b</strong> c* <del>d</del> <strong>e</strong></p>",
+        "should not nest attention too deep"
+    );
+
+    assert_eq!(
+        to_html_with_options("[a ![b](c)](d) [e](f)", &inline)?,
+        "<p>[a <img src=\"c\" alt=\"b\" />](d) <a href=\"f\">e</a></p>",
+        "should not nest labels too deep"
+    );
+
+    assert_eq!(
+        to_html_with_options("[ [ [ [a](b)", &inline)?,
+        "<p>[ [ [ <a href=\"b\">a</a></p>",
+        "should not count labels that do not form"
+    );
+
+    assert_eq!(
+        to_html_with_options("![ ![ [a](b)", &inline)?,
+        "<p>![ ![ <a href=\"b\">a</a></p>",
+        "should not count image labels that do not form"
+    );
+
+    assert_eq!(
+        to_html_with_options("[a ![b](c \"d\")][e]\n\n[e]: f", &inline)?,
+        "<p>[a <img src=\"c\" alt=\"b\" title=\"d\" />][e]</p>\n",
+        "should not nest references too deep"
+    );
+
+    assert_eq!(
+        to_html_with_options(
+            &format!("{}a{}", "![".repeat(1_000), "](b)".repeat(1_000)),
+            &inline
+        )?
+        .matches("<img")
+        .count(),
+        1,
+        "should not nest many labels too deep"
+    );
+
+    assert_eq!(
+        to_html_with_options(
+            &format!("{}a{}", "*".repeat(10_000), "*".repeat(10_000)),
+            &inline
+        )?
+        .matches("<strong>")
+        .count(),
+        1,
+        "should not nest much attention too deep"
+    );
+
+    Ok(())
+}
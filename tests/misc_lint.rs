@@ -0,0 +1,293 @@
+use markdown::{
+    lint::{lint, lint_tree, Message, Options, Rule, Severity},
+    to_mdast,
+    unist::Position,
+    ParseOptions, PositionEncoding,
+};
+use pretty_assertions::assert_eq;
+
+/// Get the rule, start and end offsets, and reason of messages.
+fn check(value: &str, options: &Options) -> Result<Vec<(Rule, usize, usize, String)>, String> {
+    Ok(lint(value, options)?
+        .into_iter()
+        .map(|d| {
+            (
+                d.rule,
+                d.position.start.offset,
+                d.position.end.offset,
+                d.reason,
+            )
+        })
+        .collect())
+}
+
+#[test]
+fn lint_headings() -> Result<(), String> {
+    let options = Options::default();
+
+    assert_eq!(
+        check("# a\n\n### b\n\n## c\n\n#### d", &options)?,
+        vec![
+            (
+                Rule::HeadingIncrement,
+                5,
+                10,
+                "Unexpected heading rank `3`, expected rank `2`".into()
+            ),
+            (
+                Rule::HeadingIncrement,
+                18,
+                24,
+                "Unexpected heading rank `4`, expected rank `3`".into()
+            )
+        ],
+        "should warn about skipped heading ranks"
+    );
+
+    assert_eq!(
+        check("# A *b*\n\n> ## a b\n\n#", &options)?,
+        vec![(
+            Rule::DuplicateHeading,
+            11,
+            17,
+            "Unexpected heading with the same text as the heading at 1:1".into()
+        )],
+        "should warn about duplicate headings"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lint_references() -> Result<(), String> {
+    let options = Options {
+        parse: ParseOptions::gfm(),
+        ..Options::default()
+    };
+
+    assert_eq!(
+        check(
+            "[a][b] [c] \\[d][e] [^f] ![g][]\n\n[c]: h\n[G]: i\n[j]: k\n\n[^l]: m",
+            &options
+        )?,
+        vec![
+            (
+                Rule::UndefinedReference,
+                3,
+                6,
+                "Undefined reference `[b]`".into()
+            ),
+            (
+                Rule::UndefinedReference,
+                15,
+                18,
+                "Undefined reference `[e]`".into()
+            ),
+            (
+                Rule::UndefinedReference,
+                19,
+                23,
+                "Undefined footnote `[^f]`".into()
+            ),
+            (
+                Rule::UnusedDefinition,
+                46,
+                52,
+                "Unused definition `[j]`".into()
+            ),
+            (
+                Rule::UnusedDefinition,
+                54,
+                61,
+                "Unused footnote definition `[^l]`".into()
+            )
+        ],
+        "should warn about undefined references and unused definitions"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lint_markers_and_whitespace() -> Result<(), String> {
+    let options = Options::default();
+
+    assert_eq!(
+        check("- a\n\n* b\n\n1. c\n2) d\n\n+ e", &options)?,
+        vec![
+            (
+                Rule::ListMarkerStyle,
+                5,
+                6,
+                "Unexpected list item marker `*`, expected `-`".into()
+            ),
+            (
+                Rule::ListMarkerStyle,
+                16,
+                17,
+                "Unexpected list item marker `)`, expected `.`".into()
+            ),
+            (
+                Rule::ListMarkerStyle,
+                21,
+                22,
+                "Unexpected list item marker `+`, expected `-`".into()
+            )
+        ],
+        "should warn about inconsistent list item markers"
+    );
+
+    assert_eq!(
+        check("a\tb \nc  \nd \t\r\n\te", &options)?,
+        vec![
+            (Rule::HardTab, 1, 2, "Unexpected tab".into()),
+            (
+                Rule::TrailingSpace,
+                3,
+                4,
+                "Unexpected whitespace at end of line".into()
+            ),
+            (
+                Rule::TrailingSpace,
+                10,
+                12,
+                "Unexpected whitespace at end of line".into()
+            ),
+            (Rule::HardTab, 11, 12, "Unexpected tab".into()),
+            (Rule::HardTab, 14, 15, "Unexpected tab".into())
+        ],
+        "should warn about tabs and trailing whitespace, but not hard breaks"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lint_links() -> Result<(), String> {
+    assert_eq!(
+        check(
+            "![](a) ![ ][b] ![c](d)\n\nSee https://e.com. <https://f.com> [https://g.com](h)\n\n[b]: i",
+            &Options::default()
+        )?,
+        vec![
+            (
+                Rule::ImageAlt,
+                0,
+                6,
+                "Unexpected image without alternative text".into()
+            ),
+            (
+                Rule::ImageAlt,
+                7,
+                14,
+                "Unexpected image without alternative text".into()
+            ),
+            (
+                Rule::BareUrl,
+                28,
+                41,
+                "Unexpected bare URL `https://e.com`, expected `<https://e.com>`".into()
+            )
+        ],
+        "should warn about missing alt and bare URLs"
+    );
+
+    assert_eq!(
+        check(
+            "www.a.com",
+            &Options {
+                parse: ParseOptions::gfm(),
+                ..Options::default()
+            }
+        )?,
+        vec![(
+            Rule::BareUrl,
+            0,
+            9,
+            "Unexpected bare URL `http://www.a.com`, expected `<http://www.a.com>`".into()
+        )],
+        "should warn about GFM autolink literals"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lint_options() -> Result<(), String> {
+    assert_eq!(
+        lint(
+            "a\t",
+            &Options {
+                hard_tab: Severity::Off,
+                trailing_space: Severity::Error,
+                ..Options::default()
+            }
+        )?,
+        vec![Message {
+            rule: Rule::TrailingSpace,
+            severity: Severity::Error,
+            reason: "Unexpected whitespace at end of line".into(),
+            position: Position::new(1, 2, 1, 1, 3, 2)
+        }],
+        "should support severities"
+    );
+
+    assert_eq!(
+        lint(
+            "a {b",
+            &Options {
+                parse: ParseOptions::mdx(),
+                ..Options::default()
+            }
+        ),
+        Err("1:5: Unexpected end of file in expression, expected a corresponding closing brace for `{`".into()),
+        "should error when MDX cannot be parsed"
+    );
+
+    assert_eq!(
+        lint(
+            "# 😀\n\n# 😀 \n",
+            &Options {
+                parse: ParseOptions {
+                    position_encoding: PositionEncoding::Utf16,
+                    ..ParseOptions::default()
+                },
+                ..Options::default()
+            }
+        )?,
+        vec![
+            Message {
+                rule: Rule::DuplicateHeading,
+                severity: Severity::Warning,
+                reason: "Unexpected heading with the same text as the heading at 1:1".into(),
+                position: Position::new(3, 1, 6, 3, 6, 11)
+            },
+            Message {
+                rule: Rule::TrailingSpace,
+                severity: Severity::Warning,
+                reason: "Unexpected whitespace at end of line".into(),
+                position: Position::new(3, 5, 10, 3, 6, 11)
+            }
+        ],
+        "should count positions in `position_encoding`"
+    );
+
+    let value = "# a\n\n### a\t\n\n* [b][c]\n- d\n";
+    let tree = to_mdast(value, &ParseOptions::default())?;
+    assert_eq!(
+        lint_tree(value, &tree, &Options::default())
+            .iter()
+            .map(|d| d.rule)
+            .collect::<Vec<_>>(),
+        vec![
+            Rule::HeadingIncrement,
+            Rule::DuplicateHeading,
+            Rule::UndefinedReference
+        ],
+        "should check a tree, without rules that need the parser"
+    );
+
+    assert_eq!(Rule::BareUrl.as_str(), "bare-url", "should serialize rules");
+
+    Ok(())
+}
@@ -0,0 +1,441 @@
+#![cfg(feature = "lsp")]
+
+use pretty_assertions::assert_eq;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Run the `markdown-lsp` binary with `messages` (requests get an `id`),
+/// followed by `shutdown` and `exit`, and get the messages it sends back.
+fn run(messages: &[Value]) -> Vec<Value> {
+    let mut input = vec![];
+    let mut all = vec![json!({"method": "initialize", "params": {}})];
+    all.extend_from_slice(messages);
+    all.push(json!({"method": "shutdown"}));
+    let mut id = 0;
+
+    for message in &all {
+        let mut message = message.clone();
+        message["jsonrpc"] = json!("2.0");
+
+        if message.get("id").is_none() && !message["method"].as_str().unwrap().contains('/') {
+            message["id"] = json!(id);
+            id += 1;
+        }
+
+        let body = message.to_string();
+        write!(input, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+    }
+
+    let exit = json!({"jsonrpc": "2.0", "method": "exit"}).to_string();
+    write!(input, "Content-Length: {}\r\n\r\n{}", exit.len(), exit).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_markdown-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("should spawn");
+    child.stdin.take().unwrap().write_all(&input).unwrap();
+    let output = child.wait_with_output().expect("should run");
+    assert!(output.status.success(), "should exit after shutdown");
+
+    parse(&output.stdout)
+}
+
+/// Get the messages in `output`.
+fn parse(output: &[u8]) -> Vec<Value> {
+    let output = String::from_utf8(output.to_vec()).unwrap();
+    let mut rest = output.as_str();
+    let mut replies = vec![];
+
+    while let Some(index) = rest.find("\r\n\r\n") {
+        let length = rest[..index]
+            .strip_prefix("Content-Length: ")
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        let body = &rest[index + 4..index + 4 + length];
+        replies.push(serde_json::from_str(body).unwrap());
+        rest = &rest[index + 4 + length..];
+    }
+
+    replies
+}
+
+/// Open a document.
+fn open(uri: &str, text: &str) -> Value {
+    json!({
+        "method": "textDocument/didOpen",
+        "params": {"textDocument": {"uri": uri, "languageId": "markdown", "version": 1, "text": text}}
+    })
+}
+
+/// Make a request about a position in `a.md`.
+fn request(method: &str, line: u64, character: u64) -> Value {
+    json!({
+        "id": method,
+        "method": method,
+        "params": {
+            "textDocument": {"uri": "file:///a.md"},
+            "position": {"line": line, "character": character},
+            "context": {"includeDeclaration": true}
+        }
+    })
+}
+
+/// Get the result of the request with `id`.
+fn result<'a>(replies: &'a [Value], id: &str) -> &'a Value {
+    &replies
+        .iter()
+        .find(|reply| reply["id"] == id)
+        .expect("should reply")["result"]
+}
+
+/// Get the last published diagnostics.
+fn diagnostics(replies: &[Value]) -> &Value {
+    &replies
+        .iter()
+        .rev()
+        .find(|reply| reply["method"] == "textDocument/publishDiagnostics")
+        .expect("should publish diagnostics")["params"]["diagnostics"]
+}
+
+/// Make a range.
+fn range(start: (u64, u64), end: (u64, u64)) -> Value {
+    json!({
+        "start": {"line": start.0, "character": start.1},
+        "end": {"line": end.0, "character": end.1}
+    })
+}
+
+#[test]
+fn lsp_lifecycle() {
+    let replies = run(&[]);
+    let capabilities = &replies[0]["result"]["capabilities"];
+    assert_eq!(
+        capabilities["definitionProvider"],
+        json!(true),
+        "should announce capabilities"
+    );
+    assert_eq!(
+        replies[1],
+        json!({"jsonrpc": "2.0", "id": 1, "result": null}),
+        "should support `shutdown`"
+    );
+
+    let replies = run(&[json!({"id": "x", "method": "a/b", "params": {}})]);
+    assert_eq!(
+        replies[1]["error"]["code"],
+        json!(-32601),
+        "should error on unknown methods"
+    );
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_markdown-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("should spawn");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"Content-Length: 18446744073709551615\r\n\r\n")
+        .unwrap();
+    let output = child.wait_with_output().expect("should run");
+    assert_eq!(
+        (output.status.code(), String::from_utf8(output.stderr).unwrap()),
+        (
+            Some(1),
+            "markdown-lsp: Cannot read body of 18446744073709551615 bytes, expected at most 67108864 bytes\n".into()
+        ),
+        "should exit on too large messages"
+    );
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_markdown-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("should spawn");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(format!("X-{}\r\n", "a".repeat(10_000)).as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().expect("should run");
+    assert_eq!(
+        (
+            output.status.code(),
+            String::from_utf8(output.stderr).unwrap()
+        ),
+        (
+            Some(1),
+            "markdown-lsp: Cannot read header longer than 8192 bytes\n".into()
+        ),
+        "should exit on too long header lines"
+    );
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_markdown-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("should spawn");
+    let mut input = b"Content-Length: 3\r\n\r\n{a}".to_vec();
+    for message in [
+        json!({"jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {}}),
+        json!({"jsonrpc": "2.0", "id": 1, "method": "shutdown"}),
+        json!({"jsonrpc": "2.0", "method": "exit"}),
+    ] {
+        let body = message.to_string();
+        write!(input, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+    }
+    child.stdin.take().unwrap().write_all(&input).unwrap();
+    let output = child.wait_with_output().expect("should run");
+    let replies = parse(&output.stdout);
+    assert_eq!(
+        (&replies[0]["id"], &replies[0]["error"]["code"]),
+        (&json!(null), &json!(-32700)),
+        "should reply with a parse error to bodies that are not JSON"
+    );
+    assert_eq!(
+        (output.status.code(), &replies[2]),
+        (Some(0), &json!({"jsonrpc": "2.0", "id": 1, "result": null})),
+        "should keep serving after bodies that are not JSON"
+    );
+}
+
+#[test]
+fn lsp_symbols_and_folding() {
+    let text = "# a\n\n## b *c*\n\n- d\n- e\n\n# f\n\n```\ng\n```\n\n> h\n> i\n";
+    let replies = run(&[
+        open("file:///a.md", text),
+        request("textDocument/documentSymbol", 0, 0),
+        request("textDocument/foldingRange", 0, 0),
+    ]);
+
+    let symbols = result(&replies, "textDocument/documentSymbol");
+    assert_eq!(
+        symbols
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d["name"].clone())
+            .collect::<Vec<_>>(),
+        vec![json!("a"), json!("f")],
+        "should serve top-level headings"
+    );
+    assert_eq!(
+        symbols[0]["children"][0]["name"],
+        json!("b c"),
+        "should nest headings"
+    );
+    assert_eq!(
+        symbols[0]["children"][0]["range"],
+        range((2, 0), (2, 8)),
+        "should serve heading ranges"
+    );
+
+    assert_eq!(
+        result(&replies, "textDocument/foldingRange"),
+        &json!([
+            {"startLine": 4, "endLine": 5},
+            {"startLine": 9, "endLine": 11},
+            {"startLine": 13, "endLine": 14}
+        ]),
+        "should serve folding ranges of lists, code, and block quotes"
+    );
+}
+
+#[test]
+fn lsp_references() {
+    let text = "[a][B] and ![c][b] and 😀 [b]\n\nx[^d]\n\n[b]: <https://e> \"f\"\n\n[^d]: g\n";
+    let replies = run(&[
+        open("file:///a.md", text),
+        request("textDocument/definition", 0, 2),
+        request("textDocument/references", 4, 1),
+        request("textDocument/hover", 0, 27),
+    ]);
+
+    assert_eq!(
+        result(&replies, "textDocument/definition"),
+        &json!({"uri": "file:///a.md", "range": range((4, 0), (4, 20))}),
+        "should serve definitions"
+    );
+
+    assert_eq!(
+        result(&replies, "textDocument/references"),
+        &json!([
+            {"uri": "file:///a.md", "range": range((0, 0), (0, 6))},
+            {"uri": "file:///a.md", "range": range((0, 11), (0, 18))},
+            {"uri": "file:///a.md", "range": range((0, 26), (0, 29))},
+            {"uri": "file:///a.md", "range": range((4, 0), (4, 20))}
+        ]),
+        "should serve references (with UTF-16 characters)"
+    );
+
+    assert_eq!(
+        result(&replies, "textDocument/hover")["contents"]["value"],
+        json!("```markdown\n[b]: <https://e> \"f\"\n```"),
+        "should serve hover info"
+    );
+
+    let replies = run(&[
+        open("file:///a.md", text),
+        request("textDocument/definition", 2, 2),
+    ]);
+    assert_eq!(
+        result(&replies, "textDocument/definition"),
+        &json!({"uri": "file:///a.md", "range": range((6, 0), (6, 7))}),
+        "should serve definitions of footnotes"
+    );
+}
+
+#[test]
+fn lsp_completion() {
+    let text = "[a]: b\n[C d]: e\n[^f]: g\n\n[x][\n\n[^";
+    let replies = run(&[
+        open("file:///a.md", text),
+        request("textDocument/completion", 4, 4),
+    ]);
+    let labels = result(&replies, "textDocument/completion")
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["label"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        labels,
+        vec![json!("a"), json!("C d")],
+        "should complete definition labels"
+    );
+
+    let replies = run(&[
+        open("file:///a.md", text),
+        request("textDocument/completion", 6, 2),
+    ]);
+    assert_eq!(
+        result(&replies, "textDocument/completion")[0]["label"],
+        json!("f"),
+        "should complete footnote labels"
+    );
+}
+
+#[test]
+fn lsp_diagnostics() {
+    let replies = run(&[open("file:///a.md", "[a][b] [c] [^d] \\[e][f]\n")]);
+    assert_eq!(
+        diagnostics(&replies),
+        &json!([
+            {
+                "range": range((0, 3), (0, 6)),
+                "severity": 2,
+                "source": "markdown",
+                "code": "undefined-reference",
+                "message": "Undefined reference `[b]`"
+            },
+            {
+                "range": range((0, 11), (0, 15)),
+                "severity": 2,
+                "source": "markdown",
+                "code": "undefined-reference",
+                "message": "Undefined footnote `[^d]`"
+            },
+            {
+                "range": range((0, 20), (0, 23)),
+                "severity": 2,
+                "source": "markdown",
+                "code": "undefined-reference",
+                "message": "Undefined reference `[f]`"
+            }
+        ]),
+        "should warn about undefined references"
+    );
+
+    let replies = run(&[open("file:///a.mdx", "# a\n\nb {c\n")]);
+    assert_eq!(
+        diagnostics(&replies),
+        &json!([{
+            "range": range((2, 4), (2, 4)),
+            "severity": 1,
+            "source": "markdown",
+            "message": "Unexpected end of file in expression, expected a corresponding closing brace for `{`"
+        }]),
+        "should report broken MDX"
+    );
+
+    let replies = run(&[
+        open("file:///a.md", "[a]\n\n[b]: c\n"),
+        json!({
+            "method": "textDocument/didChange",
+            "params": {
+                "textDocument": {"uri": "file:///a.md", "version": 2},
+                "contentChanges": [{"range": range((0, 1), (0, 2)), "text": "x][b"}]
+            }
+        }),
+        request("textDocument/definition", 0, 1),
+    ]);
+    assert_eq!(
+        result(&replies, "textDocument/definition"),
+        &json!({"uri": "file:///a.md", "range": range((2, 0), (2, 6))}),
+        "should support incremental changes"
+    );
+
+    let replies = run(&[
+        open("file:///a.md", "# a\n\n### a \n\n[b][c]\n"),
+        json!({
+            "method": "textDocument/didChange",
+            "params": {
+                "textDocument": {"uri": "file:///a.md", "version": 2},
+                "contentChanges": [{"range": range((4, 6), (4, 6)), "text": " [d][e]"}]
+            }
+        }),
+    ]);
+    assert_eq!(
+        diagnostics(&replies),
+        &json!([
+            {
+                "range": range((4, 3), (4, 6)),
+                "severity": 2,
+                "source": "markdown",
+                "code": "undefined-reference",
+                "message": "Undefined reference `[c]`"
+            },
+            {
+                "range": range((4, 10), (4, 13)),
+                "severity": 2,
+                "source": "markdown",
+                "code": "undefined-reference",
+                "message": "Undefined reference `[e]`"
+            }
+        ]),
+        "should only check references, after changes"
+    );
+}
+
+#[test]
+fn lsp_changes() {
+    let change = json!({
+        "method": "textDocument/didChange",
+        "params": {
+            "textDocument": {"uri": "file:///a.md", "version": 2},
+            "contentChanges": [{"range": range((4, 0), (5, 1)), "text": "---\n"}]
+        }
+    });
+    let changed = run(&[
+        open("file:///a.md", "- a\n| b |\n# c\n<\n\n>\n\td\n    e\n"),
+        change,
+        request("textDocument/foldingRange", 0, 0),
+    ]);
+    let opened = run(&[
+        open("file:///a.md", "- a\n| b |\n# c\n<\n---\n\n\td\n    e\n"),
+        request("textDocument/foldingRange", 0, 0),
+    ]);
+
+    assert_eq!(
+        result(&changed, "textDocument/foldingRange"),
+        result(&opened, "textDocument/foldingRange"),
+        "should be the same after changes as when opened"
+    );
+}
@@ -0,0 +1,94 @@
+use markdown::{
+    mdast,
+    mdast::{AlignKind, Node},
+    to_mdast,
+    visit::{visit_mut, ActionMut},
+    ParseOptions,
+};
+use pretty_assertions::assert_eq;
+
+/// Turn `value` into a tree without positional info (positions and stops).
+fn parse(value: &str, options: &ParseOptions) -> Result<Node, String> {
+    let mut tree = to_mdast(value, options)?;
+    visit_mut(&mut tree, &mut |node: &mut Node, _: &[usize]| {
+        node.position_set(None);
+
+        match node {
+            Node::MdxFlowExpression(x) => x.stops.clear(),
+            Node::MdxTextExpression(x) => x.stops.clear(),
+            _ => {}
+        }
+
+        ActionMut::Continue
+    });
+    Ok(tree)
+}
+
+#[test]
+fn mdast_builder() -> Result<(), String> {
+    assert_eq!(
+        mdast!(root [
+            heading(2) ["a ", emphasis ["b"]],
+            block_quote [
+                list(true) [
+                    list_item [paragraph [strong ["c"], hard_break, "d"]],
+                ],
+            ],
+            thematic_break,
+            code(Some("js"), "e"),
+            paragraph [
+                link("f") ["g"],
+                " ",
+                image("h", "i"),
+                " ",
+                inline_code("j"),
+                " ",
+                link_reference("K") ["k"],
+                " ",
+                html("<l>"),
+            ],
+            definition("K", "m"),
+        ]),
+        parse(
+            "## a *b*\n\n> 1. **c**\\\n>    d\n\n***\n\n```js\ne\n```\n\n[g](f) ![i](h) `j` [k][K] <l>\n\n[K]: m",
+            &ParseOptions::default()
+        )?,
+        "should build markdown"
+    );
+
+    assert_eq!(
+        mdast!(root [
+            table(vec![AlignKind::Left, AlignKind::None]) [
+                table_row [table_cell ["a"], table_cell ["b"]],
+                table_row [table_cell [delete ["c"]], table_cell []],
+            ],
+            paragraph [footnote_reference("D")],
+            footnote_definition("D") [paragraph ["e"]],
+        ]),
+        parse(
+            "| a | b |\n| :- | - |\n| ~c~ | |\n\n[^D]\n\n[^D]: e",
+            &ParseOptions::gfm()
+        )?,
+        "should build GFM"
+    );
+
+    let name = "Box";
+    assert_eq!(
+        mdast!(root [
+            mdx_jsx_flow_element(Some(name)) [
+                paragraph [mdx_jsx_text_element(None) ["a"], " ", mdx_text_expression("b")],
+            ],
+            mdx_flow_expression("c"),
+        ]),
+        parse("<Box>\n  <>a</> {b}\n</Box>\n\n{c}", &ParseOptions::mdx())?,
+        "should build MDX"
+    );
+
+    assert_eq!(
+        mdast!(paragraph [{Node::text(name)}, "!"]),
+        Node::paragraph(vec![Node::text("Box"), Node::text("!")]),
+        "should support expressions as children"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use markdown::{mdast::Node, to_mdast, unist::Point, ParseOptions};
+use pretty_assertions::assert_eq;
+
+/// Get the types and text of nodes, as `type:text`.
+fn names(path: &[&Node]) -> Vec<String> {
+    path.iter()
+        .map(|node| {
+            let debug = format!("{:?}", node);
+            let name = &debug[..debug.find(' ').unwrap_or(debug.len())];
+            format!("{}:{}", name, node.to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn node_at_offset() -> Result<(), String> {
+    let tree = to_mdast(
+        "# a *b*\n\n> - c [d](e)\n\n| f |\n| - |\n| g |",
+        &ParseOptions::gfm(),
+    )?;
+
+    assert_eq!(
+        names(&tree.at_offset(5)),
+        vec!["Root:a bc dfg", "Heading:a b", "Emphasis:b", "Text:b"],
+        "should get the deepest node and its ancestors"
+    );
+    assert_eq!(
+        names(&tree.at_offset(4)),
+        vec!["Root:a bc dfg", "Heading:a b", "Emphasis:b"],
+        "should get nodes that start at an offset"
+    );
+    assert_eq!(
+        names(&tree.at_offset(3)),
+        vec!["Root:a bc dfg", "Heading:a b", "Text:a "],
+        "should not get nodes that end at an offset"
+    );
+    assert_eq!(
+        names(&tree.at_offset(8)),
+        vec!["Root:a bc dfg"],
+        "should get the root between blocks"
+    );
+    assert_eq!(
+        names(&tree.at_offset(16))[1..],
+        vec![
+            "BlockQuote:c d",
+            "List:c d",
+            "ListItem:c d",
+            "Paragraph:c d",
+            "Link:d",
+            "Text:d"
+        ],
+        "should get nodes in containers"
+    );
+    assert_eq!(
+        names(&tree.at_offset(37))[1..],
+        vec!["Table:fg", "TableRow:g", "TableCell:g", "Text:g"],
+        "should get nodes in tables"
+    );
+    assert_eq!(
+        names(&tree.at_offset(40)),
+        vec!["Root:a bc dfg"],
+        "should get the root at the end of the tree"
+    );
+    assert_eq!(
+        tree.at_offset(41).len(),
+        0,
+        "should get nothing outside of the tree"
+    );
+
+    let tree = to_mdast("a", &Default::default())?;
+
+    assert_eq!(
+        names(&tree.at_offset(1)),
+        vec!["Root:a"],
+        "should get the root at the end of a document"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn node_path_at() -> Result<(), String> {
+    let tree = to_mdast("a\n\n*b*\r\nc", &Default::default())?;
+
+    assert_eq!(
+        names(&tree.path_at(&Point::new(3, 2, 0))),
+        vec!["Root:ab\r\nc", "Paragraph:b\r\nc", "Emphasis:b", "Text:b"],
+        "should get nodes at a line and column"
+    );
+    assert_eq!(
+        names(&tree.path_at(&Point::new(4, 1, 0))),
+        vec!["Root:ab\r\nc", "Paragraph:b\r\nc", "Text:\r\nc"],
+        "should get nodes after line endings"
+    );
+    assert_eq!(
+        names(&tree.path_at(&Point::new(4, 2, 0))),
+        vec!["Root:ab\r\nc"],
+        "should get the root at the end of the tree"
+    );
+    assert_eq!(
+        tree.path_at(&Point::new(5, 1, 0)).len(),
+        0,
+        "should get nothing after the tree"
+    );
+
+    Ok(())
+}
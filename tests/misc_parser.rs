@@ -0,0 +1,157 @@
+use markdown::{
+    mdast::{Node, Paragraph, Text},
+    to_mdast,
+    unist::Position,
+    Constructs, ParseOptions, Parser, PositionEncoding,
+};
+use pretty_assertions::assert_eq;
+
+/// Push `value` in chunks of `size` characters, and check that finished
+/// nodes match the whole tree.
+fn check(value: &str, size: usize, options: &ParseOptions) -> Result<(), String> {
+    let chars = value.chars().collect::<Vec<_>>();
+    let mut parser = Parser::new(options);
+    let mut nodes = vec![];
+
+    for chunk in chars.chunks(size) {
+        nodes.append(&mut parser.push(&chunk.iter().collect::<String>())?);
+    }
+
+    let tree = parser.finish()?;
+    let children = tree.children().unwrap();
+
+    assert_eq!(
+        tree,
+        to_mdast(value, options)?,
+        "should finish with the tree"
+    );
+    assert!(
+        nodes.len() <= children.len(),
+        "should not yield more than the tree for {:?} ({})",
+        value,
+        size
+    );
+    assert_eq!(
+        nodes,
+        children[0..nodes.len()].to_vec(),
+        "should yield finished nodes for {:?} ({})",
+        value,
+        size
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parser() -> Result<(), String> {
+    let gfm = ParseOptions {
+        constructs: Constructs {
+            frontmatter: true,
+            ..Constructs::gfm()
+        },
+        ..ParseOptions::gfm()
+    };
+    let cases = [
+        "a\n\nb\n\nc",
+        "a\nb\n=\n\nc\n---\n\n***\n",
+        "# a\n## b\n\n    c\n\n    d\ne",
+        "- a\n- b\n\n- c\n\nd\n\n1. e\n\n2. f\n3. g\n",
+        "1. a\n\n2. b\n\n22. c\n",
+        "> a\nb\n\n> c\n\n```js\nd\n\n\ne\n```\nf",
+        "<div>\n\na\n\n</div>\n\n<!--\n\nb\n\n-->\nc",
+        "[a]: b\n\n[a] [c]\n\n[c]: d\n\n[c]",
+        "a[^b]\n\n[^b]: c\n\n    d\n\ne[^b]",
+        "| a |\n| - |\n| b |\nc\n\nd\n| - |",
+        "---\na: b\n---\n\nc\n\n---\nd: e\n---\n",
+        "a\r\n\r\nb\r\n\r\n* c\r\n\r\nd\re\r\rf",
+        "👍\n\n👍👍\n\n👍",
+        "[^2]: charlie\n    indented delta\n\nx\n",
+        "[a]: b\n    c\n\nd\n",
+        "a\n===\nb\n\nc\n",
+        "[a]:\n\n[a]:\n| a |\n't'\n",
+        "[foo][bar]\n\nb\n\nc\n\n[bar]: /url\n",
+        "[^a]\n\nb\n\nc\n\n[^a]: c\n",
+        "a [b *c] d*\n\ne\n\nf\n\n[b *c]: g\n",
+        "a *[b* c]\n\n\\[d]\n\n[e]\n",
+    ];
+
+    for value in &cases {
+        for size in [1, 2, 3, 5, 8, 1000] {
+            check(value, size, &gfm)?;
+        }
+    }
+
+    for position_encoding in [PositionEncoding::Utf16, PositionEncoding::CodePoint] {
+        let options = ParseOptions {
+            constructs: Constructs {
+                frontmatter: true,
+                ..Constructs::gfm()
+            },
+            position_encoding,
+            ..ParseOptions::gfm()
+        };
+
+        for value in cases
+            .iter()
+            .chain(&["😀😀 a\n\nb 😀 *c*\n\n# d\n", "👍\r\n\r\n> 👍\r\n\r\nb"])
+        {
+            for size in [1, 3, 1000] {
+                check(value, size, &options)?;
+            }
+        }
+    }
+
+    let mut value = String::from("a\n\n```\n");
+    for index in 0..2000 {
+        value.push_str(&format!("{}\n", index));
+    }
+    value.push_str("```\n\nb [c]\n\n[c]: d\n\ne\n");
+
+    for size in [7, 100] {
+        check(&value, size, &gfm)?;
+    }
+
+    let options = ParseOptions::default();
+    let mut parser = Parser::new(&options);
+
+    assert_eq!(parser.push("a")?, vec![], "should not yield partial lines");
+    assert_eq!(
+        parser.push("\n\n")?,
+        vec![],
+        "should not yield the last node"
+    );
+    assert_eq!(
+        parser.push("b\n")?,
+        vec![Node::Paragraph(Paragraph {
+            children: vec![Node::Text(Text {
+                value: "a".into(),
+                position: Some(Position::new(1, 1, 0, 1, 2, 1))
+            })],
+            position: Some(Position::new(1, 1, 0, 1, 2, 1))
+        })],
+        "should yield nodes when the first line of the next node is complete"
+    );
+    assert_eq!(parser.push("\nc\n")?.len(), 1, "should yield more nodes");
+
+    let mut parser = Parser::new(&options);
+    assert_eq!(
+        parser.push("[a]\n\nb\n")?,
+        vec![],
+        "should not yield nodes with text that could turn into references"
+    );
+    assert_eq!(
+        parser.push("\nc\n\nd\n")?,
+        vec![],
+        "should not yield nodes after text that could turn into references"
+    );
+    parser.push("\n[a]: c\n")?;
+    assert!(
+        matches!(
+            parser.finish()?.children().unwrap()[0].children().unwrap()[0],
+            Node::LinkReference(_)
+        ),
+        "should know all definitions when finished"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use markdown::{mdast::Node, to_mdast, unist::Position, ParseOptions, PositionEncoding};
+use pretty_assertions::assert_eq;
+
+/// Get the positions of `tree` and its descendants, in tree order.
+fn positions(tree: &Node) -> Vec<Position> {
+    let mut result = vec![tree.position().unwrap().clone()];
+
+    if let Some(children) = tree.children() {
+        for child in children {
+            result.append(&mut positions(child));
+        }
+    }
+
+    result
+}
+
+/// Get options that count in `position_encoding`.
+fn options(position_encoding: PositionEncoding, mdx: bool) -> ParseOptions {
+    let options = if mdx {
+        ParseOptions::mdx()
+    } else {
+        ParseOptions::default()
+    };
+
+    ParseOptions {
+        position_encoding,
+        ..options
+    }
+}
+
+#[test]
+fn position_encoding() -> Result<(), String> {
+    let value = "# 😀 *ä*\n\n\t> b\r\nc `🎉`";
+
+    assert_eq!(
+        positions(&to_mdast(value, &options(PositionEncoding::Byte, false))?),
+        vec![
+            Position::new(1, 1, 0, 4, 9, 27),
+            Position::new(1, 1, 0, 1, 12, 11),
+            Position::new(1, 3, 2, 1, 8, 7),
+            Position::new(1, 8, 7, 1, 12, 11),
+            Position::new(1, 9, 8, 1, 11, 10),
+            Position::new(3, 1, 13, 3, 8, 17),
+            Position::new(4, 1, 19, 4, 9, 27),
+            Position::new(4, 1, 19, 4, 3, 21),
+            Position::new(4, 3, 21, 4, 9, 27),
+        ],
+        "should count bytes by default, with virtual columns for tabs"
+    );
+
+    assert_eq!(
+        positions(&to_mdast(value, &options(PositionEncoding::Utf16, false))?),
+        vec![
+            Position::new(1, 1, 0, 4, 7, 22),
+            Position::new(1, 1, 0, 1, 9, 8),
+            Position::new(1, 3, 2, 1, 6, 5),
+            Position::new(1, 6, 5, 1, 9, 8),
+            Position::new(1, 7, 6, 1, 8, 7),
+            Position::new(3, 1, 10, 3, 5, 14),
+            Position::new(4, 1, 16, 4, 7, 22),
+            Position::new(4, 1, 16, 4, 3, 18),
+            Position::new(4, 3, 18, 4, 7, 22),
+        ],
+        "should count UTF-16 code units"
+    );
+
+    assert_eq!(
+        positions(&to_mdast(
+            value,
+            &options(PositionEncoding::CodePoint, false)
+        )?),
+        vec![
+            Position::new(1, 1, 0, 4, 6, 20),
+            Position::new(1, 1, 0, 1, 8, 7),
+            Position::new(1, 3, 2, 1, 5, 4),
+            Position::new(1, 5, 4, 1, 8, 7),
+            Position::new(1, 6, 5, 1, 7, 6),
+            Position::new(3, 1, 9, 3, 5, 13),
+            Position::new(4, 1, 15, 4, 6, 20),
+            Position::new(4, 1, 15, 4, 3, 17),
+            Position::new(4, 3, 17, 4, 6, 20),
+        ],
+        "should count code points"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn position_encoding_errors() {
+    assert_eq!(
+        to_mdast("😀\t<a>", &options(PositionEncoding::Byte, true)),
+        Err("1:12: Expected a closing tag for `<a>` (1:9) before the end of `Paragraph` (mdx-jsx:end-tag-mismatch)".into()),
+        "should count bytes in errors by default"
+    );
+
+    assert_eq!(
+        to_mdast("😀\t<a>", &options(PositionEncoding::Utf16, true)),
+        Err("1:7: Expected a closing tag for `<a>` (1:4) before the end of `Paragraph` (mdx-jsx:end-tag-mismatch)".into()),
+        "should count UTF-16 code units in errors"
+    );
+
+    assert_eq!(
+        to_mdast("😀 {", &options(PositionEncoding::CodePoint, true)),
+        Err("1:4: Unexpected end of file in expression, expected a corresponding closing brace for `{`".into()),
+        "should count code points in errors"
+    );
+
+    assert_eq!(
+        to_mdast("<a>\n\t😀 </b>", &options(PositionEncoding::Utf16, true)),
+        Err("2:5: Unexpected closing tag `</b>`, expected corresponding closing tag for `<a>` (1:1) (mdx-jsx:end-tag-mismatch)".into()),
+        "should count places after line endings in errors"
+    );
+}
@@ -0,0 +1,149 @@
+use markdown::{
+    mdast::{LinkReference, Node, ReferenceKind, Text},
+    references::{resolve_references, Label},
+    to_markdown, to_mdast,
+    unist::Position,
+    ParseOptions,
+};
+use pretty_assertions::assert_eq;
+
+/// Get the identifiers of labels.
+fn identifiers(labels: &[Label]) -> Vec<(&str, bool)> {
+    labels
+        .iter()
+        .map(|d| (d.identifier.as_str(), d.footnote))
+        .collect()
+}
+
+#[test]
+fn references_definitions() -> Result<(), String> {
+    let tree = to_mdast(
+        "[a] [B][] ![c][b]\n\n[a]: d\n[b]: e 'f'\n[B]: g\n[h]: i",
+        &Default::default(),
+    )?;
+    let references = resolve_references(&tree);
+
+    assert_eq!(
+        references
+            .definitions
+            .iter()
+            .map(|d| (d.identifier.as_str(), d.url.as_str()))
+            .collect::<Vec<_>>(),
+        vec![("a", "d"), ("b", "e"), ("h", "i")],
+        "should get the first definition of each identifier"
+    );
+    assert_eq!(
+        references.definition(" B ").map(|d| d.url.as_str()),
+        Some("e"),
+        "should get definitions by normalized identifier"
+    );
+    assert_eq!(
+        references.undefined,
+        vec![],
+        "should not report defined references"
+    );
+    assert_eq!(
+        references.unused,
+        vec![Label {
+            identifier: "h".into(),
+            footnote: false,
+            position: Some(Position::new(6, 1, 44, 6, 7, 50))
+        }],
+        "should report unused definitions"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn references_undefined() -> Result<(), String> {
+    let mut tree = to_mdast("a\n\n[b]: c", &Default::default())?;
+
+    if let Node::Paragraph(paragraph) = &mut tree.children_mut().unwrap()[0] {
+        paragraph.children.push(Node::LinkReference(LinkReference {
+            children: vec![Node::Text(Text {
+                value: "d".into(),
+                position: None,
+            })],
+            position: None,
+            reference_kind: ReferenceKind::Full,
+            identifier: "d".into(),
+            label: Some("d".into()),
+        }));
+    }
+
+    let references = resolve_references(&tree);
+    assert_eq!(
+        identifiers(&references.undefined),
+        vec![("d", false)],
+        "should report undefined references"
+    );
+    assert_eq!(
+        identifiers(&references.unused),
+        vec![("b", false)],
+        "should report unused definitions when references are missing"
+    );
+
+    references.rewrite(&mut tree);
+    assert_eq!(
+        to_markdown(&tree),
+        "a[d][d]\n\n[b]: c\n",
+        "should not rewrite undefined references"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn references_footnotes() -> Result<(), String> {
+    let tree = to_mdast(
+        "a[^b] c[^d] e[^b]\n\n[^d]: f[^g]\n\n[^b]: h\n\n[^g]: i\n\n[^j]: k[^l]\n\n[^l]: m",
+        &ParseOptions::gfm(),
+    )?;
+    let references = resolve_references(&tree);
+
+    assert_eq!(
+        references
+            .footnote_definitions
+            .iter()
+            .map(|d| d.identifier.as_str())
+            .collect::<Vec<_>>(),
+        vec!["b", "d", "g"],
+        "should get footnote definitions in call order"
+    );
+    assert_eq!(
+        references
+            .footnote_definition("G")
+            .map(|d| d.children[0].to_string()),
+        Some("i".into()),
+        "should get footnote definitions by identifier"
+    );
+    assert_eq!(
+        identifiers(&references.unused),
+        vec![("j", true)],
+        "should report unused footnote definitions"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn references_rewrite() -> Result<(), String> {
+    let mut tree = to_mdast(
+        "[a] and [*b*][a] and ![c][A]\n\n[a]: <d e> \"f\"",
+        &Default::default(),
+    )?;
+    resolve_references(&tree).rewrite(&mut tree);
+
+    assert_eq!(
+        to_markdown(&tree),
+        "[a](<d e> \"f\") and [*b*](<d e> \"f\") and ![c](<d e> \"f\")\n\n[a]: <d e> \"f\"\n",
+        "should turn references into links and images"
+    );
+    assert!(
+        matches!(&tree.children().unwrap()[0].children().unwrap()[0], Node::Link(link) if link.position.is_some()),
+        "should keep positions"
+    );
+
+    Ok(())
+}
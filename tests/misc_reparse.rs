@@ -0,0 +1,250 @@
+use markdown::{reparse, to_mdast, Constructs, Edit, ParseOptions, PositionEncoding};
+use pretty_assertions::assert_eq;
+
+/// Apply `edit` to `value`, and check that the result matches parsing the
+/// whole document again.
+fn check(value: &str, edit: &Edit, options: &ParseOptions) -> Result<(), String> {
+    let tree = to_mdast(value, options)?;
+    let result = reparse(value, tree, edit, options)?;
+    let expected = to_mdast(&result.value, options)?;
+
+    assert_eq!(
+        result.tree, expected,
+        "should match parsing again for {:?} with {:?}",
+        value, edit
+    );
+    assert!(
+        result.changed.end <= expected.children().unwrap().len(),
+        "should report changed nodes in the tree"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn reparse_edits() -> Result<(), String> {
+    let gfm = ParseOptions {
+        constructs: Constructs {
+            frontmatter: true,
+            ..Constructs::gfm()
+        },
+        ..ParseOptions::gfm()
+    };
+    let cases = [
+        "a\n\nb\n\nc",
+        "a\nb\n=\n\nc\n---\n\n***\n",
+        "# a\n## b\n\n    c\n\n    d\ne",
+        "- a\n- b\n\n- c\n\nd\n\n1. e\n\n2. f\n3. g\n",
+        "> a\nb\n\n> c\n\n```js\nd\n\n\ne\n```\nf",
+        "<div>\n\na\n\n</div>\n\n<!--\n\nb\n\n-->\nc",
+        "[a]: b\n\n[a] [c]\n\n[c]: d\n\n[c]",
+        "a[^b]\n\n[^b]: c\n\n    d\n\ne[^b]",
+        "| a |\n| - |\n| b |\nc\n\nd\n| - |",
+        "---\na: b\n---\n\nc\n\n---\nd: e\n---\n",
+        "a\r\n\r\nb\r\n\r\n* c\r\n\r\nd\re\r\rf",
+        "👍\n\n👍👍\n\n👍",
+    ];
+    let inserts = [
+        "", "x", "\n", "\n\n", "=", "> ", "```", "- ", "[c]: e", "\r\n", "---\n",
+    ];
+
+    for value in &cases {
+        let mut start = 0;
+        while start <= value.len() {
+            if value.is_char_boundary(start) {
+                for size in [0, 1, 3] {
+                    let end = start + size;
+                    if end <= value.len() && value.is_char_boundary(end) {
+                        for insert in &inserts {
+                            let edit = Edit {
+                                start,
+                                end,
+                                value: (*insert).into(),
+                            };
+                            check(value, &edit, &gfm)?;
+                        }
+                    }
+                }
+            }
+            start += 1;
+        }
+    }
+
+    let found = [
+        ("[^a]: b\n\tc\n\nd [^a] e.", 21, 21, "x"),
+        ("- e\n```\n", 8, 8, ""),
+        ("a\n---\n===\n    c\n1. i\n", 6, 6, "\n\n"),
+        ("---\n[a]: b\n  f\n====\n", 15, 15, "---\n"),
+        ("> d\n\tj\n    c\n", 4, 4, "\n"),
+    ];
+
+    for (value, start, end, insert) in &found {
+        let edit = Edit {
+            start: *start,
+            end: *end,
+            value: (*insert).into(),
+        };
+        check(value, &edit, &gfm)?;
+    }
+
+    check(
+        "👍👍\n\na\n\nb",
+        &Edit {
+            start: 11,
+            end: 12,
+            value: "c".into(),
+        },
+        &ParseOptions {
+            position_encoding: PositionEncoding::Utf16,
+            ..ParseOptions::default()
+        },
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn reparse_random() -> Result<(), String> {
+    let gfm = ParseOptions {
+        constructs: Constructs {
+            frontmatter: true,
+            math_flow: true,
+            ..Constructs::gfm()
+        },
+        ..ParseOptions::gfm()
+    };
+    let lines = [
+        "",
+        "a",
+        "[a]: b",
+        "    c",
+        "\tc",
+        "  d",
+        "> e",
+        "- f",
+        "1. g",
+        "  - h",
+        "# i",
+        "===",
+        "---",
+        "+++",
+        "```",
+        "$$",
+        "<div>",
+        "</div>",
+        "| j |",
+        "|---|",
+        "[^k]: l",
+        "m [a] [^k].",
+    ];
+    let inserts = [
+        "", "x", "\n", "\n\n", "=", "> ", "```", "- ", "[a]: n", "\r\n", "---\n", "    ", "|",
+    ];
+    // Linear congruential generator, so that the test is the same each time.
+    let mut seed: u64 = 1;
+    let mut random = |max: usize| {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (seed >> 33) as usize % max
+    };
+
+    for options in [ParseOptions::default(), gfm] {
+        let mut count = 0;
+        while count < 2000 {
+            let mut value = String::new();
+            let mut line = random(8) + 1;
+            while line > 0 {
+                value.push_str(lines[random(lines.len())]);
+                value.push('\n');
+                line -= 1;
+            }
+
+            let mut tree = to_mdast(&value, &options)?;
+            let mut edits = 3;
+            while edits > 0 {
+                let start = random(value.len() + 1);
+                let end = (start + random(4)).min(value.len());
+                let edit = Edit {
+                    start,
+                    end,
+                    value: inserts[random(inserts.len())].into(),
+                };
+                let result = reparse(&value, tree, &edit, &options)?;
+
+                assert_eq!(
+                    result.tree,
+                    to_mdast(&result.value, &options)?,
+                    "should match parsing again for {:?} with {:?}",
+                    value,
+                    edit
+                );
+
+                value = result.value;
+                tree = result.tree;
+                edits -= 1;
+            }
+
+            count += 1;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn reparse_reuse() -> Result<(), String> {
+    let options = ParseOptions::default();
+    let value = "a\n\nb\n\nc\n\nd\n\ne";
+    let tree = to_mdast(value, &options)?;
+
+    let result = reparse(
+        value,
+        tree,
+        &Edit {
+            start: 6,
+            end: 7,
+            value: "f\ng".into(),
+        },
+        &options,
+    )?;
+    assert_eq!(result.value, "a\n\nb\n\nf\ng\n\nd\n\ne");
+    assert_eq!(
+        result.changed,
+        1..3,
+        "should only parse nodes around the edit again"
+    );
+
+    let tree = to_mdast(value, &options)?;
+    let result = reparse(
+        value,
+        tree,
+        &Edit {
+            start: 3,
+            end: 3,
+            value: "```\n".into(),
+        },
+        &options,
+    )?;
+    assert_eq!(result.changed, 0..2, "should parse until the end if needed");
+
+    assert_eq!(
+        reparse(
+            "a",
+            to_mdast("a", &options)?,
+            &Edit {
+                start: 2,
+                end: 2,
+                value: "b".into()
+            },
+            &options
+        ),
+        Err(
+            "Cannot apply edit at 2-2: expected character boundaries in a document of 1 bytes"
+                .into()
+        ),
+        "should fail on edits outside the document"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use markdown::{to_html_with_options, CompileOptions, Options, ParseOptions, SanitizeSchema};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn sanitize_html() -> Result<(), String> {
+    let sanitize = Options {
+        parse: ParseOptions::gfm(),
+        compile: CompileOptions {
+            allow_dangerous_html: true,
+            html_sanitize: Some(SanitizeSchema::default()),
+            ..Default::default()
+        },
+    };
+
+    assert_eq!(
+        to_html_with_options(
+            "<div align=\"center\" class=\"a\" onclick=\"b\">\n\n*c*\n\n</div>",
+            &sanitize
+        )?,
+        "<div align=\"center\">\n<p><em>c</em></p>\n</div>",
+        "should keep allowed elements and attributes (flow)"
+    );
+
+    assert_eq!(
+        to_html_with_options("a <kbd style=\"b\">c</kbd> <x-y>d</x-y>", &sanitize)?,
+        "<p>a <kbd>c</kbd> d</p>",
+        "should keep allowed elements and attributes (text)"
+    );
+
+    assert_eq!(
+        to_html_with_options(
+            "<a href=\"javascript:alert(1)\">a</a> <img src=\"jav&#x09;ascript:b\" alt=\"c\">",
+            &sanitize
+        )?,
+        "<p><a>a</a> <img alt=\"c\" /></p>",
+        "should drop dangerous URLs"
+    );
+
+    assert_eq!(
+        to_html_with_options(
+            "<a href=\"https://a?b&amp;c\" title='d\"e'>f</a>",
+            &sanitize
+        )?,
+        "<p><a href=\"https://a?b&amp;c\" title=\"d&quot;e\">f</a></p>",
+        "should keep safe URLs, and encode values"
+    );
+
+    assert_eq!(
+        to_html_with_options("<h2 id=\"a\" name=\"b\">c</h2>", &sanitize)?,
+        "<h2 id=\"user-content-a\" name=\"user-content-b\">c</h2>",
+        "should clobber `id` and `name`"
+    );
+
+    assert_eq!(
+        to_html_with_options(
+            "<script>\nalert(1)\n</script>\n<style>a{}</style>",
+            &sanitize
+        )?,
+        "\n",
+        "should strip `script` and `style`"
+    );
+
+    assert_eq!(
+        to_html_with_options("<!-- a -->\n\nb <!--c--> <?d?> <![CDATA[e]]>", &sanitize)?,
+        "\n<p>b   </p>",
+        "should drop comments, instructions, and CDATA"
+    );
+
+    assert_eq!(
+        to_html_with_options("a <b>c\n\nd</b>", &sanitize)?,
+        "<p>a <b>c</b></p>\n<p>d</p>",
+        "should close elements at the end of paragraphs"
+    );
+
+    assert_eq!(
+        to_html_with_options("> <details>\n> a\n\nb", &sanitize)?,
+        "<blockquote>\n<details>\na</details>\n</blockquote>\n<p>b</p>",
+        "should close elements at the end of containers"
+    );
+
+    assert_eq!(
+        to_html_with_options("# <sup>a\n\n| <sub>b |\n| - |", &sanitize)?,
+        "<h1><sup>a</sup></h1>\n<table>\n<thead>\n<tr>\n<th><sub>b</sub></th>\n</tr>\n</thead>\n</table>",
+        "should close elements at the end of headings and cells"
+    );
+
+    assert_eq!(
+        to_html_with_options("*<b>a* **<i>b** ~~<sub>c~~", &sanitize)?,
+        "<p><em><b>a</b></em> <strong><i>b</i></strong> <del><sub>c</sub></del></p>",
+        "should close elements at the end of emphasis, strong, and delete"
+    );
+
+    assert_eq!(
+        to_html_with_options("[<b>a](x) [*<i>b*][c]\n\n[c]: y", &sanitize)?,
+        "<p><a href=\"x\"><b>a</b></a> <a href=\"y\"><em><i>b</i></em></a></p>\n",
+        "should close elements at the end of link labels"
+    );
+
+    assert_eq!(
+        to_html_with_options("<b>*a</b>*", &sanitize)?,
+        "<p><b><em>a</em></b></p>",
+        "should not close elements outside of emphasis from inside it"
+    );
+
+    assert_eq!(
+        to_html_with_options("<div>\n<p>\n\na</div>", &sanitize)?,
+        "<div>\n<p>\n<p>a</p></p></div>",
+        "should not close elements outside of paragraphs from inside them"
+    );
+
+    assert_eq!(
+        to_html_with_options("<ul>\n<li>a\n</ul>", &sanitize)?,
+        "<ul>\n<li>a\n</li></ul>",
+        "should close elements opened in a closed element"
+    );
+
+    assert_eq!(
+        to_html_with_options("![a <b title=\"c\">d</b>](e)", &sanitize)?,
+        "<p><img src=\"e\" alt=\"a d\" /></p>",
+        "should drop HTML in image alt"
+    );
+
+    assert_eq!(
+        to_html_with_options("a <b c=\"d\" c=\"e\" title f g=h>i", &sanitize)?,
+        "<p>a <b title=\"\">i</b></p>",
+        "should support attributes without value, and drop duplicates"
+    );
+
+    assert_eq!(
+        to_html_with_options(
+            "<span class=\"a\">b",
+            &Options {
+                compile: CompileOptions {
+                    allow_dangerous_html: true,
+                    html_sanitize: Some(SanitizeSchema {
+                        attributes: vec![("span".into(), vec!["class".into()])],
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        )?,
+        "<p><span class=\"a\">b</span></p>",
+        "should support a custom schema"
+    );
+
+    assert_eq!(
+        to_html_with_options(
+            "<b onclick=\"a\">",
+            &Options {
+                compile: CompileOptions {
+                    html_sanitize: Some(SanitizeSchema::default()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        )?,
+        "&lt;b onclick=&quot;a&quot;&gt;",
+        "should not do anything without `allow_dangerous_html`"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use markdown::{
+    mdast::Node,
+    select::{select, select_all},
+    to_mdast, ParseOptions,
+};
+use pretty_assertions::assert_eq;
+
+/// Get the text of all nodes that match `selector`.
+fn all(tree: &Node, selector: &str) -> Result<Vec<String>, String> {
+    Ok(select_all(tree, selector)?
+        .iter()
+        .map(|d| d.to_string())
+        .collect())
+}
+
+#[test]
+fn select_types_and_attributes() -> Result<(), String> {
+    let tree = to_mdast(
+        "# a\n\n## b\n\n```js x y\nc\n```\n\n1. [x] d\n2. [ ] e\n3. f\n\n[g](https://h.md) ![i](j.png)",
+        &ParseOptions::gfm(),
+    )?;
+
+    assert_eq!(
+        all(&tree, "heading")?,
+        vec!["a", "b"],
+        "should support types"
+    );
+    assert_eq!(
+        all(&tree, "heading[depth=2]")?,
+        vec!["b"],
+        "should support numeric fields"
+    );
+    assert_eq!(
+        all(&tree, "code[lang=\"js\"]")?,
+        vec!["c"],
+        "should support quoted values"
+    );
+    assert_eq!(
+        all(&tree, "code[meta~=y], code[meta~=z]")?,
+        vec!["c"],
+        "should support `~=`"
+    );
+    assert_eq!(
+        all(&tree, "list[ordered=true] > listItem[checked]")?,
+        vec!["d", "e"],
+        "should support checking whether fields exist"
+    );
+    assert_eq!(
+        all(&tree, "listItem[checked=false]")?,
+        vec!["e"],
+        "should support boolean fields"
+    );
+    assert_eq!(
+        all(&tree, "[url^=https][url$='.md'], image[url*=png]")?,
+        vec!["g", ""],
+        "should support `^=`, `$=`, `*=`, and lists"
+    );
+    assert_eq!(
+        all(&tree, "root")?.len(),
+        1,
+        "should support matching the tree itself"
+    );
+    assert_eq!(all(&tree, "paragraph > *")?.len(), 6, "should support `*`");
+
+    Ok(())
+}
+
+#[test]
+fn select_combinators_and_pseudos() -> Result<(), String> {
+    let tree = to_mdast(
+        "# Intro\n\na [b](c)\n\n## Install\n\nd [e](f)\n\ng [h](i)\n\n> j\n>\n> k\n\n- l\n- m\n- n\n- o",
+        &Default::default(),
+    )?;
+
+    assert_eq!(
+        select(&tree, "heading[depth=2] + paragraph link")?.map(ToString::to_string),
+        Some("e".into()),
+        "should support `+` and descendants"
+    );
+    assert_eq!(
+        all(&tree, "heading[depth=2] ~ paragraph")?,
+        vec!["d e", "g h"],
+        "should support `~`"
+    );
+    assert_eq!(
+        all(
+            &tree,
+            "blockquote > :first-child, blockquote > paragraph:last-child"
+        )?,
+        vec!["j", "k"],
+        "should support `:first-child`, `:last-child`"
+    );
+    assert_eq!(
+        all(&tree, "listItem:nth-child(odd)")?,
+        vec!["l", "n"],
+        "should support `:nth-child(odd)`"
+    );
+    assert_eq!(
+        all(&tree, "listItem:nth-child(-n + 2)")?,
+        vec!["l", "m"],
+        "should support `:nth-child(an+b)`"
+    );
+    assert_eq!(
+        all(&tree, "listItem:nth-last-child(1)")?,
+        vec!["o"],
+        "should support `:nth-last-child`"
+    );
+    assert_eq!(
+        all(&tree, "paragraph:has(link):not(:has(> link[url=c]))")?,
+        vec!["d e", "g h"],
+        "should support `:has()` and `:not()`"
+    );
+    assert_eq!(
+        all(
+            &tree,
+            "listItem:has(> paragraph > text:only-child):is(:first-child)"
+        )?,
+        vec!["l"],
+        "should support `:is()` and `:only-child`"
+    );
+    assert_eq!(
+        all(&tree, ":root > heading")?,
+        vec!["Intro", "Install"],
+        "should support `:root`"
+    );
+    assert_eq!(
+        select(&tree, "table")?,
+        None,
+        "should return `None` if nothing matches"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn select_errors() {
+    let tree = to_mdast("a", &Default::default()).unwrap();
+
+    assert_eq!(
+        select(&tree, "").unwrap_err(),
+        "Unexpected end of selector, expected a type, `*`, `[`, or `:`",
+        "should error on empty selectors"
+    );
+    assert_eq!(
+        select(&tree, "a >").unwrap_err(),
+        "Unexpected end of selector, expected a type, `*`, `[`, or `:`",
+        "should error on trailing combinators"
+    );
+    assert_eq!(
+        select(&tree, "a[b=\"c]").unwrap_err(),
+        "Unexpected end of selector, expected `\"`",
+        "should error on unclosed strings"
+    );
+    assert_eq!(
+        select(&tree, "a:b").unwrap_err(),
+        "Unexpected unknown pseudo-class `:b` at 1 in selector",
+        "should error on unknown pseudo-classes"
+    );
+    assert_eq!(
+        select(&tree, "a:has(b").unwrap_err(),
+        "Unexpected end of selector, expected `)`",
+        "should error on unclosed pseudo-classes"
+    );
+    assert_eq!(
+        select(&tree, "a:nth-child(x)").unwrap_err(),
+        "Unexpected `x` at 12 in selector, expected `an+b`, `odd`, or `even`",
+        "should error on invalid `an+b`"
+    );
+    assert_eq!(
+        select(&tree, "a ]").unwrap_err(),
+        "Unexpected `]` at 2 in selector, expected a type, `*`, `[`, or `:`",
+        "should error on unexpected characters"
+    );
+}
@@ -0,0 +1,63 @@
+use markdown::{to_html_with_options, CompileOptions, Options, ParseOptions};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn sourcepos() -> Result<(), String> {
+    let sourcepos = Options {
+        parse: ParseOptions::gfm(),
+        compile: CompileOptions {
+            sourcepos: true,
+            ..Default::default()
+        },
+    };
+
+    assert_eq!(
+        to_html_with_options("a\nb", &Options::default())?,
+        "<p>a\nb</p>",
+        "should not add positions by default"
+    );
+
+    assert_eq!(
+        to_html_with_options("a *b*\nc", &sourcepos)?,
+        "<p data-sourcepos=\"1:1-2:1\">a <em>b</em>\nc</p>",
+        "should add positions to paragraphs"
+    );
+
+    assert_eq!(
+        to_html_with_options("# a #\n\nb\n---", &sourcepos)?,
+        "<h1 data-sourcepos=\"1:1-1:5\">a</h1>\n<h2 data-sourcepos=\"3:1-4:3\">b</h2>",
+        "should add positions to headings"
+    );
+
+    assert_eq!(
+        to_html_with_options("> a\n> b\n\n***", &sourcepos)?,
+        "<blockquote data-sourcepos=\"1:1-2:3\">\n<p data-sourcepos=\"1:3-2:3\">a\nb</p>\n</blockquote>\n<hr data-sourcepos=\"4:1-4:3\" />",
+        "should add positions to block quotes and thematic breaks"
+    );
+
+    assert_eq!(
+        to_html_with_options("    a\n\n```js\nb\n```", &sourcepos)?,
+        "<pre data-sourcepos=\"1:1-1:5\"><code>a\n</code></pre>\n<pre data-sourcepos=\"3:1-5:3\"><code class=\"language-js\">b\n</code></pre>",
+        "should add positions to code"
+    );
+
+    assert_eq!(
+        to_html_with_options("- a\n- b\n\n3. c", &sourcepos)?,
+        "<ul data-sourcepos=\"1:1-2:3\">\n<li data-sourcepos=\"1:1-1:3\">a</li>\n<li data-sourcepos=\"2:1-2:3\">b</li>\n</ul>\n<ol data-sourcepos=\"4:1-4:4\" start=\"3\">\n<li data-sourcepos=\"4:1-4:4\">c</li>\n</ol>",
+        "should add positions to lists and items"
+    );
+
+    assert_eq!(
+        to_html_with_options("- a\r\n\r\n  b\r\n\r\nc", &sourcepos)?,
+        "<ul data-sourcepos=\"1:1-3:3\">\r\n<li data-sourcepos=\"1:1-3:3\">\r\n<p data-sourcepos=\"1:3-1:3\">a</p>\r\n<p data-sourcepos=\"3:3-3:3\">b</p>\r\n</li>\r\n</ul>\r\n<p data-sourcepos=\"5:1-5:1\">c</p>",
+        "should end before line endings"
+    );
+
+    assert_eq!(
+        to_html_with_options("| a |\n| - |\n| b |", &sourcepos)?,
+        "<table data-sourcepos=\"1:1-3:5\">\n<thead>\n<tr data-sourcepos=\"1:1-1:5\">\n<th data-sourcepos=\"1:1-1:5\">a</th>\n</tr>\n</thead>\n<tbody>\n<tr data-sourcepos=\"3:1-3:5\">\n<td data-sourcepos=\"3:1-3:5\">b</td>\n</tr>\n</tbody>\n</table>",
+        "should add positions to tables"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,323 @@
+use markdown::{
+    mdast::{Emphasis, InlineCode, Node, Paragraph, Root, Text},
+    to_markdown, to_mdast, Constructs, ParseOptions,
+};
+use pretty_assertions::assert_eq;
+
+/// Remove positions from a tree.
+fn strip(node: &mut Node) {
+    node.position_set(None);
+
+    if let Some(children) = node.children_mut() {
+        for child in children {
+            strip(child);
+        }
+    }
+}
+
+/// Check that `value` turns into `expected`, which parses into the same tree.
+fn check(value: &str, expected: &str, options: &ParseOptions) -> Result<(), String> {
+    let mut tree = to_mdast(value, options)?;
+    let result = to_markdown(&tree);
+    assert_eq!(result, expected, "should serialize {:?}", value);
+
+    let mut again = to_mdast(&result, options)?;
+    strip(&mut tree);
+    strip(&mut again);
+    assert_eq!(again, tree, "should round-trip {:?}", value);
+    Ok(())
+}
+
+#[test]
+fn to_markdown_flow() -> Result<(), String> {
+    let options = ParseOptions::default();
+
+    assert_eq!(
+        to_markdown(&to_mdast("", &options)?),
+        "",
+        "should support an empty document"
+    );
+
+    check(
+        "Alpha\nbravo\n\n***\n\n    charlie",
+        "Alpha\nbravo\n\n***\n\n```\ncharlie\n```\n",
+        &options,
+    )?;
+
+    check(
+        "# a #\n\nb\n=\n\n###### #\n\n#",
+        "# a\n\n# b\n\n######\n\n#\n",
+        &options,
+    )?;
+
+    check("a\nb\n=", "a\nb\n===\n", &options)?;
+    check("## a \\#", "## a \\#\n", &options)?;
+
+    check("> a\n>\n> > b\n\nc", "> a\n>\n> > b\n\nc\n", &options)?;
+
+    check(
+        "* a\n* b\n\n    c\n* d\n\n3) e\n\n1. f\n2. g",
+        "- a\n- b\n\n  c\n- d\n\n3. e\n\n1) f\n2) g\n",
+        &options,
+    )?;
+
+    check("- a\n\n- b\n\n-", "- a\n\n- b\n\n-\n", &options)?;
+
+    check("- - *", "- - +\n", &options)?;
+    check("* - -", "- - +\n", &options)?;
+    check("- - - a\n  - b", "- - + a\n  - b\n", &options)?;
+
+    check(
+        "~~~js eval\n```\n~~~\n\n~~~ a`b\n~~~",
+        "````js eval\n```\n````\n\n~~~a`b\n~~~\n",
+        &options,
+    )?;
+
+    check(
+        "```\\&amp; a\\\\b&amp;\n```",
+        "```\\&amp; a\\\\b\\&\n```\n",
+        &options,
+    )?;
+
+    check(
+        "[a]: <b c> 'd\"'\n[e]: f(g) (h)",
+        "[a]: <b c> \"d\\\"\"\n\n[e]: f\\(g\\) \"h\"\n",
+        &options,
+    )?;
+
+    check(
+        "<div>\n*a*\n</div>\n\nb",
+        "<div>\n*a*\n</div>\n\nb\n",
+        &options,
+    )?;
+
+    check("- a\n\n\n- b", "- a\n\n\n\n- b\n", &options)?;
+    check("- a\n\n\nb", "- a\n\n\n\nb\n", &options)?;
+    check("- a\n\n\n", "- a\n\n\n", &options)?;
+
+    check("a\rb\n===", "a\rb\n===\n", &options)?;
+    check("> a\r    2) ", "> a\r> 2\\)\n", &options)?;
+    check("a\r\t*b*\r c", "a\r*b*\rc\n", &options)?;
+    check("```\r\ra\r\r```", "```\n\ra\r\r```\n", &options)?;
+
+    Ok(())
+}
+
+#[test]
+fn to_markdown_text() -> Result<(), String> {
+    let options = ParseOptions::default();
+
+    check(
+        "a \\* b \\_ c \\[d\\] \\`e\\` \\<f> &amp;",
+        "a \\* b \\_ c \\[d\\] \\`e\\` \\<f> \\&\n",
+        &options,
+    )?;
+
+    check(
+        "\\# a\n\\> b\n\\- c\n1\\. d\n2\\) e\n\\+ f",
+        "\\# a\n\\> b\n\\- c\n1\\. d\n2\\) e\n\\+ f\n",
+        &options,
+    )?;
+
+    check("a # b > c - d 1. e", "a # b > c - d 1. e\n", &options)?;
+
+    check("&#32;a&#9;\nb&#x20;", "&#x20;a&#x9;\nb&#x20;\n", &options)?;
+
+    check("a&#10;&#10;b", "a\n&#xA;b\n", &options)?;
+
+    check(
+        "*a* __b__ ***c*** *d **e** f* _*g*_",
+        "*a* **b** ***c*** *d **e** f* _*g*_\n",
+        &options,
+    )?;
+
+    check("*&#32;a&#32;*", "*&#x20;a&#x20;*\n", &options)?;
+
+    check("=*`a*|", "&#x3D;*\\`a*|\n", &options)?;
+    check("$**~1. b**  ~", "&#x24;**\\~1. b**  \\~\n", &options)?;
+    check("*a-*\\|", "*a-*\\|\n", &options)?;
+    check("_a_*b* __c__**d**", "*a*_b_ **c**__d__\n", &options)?;
+
+    check("`a` `` ` `` `` b` ``", "`a` `` ` `` `` b` ``\n", &options)?;
+
+    check("a\\\nb  \nc", "a\\\nb\\\nc\n", &options)?;
+
+    check(
+        "[a](b 'c') ![d](<e f>) <https://g> <h@i.j>",
+        "[a](b \"c\") ![d](<e f>) <https://g> <h@i.j>\n",
+        &options,
+    )?;
+
+    check(
+        "[a][] [b] [c][A] ![a] \\[d]\n\n[a]: e\n[b]: f\n[c]: g",
+        "[a][] [b] [c][A] ![a] \\[d\\]\n\n[a]: e\n\n[b]: f\n\n[c]: g\n",
+        &options,
+    )?;
+
+    check("\\![a]\n\n[a]: b", "\\![a]\n\n[a]: b\n", &options)?;
+
+    check(
+        "[a\\]b][] [c *d*]\n\n[a\\]b]: e\n[c *d*]: f",
+        "[a\\]b][] [c *d*]\n\n[a\\]b]: e\n\n[c *d*]: f\n",
+        &options,
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn to_markdown_references() -> Result<(), String> {
+    let options = ParseOptions::default();
+
+    check(
+        "[foo*]: /url\n\n*[foo*]",
+        "[foo*]: /url\n\n\\*[foo*]\n",
+        &options,
+    )?;
+
+    check(
+        "![*foo* Bar][]\n\n[*foo* bar]: /url",
+        "![*foo* Bar][]\n\n[*foo* bar]: /url\n",
+        &options,
+    )?;
+
+    check(
+        "[\\[\\+\\]] [a&#91;b&copy;]\n\n[\\[\\+\\]]: c\n[a&#91;b&copy;]: d",
+        "[\\[\\+\\]] [a&#91;b&copy;]\n\n[\\[\\+\\]]: c\n\n[a&#91;b&copy;]: d\n",
+        &options,
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn to_markdown_commonmark() -> Result<(), String> {
+    let options = ParseOptions::default();
+    // Inputs of the examples in `commonmark.rs`.
+    let source = include_str!("commonmark.rs");
+    let start = "to_html_with_options(\n            r";
+    let mut rest = source;
+    let mut count = 0;
+
+    while let Some(index) = rest.find(start) {
+        rest = &rest[index + start.len()..];
+        let hashes = rest.find('"').unwrap();
+        rest = &rest[hashes + 1..];
+        let value = &rest[..rest.find(&format!("\"{}", "#".repeat(hashes))).unwrap()];
+        let mut tree = to_mdast(value, &options)?;
+        let result = to_markdown(&tree);
+        let mut again = to_mdast(&result, &options)?;
+        strip(&mut tree);
+        strip(&mut again);
+        assert_eq!(again, tree, "should round-trip {:?} ({:?})", value, result);
+        count += 1;
+    }
+
+    assert!(count > 600, "should find the examples");
+
+    Ok(())
+}
+
+#[test]
+fn to_markdown_extensions() -> Result<(), String> {
+    let options = ParseOptions {
+        constructs: Constructs {
+            frontmatter: true,
+            math_flow: true,
+            math_text: true,
+            ..Constructs::gfm()
+        },
+        ..ParseOptions::gfm()
+    };
+
+    check("---\na: b\n---\n\nc", "---\na: b\n---\n\nc\n", &options)?;
+    check("+++\na = \"b\"\n+++", "+++\na = \"b\"\n+++\n", &options)?;
+
+    check(
+        "| a | b | c | d |\n| :- | -: | :-: | - |\n| `e\\|f` | g\\|h |",
+        "| a      |    b |  c  | d   |\n| :----- | ---: | :-: | --- |\n| `e\\|f` | g\\|h |\n",
+        &options,
+    )?;
+
+    check("a | b\n\\- | -", "a | b\n\\- | -\n", &options)?;
+
+    check("* [x] a\n* [ ] b\n* c", "- [x] a\n- [ ] b\n- c\n", &options)?;
+
+    check(
+        "~a~ ~~b~~ www.c.com https://d.com e@f.com",
+        "~~a~~ ~~b~~ www.c.com <https://d.com> <e@f.com>\n",
+        &options,
+    )?;
+
+    check(
+        "www.c.d~ www.c.d> a@b.c_ (www.c.d). [www.c.d](e)",
+        "[www\\.c.d](http://www.c.d)\\~ [www\\.c.d>](http://www.c.d>) a\\@b.c\\_ (www.c.d). [www.c.d](e)\n",
+        &options,
+    )?;
+
+    check(
+        "www\\.c.d http\\://e.f g\\@h.i j@k_.l",
+        "www\\.c.d http\\://e.f g\\@h.i [j\\@k\\_.l](mailto:j@k_.l)\n",
+        &options,
+    )?;
+
+    check("$$\\&amp;\nm\n$$", "$$\\&amp;\nm\n$$\n", &options)?;
+
+    check(
+        "a[^b].\n\n[^b]: c\n\n    d",
+        "a[^b].\n\n[^b]: c\n\n    d\n",
+        &options,
+    )?;
+
+    check(
+        "$a$ $$`$$\n\n$$ b\nc\n$$",
+        "$a$ $`$\n\n$$b\nc\n$$\n",
+        &options,
+    )?;
+
+    let mdx = ParseOptions::mdx();
+
+    check(
+        "import a from 'b'\n\n<A b=\"c\" d={e} {...f} g>\n  *h*\n</A>\n\n{1 + 1} <B /> <>i</>",
+        "import a from 'b'\n\n<A b=\"c\" d={e} {...f} g>\n  *h*\n</A>\n\n{1 + 1} <B /> <>i</>\n",
+        &mdx,
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn to_markdown_trees() {
+    assert_eq!(
+        to_markdown(&Node::Root(Root {
+            children: vec![Node::Paragraph(Paragraph {
+                children: vec![
+                    Node::Emphasis(Emphasis {
+                        children: vec![Node::Text(Text {
+                            value: " a".into(),
+                            position: None
+                        })],
+                        position: None
+                    }),
+                    Node::InlineCode(InlineCode {
+                        value: "`".into(),
+                        position: None
+                    })
+                ],
+                position: None
+            })],
+            position: None
+        })),
+        "*&#x20;a*`` ` ``\n",
+        "should serialize trees that were not parsed"
+    );
+
+    assert_eq!(
+        to_markdown(&Node::Text(Text {
+            value: "a".into(),
+            position: None
+        })),
+        "a\n",
+        "should serialize nodes other than roots"
+    );
+}
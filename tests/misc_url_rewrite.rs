@@ -0,0 +1,108 @@
+use markdown::{
+    mdast::{Definition, Node, Root},
+    to_html_with_options, to_mdast, CompileOptions, Options, ParseOptions, UrlKind,
+};
+use pretty_assertions::assert_eq;
+use std::sync::Arc;
+
+#[test]
+fn url_rewrite() -> Result<(), String> {
+    let debug = Options {
+        parse: ParseOptions::gfm(),
+        compile: CompileOptions {
+            url_rewrite: Some(Arc::new(|url, kind| Some(format!("{:?}/{}", kind, url)))),
+            ..Default::default()
+        },
+    };
+
+    let docs = Options {
+        compile: CompileOptions {
+            url_rewrite: Some(Arc::new(|url, kind| {
+                if *kind == UrlKind::Image {
+                    Some(format!("https://cdn.example.com/{}", url))
+                } else {
+                    url.strip_suffix(".md").map(|stem| format!("{}.html", stem))
+                }
+            })),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    assert_eq!(
+        to_html_with_options("[a](b)", &debug)?,
+        "<p><a href=\"Link/b\">a</a></p>",
+        "should rewrite links"
+    );
+
+    assert_eq!(
+        to_html_with_options("![a](b)", &debug)?,
+        "<p><img src=\"Image/b\" alt=\"a\" /></p>",
+        "should rewrite images"
+    );
+
+    assert_eq!(
+        to_html_with_options("[a] ![b][a]\n\n[a]: c", &debug)?,
+        "<p><a href=\"Definition/c\">a</a> <img src=\"Definition/c\" alt=\"b\" /></p>\n",
+        "should rewrite definitions"
+    );
+
+    assert_eq!(
+        to_html_with_options("<https://a> <b@c.d>", &debug)?,
+        "<p><a href=\"Autolink/https://a\">https://a</a> <a href=\"Autolink/mailto:b@c.d\">b@c.d</a></p>",
+        "should rewrite autolinks"
+    );
+
+    assert_eq!(
+        to_html_with_options("www.a.com b@c.d https://e", &debug)?,
+        "<p><a href=\"Autolink/http://www.a.com\">www.a.com</a> <a href=\"Autolink/mailto:b@c.d\">b@c.d</a> <a href=\"Autolink/https://e\">https://e</a></p>",
+        "should rewrite GFM autolink literals"
+    );
+
+    assert_eq!(
+        to_html_with_options("[a](<> \"b\")", &debug)?,
+        "<p><a href=\"\" title=\"b\">a</a></p>",
+        "should not rewrite missing destinations"
+    );
+
+    assert_eq!(
+        to_html_with_options("[a](b&amp;c.md) ![d](e.png) [f](https://g)", &docs)?,
+        "<p><a href=\"b&amp;c.html\">a</a> <img src=\"https://cdn.example.com/e.png\" alt=\"d\" /> <a href=\"https://g\">f</a></p>",
+        "should rewrite decoded URLs, and keep them if `None`"
+    );
+
+    assert_eq!(
+        to_html_with_options(
+            "[a](b)",
+            &Options {
+                compile: CompileOptions {
+                    url_rewrite: Some(Arc::new(|_url, _kind| Some("javascript:alert(1)".into()))),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        )?,
+        "<p><a href=\"\">a</a></p>",
+        "should sanitize rewritten URLs"
+    );
+
+    let mut tree = to_mdast("[a]: b.md", &ParseOptions::default())?;
+    tree.rewrite_urls(&|url, _kind| url.strip_suffix(".md").map(|stem| format!("{}.html", stem)));
+
+    assert_eq!(
+        tree,
+        Node::Root(Root {
+            children: vec![Node::Definition(Definition {
+                url: "b.html".into(),
+                title: None,
+                identifier: "a".into(),
+                label: Some("a".into()),
+                position: Some(markdown::unist::Position::new(1, 1, 0, 1, 10, 9))
+            })],
+            position: Some(markdown::unist::Position::new(1, 1, 0, 1, 10, 9))
+        }),
+        "should rewrite URLs in mdast"
+    );
+
+    Ok(())
+}
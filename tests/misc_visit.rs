@@ -0,0 +1,208 @@
+use markdown::{
+    mdast::{Node, Text},
+    to_markdown, to_mdast,
+    visit::{visit, visit_mut, Action, ActionMut, Visitor, VisitorMut},
+};
+use pretty_assertions::assert_eq;
+
+/// Name of a node, such as `heading`.
+fn name(node: &Node) -> String {
+    let debug = format!("{:?}", node);
+    let name = &debug[..debug.find(' ').unwrap_or(debug.len())];
+    let mut chars = name.chars();
+    let first = chars.next().unwrap().to_ascii_lowercase();
+    format!("{}{}", first, chars.as_str())
+}
+
+/// Visitor that logs what it enters and exits.
+struct Log {
+    events: Vec<String>,
+    enter: fn(&Node) -> Action,
+}
+
+impl Visitor for Log {
+    fn enter(&mut self, node: &Node, ancestors: &[&Node], index: Option<usize>) -> Action {
+        self.events.push(format!(
+            "enter {} {} {:?}",
+            name(node),
+            ancestors.len(),
+            index
+        ));
+        (self.enter)(node)
+    }
+
+    fn exit(&mut self, node: &Node, _: &[&Node], _: Option<usize>) -> Action {
+        self.events.push(format!("exit {}", name(node)));
+        Action::Continue
+    }
+}
+
+#[test]
+fn visit_order() -> Result<(), String> {
+    let tree = to_mdast("# a *b*\n\nc", &Default::default())?;
+
+    let mut log = Log {
+        events: vec![],
+        enter: |_| Action::Continue,
+    };
+    visit(&tree, &mut log);
+    assert_eq!(
+        log.events,
+        vec![
+            "enter root 0 None",
+            "enter heading 1 Some(0)",
+            "enter text 2 Some(0)",
+            "exit text",
+            "enter emphasis 2 Some(1)",
+            "enter text 3 Some(0)",
+            "exit text",
+            "exit emphasis",
+            "exit heading",
+            "enter paragraph 1 Some(1)",
+            "enter text 2 Some(0)",
+            "exit text",
+            "exit paragraph",
+            "exit root"
+        ],
+        "should enter and exit nodes depth-first, with ancestors and indices"
+    );
+
+    let mut log = Log {
+        events: vec![],
+        enter: |node| match node {
+            Node::Heading(_) => Action::Skip,
+            _ => Action::Continue,
+        },
+    };
+    visit(&tree, &mut log);
+    assert_eq!(
+        log.events[1..4],
+        vec![
+            "enter heading 1 Some(0)",
+            "exit heading",
+            "enter paragraph 1 Some(1)"
+        ],
+        "should support `Action::Skip`"
+    );
+
+    let mut log = Log {
+        events: vec![],
+        enter: |node| match node {
+            Node::Emphasis(_) => Action::Exit,
+            _ => Action::Continue,
+        },
+    };
+    visit(&tree, &mut log);
+    assert_eq!(
+        log.events.last().unwrap(),
+        "enter emphasis 2 Some(1)",
+        "should support `Action::Exit`"
+    );
+
+    let mut texts = vec![];
+    let mut collect = |node: &Node, ancestors: &[&Node], _: Option<usize>| {
+        if let Node::Text(x) = node {
+            let parent = ancestors[ancestors.len() - 1];
+            texts.push(format!("{}:{}", x.value, name(parent)));
+        }
+        Action::Continue
+    };
+    visit(&tree, &mut collect);
+    assert_eq!(
+        texts,
+        vec!["a :heading", "b:emphasis", "c:paragraph"],
+        "should support closures"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn visit_mut_changes() -> Result<(), String> {
+    let mut tree = to_mdast("a *b* c\n\n- d\n- e\n\n***\n\nf", &Default::default())?;
+    visit_mut(&mut tree, &mut |node: &mut Node, _: &[usize]| match node {
+        Node::ThematicBreak(_) | Node::Emphasis(_) => ActionMut::Remove,
+        _ => ActionMut::Continue,
+    });
+    assert_eq!(
+        to_markdown(&tree),
+        "a  c\n\n- d\n- e\n\nf\n",
+        "should support `ActionMut::Remove`"
+    );
+
+    let mut tree = to_mdast("a *b* c", &Default::default())?;
+    let mut values = vec![];
+    visit_mut(&mut tree, &mut |node: &mut Node, _: &[usize]| match node {
+        Node::Emphasis(x) => ActionMut::Replace(Node::Text(Text {
+            value: x.children[0].to_string().to_uppercase(),
+            position: None,
+        })),
+        Node::Text(x) => {
+            values.push(x.value.clone());
+            ActionMut::Continue
+        }
+        _ => ActionMut::Continue,
+    });
+    assert_eq!(
+        values,
+        vec!["a ", " c"],
+        "should not visit replacements as if they were entered"
+    );
+    assert_eq!(
+        to_markdown(&tree),
+        "a B c\n",
+        "should support `ActionMut::Replace`"
+    );
+
+    let mut tree = to_mdast("> a\n\nb\n\nc", &Default::default())?;
+    let mut paths = vec![];
+    visit_mut(&mut tree, &mut |node: &mut Node, path: &[usize]| {
+        if let Node::Paragraph(_) = node {
+            paths.push(path.to_vec());
+            return ActionMut::Exit;
+        }
+        ActionMut::Continue
+    });
+    assert_eq!(
+        paths,
+        vec![vec![0, 0]],
+        "should support paths and `ActionMut::Exit`"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn visit_mut_exit() -> Result<(), String> {
+    /// Unwrap emphasis on exit, after its children are changed.
+    struct Unwrap;
+
+    impl VisitorMut for Unwrap {
+        fn enter(&mut self, node: &mut Node, _: &[usize]) -> ActionMut {
+            if let Node::Text(x) = node {
+                x.value = x.value.to_uppercase();
+            }
+            ActionMut::Continue
+        }
+
+        fn exit(&mut self, node: &mut Node, _: &[usize]) -> ActionMut {
+            if let Node::Emphasis(x) = node {
+                return ActionMut::Replace(x.children.remove(0));
+            }
+            if let Node::InlineCode(_) = node {
+                return ActionMut::Remove;
+            }
+            ActionMut::Continue
+        }
+    }
+
+    let mut tree = to_mdast("a *b* `c` d", &Default::default())?;
+    visit_mut(&mut tree, &mut Unwrap);
+    assert_eq!(
+        to_markdown(&tree),
+        "A B  D\n",
+        "should support changes when exiting"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use core::fmt;
+use markdown::{to_html_into, to_html_with_options, CompileOptions, Options, SanitizeSchema};
+use pretty_assertions::assert_eq;
+
+/// Writer that collects chunks.
+#[derive(Default)]
+struct Chunks(Vec<String>);
+
+impl fmt::Write for Chunks {
+    fn write_str(&mut self, value: &str) -> fmt::Result {
+        self.0.push(value.into());
+        Ok(())
+    }
+}
+
+/// Writer that always fails.
+struct Failing;
+
+impl fmt::Write for Failing {
+    fn write_str(&mut self, _value: &str) -> fmt::Result {
+        Err(fmt::Error)
+    }
+}
+
+#[test]
+fn write() -> Result<(), String> {
+    let sanitize = Options {
+        compile: CompileOptions {
+            allow_dangerous_html: true,
+            html_sanitize: Some(SanitizeSchema::default()),
+            ..Default::default()
+        },
+        ..Options::gfm()
+    };
+    let cases = [
+        ("", Options::default()),
+        ("a", Options::default()),
+        (
+            "# a\n\nb\n\n* c\n* d\n\n> e\n\n```\nf\n```\n",
+            Options::default(),
+        ),
+        ("a\r\n\r\nb\r\n", Options::default()),
+        ("[a]\n\n[a]: b\n\nc", Options::default()),
+        ("a[^b]\n\n[^b]: c\n\nd", Options::gfm()),
+        ("| a |\n| - |\n| b |\n\nc", Options::gfm()),
+        ("<div>\n\na\n\n</div>\n\n<b>c", sanitize),
+        ("a 👍\n\nb 👍", Options::default()),
+    ];
+
+    for (value, options) in &cases {
+        let mut result = String::new();
+        to_html_into(value, options, &mut result)?;
+        assert_eq!(
+            result,
+            to_html_with_options(value, options)?,
+            "should match `to_html_with_options` for {:?}",
+            value
+        );
+    }
+
+    let mut chunks = Chunks::default();
+    to_html_into("# a\n\nb\n\n* c", &Options::default(), &mut chunks)?;
+    assert_eq!(
+        chunks.0,
+        vec![
+            "<h1>a</h1>",
+            "\n",
+            "<p>b</p>",
+            "\n",
+            "<ul>\n<li>c</li>\n</ul>"
+        ],
+        "should write completed top-level blocks"
+    );
+
+    let mut chunks = Chunks::default();
+    to_html_into("a[^b]\n\n[^b]: c", &Options::gfm(), &mut chunks)?;
+    assert_eq!(
+        chunks.0.last().map(|d| d.starts_with("<section")),
+        Some(true),
+        "should write footnotes at the end"
+    );
+
+    assert_eq!(
+        to_html_into("a", &Options::default(), &mut Failing),
+        Err("Cannot write HTML".into()),
+        "should fail if the writer fails"
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn write_io() -> Result<(), String> {
+    use markdown::to_html_into_io;
+
+    /// Writer that always fails.
+    struct FailingIo;
+
+    impl std::io::Write for FailingIo {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(std::io::ErrorKind::Other, "x"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut result = vec![];
+    to_html_into_io("# a\n\nb", &Options::default(), &mut result)?;
+    assert_eq!(
+        String::from_utf8(result).unwrap(),
+        "<h1>a</h1>\n<p>b</p>",
+        "should support `io::Write`"
+    );
+
+    assert_eq!(
+        to_html_into_io("a", &Options::default(), &mut FailingIo),
+        Err("Cannot write HTML: x".into()),
+        "should fail if the writer fails"
+    );
+
+    Ok(())
+}